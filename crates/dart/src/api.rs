@@ -0,0 +1,198 @@
+pub(crate) mod company;
+pub(crate) mod corp_code;
+pub(crate) mod document;
+pub(crate) mod financial;
+pub(crate) mod list;
+pub(crate) mod periodic_report;
+
+use crate::throttle::Throttle;
+use crate::DartError;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The environment variable the API keys are read from by [`DartApi::all_from_env`].
+const API_KEY_ENV: &str = "OPEN_DART_API_KEY";
+/// The environment variable which overrides the domain for [`DartApi::all_from_env`],
+/// e.g. to run against a mock server in CI.
+const BASE_URL_ENV: &str = "OPEN_DART_BASE_URL";
+/// Every ZIP archive starts with the signature of its first local file header.
+const ZIP_MAGIC_NUMBER: [u8; 4] = [0x50, 0x4b, 0x03, 0x04];
+
+#[derive(Debug, Clone)]
+pub struct DartApi {
+    client: Client,
+    /// The domain, including the protocol of the api
+    pub domain: String,
+    api_key: String,
+    /// Shared by clones, so they don't exceed the rate limit together.
+    throttle: Option<Arc<Throttle>>,
+}
+
+/// The fields every OpenDART response has.
+#[derive(Deserialize, Debug)]
+struct Status {
+    status: String,
+    message: String,
+}
+
+impl DartApi {
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            client: Client::new(),
+            domain: "https://opendart.fss.or.kr".to_string(),
+            api_key: api_key.to_string(),
+            throttle: None,
+        }
+    }
+
+    /// Read the API key from `OPEN_DART_API_KEY`,
+    /// and the domain from `OPEN_DART_BASE_URL` when it is set.
+    ///
+    /// Only the first key is used when `OPEN_DART_API_KEY` lists several.
+    pub fn from_env() -> Result<Self, DartError> {
+        Ok(Self::all_from_env()?.remove(0))
+    }
+
+    /// A client per key of `OPEN_DART_API_KEY`, which may list several keys separated by commas,
+    /// with the domain from `OPEN_DART_BASE_URL` when it is set.
+    pub fn all_from_env() -> Result<Vec<Self>, DartError> {
+        let api_keys =
+            std::env::var(API_KEY_ENV).map_err(|_| DartError::MissingApiKey(API_KEY_ENV))?;
+        let domain = std::env::var(BASE_URL_ENV).ok();
+        let apis: Vec<Self> = api_keys
+            .split(',')
+            .map(str::trim)
+            .filter(|api_key| !api_key.is_empty())
+            .map(|api_key| match &domain {
+                Some(domain) => Self::new(api_key).with_domain(domain.trim_end_matches('/')),
+                None => Self::new(api_key),
+            })
+            .collect();
+        if apis.is_empty() {
+            return Err(DartError::MissingApiKey(API_KEY_ENV));
+        }
+        Ok(apis)
+    }
+
+    /// An identifier of the API key which doesn't reveal it,
+    /// the first 16 hex digits of its SHA-256, e.g. to track the requests sent with it.
+    pub fn key_id(&self) -> String {
+        format!("{:x}", Sha256::digest(self.api_key.as_bytes()))[..16].to_string()
+    }
+
+    /// Send requests to `domain` instead, e.g. a mock server.
+    pub fn with_domain(mut self, domain: &str) -> Self {
+        self.domain = domain.to_string();
+        self
+    }
+
+    /// Send at most `requests_per_minute` requests, waiting before the ones which would exceed it.
+    ///
+    /// OpenDART blocks keys which send too many requests in a short time,
+    /// which bulk downloads would otherwise do.
+    pub fn with_rate_limit(mut self, requests_per_minute: u32) -> Self {
+        let interval = Duration::from_secs(60) / requests_per_minute.max(1);
+        self.throttle = Some(Arc::new(Throttle::new(interval)));
+        self
+    }
+
+    /// Get the endpoint at `path`, which should start with a `/`, returning the body.
+    async fn get(&self, path: &str, query: &[(&str, &str)]) -> Result<Vec<u8>, DartError> {
+        if let Some(throttle) = &self.throttle {
+            throttle.wait().await;
+        }
+
+        let response = self
+            .client
+            .get(format!("{}{}", self.domain, path))
+            .query(&[("crtfc_key", self.api_key.as_str())])
+            .query(query)
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.bytes().await?.to_vec();
+        if !status.is_success() {
+            return Err(DartError::Response {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            });
+        }
+        Ok(body)
+    }
+
+    /// Get the JSON endpoint at `path`, which should start with a `/`.
+    ///
+    /// Returns `None` when OpenDART has no data for the request.
+    #[tracing::instrument(skip(self))]
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Option<T>, DartError> {
+        let body = String::from_utf8_lossy(&self.get(path, query).await?).into_owned();
+
+        let deserialize_error = |source| DartError::Deserialization {
+            source,
+            message: "Failed to deserialize response",
+            serialized: body.clone(),
+        };
+        let Status { status, message } = serde_json::from_str(&body).map_err(deserialize_error)?;
+        match status.as_str() {
+            "000" => Ok(Some(
+                serde_json::from_str(&body).map_err(deserialize_error)?,
+            )),
+            "013" => {
+                tracing::debug!(%message, "No data");
+                Ok(None)
+            }
+            _ => Err(status_error(status, message)),
+        }
+    }
+
+    /// Get the ZIP endpoint at `path`, which should start with a `/`, returning the archive.
+    ///
+    /// Returns `None` when OpenDART has no archive for the request, status `013` or `014`.
+    /// Unlike the JSON endpoints, failures are answered with an XML `<result>` instead of the archive.
+    async fn get_zip(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Option<Vec<u8>>, DartError> {
+        let body = self.get(path, query).await?;
+        if body.starts_with(&ZIP_MAGIC_NUMBER) {
+            return Ok(Some(body));
+        }
+
+        let body = String::from_utf8_lossy(&body).into_owned();
+        let (Some(status), Some(message)) = (xml_text(&body, "status"), xml_text(&body, "message"))
+        else {
+            return Err(DartError::UnrecognizedArchive(body));
+        };
+        match status {
+            "013" | "014" => {
+                tracing::debug!(%message, "No archive");
+                Ok(None)
+            }
+            _ => Err(status_error(status.to_string(), message.to_string())),
+        }
+    }
+}
+
+/// The error of an unsuccessful `status`, typed for the statuses callers act on.
+fn status_error(status: String, message: String) -> DartError {
+    match status.as_str() {
+        "020" => DartError::RateLimited { message },
+        _ => DartError::Status { status, message },
+    }
+}
+
+/// The text of the first `<tag>` of `xml`, which is enough for the flat `<result>` of OpenDART.
+fn xml_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{tag}>"))? + tag.len() + 2;
+    let end = start + xml[start..].find(&format!("</{tag}>"))?;
+    Some(xml[start..end].trim())
+}
@@ -0,0 +1,108 @@
+use crate::{DartApi, DartError};
+use serde::Deserialize;
+
+/// The overview of a company, as `company.json` returns it.
+///
+/// Fields OpenDART has no value for are blank.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct CompanyOverview {
+    /// 고유번호
+    pub corp_code: String,
+    /// 정식명칭
+    pub corp_name: String,
+    /// 종목코드, blank for companies which aren't listed
+    #[serde(default)]
+    pub stock_code: String,
+    /// 법인구분, `Y` 유가, `K` 코스닥, `N` 코넥스, `E` 기타
+    #[serde(default)]
+    pub corp_cls: String,
+    /// 대표자명
+    #[serde(default)]
+    pub ceo_nm: String,
+    /// 법인등록번호
+    #[serde(default)]
+    pub jurir_no: String,
+    /// 사업자등록번호
+    #[serde(default)]
+    pub bizr_no: String,
+    /// 주소
+    #[serde(default)]
+    pub adres: String,
+    /// 업종코드, a KSIC code of up to 5 digits
+    #[serde(default)]
+    pub induty_code: String,
+    /// 설립일(YYYYMMDD)
+    #[serde(default)]
+    pub est_dt: String,
+    /// 결산월(MM)
+    #[serde(default)]
+    pub acc_mt: String,
+}
+
+impl DartApi {
+    /// 기업개황 (`company.json`)
+    ///
+    /// Returns `None` when OpenDART has no overview of the company.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_company_overview(
+        &self,
+        corp_code: &str,
+    ) -> Result<Option<CompanyOverview>, DartError> {
+        self.get_json("/api/company.json", &[("corp_code", corp_code)])
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DartApi;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn company_overview_should_deserialize() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/company.json"))
+            .and(query_param("crtfc_key", "key"))
+            .and(query_param("corp_code", "00126380"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{
+                    "status": "000",
+                    "message": "정상",
+                    "corp_code": "00126380",
+                    "corp_name": "삼성전자(주)",
+                    "corp_name_eng": "SAMSUNG ELECTRONICS CO,.LTD",
+                    "stock_name": "삼성전자",
+                    "stock_code": "005930",
+                    "ceo_nm": "한종희, 경계현",
+                    "corp_cls": "Y",
+                    "jurir_no": "1301110006246",
+                    "bizr_no": "1248100998",
+                    "adres": "경기도 수원시 영통구  삼성로 129 (매탄동)",
+                    "hm_url": "www.samsung.com/sec",
+                    "ir_url": "",
+                    "phn_no": "02-2255-0114",
+                    "fax_no": "031-200-7538",
+                    "induty_code": "264",
+                    "est_dt": "19690113",
+                    "acc_mt": "12"
+                }"#,
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let api = DartApi::new("key").with_domain(&mock_server.uri());
+
+        let overview = api
+            .get_company_overview("00126380")
+            .await
+            .expect("Failed to get company overview")
+            .expect("The company should have an overview");
+
+        assert_eq!(overview.jurir_no, "1301110006246");
+        assert_eq!(overview.induty_code, "264");
+        assert_eq!(overview.acc_mt, "12");
+    }
+}
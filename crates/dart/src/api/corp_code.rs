@@ -0,0 +1,135 @@
+use crate::{DartApi, DartError};
+use serde::Deserialize;
+use std::io::{Cursor, Read};
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+#[derive(Deserialize, Debug)]
+struct CorpCodeResult {
+    #[serde(default)]
+    list: Vec<CorpCode>,
+}
+
+/// A company of `CORPCODE.xml`, every company which ever filed with DART.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct CorpCode {
+    /// 고유번호
+    pub corp_code: String,
+    /// 정식명칭
+    pub corp_name: String,
+    /// 종목코드, blank for companies which aren't listed
+    #[serde(default)]
+    pub stock_code: String,
+    /// 최종변경일자
+    #[serde(default)]
+    pub modify_date: String,
+}
+
+impl DartApi {
+    /// 고유번호 (`corpCode.xml`)
+    ///
+    /// OpenDART serves the companies as a ZIP archive of a single `CORPCODE.xml`.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_corp_codes(&self) -> Result<Vec<CorpCode>, DartError> {
+        let Some(archive) = self.get_zip("/api/corpCode.xml", &[]).await? else {
+            return Ok(Vec::new());
+        };
+        let mut archive = ZipArchive::new(Cursor::new(archive))?;
+        let mut xml = String::new();
+        archive
+            .by_index(0)?
+            .read_to_string(&mut xml)
+            .map_err(ZipError::from)?;
+        let result: CorpCodeResult = quick_xml::de::from_str(&xml)?;
+        tracing::debug!(count = result.list.len(), "Read corp codes");
+        Ok(result.list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DartApi, DartError};
+    use std::io::Write;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    const CORPCODE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<result>
+    <list>
+        <corp_code>00126380</corp_code>
+        <corp_name>삼성전자</corp_name>
+        <corp_eng_name>SAMSUNG ELECTRONICS CO,.LTD</corp_eng_name>
+        <stock_code>005930</stock_code>
+        <modify_date>20240620</modify_date>
+    </list>
+    <list>
+        <corp_code>00434003</corp_code>
+        <corp_name>다코</corp_name>
+        <corp_eng_name>Daco corporation</corp_eng_name>
+        <stock_code> </stock_code>
+        <modify_date>20170630</modify_date>
+    </list>
+</result>"#;
+
+    fn zip(name: &str, content: &str) -> Vec<u8> {
+        let mut writer = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer
+            .start_file(name, SimpleFileOptions::default())
+            .expect("Failed to start file");
+        writer
+            .write_all(content.as_bytes())
+            .expect("Failed to write file");
+        writer
+            .finish()
+            .expect("Failed to finish archive")
+            .into_inner()
+    }
+
+    async fn api(mock_server: &MockServer, body: Vec<u8>) -> DartApi {
+        Mock::given(method("GET"))
+            .and(path("/api/corpCode.xml"))
+            .and(query_param("crtfc_key", "key"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(body))
+            .expect(1)
+            .mount(mock_server)
+            .await;
+        DartApi::new("key").with_domain(&mock_server.uri())
+    }
+
+    #[tokio::test]
+    async fn corp_codes_should_be_read_from_archive() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        let api = api(&mock_server, zip("CORPCODE.xml", CORPCODE_XML)).await;
+
+        let corp_codes = api
+            .get_corp_codes()
+            .await
+            .expect("Failed to get corp codes");
+
+        assert_eq!(corp_codes.len(), 2);
+        assert_eq!(corp_codes[0].corp_code, "00126380");
+        assert_eq!(corp_codes[0].stock_code, "005930");
+        assert_eq!(corp_codes[1].stock_code.trim(), "");
+        assert_eq!(corp_codes[1].modify_date, "20170630");
+    }
+
+    #[tokio::test]
+    async fn corp_code_errors_should_fail() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        let api = api(
+            &mock_server,
+            r#"<?xml version="1.0" encoding="UTF-8"?><result><status>010</status><message>등록되지 않은 키입니다.</message></result>"#
+                .as_bytes()
+                .to_vec(),
+        )
+        .await;
+
+        let result = api.get_corp_codes().await;
+
+        assert!(matches!(result, Err(DartError::Status { status, .. }) if status == "010"));
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{DartApi, DartError};
+
+impl DartApi {
+    /// 공시서류원본파일 (`document.xml`)
+    ///
+    /// Returns the ZIP archive of the documents of the filing with `rcept_no`,
+    /// or `None` when OpenDART has no documents for it, status `013` or `014`.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_document(&self, rcept_no: &str) -> Result<Option<Vec<u8>>, DartError> {
+        self.get_zip("/api/document.xml", &[("rcept_no", rcept_no)])
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DartApi, DartError};
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn api(mock_server: &MockServer, body: Vec<u8>) -> DartApi {
+        Mock::given(method("GET"))
+            .and(path("/api/document.xml"))
+            .and(query_param("crtfc_key", "key"))
+            .and(query_param("rcept_no", "20240312000736"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(body))
+            .expect(1)
+            .mount(mock_server)
+            .await;
+        DartApi::new("key").with_domain(&mock_server.uri())
+    }
+
+    fn result(status: &str, message: &str) -> Vec<u8> {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><result><status>{status}</status><message>{message}</message></result>"#
+        )
+        .into_bytes()
+    }
+
+    #[tokio::test]
+    async fn document_should_return_archive() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        // Only the signature is checked, the archive isn't read
+        let mut archive = vec![0x50, 0x4b, 0x03, 0x04];
+        archive.extend_from_slice(&[0; 18]);
+        let api = api(&mock_server, archive.clone()).await;
+
+        let document = api
+            .get_document("20240312000736")
+            .await
+            .expect("Failed to get document");
+
+        assert_eq!(document, Some(archive));
+    }
+
+    #[tokio::test]
+    async fn missing_document_should_return_none() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        let api = api(&mock_server, result("014", "파일이 존재하지 않습니다.")).await;
+
+        let document = api
+            .get_document("20240312000736")
+            .await
+            .expect("Failed to get document");
+
+        assert_eq!(document, None);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_document_should_fail_as_rate_limited() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        let api = api(&mock_server, result("020", "요청 제한을 초과하였습니다.")).await;
+
+        let result = api.get_document("20240312000736").await;
+
+        assert!(matches!(result, Err(DartError::RateLimited { .. })));
+    }
+}
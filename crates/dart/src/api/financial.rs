@@ -0,0 +1,171 @@
+use crate::{DartApi, DartError};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+struct FinancialStatementResponse {
+    #[serde(default)]
+    list: Vec<FinancialStatementItem>,
+}
+
+/// An account of a single-company financial statement, as `fnlttSinglAcntAll` returns it.
+///
+/// Amounts are formatted numbers, and blank when the statement has no value for the period.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct FinancialStatementItem {
+    /// 접수번호
+    pub rcept_no: String,
+    /// 보고서 코드
+    pub reprt_code: String,
+    /// 사업 연도
+    pub bsns_year: String,
+    /// 고유번호
+    pub corp_code: String,
+    /// 재무제표구분, e.g. `BS`
+    pub sj_div: String,
+    /// 재무제표명
+    pub sj_nm: String,
+    /// 계정ID, the IFRS taxonomy id
+    pub account_id: String,
+    /// 계정명
+    pub account_nm: String,
+    /// 계정상세, the `|`-separated groups of the account
+    #[serde(default)]
+    pub account_detail: Option<String>,
+    /// 당기금액
+    #[serde(default)]
+    pub thstrm_amount: Option<String>,
+    /// 전기금액
+    #[serde(default)]
+    pub frmtrm_amount: Option<String>,
+    /// 전전기금액
+    #[serde(default)]
+    pub bfefrmtrm_amount: Option<String>,
+    /// 계정과목 정렬순서
+    pub ord: String,
+    /// 통화 단위
+    pub currency: String,
+}
+
+impl DartApi {
+    /// 단일회사 전체 재무제표 (`fnlttSinglAcntAll`)
+    ///
+    /// * `fs_div` - `CFS` for the consolidated statements, `OFS` for the separate ones
+    ///
+    /// Returns no items when the company didn't file the statements,
+    /// e.g. consolidated statements of a company without subsidiaries.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_single_company_financial_statements(
+        &self,
+        corp_code: &str,
+        bsns_year: &str,
+        reprt_code: &str,
+        fs_div: &str,
+    ) -> Result<Vec<FinancialStatementItem>, DartError> {
+        let response: Option<FinancialStatementResponse> = self
+            .get_json(
+                "/api/fnlttSinglAcntAll.json",
+                &[
+                    ("corp_code", corp_code),
+                    ("bsns_year", bsns_year),
+                    ("reprt_code", reprt_code),
+                    ("fs_div", fs_div),
+                ],
+            )
+            .await?;
+        Ok(response.map(|response| response.list).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DartApi, DartError};
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const BODY: &str = r#"{
+        "status": "000",
+        "message": "정상",
+        "list": [{
+            "rcept_no": "20240312000736",
+            "reprt_code": "11011",
+            "bsns_year": "2023",
+            "corp_code": "00126380",
+            "sj_div": "BS",
+            "sj_nm": "재무상태표",
+            "account_id": "ifrs-full_CurrentAssets",
+            "account_nm": "유동자산",
+            "account_detail": "-",
+            "thstrm_nm": "제 55 기",
+            "thstrm_amount": "195936557000000",
+            "frmtrm_nm": "제 54 기",
+            "frmtrm_amount": "218470581000000",
+            "bfefrmtrm_nm": "제 53 기",
+            "bfefrmtrm_amount": "218163185000000",
+            "ord": "1",
+            "currency": "KRW"
+        }]
+    }"#;
+
+    async fn api(mock_server: &MockServer, body: &str) -> DartApi {
+        Mock::given(method("GET"))
+            .and(path("/api/fnlttSinglAcntAll.json"))
+            .and(query_param("crtfc_key", "key"))
+            .and(query_param("fs_div", "CFS"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body))
+            .expect(1)
+            .mount(mock_server)
+            .await;
+        DartApi::new("key").with_domain(&mock_server.uri())
+    }
+
+    #[tokio::test]
+    async fn financial_statements_should_deserialize() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        let api = api(&mock_server, BODY).await;
+
+        let items = api
+            .get_single_company_financial_statements("00126380", "2023", "11011", "CFS")
+            .await
+            .expect("Failed to get financial statements");
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].account_id, "ifrs-full_CurrentAssets");
+        assert_eq!(items[0].thstrm_amount.as_deref(), Some("195936557000000"));
+    }
+
+    #[tokio::test]
+    async fn no_data_should_return_no_items() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        let api = api(
+            &mock_server,
+            r#"{"status": "013", "message": "조회된 데이타가 없습니다."}"#,
+        )
+        .await;
+
+        let items = api
+            .get_single_company_financial_statements("00126380", "2023", "11011", "CFS")
+            .await
+            .expect("Failed to get financial statements");
+
+        assert!(items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn other_statuses_should_fail() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        let api = api(
+            &mock_server,
+            r#"{"status": "100", "message": "필드의 부적절한 값입니다."}"#,
+        )
+        .await;
+
+        let result = api
+            .get_single_company_financial_statements("00126380", "2023", "11011", "CFS")
+            .await;
+
+        assert!(matches!(result, Err(DartError::Status { status, .. }) if status == "100"));
+    }
+}
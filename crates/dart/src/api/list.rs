@@ -0,0 +1,120 @@
+use crate::{DartApi, DartError};
+use serde::Deserialize;
+
+/// A page of `list.json`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ListResponse {
+    pub page_no: u32,
+    pub page_count: u32,
+    pub total_count: u32,
+    pub total_page: u32,
+    #[serde(default)]
+    pub list: Vec<ListItem>,
+}
+
+/// A filing of `list.json`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ListItem {
+    /// 법인구분, `Y` for KOSPI, `K` for KOSDAQ, `N` for KONEX and `E` for the others
+    #[serde(default)]
+    pub corp_cls: String,
+    /// 종목명(법인명)
+    pub corp_name: String,
+    /// 고유번호
+    pub corp_code: String,
+    /// 종목코드
+    #[serde(default)]
+    pub stock_code: String,
+    /// 보고서명
+    pub report_nm: String,
+    /// 접수번호
+    pub rcept_no: String,
+    /// 공시 제출인명
+    pub flr_nm: String,
+    /// 접수일자
+    pub rcept_dt: String,
+    /// 비고
+    #[serde(default)]
+    pub rm: String,
+}
+
+impl DartApi {
+    /// 공시검색 (`list.json`)
+    ///
+    /// * `bgn_de`, `end_de` - The first and last receipt dates, `YYYYMMDD`
+    /// * `page_count` - At most 100
+    ///
+    /// Returns `None` when no filings match, status `013`.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_list(
+        &self,
+        bgn_de: &str,
+        end_de: &str,
+        page_no: u32,
+        page_count: u32,
+    ) -> Result<Option<ListResponse>, DartError> {
+        self.get_json(
+            "/api/list.json",
+            &[
+                ("bgn_de", bgn_de),
+                ("end_de", end_de),
+                ("page_no", &page_no.to_string()),
+                ("page_count", &page_count.to_string()),
+            ],
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DartApi;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn list_should_deserialize() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/list.json"))
+            .and(query_param("crtfc_key", "key"))
+            .and(query_param("bgn_de", "20241001"))
+            .and(query_param("page_no", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{
+                    "status": "000",
+                    "message": "정상",
+                    "page_no": 2,
+                    "page_count": 1,
+                    "total_count": 2,
+                    "total_page": 2,
+                    "list": [{
+                        "corp_cls": "Y",
+                        "corp_name": "삼성전자",
+                        "corp_code": "00126380",
+                        "stock_code": "005930",
+                        "report_nm": "[기재정정]사업보고서 (2023.12)",
+                        "rcept_no": "20241001000001",
+                        "flr_nm": "삼성전자",
+                        "rcept_dt": "20241001",
+                        "rm": "유"
+                    }]
+                }"#,
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let api = DartApi::new("key").with_domain(&mock_server.uri());
+
+        let page = api
+            .get_list("20241001", "20241001", 2, 1)
+            .await
+            .expect("Failed to get list")
+            .expect("The page should have filings");
+
+        assert_eq!(page.total_page, 2);
+        assert_eq!(page.list[0].report_nm, "[기재정정]사업보고서 (2023.12)");
+        assert_eq!(page.list[0].rm, "유");
+    }
+}
@@ -0,0 +1,313 @@
+use crate::{DartApi, DartError};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+struct PeriodicReportResponse<T> {
+    #[serde(default = "Vec::new")]
+    list: Vec<T>,
+}
+
+/// A row of 최대주주 현황, as `hyslrSttus` returns it.
+///
+/// Numbers are formatted, and OpenDART fills fields without a value with `-`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct MajorShareholderItem {
+    /// 접수번호
+    pub rcept_no: String,
+    /// 고유번호
+    pub corp_code: String,
+    /// 성명, `계` for the total
+    pub nm: String,
+    /// 관계
+    #[serde(default)]
+    pub relate: String,
+    /// 주식 종류
+    #[serde(default)]
+    pub stock_knd: String,
+    /// 기초 소유 주식 수
+    #[serde(default)]
+    pub bsis_posesn_stock_co: String,
+    /// 기초 소유 주식 지분 율
+    #[serde(default)]
+    pub bsis_posesn_stock_qota_rt: String,
+    /// 기말 소유 주식 수
+    #[serde(default)]
+    pub trmend_posesn_stock_co: String,
+    /// 기말 소유 주식 지분 율
+    #[serde(default)]
+    pub trmend_posesn_stock_qota_rt: String,
+    /// 비고
+    #[serde(default)]
+    pub rm: String,
+    /// 결산기준일, e.g. `2023-12-31`
+    #[serde(default)]
+    pub stlm_dt: String,
+}
+
+/// A row of 임원 현황, as `exctvSttus` returns it.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ExecutiveItem {
+    /// 접수번호
+    pub rcept_no: String,
+    /// 고유번호
+    pub corp_code: String,
+    /// 성명
+    pub nm: String,
+    /// 성별
+    #[serde(default)]
+    pub sexdstn: String,
+    /// 출생 년월
+    #[serde(default)]
+    pub birth_ym: String,
+    /// 직위
+    #[serde(default)]
+    pub ofcps: String,
+    /// 등기 임원 여부
+    #[serde(default)]
+    pub rgist_exctv_at: String,
+    /// 상근 여부
+    #[serde(default)]
+    pub fte_at: String,
+    /// 담당 업무
+    #[serde(default)]
+    pub chrg_job: String,
+    /// 주요 경력
+    #[serde(default)]
+    pub main_career: String,
+    /// 최대 주주와의 관계
+    #[serde(default)]
+    pub mxmm_shrholdr_relate: String,
+    /// 재직 기간
+    #[serde(default)]
+    pub hffc_pd: String,
+    /// 임기 만료일
+    #[serde(default)]
+    pub tenure_end_on: String,
+    /// 결산기준일
+    #[serde(default)]
+    pub stlm_dt: String,
+}
+
+/// A row of 직원 현황, as `empSttus` returns it.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct EmployeeItem {
+    /// 접수번호
+    pub rcept_no: String,
+    /// 고유번호
+    pub corp_code: String,
+    /// 사업부문
+    #[serde(default)]
+    pub fo_bbm: String,
+    /// 성별
+    #[serde(default)]
+    pub sexdstn: String,
+    /// 정규직 수
+    #[serde(default)]
+    pub rgllbr_co: String,
+    /// 계약직 수
+    #[serde(default)]
+    pub cnttk_co: String,
+    /// 합계
+    #[serde(default)]
+    pub sm: String,
+    /// 평균 근속 연수
+    #[serde(default)]
+    pub avrg_cnwk_sdytrn: String,
+    /// 연간 급여 총액
+    #[serde(default)]
+    pub fyer_salary_totamt: String,
+    /// 1인평균 급여 액
+    #[serde(default)]
+    pub jan_salary_am: String,
+    /// 비고
+    #[serde(default)]
+    pub rm: String,
+    /// 결산기준일
+    #[serde(default)]
+    pub stlm_dt: String,
+}
+
+impl DartApi {
+    /// 최대주주 현황 (`hyslrSttus`)
+    ///
+    /// Returns no items when the report wasn't filed.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_major_shareholders(
+        &self,
+        corp_code: &str,
+        bsns_year: &str,
+        reprt_code: &str,
+    ) -> Result<Vec<MajorShareholderItem>, DartError> {
+        self.get_periodic_report("/api/hyslrSttus.json", corp_code, bsns_year, reprt_code)
+            .await
+    }
+
+    /// 임원 현황 (`exctvSttus`)
+    ///
+    /// Returns no items when the report wasn't filed.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_executives(
+        &self,
+        corp_code: &str,
+        bsns_year: &str,
+        reprt_code: &str,
+    ) -> Result<Vec<ExecutiveItem>, DartError> {
+        self.get_periodic_report("/api/exctvSttus.json", corp_code, bsns_year, reprt_code)
+            .await
+    }
+
+    /// 직원 현황 (`empSttus`)
+    ///
+    /// Returns no items when the report wasn't filed.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_employees(
+        &self,
+        corp_code: &str,
+        bsns_year: &str,
+        reprt_code: &str,
+    ) -> Result<Vec<EmployeeItem>, DartError> {
+        self.get_periodic_report("/api/empSttus.json", corp_code, bsns_year, reprt_code)
+            .await
+    }
+
+    /// The periodic report endpoints share their query, and none of them are paged.
+    async fn get_periodic_report<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        corp_code: &str,
+        bsns_year: &str,
+        reprt_code: &str,
+    ) -> Result<Vec<T>, DartError> {
+        let response: Option<PeriodicReportResponse<T>> = self
+            .get_json(
+                path,
+                &[
+                    ("corp_code", corp_code),
+                    ("bsns_year", bsns_year),
+                    ("reprt_code", reprt_code),
+                ],
+            )
+            .await?;
+        Ok(response.map(|response| response.list).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DartApi, DartError};
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn api(mock_server: &MockServer, endpoint: &str, body: &str) -> DartApi {
+        Mock::given(method("GET"))
+            .and(path(endpoint))
+            .and(query_param("crtfc_key", "key"))
+            .and(query_param("corp_code", "00126380"))
+            .and(query_param("bsns_year", "2023"))
+            .and(query_param("reprt_code", "11011"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body))
+            .expect(1)
+            .mount(mock_server)
+            .await;
+        DartApi::new("key").with_domain(&mock_server.uri())
+    }
+
+    #[tokio::test]
+    async fn major_shareholders_should_deserialize() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        let api = api(
+            &mock_server,
+            "/api/hyslrSttus.json",
+            r#"{
+                "status": "000",
+                "message": "정상",
+                "list": [{
+                    "rcept_no": "20240312000736",
+                    "corp_cls": "Y",
+                    "corp_code": "00126380",
+                    "corp_name": "삼성전자",
+                    "nm": "삼성생명보험(주)",
+                    "relate": "계열회사",
+                    "stock_knd": "보통주",
+                    "bsis_posesn_stock_co": "508,157,148",
+                    "bsis_posesn_stock_qota_rt": "8.51",
+                    "trmend_posesn_stock_co": "508,157,148",
+                    "trmend_posesn_stock_qota_rt": "8.51",
+                    "rm": "-",
+                    "stlm_dt": "2023-12-31"
+                }]
+            }"#,
+        )
+        .await;
+
+        let items = api
+            .get_major_shareholders("00126380", "2023", "11011")
+            .await
+            .expect("Failed to get major shareholders");
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].nm, "삼성생명보험(주)");
+        assert_eq!(items[0].trmend_posesn_stock_qota_rt, "8.51");
+    }
+
+    #[tokio::test]
+    async fn missing_fields_should_default_to_blank() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        let api = api(
+            &mock_server,
+            "/api/empSttus.json",
+            r#"{
+                "status": "000",
+                "message": "정상",
+                "list": [{"rcept_no": "20240312000736", "corp_code": "00126380", "sm": "124,804"}]
+            }"#,
+        )
+        .await;
+
+        let items = api
+            .get_employees("00126380", "2023", "11011")
+            .await
+            .expect("Failed to get employees");
+
+        assert_eq!(items[0].sm, "124,804");
+        assert_eq!(items[0].fo_bbm, "");
+    }
+
+    #[tokio::test]
+    async fn unfiled_reports_should_return_no_items() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        let api = api(
+            &mock_server,
+            "/api/exctvSttus.json",
+            r#"{"status": "013", "message": "조회된 데이타가 없습니다."}"#,
+        )
+        .await;
+
+        let items = api
+            .get_executives("00126380", "2023", "11011")
+            .await
+            .expect("Failed to get executives");
+
+        assert!(items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rate_limited_requests_should_fail_as_rate_limited() {
+        tracing_setup::span!("test");
+        let mock_server = MockServer::start().await;
+        let api = api(
+            &mock_server,
+            "/api/exctvSttus.json",
+            r#"{"status": "020", "message": "요청 제한을 초과하였습니다."}"#,
+        )
+        .await;
+
+        let result = api.get_executives("00126380", "2023", "11011").await;
+
+        assert!(matches!(result, Err(DartError::RateLimited { .. })));
+    }
+}
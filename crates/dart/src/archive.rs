@@ -0,0 +1,101 @@
+use sha2::{Digest, Sha256};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Document archives on disk, addressed by the SHA-256 of their content.
+///
+/// An archive is stored at `<root>/<first two hex digits>/<sha256>.zip`,
+/// so downloading the same archive again, or for another filing, stores nothing new.
+#[derive(Debug, Clone)]
+pub struct ArchiveStore {
+    root: PathBuf,
+}
+
+/// Where [`ArchiveStore::put`] stored an archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredArchive {
+    /// The lowercase hex SHA-256 of the archive
+    pub sha256: String,
+    pub byte_size: u64,
+}
+
+impl ArchiveStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// The path of the archive with `sha256`, whether it is stored or not.
+    pub fn path(&self, sha256: &str) -> PathBuf {
+        self.root
+            .join(&sha256[..2.min(sha256.len())])
+            .join(format!("{sha256}.zip"))
+    }
+
+    /// Store `archive`, unless an archive with the same content is already stored.
+    ///
+    /// The archive is written to a temporary file which is then renamed,
+    /// so an interrupted write never leaves a partial archive at [`Self::path`].
+    #[tracing::instrument(skip(self, archive))]
+    pub async fn put(&self, archive: &[u8]) -> io::Result<StoredArchive> {
+        let stored = StoredArchive {
+            sha256: format!("{:x}", Sha256::digest(archive)),
+            byte_size: archive.len() as u64,
+        };
+        let path = self.path(&stored.sha256);
+        if tokio::fs::try_exists(&path).await? {
+            tracing::trace!(?path, "Archive is already stored");
+            return Ok(stored);
+        }
+
+        let directory = path.parent().unwrap_or(Path::new("."));
+        tokio::fs::create_dir_all(directory).await?;
+        let temporary = directory.join(format!("{}.{}.tmp", stored.sha256, std::process::id()));
+        tokio::fs::write(&temporary, archive).await?;
+        tokio::fs::rename(&temporary, &path).await?;
+        tracing::trace!(?path, "Stored archive");
+        Ok(stored)
+    }
+
+    /// The archive with `sha256`.
+    pub async fn get(&self, sha256: &str) -> io::Result<Vec<u8>> {
+        tokio::fs::read(self.path(sha256)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(name: &str) -> ArchiveStore {
+        let root = std::env::temp_dir().join(format!("dart-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        ArchiveStore::new(root)
+    }
+
+    #[tokio::test]
+    async fn archives_should_be_stored_once_by_content() {
+        let store = store("archives_should_be_stored_once_by_content");
+
+        let first = store.put(b"archive").await.expect("Failed to put archive");
+        let second = store.put(b"archive").await.expect("Failed to put archive");
+        let read = store
+            .get(&first.sha256)
+            .await
+            .expect("Failed to get archive");
+
+        assert_eq!(first, second);
+        assert_eq!(first.byte_size, 7);
+        assert_eq!(read, b"archive");
+        let directory = store
+            .path(&first.sha256)
+            .parent()
+            .expect("Archives should be in a directory")
+            .to_path_buf();
+        assert_eq!(
+            std::fs::read_dir(directory)
+                .expect("Failed to read directory")
+                .count(),
+            1
+        );
+    }
+}
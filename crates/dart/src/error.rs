@@ -0,0 +1,34 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DartError {
+    #[error("Deserialization error: {message}, serialized: {serialized}")]
+    Deserialization {
+        #[source]
+        source: serde_json::Error,
+        message: &'static str,
+        serialized: String,
+    },
+    #[error("Missing API key: set {0}")]
+    MissingApiKey(&'static str),
+    #[error("HTTP error: {0}")]
+    Reqwest(#[from] reqwest::Error),
+    #[error("Unsuccessful response error: status: {status}, body: {body}")]
+    Response {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+    /// A ZIP endpoint answered with neither an archive nor a `<result>`.
+    #[error("Unrecognized archive response: {0}")]
+    UnrecognizedArchive(String),
+    #[error("Failed to read archive: {0}")]
+    Archive(#[from] zip::result::ZipError),
+    #[error("Failed to deserialize XML: {0}")]
+    Xml(#[from] quick_xml::DeError),
+    /// OpenDART answered with status `020`, as the key exceeded its requests for the day.
+    #[error("OpenDART rate limit exceeded: {message}")]
+    RateLimited { message: String },
+    /// OpenDART answered with a status other than `000`, `013` or `020`.
+    #[error("OpenDART status {status}: {message}")]
+    Status { status: String, message: String },
+}
@@ -0,0 +1,25 @@
+//! # DART
+//!
+//! A client for the OpenDART endpoints the runners use.
+//!
+//! Every endpoint answers with a `status` and `message`,
+//! where `000` means success and `013` means there is no data for the request.
+//! Endpoints return an empty result for `013`, [`DartError::RateLimited`] for `020`,
+//! which OpenDART answers once a key exceeded its requests for the day,
+//! and [`DartError::Status`] for the other statuses.
+//!
+//! Document archives are kept on disk by [`ArchiveStore`], addressed by their content.
+
+mod api;
+mod archive;
+mod error;
+mod throttle;
+
+pub use api::company::CompanyOverview;
+pub use api::corp_code::CorpCode;
+pub use api::financial::FinancialStatementItem;
+pub use api::list::{ListItem, ListResponse};
+pub use api::periodic_report::{EmployeeItem, ExecutiveItem, MajorShareholderItem};
+pub use api::DartApi;
+pub use archive::{ArchiveStore, StoredArchive};
+pub use error::DartError;
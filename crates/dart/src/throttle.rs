@@ -0,0 +1,50 @@
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Spaces requests at least `interval` apart.
+#[derive(Debug)]
+pub(crate) struct Throttle {
+    interval: Duration,
+    /// When the next request may be sent
+    next: Mutex<Instant>,
+}
+
+impl Throttle {
+    pub(crate) fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Wait until a request may be sent, and reserve the slot for it.
+    ///
+    /// Waiters take turns in the order they arrived, as the lock of tokio is fair.
+    pub(crate) async fn wait(&self) {
+        let mut next = self.next.lock().await;
+        let now = Instant::now();
+        if *next > now {
+            tokio::time::sleep_until(*next).await;
+        }
+        *next = (*next).max(now) + self.interval;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn requests_should_be_spaced_by_interval() {
+        let throttle = Throttle::new(Duration::from_millis(50));
+        let started = Instant::now();
+
+        for _ in 0..3 {
+            throttle.wait().await;
+        }
+
+        // The first request is sent right away, the other two wait an interval each
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -46,7 +46,7 @@ pub struct IncomeStatParams {
 mod tests {
     use crate::model::DataApiKey;
 
-    const SERIALIZED_PARAMS: &str = r#"{"numOfRows":10,"pageNo":1,"resultType":"json","serviceKey":"test","crno":"1234567890123","bizYear":null}"#;
+    const SERIALIZED_PARAMS: &str = r#"{"numOfRows":10,"pageNo":1,"resultType":"json","serviceKey":"test","crno":"1234567890120","bizYear":null}"#;
 
     #[test]
     fn serialize_get_income_stat_params() {
@@ -56,7 +56,7 @@ mod tests {
             result_type: "json".to_string(),
             service_key: DataApiKey::new("test"),
             crno: Some(
-                "1234567890123"
+                "1234567890120"
                     .try_into()
                     .expect("Failed to parse CorporationRegistrationNumber"),
             ),
@@ -76,7 +76,7 @@ mod tests {
         assert_eq!(deserialized.page_no, 1);
         assert_eq!(deserialized.result_type, "json");
         assert_eq!(deserialized.service_key, DataApiKey::new("test"));
-        assert_eq!(deserialized.crno.unwrap().to_string(), "1234567890123");
+        assert_eq!(deserialized.crno.unwrap().to_string(), "1234567890120");
         assert!(deserialized.biz_year.is_none());
     }
 }
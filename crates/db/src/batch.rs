@@ -0,0 +1,103 @@
+//! Buffering channel consumers into batches, which are written one transaction at a time.
+
+use crate::DbError;
+use diesel::result::DatabaseErrorKind;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// The most rows written in one transaction.
+pub(crate) const BATCH_SIZE: usize = 100;
+/// How long a batch waits to fill up after its first row,
+/// so rows trickling in from a slow scraper are still written promptly.
+pub(crate) const BATCH_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Receive the next batch of up to `size` messages, waiting at most `timeout` after the first one.
+///
+/// Returns `None` once the channel is closed and drained.
+pub(crate) async fn recv_batch<T>(
+    rx: &mut UnboundedReceiver<T>,
+    size: usize,
+    timeout: Duration,
+) -> Option<Vec<T>> {
+    let first = rx.recv().await?;
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut batch = vec![first];
+    while batch.len() < size {
+        match tokio::time::timeout_at(deadline, rx.recv()).await {
+            Ok(Some(message)) => batch.push(message),
+            Ok(None) | Err(_) => break,
+        }
+    }
+    Some(batch)
+}
+
+/// Whether a row failing with `error` belongs in a dead-letter table,
+/// i.e. the row violates a constraint.
+///
+/// Other database errors, such as a closed connection or a serialization failure,
+/// say nothing about the row, so they are propagated instead.
+pub(crate) fn is_rejected_row(error: &DbError) -> bool {
+    matches!(
+        error,
+        DbError::Diesel(diesel::result::Error::DatabaseError(
+            DatabaseErrorKind::UniqueViolation
+                | DatabaseErrorKind::ForeignKeyViolation
+                | DatabaseErrorKind::CheckViolation
+                | DatabaseErrorKind::NotNullViolation,
+            _,
+        ))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn batches_should_be_split_by_size_and_end_with_the_channel() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        for i in 0..5 {
+            tx.send(i).expect("Failed to send");
+        }
+        drop(tx);
+
+        let timeout = Duration::from_millis(10);
+        assert_eq!(recv_batch(&mut rx, 2, timeout).await, Some(vec![0, 1]));
+        assert_eq!(recv_batch(&mut rx, 2, timeout).await, Some(vec![2, 3]));
+        assert_eq!(recv_batch(&mut rx, 2, timeout).await, Some(vec![4]));
+        assert_eq!(recv_batch(&mut rx, 2, timeout).await, None);
+    }
+
+    #[test]
+    fn only_constraint_violations_should_reject_rows() {
+        let error = |kind| {
+            DbError::Diesel(diesel::result::Error::DatabaseError(
+                kind,
+                Box::new(String::new()),
+            ))
+        };
+
+        assert!(is_rejected_row(&error(DatabaseErrorKind::CheckViolation)));
+        assert!(is_rejected_row(&error(
+            DatabaseErrorKind::ForeignKeyViolation
+        )));
+        assert!(!is_rejected_row(&error(
+            DatabaseErrorKind::ClosedConnection
+        )));
+        assert!(!is_rejected_row(&error(
+            DatabaseErrorKind::SerializationFailure
+        )));
+    }
+
+    #[tokio::test]
+    async fn batch_should_be_cut_by_timeout() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tx.send(0).expect("Failed to send");
+
+        let batch = recv_batch(&mut rx, 10, Duration::from_millis(10)).await;
+
+        assert_eq!(batch, Some(vec![0]));
+        drop(tx);
+    }
+}
@@ -0,0 +1,75 @@
+//! Bodies of `smes.html_revision`, which are zstd-compressed and addressed by the SHA-256 of the content.
+
+use crate::DbError;
+use sha2::{Digest, Sha256};
+
+/// Every zstd frame starts with these bytes, which no HTML page starts with.
+const ZSTD_MAGIC_NUMBER: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// A compressed body and the hash of its uncompressed content.
+pub(crate) struct Blob {
+    pub sha256: String,
+    pub body: Vec<u8>,
+}
+
+impl Blob {
+    pub fn compress(content: &str) -> Result<Self, DbError> {
+        Ok(Self {
+            sha256: sha256_hex(content),
+            body: zstd::encode_all(content.as_bytes(), zstd::DEFAULT_COMPRESSION_LEVEL)?,
+        })
+    }
+}
+
+/// The lowercase hex SHA-256 of `content`.
+pub(crate) fn sha256_hex(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Whether `body` was written by [`Blob::compress`].
+pub(crate) fn is_compressed(body: &[u8]) -> bool {
+    body.starts_with(&ZSTD_MAGIC_NUMBER)
+}
+
+/// Decompress a body written by [`Blob::compress`].
+///
+/// Bodies backfilled by the migration are stored uncompressed,
+/// as the databases can hash but not compress, until they are compressed after migrating,
+/// and are returned as is.
+pub(crate) fn decompress(body: &[u8]) -> Result<String, DbError> {
+    let bytes = if is_compressed(body) {
+        zstd::decode_all(body)?
+    } else {
+        body.to_vec()
+    };
+    String::from_utf8(bytes)
+        .map_err(|e| DbError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compressed_blob_should_round_trip() {
+        let content = "<html><body><h2>유동자산</h2></body></html>";
+        let blob = Blob::compress(content).expect("Failed to compress");
+
+        assert_eq!(blob.sha256, sha256_hex(content));
+        assert_eq!(blob.sha256.len(), 64);
+        assert!(blob.body.starts_with(&ZSTD_MAGIC_NUMBER));
+        assert_eq!(
+            decompress(&blob.body).expect("Failed to decompress"),
+            content
+        );
+    }
+
+    #[test]
+    fn uncompressed_body_should_be_returned_as_is() {
+        let content = "<html></html>";
+        assert_eq!(
+            decompress(content.as_bytes()).expect("Failed to decompress"),
+            content
+        );
+    }
+}
@@ -0,0 +1,1699 @@
+//! Tests every backend has to pass, to behave the same as Postgres.
+//!
+//! Each test is written once against [`TestContext`],
+//! and [`conformance_tests!`] runs it against Postgres, SQLite and the in-memory db.
+
+use crate::dart::{
+    AmendmentChainDb, ApiQuotaDb, CompanyIdDb, CompanyProfileDb, DocumentDb, FilingDb,
+    FinancialItemDb, PeriodicReportDb,
+};
+use crate::ingest::IngestRunDb;
+use crate::link::CompanyLinkDb;
+use crate::model::dart::{
+    CompanyId, FinancialStatementKey, FsDiv, Gender, NewCompanyProfile, NewDocument, NewEmployee,
+    NewExecutive, NewFiling, NewFinancialItem, NewFinancialStatementCheck, NewMajorShareholder,
+    NewPeriodicReportCheck, PeriodicReportKey, PeriodicReportSection, ReportCode, Statement,
+};
+use crate::model::ingest::{IngestCounts, NewIngestRun};
+use crate::model::link::{MatchMethod, NewCompanyLink};
+use crate::model::smes::{NewCompany, NewHtml};
+use crate::query::{CompanyQuery, FilingQuery, KeysetQuery};
+use crate::smes::{CompanyDb, HtmlDb};
+use crate::test_utils::TestContext;
+use crate::{blob, Db, DbError};
+
+use chrono::NaiveDate;
+use diesel::result::DatabaseErrorKind;
+use fake::{Fake, Faker};
+use hashbrown::HashSet;
+use tokio::sync::mpsc;
+use types::company;
+
+macro_rules! conformance_tests {
+    ($($test:ident),* $(,)?) => {
+        conformance_tests!(@backend postgres, PostgresDb, PostgresTestContext, $($test),*);
+        conformance_tests!(@backend sqlite, SqliteDb, SqliteTestContext, $($test),*);
+        conformance_tests!(@backend in_memory, InMemoryDb, InMemoryTestContext, $($test),*);
+    };
+    (@backend $backend:ident, $db:ident, $context:ident, $($test:ident),*) => {
+        mod $backend {
+            use crate::test_utils::{$context, TestContext};
+            use crate::$db;
+
+            $(
+                #[tokio::test]
+                async fn $test() {
+                    tracing_setup::span!("test");
+                    let function_id = utils::function_id!();
+                    let ctx = $context::new(&function_id).await;
+                    super::$test::<$db, _>(ctx).await;
+                }
+            )*
+        }
+    };
+}
+
+conformance_tests!(
+    companies_should_round_trip,
+    duplicate_company_should_violate_unique_key,
+    invalid_digits_should_violate_check,
+    html_without_company_should_be_dead_lettered,
+    duplicate_html_should_be_dead_lettered,
+    html_revisions_should_keep_history_and_skip_unchanged,
+    list_snapshots_should_track_delisted_companies,
+    duplicate_filing_should_violate_unique_key,
+    upsert_filings_should_accumulate_history,
+    filings_should_create_placeholder_company_ids,
+    company_link_should_reference_both_companies,
+    company_query_should_filter_and_page,
+    filing_query_should_filter_and_stream,
+    filing_flags_should_follow_remark,
+    amendment_chains_should_resolve_effective_filings,
+    latest_receipt_date_should_track_newest_filing,
+    financial_statements_should_be_replaced_as_a_whole,
+    financial_statement_checks_should_be_upserted,
+    documents_should_be_checked_once_per_filing,
+    company_profiles_should_be_upserted,
+    periodic_report_sections_should_be_replaced_as_a_whole,
+    periodic_report_checks_should_be_upserted,
+    api_quota_should_stop_at_limit,
+    ingest_run_should_stamp_written_rows,
+    failed_ingest_run_should_record_error,
+);
+
+/// The kind of the constraint violation `result` failed with.
+fn violation_kind<T>(result: Result<T, DbError>) -> DatabaseErrorKind {
+    match result {
+        Err(DbError::Diesel(diesel::result::Error::DatabaseError(kind, _))) => kind,
+        Err(e) => panic!("Expected a constraint violation, got {e:?}"),
+        Ok(_) => panic!("Expected a constraint violation, but succeeded"),
+    }
+}
+
+fn fake_company(smes_id: &str) -> NewCompany {
+    NewCompany {
+        smes_id: smes_id.try_into().expect("Failed to create smes_id"),
+        ..Faker.fake()
+    }
+}
+
+fn fake_filing(dart_id: &str, receipt_date: &str, receipt_number: &str) -> NewFiling {
+    NewFiling {
+        dart_id: dart_id.try_into().expect("Failed to create dart_id"),
+        receipt_date: receipt_date
+            .try_into()
+            .expect("Failed to create receipt_date"),
+        receipt_number: receipt_number
+            .try_into()
+            .expect("Failed to create receipt_number"),
+        ..Faker.fake()
+    }
+}
+
+fn financial_item(dart_id: &str, fs_div: FsDiv, ord: i32, amount: i64) -> NewFinancialItem {
+    NewFinancialItem {
+        dart_id: dart_id.try_into().expect("Failed to create dart_id"),
+        business_year: 2023,
+        report_code: ReportCode::Annual,
+        fs_div,
+        statement: Statement::BalanceSheet,
+        ord,
+        account_detail: "-".to_string(),
+        account_id: format!("ifrs-full_Account{ord}"),
+        account_name: format!("계정{ord}"),
+        current_amount: Some(amount),
+        previous_amount: None,
+        before_previous_amount: None,
+        currency: "KRW".to_string(),
+        receipt_number: "20240312000736"
+            .try_into()
+            .expect("Failed to create receipt_number"),
+    }
+}
+
+fn periodic_report_key(dart_id: &str, report_code: ReportCode) -> PeriodicReportKey {
+    PeriodicReportKey {
+        dart_id: dart_id.try_into().expect("Failed to create dart_id"),
+        business_year: 2023,
+        report_code,
+    }
+}
+
+fn major_shareholder(ord: i32, name: &str, stake_at_end: f64) -> NewMajorShareholder {
+    NewMajorShareholder {
+        ord,
+        name: name.to_string(),
+        relation: Some("본인".to_string()),
+        stock_kind: Some("보통주".to_string()),
+        shares_at_start: None,
+        stake_at_start: None,
+        shares_at_end: Some(1_000),
+        stake_at_end: Some(stake_at_end),
+        remark: None,
+        settlement_date: Some("20231231".try_into().expect("Failed to create date")),
+        receipt_number: "20240312000736"
+            .try_into()
+            .expect("Failed to create receipt_number"),
+    }
+}
+
+fn new_document(receipt_number: &str, content: &str) -> NewDocument {
+    NewDocument {
+        receipt_number: receipt_number
+            .try_into()
+            .expect("Failed to create receipt_number"),
+        sha256: Some(blob::sha256_hex(content)),
+        byte_size: Some(content.len() as i64),
+    }
+}
+
+fn html_channel(htmls: Vec<NewHtml>) -> mpsc::UnboundedReceiver<NewHtml> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    for html in htmls {
+        tx.send(html).expect("Failed to send html");
+    }
+    rx
+}
+
+async fn companies_should_round_trip<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let ids = (0..10_u64).map(|i| 1000000 + i).collect::<Vec<_>>();
+    let mut inserted_companies = ctx.populate_companies(&ids).await;
+
+    let renamed_company = NewCompany {
+        company_name: "주식회사 루키게임즈".try_into().expect("Failed to convert"),
+        ..inserted_companies[0].clone()
+    };
+    inserted_companies[0] = renamed_company.clone();
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    db.upsert_companies(vec![renamed_company.clone()])
+        .await
+        .expect("Failed to upsert companies");
+    let mut selected_companies: Vec<_> = db
+        .get_companies()
+        .await
+        .expect("Failed to get companies")
+        .into_iter()
+        .map(NewCompany::from)
+        .collect();
+    let found = db
+        .find_companies_by_name("(주)루키게임즈")
+        .await
+        .expect("Failed to find companies");
+    // endregion: Action
+
+    // region: Assert
+    inserted_companies.sort_by_key(|c| c.smes_id.clone());
+    selected_companies.sort_by_key(|c| c.smes_id.clone());
+    assert_eq!(inserted_companies, selected_companies);
+    assert_eq!(found.len(), 1);
+    assert_eq!(NewCompany::from(found[0].clone()), renamed_company);
+    // endregion: Assert
+}
+
+async fn duplicate_company_should_violate_unique_key<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let existing = ctx.populate_companies(&[1000000]).await;
+    let batch = vec![fake_company("1000001"), existing[0].clone()];
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    let result = db.insert_companies(batch).await;
+    let smes_ids = db.get_smes_ids().await.expect("Failed to get smes_ids");
+    // endregion: Action
+
+    // region: Assert
+    assert!(matches!(
+        violation_kind(result),
+        DatabaseErrorKind::UniqueViolation
+    ));
+    // The whole batch is rolled back
+    assert_eq!(smes_ids, HashSet::from([existing[0].smes_id.clone()]));
+    // endregion: Assert
+}
+
+async fn invalid_digits_should_violate_check<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    // `From<String>` skips the validation of the types crate,
+    // so these only get caught by the database.
+    let company = NewCompany {
+        smes_id: company::SmesId::from("123".to_string()),
+        ..fake_company("1000000")
+    };
+    let company_id = CompanyId {
+        stock_code: Some(company::StockCode::from("12345a".to_string())),
+        ..Faker.fake()
+    };
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    let company_result = db.insert_companies(vec![company]).await;
+    let company_id_result = db.upsert_company_ids(vec![company_id]).await;
+    // endregion: Action
+
+    // region: Assert
+    assert!(matches!(
+        violation_kind(company_result),
+        DatabaseErrorKind::CheckViolation
+    ));
+    assert!(matches!(
+        violation_kind(company_id_result),
+        DatabaseErrorKind::CheckViolation
+    ));
+    assert!(db
+        .get_company_ids()
+        .await
+        .expect("Failed to get company_ids")
+        .is_empty());
+    // endregion: Assert
+}
+
+async fn html_without_company_should_be_dead_lettered<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    ctx.populate_companies(&[1000000]).await;
+    let orphan = NewHtml {
+        smes_id: "2000000".try_into().expect("Failed to create smes_id"),
+        ..Faker.fake()
+    };
+    let html = NewHtml {
+        smes_id: "1000000".try_into().expect("Failed to create smes_id"),
+        ..Faker.fake()
+    };
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    db.insert_html_channel(html_channel(vec![orphan.clone(), html.clone()]))
+        .await
+        .expect("Failed to insert htmls");
+    db.upsert_html_channel(html_channel(vec![orphan.clone()]))
+        .await
+        .expect("Failed to upsert htmls");
+    let dead_letters = db
+        .select_html_dead_letters()
+        .await
+        .expect("Failed to select dead letters");
+    let selected: Vec<_> = db
+        .select_htmls()
+        .await
+        .expect("Failed to select htmls")
+        .into_iter()
+        .map(NewHtml::from)
+        .collect();
+    // endregion: Action
+
+    // region: Assert
+    // The rest of the batch is stored despite the rejected page
+    assert_eq!(selected, vec![html]);
+    assert_eq!(dead_letters.len(), 2);
+    for dead_letter in &dead_letters {
+        assert_eq!(dead_letter.smes_id, orphan.smes_id);
+        assert_eq!(dead_letter.html_content, orphan.html_content);
+        assert!(!dead_letter.error.is_empty());
+    }
+    // The page of the rejected insert isn't stored as a revision either
+    assert!(db
+        .select_html_revisions(orphan.smes_id.as_ref().as_str())
+        .await
+        .expect("Failed to select revisions")
+        .is_empty());
+    // endregion: Assert
+}
+
+async fn duplicate_html_should_be_dead_lettered<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let htmls = ctx.populate_htmls(&[1000000]).await;
+    let updated_html = NewHtml {
+        html_content: "<html><body>updated</body></html>"
+            .try_into()
+            .expect("Failed to convert"),
+        ..htmls[0].clone()
+    };
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    db.insert_html_channel(html_channel(vec![updated_html.clone()]))
+        .await
+        .expect("Failed to insert htmls");
+    let dead_letters = db
+        .select_html_dead_letters()
+        .await
+        .expect("Failed to select dead letters");
+    let revisions = db
+        .select_html_revisions(updated_html.smes_id.as_ref().as_str())
+        .await
+        .expect("Failed to select revisions");
+    db.upsert_html_channel(html_channel(vec![updated_html.clone()]))
+        .await
+        .expect("Failed to upsert htmls");
+    let selected = db
+        .select_html(updated_html.smes_id.as_ref().as_str())
+        .await
+        .expect("Failed to select html");
+    // endregion: Action
+
+    // region: Assert
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].smes_id, updated_html.smes_id);
+    assert_eq!(dead_letters[0].html_content, updated_html.html_content);
+    // The revision written before the violation is rolled back with the page
+    assert_eq!(revisions.len(), 1);
+    assert_eq!(selected.map(NewHtml::from), Some(updated_html));
+    // endregion: Assert
+}
+
+async fn html_revisions_should_keep_history_and_skip_unchanged<D: Db, C: TestContext<D>>(
+    mut ctx: C,
+) {
+    // region: Arrange
+    let htmls = ctx.populate_htmls(&[1000000]).await;
+    let first = htmls[0].clone();
+    let second = NewHtml {
+        html_content: "<html><body>second</body></html>"
+            .try_into()
+            .expect("Failed to convert"),
+        ..first.clone()
+    };
+    let smes_id = first.smes_id.as_ref().as_str();
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    let inserted = db
+        .select_html(smes_id)
+        .await
+        .expect("Failed to select html")
+        .expect("Html not found");
+    db.upsert_html_channel(html_channel(vec![first.clone()]))
+        .await
+        .expect("Failed to upsert htmls");
+    let unchanged = db
+        .select_html(smes_id)
+        .await
+        .expect("Failed to select html")
+        .expect("Html not found");
+    let unchanged_revisions = db
+        .select_html_revisions(smes_id)
+        .await
+        .expect("Failed to select revisions");
+
+    db.upsert_html_channel(html_channel(vec![second.clone()]))
+        .await
+        .expect("Failed to upsert htmls");
+    let changed = db
+        .select_html(smes_id)
+        .await
+        .expect("Failed to select html");
+
+    db.upsert_html_channel(html_channel(vec![first.clone()]))
+        .await
+        .expect("Failed to upsert htmls");
+    let reverted = db
+        .select_html(smes_id)
+        .await
+        .expect("Failed to select html");
+    let revisions = db
+        .select_html_revisions(smes_id)
+        .await
+        .expect("Failed to select revisions");
+    // endregion: Action
+
+    // region: Assert
+    assert_eq!(unchanged_revisions.len(), 1);
+    assert_eq!(unchanged.updated_at, inserted.updated_at);
+    assert_eq!(changed.map(NewHtml::from), Some(second.clone()));
+    assert_eq!(reverted.map(NewHtml::from), Some(first.clone()));
+
+    let contents: Vec<_> = revisions
+        .iter()
+        .map(|revision| revision.html_content.clone())
+        .collect();
+    // The first page is fetched again after the second, so it's the latest revision
+    assert_eq!(contents, vec![second.html_content, first.html_content]);
+    for revision in &revisions {
+        assert_eq!(
+            revision.sha256,
+            blob::sha256_hex(revision.html_content.as_ref().as_str())
+        );
+    }
+    // endregion: Assert
+}
+
+async fn list_snapshots_should_track_delisted_companies<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let ids = (0..3_u64).map(|i| 1000000 + i).collect::<Vec<_>>();
+    let companies = ctx.populate_companies(&ids).await;
+    let all_ids: HashSet<_> = companies.iter().map(|c| c.smes_id.clone()).collect();
+    let delisted_id = companies[0].smes_id.clone();
+    let mut remaining_ids = all_ids.clone();
+    remaining_ids.remove(&delisted_id);
+    let unknown_id: company::SmesId = "2000000".try_into().expect("Failed to create smes_id");
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    let first = db
+        .insert_list_snapshot(all_ids)
+        .await
+        .expect("Failed to insert snapshot");
+    let second = db
+        .insert_list_snapshot(remaining_ids)
+        .await
+        .expect("Failed to insert snapshot");
+    let unknown_result = db.insert_list_snapshot(HashSet::from([unknown_id])).await;
+    let snapshots = db
+        .get_list_snapshots()
+        .await
+        .expect("Failed to get snapshots");
+    let diff = db
+        .get_list_snapshot_diff(first.snapshot_id, second.snapshot_id)
+        .await
+        .expect("Failed to get snapshot diff");
+    let delisted: Vec<_> = db
+        .get_companies()
+        .await
+        .expect("Failed to get companies")
+        .into_iter()
+        .filter(|c| c.delisted_at.is_some())
+        .map(|c| c.smes_id)
+        .collect();
+    // endregion: Action
+
+    // region: Assert
+    assert!(matches!(
+        violation_kind(unknown_result),
+        DatabaseErrorKind::ForeignKeyViolation
+    ));
+    assert_eq!(snapshots, vec![first, second]);
+    assert_eq!(diff.removed, HashSet::from([delisted_id.clone()]));
+    assert!(diff.added.is_empty());
+    assert_eq!(delisted, vec![delisted_id]);
+    // endregion: Assert
+}
+
+async fn duplicate_filing_should_violate_unique_key<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let filings = ctx.populate_filings(&[10000000, 10000001]).await;
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    let result = db.insert_filings(vec![filings[0].clone()]).await;
+    let selected = db.get_filings().await.expect("Failed to get filings");
+    // endregion: Action
+
+    // region: Assert
+    assert!(matches!(
+        violation_kind(result),
+        DatabaseErrorKind::UniqueViolation
+    ));
+    assert_eq!(selected.len(), filings.len());
+    // endregion: Assert
+}
+
+async fn upsert_filings_should_accumulate_history<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let first = fake_filing("10000000", "20240101", "20240101000001");
+    let db = ctx.db();
+    db.insert_filings(vec![first.clone()])
+        .await
+        .expect("Failed to insert filings");
+
+    let second = fake_filing("10000000", "20240102", "20240102000001");
+    let corrected = NewFiling {
+        remark: "정".to_string().into(),
+        ..first.clone()
+    };
+    // endregion: Arrange
+
+    // region: Action
+    // The same receipt number twice in one call, the last one wins
+    db.upsert_filings(vec![first.clone(), second.clone(), corrected.clone()])
+        .await
+        .expect("Failed to upsert filings");
+    db.upsert_filings(vec![second.clone()])
+        .await
+        .expect("Failed to upsert filings");
+    let mut selected: Vec<NewFiling> = db
+        .get_filings()
+        .await
+        .expect("Failed to get filings")
+        .into_iter()
+        .map(NewFiling::from)
+        .collect();
+    // endregion: Action
+
+    // region: Assert
+    selected.sort_by_key(|f| f.receipt_number.clone());
+    assert_eq!(selected, vec![corrected, second]);
+    // endregion: Assert
+}
+
+async fn filings_should_create_placeholder_company_ids<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    // Filed by a shareholder, so the company is named after the company it was listed with
+    let older = NewFiling {
+        filer_name: "국민연금공단".to_string().into(),
+        company_name: Some(
+            "주식회사 루키게임즈"
+                .try_into()
+                .expect("Failed to create company_name"),
+        ),
+        ..fake_filing("10000000", "20240101", "20240101000001")
+    };
+    let newer = NewFiling {
+        filer_name: "루키게임즈".to_string().into(),
+        ..fake_filing("10000000", "20240102", "20240102000001")
+    };
+    // Unlisted, so without a stock code
+    let company_id = CompanyId {
+        dart_id: "10000000".try_into().expect("Failed to create dart_id"),
+        company_name: "루키게임즈"
+            .try_into()
+            .expect("Failed to create company_name"),
+        stock_code: None,
+        ..Faker.fake()
+    };
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    db.insert_filings(vec![older.clone()])
+        .await
+        .expect("Failed to insert filings");
+    db.upsert_filings(vec![newer.clone()])
+        .await
+        .expect("Failed to upsert filings");
+    let placeholder = db
+        .get_company_with_filings("10000000")
+        .await
+        .expect("Failed to get company with filings")
+        .expect("Expected a placeholder company id");
+
+    db.upsert_company_ids(vec![company_id.clone()])
+        .await
+        .expect("Failed to upsert company_ids");
+    let completed = db
+        .get_company_with_filings("10000000")
+        .await
+        .expect("Failed to get company with filings")
+        .expect("Expected a company id");
+    let missing = db
+        .get_company_with_filings("20000000")
+        .await
+        .expect("Failed to get company with filings");
+    // endregion: Action
+
+    // region: Assert
+    assert!(placeholder.company_id.is_placeholder());
+    assert_eq!(
+        Some(&placeholder.company_id.company_name),
+        older.company_name.as_ref()
+    );
+    assert_eq!(placeholder.company_id.stock_code, None);
+    assert_eq!(
+        db.find_company_ids_by_name("(주)루키게임즈")
+            .await
+            .expect("Failed to find company_ids")
+            .len(),
+        1
+    );
+
+    assert_eq!(completed.company_id, company_id);
+    let receipt_numbers: Vec<_> = completed
+        .filings
+        .into_iter()
+        .map(|filing| filing.receipt_number)
+        .collect();
+    assert_eq!(
+        receipt_numbers,
+        vec![newer.receipt_number, older.receipt_number]
+    );
+    assert!(missing.is_none());
+    // endregion: Assert
+}
+
+async fn company_link_should_reference_both_companies<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let htmls = ctx.populate_htmls(&[1000000]).await;
+    let company_ids = ctx.populate_company_ids(&[10000000, 10000001]).await;
+    let filings = ctx.populate_filings(&[10000000]).await;
+
+    let link = NewCompanyLink {
+        smes_id: htmls[0].smes_id.clone(),
+        dart_id: company_ids[0].dart_id.clone(),
+        business_registration_number: None,
+        corporation_registration_number: None,
+        match_method: MatchMethod::NameAndRepresentative,
+        confidence: 0.8,
+    };
+    // Matched by name before the registration number matched the other company
+    let stale_link = NewCompanyLink {
+        dart_id: company_ids[1].dart_id.clone(),
+        ..link.clone()
+    };
+    let dangling_link = NewCompanyLink {
+        dart_id: "10000002".try_into().expect("Failed to create dart_id"),
+        ..link.clone()
+    };
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    db.replace_company_links(vec![stale_link])
+        .await
+        .expect("Failed to replace company links");
+    let dangling_result = db.replace_company_links(vec![dangling_link]).await;
+    db.replace_company_links(vec![link.clone()])
+        .await
+        .expect("Failed to replace company links");
+    let linked = db
+        .get_linked_company(htmls[0].smes_id.as_ref().as_str())
+        .await
+        .expect("Failed to get linked company")
+        .expect("Company should exist");
+    // endregion: Action
+
+    // region: Assert
+    assert!(matches!(
+        violation_kind(dangling_result),
+        DatabaseErrorKind::ForeignKeyViolation
+    ));
+    assert_eq!(linked.links.len(), 1);
+    assert_eq!(NewCompanyLink::from(linked.links[0].clone()), link);
+    assert_eq!(linked.html.map(NewHtml::from), Some(htmls[0].clone()));
+    assert_eq!(linked.filings.len(), filings.len());
+    assert_eq!(
+        db.get_company_ids()
+            .await
+            .expect("Failed to get company_ids"),
+        company_ids
+    );
+    // endregion: Assert
+}
+
+async fn company_query_should_filter_and_page<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let company = |smes_id: &str, industry_code: &str, address: &str, name: &str| NewCompany {
+        industry_code: industry_code
+            .try_into()
+            .expect("Failed to create industry_code"),
+        headquarters_address: address.try_into().expect("Failed to create address"),
+        company_name: name.try_into().expect("Failed to create name"),
+        ..fake_company(smes_id)
+    };
+    let companies = vec![
+        company("1000000", "10000", "서울특별시 강남구", "(주)루키게임즈"),
+        company("1000001", "10000", "서울특별시 마포구", "루키소프트"),
+        company(
+            "1000002",
+            "20000",
+            "서울특별시 종로구",
+            "주식회사 루키_%테크",
+        ),
+        company("1000003", "10000", "경기도 성남시", "루키테크"),
+        company("1000004", "10000", "서울특별시 중구", "베테랑"),
+    ];
+    let db = ctx.db();
+    db.insert_companies(companies)
+        .await
+        .expect("Failed to insert companies");
+    let html = NewHtml {
+        smes_id: "1000001".try_into().expect("Failed to create smes_id"),
+        ..Faker.fake()
+    };
+    db.insert_html_channel(html_channel(vec![html]))
+        .await
+        .expect("Failed to insert htmls");
+    // endregion: Arrange
+
+    // region: Action
+    let query = CompanyQuery::new()
+        .industry_code("10000".try_into().expect("Failed to create industry_code"))
+        .region("서울")
+        .name_prefix("주식회사 루키")
+        .page_size(1);
+    let first = db
+        .query_companies(&query)
+        .await
+        .expect("Failed to query companies");
+    let second = db
+        .query_companies(
+            &query
+                .clone()
+                .after(first.next.clone().expect("No next page")),
+        )
+        .await
+        .expect("Failed to query companies");
+    let with_html = db
+        .query_companies(&CompanyQuery::new().has_html(true))
+        .await
+        .expect("Failed to query companies");
+    let without_html = db
+        .query_companies(&CompanyQuery::new().has_html(false))
+        .await
+        .expect("Failed to query companies");
+    // `_` and `%` in the prefix are matched literally, rather than as wildcards
+    let escaped = db
+        .query_companies(&CompanyQuery::new().name_prefix("루키_%"))
+        .await
+        .expect("Failed to query companies");
+    // endregion: Action
+
+    // region: Assert
+    let ids = |companies: &[crate::model::smes::Company]| -> Vec<String> {
+        companies
+            .iter()
+            .map(|c| c.smes_id.as_ref().to_string())
+            .collect()
+    };
+    assert_eq!(ids(&first.items), vec!["1000000"]);
+    assert_eq!(ids(&second.items), vec!["1000001"]);
+    // A full page can't tell whether it was the last, so one more, empty, page is fetched
+    let third = db
+        .query_companies(&query.after(second.next.expect("No next page")))
+        .await
+        .expect("Failed to query companies");
+    assert!(third.items.is_empty());
+    assert_eq!(third.next, None);
+
+    assert_eq!(ids(&with_html.items), vec!["1000001"]);
+    assert_eq!(
+        ids(&without_html.items),
+        vec!["1000000", "1000002", "1000003", "1000004"]
+    );
+    assert_eq!(ids(&escaped.items), vec!["1000002"]);
+    // endregion: Assert
+}
+
+async fn filing_query_should_filter_and_stream<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let filings = vec![
+        fake_filing("10000000", "20240101", "20240101000001"),
+        fake_filing("10000001", "20240201", "20240201000001"),
+        fake_filing("10000002", "20240301", "20240301000001"),
+        fake_filing("10000002", "20240401", "20240401000001"),
+        fake_filing("10000004", "20240501", "20240501000001"),
+    ];
+    let db = ctx.db();
+    db.insert_filings(filings)
+        .await
+        .expect("Failed to insert filings");
+    // endregion: Arrange
+
+    // region: Action
+    let query = FilingQuery::new()
+        .receipt_date_from("20240201".try_into().expect("Failed to create date"))
+        .receipt_date_to("20240401".try_into().expect("Failed to create date"))
+        .page_size(2);
+    let mut streamed = Vec::new();
+    let mut stream = query.stream(db);
+    while let Some(filing) = stream.next().await {
+        streamed.push(filing.expect("Failed to stream filings"));
+    }
+    drop(stream);
+    let by_dart_id = db
+        .query_filings(
+            &FilingQuery::new().dart_id("10000002".try_into().expect("Failed to create dart_id")),
+        )
+        .await
+        .expect("Failed to query filings");
+    // endregion: Action
+
+    // region: Assert
+    let receipt_numbers: Vec<String> = streamed
+        .iter()
+        .map(|f| f.receipt_number.as_ref().to_string())
+        .collect();
+    assert_eq!(
+        receipt_numbers,
+        vec!["20240201000001", "20240301000001", "20240401000001"]
+    );
+    // A company can have any number of filings
+    assert_eq!(by_dart_id.items.len(), 2);
+    assert_eq!(by_dart_id.next, None);
+    // endregion: Assert
+}
+
+async fn filing_flags_should_follow_remark<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let filing = |receipt_number: &str, remark: &str| NewFiling {
+        remark: types::filing::Remark::new(remark),
+        ..fake_filing("10000000", "20240101", receipt_number)
+    };
+    let db = ctx.db();
+    db.upsert_filings(vec![
+        filing("20240101000001", "유연"),
+        filing("20240101000002", "코정"),
+        filing("20240101000003", "철"),
+        filing("20240101000004", ""),
+    ])
+    .await
+    .expect("Failed to upsert filings");
+    // endregion: Arrange
+
+    // region: Action
+    let current = FilingQuery::new().is_corrected(false).is_withdrawn(false);
+    let before = db
+        .query_filings(&current)
+        .await
+        .expect("Failed to query filings");
+    // A correction of the first filing marks it in its remark
+    db.upsert_filings(vec![filing("20240101000001", "유연정")])
+        .await
+        .expect("Failed to upsert filings");
+    let after = db
+        .query_filings(&current)
+        .await
+        .expect("Failed to query filings");
+    let withdrawn = db
+        .query_filings(&FilingQuery::new().is_withdrawn(true))
+        .await
+        .expect("Failed to query filings");
+    // endregion: Action
+
+    // region: Assert
+    let receipt_numbers = |filings: &[crate::model::dart::Filing]| {
+        filings
+            .iter()
+            .map(|filing| filing.receipt_number.to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        receipt_numbers(&before.items),
+        vec!["20240101000001", "20240101000004"]
+    );
+    assert!(before.items[0].is_consolidated);
+    assert!(!before.items[1].is_consolidated);
+    assert_eq!(receipt_numbers(&after.items), vec!["20240101000004"]);
+    assert_eq!(receipt_numbers(&withdrawn.items), vec!["20240101000003"]);
+    // endregion: Assert
+}
+
+async fn amendment_chains_should_resolve_effective_filings<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let filing = |receipt_date: &str, receipt_number: &str, report_name: &str| NewFiling {
+        report_name: report_name
+            .try_into()
+            .expect("Failed to create report_name"),
+        ..fake_filing("10000000", receipt_date, receipt_number)
+    };
+    let db = ctx.db();
+    db.upsert_filings(vec![
+        filing("20240312", "20240312000001", "사업보고서 (2023.12)"),
+        filing(
+            "20240320",
+            "20240320000001",
+            "[기재정정]사업보고서 (2023.12)",
+        ),
+        filing("20240814", "20240814000001", "반기보고서 (2024.06)"),
+        fake_filing("10000001", "20240320", "20240320000002"),
+    ])
+    .await
+    .expect("Failed to upsert filings");
+    let dart_id: company::DartId = "10000000".try_into().expect("Failed to create dart_id");
+    // endregion: Arrange
+
+    // region: Action
+    let chain = db
+        .get_amendment_chain(
+            &dart_id,
+            &"20240312000001"
+                .try_into()
+                .expect("Failed to create receipt_number"),
+        )
+        .await
+        .expect("Failed to get amendment chain");
+    let effective = db
+        .get_effective_filings(&dart_id)
+        .await
+        .expect("Failed to get effective filings");
+    // endregion: Action
+
+    // region: Assert
+    let chain = chain.expect("The filing should be in a chain");
+    assert_eq!(chain.filings.len(), 2);
+    assert_eq!(chain.base_name(), "사업보고서 (2023.12)");
+    let receipt_numbers: Vec<_> = effective
+        .iter()
+        .map(|filing| filing.receipt_number.to_string())
+        .collect();
+    assert_eq!(receipt_numbers, vec!["20240320000001", "20240814000001"]);
+    // endregion: Assert
+}
+
+async fn latest_receipt_date_should_track_newest_filing<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let filings = vec![
+        fake_filing("10000000", "20240301", "20240301000001"),
+        fake_filing("10000001", "20240101", "20240101000001"),
+    ];
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    let before = db
+        .get_latest_receipt_date()
+        .await
+        .expect("Failed to get latest receipt date");
+    db.upsert_filings(filings)
+        .await
+        .expect("Failed to upsert filings");
+    let after = db
+        .get_latest_receipt_date()
+        .await
+        .expect("Failed to get latest receipt date");
+    // endregion: Action
+
+    // region: Assert
+    assert_eq!(before, None);
+    assert_eq!(
+        after,
+        Some("20240301".try_into().expect("Failed to create date"))
+    );
+    // endregion: Assert
+}
+
+async fn financial_statements_should_be_replaced_as_a_whole<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let company_id = CompanyId {
+        dart_id: "10000000".try_into().expect("Failed to create dart_id"),
+        ..Faker.fake()
+    };
+    ctx.db()
+        .insert_company_ids(vec![company_id])
+        .await
+        .expect("Failed to insert company ids");
+    let consolidated = vec![
+        financial_item("10000000", FsDiv::Consolidated, 1, 100),
+        financial_item("10000000", FsDiv::Consolidated, 2, 200),
+        financial_item("10000000", FsDiv::Consolidated, 3, 300),
+    ];
+    let separate = financial_item("10000000", FsDiv::Separate, 1, 10);
+    // The corrected statement drops the third account and changes the second
+    let corrected = vec![
+        financial_item("10000000", FsDiv::Consolidated, 1, 100),
+        financial_item("10000000", FsDiv::Consolidated, 2, 250),
+    ];
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    db.replace_financial_statements([consolidated, vec![separate.clone()]].concat())
+        .await
+        .expect("Failed to replace financial statements");
+    db.replace_financial_statements(corrected.clone())
+        .await
+        .expect("Failed to replace financial statements");
+    let items = db
+        .get_financial_items("10000000")
+        .await
+        .expect("Failed to get financial items");
+    let mut receipts = db
+        .get_financial_statement_receipts()
+        .await
+        .expect("Failed to get financial statement receipts");
+    receipts.sort();
+    let without_company = db
+        .replace_financial_statements(vec![financial_item(
+            "10000001",
+            FsDiv::Consolidated,
+            1,
+            100,
+        )])
+        .await;
+    // endregion: Action
+
+    // region: Assert
+    let items: Vec<NewFinancialItem> = items.into_iter().map(NewFinancialItem::from).collect();
+    assert_eq!(items, [corrected.clone(), vec![separate.clone()]].concat());
+    assert_eq!(
+        receipts,
+        vec![
+            (
+                corrected[0].statement_key(),
+                corrected[0].receipt_number.clone()
+            ),
+            (separate.statement_key(), separate.receipt_number.clone()),
+        ]
+    );
+    assert!(matches!(
+        violation_kind(without_company),
+        DatabaseErrorKind::ForeignKeyViolation
+    ));
+    // endregion: Assert
+}
+
+async fn financial_statement_checks_should_be_upserted<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let company_id = CompanyId {
+        dart_id: "10000000".try_into().expect("Failed to create dart_id"),
+        ..Faker.fake()
+    };
+    ctx.db()
+        .insert_company_ids(vec![company_id])
+        .await
+        .expect("Failed to insert company ids");
+    let key = financial_item("10000000", FsDiv::Consolidated, 1, 100).statement_key();
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    // OpenDART had no items at first
+    db.upsert_financial_statement_check(NewFinancialStatementCheck::new(key.clone(), 0, 0))
+        .await
+        .expect("Failed to upsert financial statement check");
+    // and later an incomplete statement
+    db.upsert_financial_statement_check(NewFinancialStatementCheck::new(key.clone(), 2, 1))
+        .await
+        .expect("Failed to upsert financial statement check");
+    let checks = db
+        .get_financial_statement_checks()
+        .await
+        .expect("Failed to get financial statement checks");
+    let without_company = db
+        .upsert_financial_statement_check(NewFinancialStatementCheck::new(
+            FinancialStatementKey {
+                dart_id: "10000001".try_into().expect("Failed to create dart_id"),
+                ..key.clone()
+            },
+            0,
+            0,
+        ))
+        .await;
+    let negative_count = db
+        .upsert_financial_statement_check(NewFinancialStatementCheck::new(key.clone(), -1, 0))
+        .await;
+    // endregion: Action
+
+    // region: Assert
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].statement_key(), key);
+    assert_eq!((checks[0].item_count, checks[0].skipped_count), (2, 1));
+    assert!(matches!(
+        violation_kind(without_company),
+        DatabaseErrorKind::ForeignKeyViolation
+    ));
+    assert!(matches!(
+        violation_kind(negative_count),
+        DatabaseErrorKind::CheckViolation
+    ));
+    // endregion: Assert
+}
+
+async fn documents_should_be_checked_once_per_filing<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let filings = vec![
+        fake_filing("10000000", "20240101", "20240101000001"),
+        fake_filing("10000000", "20240102", "20240102000001"),
+        fake_filing("10000000", "20240103", "20240103000001"),
+    ];
+    ctx.db()
+        .upsert_filings(filings)
+        .await
+        .expect("Failed to upsert filings");
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    let before = db
+        .get_receipt_numbers_without_document(10)
+        .await
+        .expect("Failed to get receipt numbers without document");
+    db.upsert_document(new_document("20240103000001", "first"))
+        .await
+        .expect("Failed to upsert document");
+    // A re-download replaces the previous archive
+    db.upsert_document(new_document("20240103000001", "second"))
+        .await
+        .expect("Failed to upsert document");
+    // OpenDART has no document for the filing, which isn't asked for again either
+    db.upsert_document(NewDocument::missing(
+        "20240102000001"
+            .try_into()
+            .expect("Failed to create receipt_number"),
+    ))
+    .await
+    .expect("Failed to upsert document");
+    let after = db
+        .get_receipt_numbers_without_document(10)
+        .await
+        .expect("Failed to get receipt numbers without document");
+    let limited = db
+        .get_receipt_numbers_without_document(1)
+        .await
+        .expect("Failed to get receipt numbers without document");
+    let document = db
+        .get_document("20240103000001")
+        .await
+        .expect("Failed to get document");
+    let without_document = db
+        .get_document("20240102000001")
+        .await
+        .expect("Failed to get document");
+    let missing = db
+        .get_document("20240101000001")
+        .await
+        .expect("Failed to get document");
+    let without_filing = db
+        .upsert_document(new_document("20240104000001", "first"))
+        .await;
+    let invalid_hash = db
+        .upsert_document(NewDocument {
+            sha256: Some("not a hash".to_string()),
+            ..new_document("20240101000001", "first")
+        })
+        .await;
+    let without_byte_size = db
+        .upsert_document(NewDocument {
+            byte_size: None,
+            ..new_document("20240101000001", "first")
+        })
+        .await;
+    // endregion: Action
+
+    // region: Assert
+    let receipt_numbers = |numbers: Vec<types::filing::ReceiptNumber>| {
+        numbers
+            .iter()
+            .map(|number| number.to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        receipt_numbers(before),
+        vec!["20240103000001", "20240102000001", "20240101000001"]
+    );
+    assert_eq!(receipt_numbers(after), vec!["20240101000001"]);
+    assert_eq!(receipt_numbers(limited), vec!["20240101000001"]);
+    let document = document.expect("Document should be stored");
+    assert_eq!(
+        NewDocument::from(document),
+        new_document("20240103000001", "second")
+    );
+    let without_document = without_document.expect("The missing document should be recorded");
+    assert_eq!(
+        (without_document.sha256, without_document.byte_size),
+        (None, None)
+    );
+    assert!(missing.is_none());
+    assert!(matches!(
+        violation_kind(without_filing),
+        DatabaseErrorKind::ForeignKeyViolation
+    ));
+    assert!(matches!(
+        violation_kind(invalid_hash),
+        DatabaseErrorKind::CheckViolation
+    ));
+    assert!(matches!(
+        violation_kind(without_byte_size),
+        DatabaseErrorKind::CheckViolation
+    ));
+    // endregion: Assert
+}
+
+async fn company_profiles_should_be_upserted<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let company_id = CompanyId {
+        dart_id: "10000000".try_into().expect("Failed to create dart_id"),
+        ..Faker.fake()
+    };
+    ctx.db()
+        .insert_company_ids(vec![company_id])
+        .await
+        .expect("Failed to insert company ids");
+    let profile = NewCompanyProfile {
+        dart_id: "10000000".try_into().expect("Failed to create dart_id"),
+        corporation_registration_number: Some(
+            "1301110006246"
+                .try_into()
+                .expect("Failed to create corporation_registration_number"),
+        ),
+        business_registration_number: None,
+        representative_name: Some("한종희".try_into().expect("Failed to create name")),
+        headquarters_address: None,
+        industry_code: Some("26410".try_into().expect("Failed to create industry_code")),
+        established_date: Some("19690113".try_into().expect("Failed to create date")),
+        fiscal_month: Some(12),
+    };
+    let updated = NewCompanyProfile {
+        business_registration_number: Some(
+            "1248100998"
+                .try_into()
+                .expect("Failed to create business_registration_number"),
+        ),
+        ..profile.clone()
+    };
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    db.upsert_company_profiles(vec![profile])
+        .await
+        .expect("Failed to upsert company profiles");
+    db.upsert_company_profiles(vec![updated.clone()])
+        .await
+        .expect("Failed to upsert company profiles");
+    let selected = db
+        .get_company_profile("10000000")
+        .await
+        .expect("Failed to get company profile");
+    let all = db
+        .get_company_profiles()
+        .await
+        .expect("Failed to get company profiles");
+    let without_company = db
+        .upsert_company_profiles(vec![NewCompanyProfile {
+            dart_id: "10000001".try_into().expect("Failed to create dart_id"),
+            ..updated.clone()
+        }])
+        .await;
+    let invalid_month = db
+        .upsert_company_profiles(vec![NewCompanyProfile {
+            fiscal_month: Some(13),
+            ..updated.clone()
+        }])
+        .await;
+    // endregion: Action
+
+    // region: Assert
+    let selected = selected.expect("The profile should be stored");
+    assert_eq!(NewCompanyProfile::from(selected), updated);
+    assert_eq!(all.len(), 1);
+    assert!(matches!(
+        violation_kind(without_company),
+        DatabaseErrorKind::ForeignKeyViolation
+    ));
+    assert!(matches!(
+        violation_kind(invalid_month),
+        DatabaseErrorKind::CheckViolation
+    ));
+    // endregion: Assert
+}
+
+async fn periodic_report_sections_should_be_replaced_as_a_whole<D: Db, C: TestContext<D>>(
+    mut ctx: C,
+) {
+    // region: Arrange
+    let company_id = CompanyId {
+        dart_id: "10000000".try_into().expect("Failed to create dart_id"),
+        ..Faker.fake()
+    };
+    ctx.db()
+        .insert_company_ids(vec![company_id])
+        .await
+        .expect("Failed to insert company ids");
+    let annual = periodic_report_key("10000000", ReportCode::Annual);
+    let half_year = periodic_report_key("10000000", ReportCode::HalfYear);
+    // The corrected report drops the second shareholder and changes the first
+    let corrected = vec![major_shareholder(1, "홍길동", 21.5)];
+    let executive = NewExecutive {
+        ord: 1,
+        name: "홍길동".to_string(),
+        gender: Some(Gender::Male),
+        birth_month: Some("1968년 11월".to_string()),
+        position: Some("대표이사".to_string()),
+        registration: Some("사내이사".to_string()),
+        is_full_time: Some(true),
+        responsibility: None,
+        main_career: None,
+        relation_to_major_shareholder: Some("본인".to_string()),
+        tenure: None,
+        term_end: None,
+        settlement_date: None,
+        receipt_number: "20240312000736"
+            .try_into()
+            .expect("Failed to create receipt_number"),
+    };
+    let employee = NewEmployee {
+        ord: 1,
+        business_segment: Some("전사".to_string()),
+        gender: Some(Gender::Female),
+        regular_count: Some(90),
+        contract_count: Some(10),
+        total_count: Some(100),
+        average_tenure: Some("12.5".to_string()),
+        annual_salary_total: Some(5_000_000_000),
+        average_salary: Some(50_000_000),
+        remark: None,
+        settlement_date: None,
+        receipt_number: "20240312000736"
+            .try_into()
+            .expect("Failed to create receipt_number"),
+    };
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    db.replace_major_shareholders(
+        annual.clone(),
+        vec![
+            major_shareholder(1, "홍길동", 20.0),
+            major_shareholder(2, "홍길순", 1.5),
+        ],
+    )
+    .await
+    .expect("Failed to replace major shareholders");
+    db.replace_major_shareholders(annual.clone(), corrected.clone())
+        .await
+        .expect("Failed to replace major shareholders");
+    db.replace_major_shareholders(
+        half_year.clone(),
+        vec![major_shareholder(1, "홍길동", 20.0)],
+    )
+    .await
+    .expect("Failed to replace major shareholders");
+    db.replace_executives(annual.clone(), vec![executive.clone()])
+        .await
+        .expect("Failed to replace executives");
+    db.replace_employees(annual.clone(), vec![employee.clone()])
+        .await
+        .expect("Failed to replace employees");
+    let major_shareholders = db
+        .get_major_shareholders("10000000")
+        .await
+        .expect("Failed to get major shareholders");
+    let executives = db
+        .get_executives("10000000")
+        .await
+        .expect("Failed to get executives");
+    let employees = db
+        .get_employees("10000000")
+        .await
+        .expect("Failed to get employees");
+    let mut major_shareholder_receipts = db
+        .get_periodic_report_receipts(PeriodicReportSection::MajorShareholder)
+        .await
+        .expect("Failed to get periodic report receipts");
+    major_shareholder_receipts.sort();
+    let employee_receipts = db
+        .get_periodic_report_receipts(PeriodicReportSection::Employee)
+        .await
+        .expect("Failed to get periodic report receipts");
+    let without_company = db
+        .replace_executives(
+            periodic_report_key("10000001", ReportCode::Annual),
+            vec![executive.clone()],
+        )
+        .await;
+    let duplicate_ord = db
+        .replace_employees(annual.clone(), vec![employee.clone(), employee.clone()])
+        .await;
+    // endregion: Action
+
+    // region: Assert
+    let major_shareholders: Vec<NewMajorShareholder> = major_shareholders
+        .into_iter()
+        .filter(|row| row.report_code == ReportCode::Annual)
+        .map(NewMajorShareholder::from)
+        .collect();
+    assert_eq!(major_shareholders, corrected);
+    assert_eq!(
+        executives
+            .into_iter()
+            .map(NewExecutive::from)
+            .collect::<Vec<_>>(),
+        vec![executive]
+    );
+    assert_eq!(
+        employees
+            .into_iter()
+            .map(NewEmployee::from)
+            .collect::<Vec<_>>(),
+        vec![employee.clone()]
+    );
+    let receipt_number = employee.receipt_number.clone();
+    assert_eq!(
+        major_shareholder_receipts,
+        vec![
+            (annual.clone(), receipt_number.clone()),
+            (half_year, receipt_number.clone()),
+        ]
+    );
+    assert_eq!(employee_receipts, vec![(annual.clone(), receipt_number)]);
+    assert!(matches!(
+        violation_kind(without_company),
+        DatabaseErrorKind::ForeignKeyViolation
+    ));
+    assert!(matches!(
+        violation_kind(duplicate_ord),
+        DatabaseErrorKind::UniqueViolation
+    ));
+    // A failed replacement keeps the stored section
+    let employees = db
+        .get_employees("10000000")
+        .await
+        .expect("Failed to get employees");
+    assert_eq!(employees.len(), 1);
+    // endregion: Assert
+}
+
+async fn periodic_report_checks_should_be_upserted<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let company_id = CompanyId {
+        dart_id: "10000000".try_into().expect("Failed to create dart_id"),
+        ..Faker.fake()
+    };
+    ctx.db()
+        .insert_company_ids(vec![company_id])
+        .await
+        .expect("Failed to insert company ids");
+    let annual = periodic_report_key("10000000", ReportCode::Annual);
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    // Only the total row of the major shareholders at first
+    db.upsert_periodic_report_check(NewPeriodicReportCheck::new(
+        annual.clone(),
+        PeriodicReportSection::MajorShareholder,
+        0,
+        0,
+    ))
+    .await
+    .expect("Failed to upsert periodic report check");
+    db.upsert_periodic_report_check(NewPeriodicReportCheck::new(
+        annual.clone(),
+        PeriodicReportSection::MajorShareholder,
+        3,
+        0,
+    ))
+    .await
+    .expect("Failed to upsert periodic report check");
+    db.upsert_periodic_report_check(NewPeriodicReportCheck::new(
+        annual.clone(),
+        PeriodicReportSection::Employee,
+        0,
+        0,
+    ))
+    .await
+    .expect("Failed to upsert periodic report check");
+    let mut checks = db
+        .get_periodic_report_checks()
+        .await
+        .expect("Failed to get periodic report checks");
+    checks.sort_by_key(|check| check.section);
+    let without_company = db
+        .upsert_periodic_report_check(NewPeriodicReportCheck::new(
+            periodic_report_key("10000001", ReportCode::Annual),
+            PeriodicReportSection::Executive,
+            0,
+            0,
+        ))
+        .await;
+    // endregion: Action
+
+    // region: Assert
+    let checks: Vec<_> = checks
+        .iter()
+        .map(|check| (check.report_key(), check.section, check.row_count))
+        .collect();
+    assert_eq!(
+        checks,
+        vec![
+            (annual.clone(), PeriodicReportSection::Employee, 0),
+            (annual, PeriodicReportSection::MajorShareholder, 3),
+        ]
+    );
+    assert!(matches!(
+        violation_kind(without_company),
+        DatabaseErrorKind::ForeignKeyViolation
+    ));
+    // endregion: Assert
+}
+
+async fn api_quota_should_stop_at_limit<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let day = NaiveDate::from_ymd_opt(2024, 11, 28).expect("Failed to create date");
+    let next_day = day.succ_opt().expect("Failed to create date");
+    let key_id = "0123456789abcdef";
+    let other_key_id = "fedcba9876543210";
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    let mut reserved = vec![];
+    for _ in 0..3 {
+        reserved.push(
+            db.reserve_api_request(key_id, day, 2)
+                .await
+                .expect("Failed to reserve request"),
+        );
+    }
+    let next_day_reserved = db
+        .reserve_api_request(key_id, next_day, 2)
+        .await
+        .expect("Failed to reserve request");
+    db.exhaust_api_quota(other_key_id, day)
+        .await
+        .expect("Failed to exhaust quota");
+    let exhausted_reserved = db
+        .reserve_api_request(other_key_id, day, 2)
+        .await
+        .expect("Failed to reserve request");
+    let quotas = db.get_api_quotas(day).await.expect("Failed to get quotas");
+    let invalid_key = db.reserve_api_request("KEY", day, 2).await;
+    // endregion: Action
+
+    // region: Assert
+    assert_eq!(reserved, vec![true, true, false]);
+    assert!(next_day_reserved);
+    assert!(!exhausted_reserved);
+    let quotas: Vec<_> = quotas
+        .into_iter()
+        .map(|quota| (quota.key_id, quota.request_count, quota.exhausted))
+        .collect();
+    assert_eq!(
+        quotas,
+        vec![
+            (key_id.to_string(), 2, false),
+            (other_key_id.to_string(), 0, true),
+        ]
+    );
+    assert!(matches!(
+        violation_kind(invalid_key),
+        DatabaseErrorKind::CheckViolation
+    ));
+    // endregion: Assert
+}
+
+async fn ingest_run_should_stamp_written_rows<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Arrange
+    let companies = ctx.populate_companies(&[1000000, 1000001]).await;
+    let html = NewHtml {
+        smes_id: companies[0].smes_id.clone(),
+        ..Faker.fake()
+    };
+    let filing = fake_filing("10000000", "20240101", "20240101000001");
+    let counts = IngestCounts {
+        inserted: 2,
+        updated: 1,
+        unchanged: 0,
+    };
+    // endregion: Arrange
+
+    // region: Action
+    let db = ctx.db();
+    let (run, mut run_db) = db
+        .start_ingest_run(NewIngestRun {
+            runner: "conformance".to_string(),
+            git_rev: None,
+            config: None,
+            source_url: Some("https://opendart.fss.or.kr".to_string()),
+        })
+        .await
+        .expect("Failed to start ingest run");
+    run_db
+        .upsert_companies(vec![companies[0].clone()])
+        .await
+        .expect("Failed to upsert companies");
+    run_db
+        .upsert_html_channel(html_channel(vec![html.clone()]))
+        .await
+        .expect("Failed to upsert htmls");
+    run_db
+        .upsert_filings(vec![filing])
+        .await
+        .expect("Failed to upsert filings");
+    let finished = db
+        .finish_ingest_run(run.run_id, counts)
+        .await
+        .expect("Failed to finish ingest run");
+    // Written through the handle the run was started with, so without a run
+    db.upsert_companies(vec![companies[1].clone()])
+        .await
+        .expect("Failed to upsert companies");
+
+    let stamped_companies = db
+        .query_companies(&CompanyQuery::new().run_id(run.run_id))
+        .await
+        .expect("Failed to query companies");
+    let stamped_filings = db
+        .query_filings(&FilingQuery::new().run_id(run.run_id))
+        .await
+        .expect("Failed to query filings");
+    let selected_html = db
+        .select_html(html.smes_id.as_ref().as_str())
+        .await
+        .expect("Failed to select html")
+        .expect("Html not found");
+    let runs = db
+        .get_ingest_runs()
+        .await
+        .expect("Failed to get ingest runs");
+    // endregion: Action
+
+    // region: Assert
+    assert_eq!(run.finished_at, None);
+    assert_eq!(
+        run.source_url.as_deref(),
+        Some("https://opendart.fss.or.kr")
+    );
+    assert_eq!(stamped_companies.items.len(), 1);
+    assert_eq!(stamped_companies.items[0].smes_id, companies[0].smes_id);
+    assert_eq!(stamped_filings.items.len(), 1);
+    assert_eq!(selected_html.run_id, Some(run.run_id));
+
+    assert!(finished.finished_at.is_some());
+    assert_eq!(finished.error, None);
+    assert_eq!(finished.inserted_count, Some(counts.inserted));
+    assert_eq!(finished.updated_count, Some(counts.updated));
+    assert_eq!(finished.unchanged_count, Some(counts.unchanged));
+    assert_eq!(runs, vec![finished]);
+    // endregion: Assert
+}
+
+async fn failed_ingest_run_should_record_error<D: Db, C: TestContext<D>>(mut ctx: C) {
+    // region: Action
+    let db = ctx.db();
+    let (run, _) = db
+        .start_ingest_run(NewIngestRun {
+            runner: "conformance".to_string(),
+            git_rev: None,
+            config: None,
+            source_url: None,
+        })
+        .await
+        .expect("Failed to start ingest run");
+    let failed = db
+        .fail_ingest_run(run.run_id, "Failed to connect".to_string())
+        .await
+        .expect("Failed to fail ingest run");
+    let runs = db
+        .get_ingest_runs()
+        .await
+        .expect("Failed to get ingest runs");
+    // endregion: Action
+
+    // region: Assert
+    assert!(failed.finished_at.is_some());
+    assert_eq!(failed.error.as_deref(), Some("Failed to connect"));
+    assert_eq!(failed.inserted_count, None);
+    assert_eq!(runs, vec![failed]);
+    // endregion: Assert
+}
@@ -1,5 +1,20 @@
+mod amendment;
+mod api_quota;
 mod company_id;
+mod company_profile;
+mod document;
 mod filing;
+mod financial_item;
+mod periodic_report;
 
+pub use amendment::{amendment_chains, AmendmentChainDb};
+pub use api_quota::ApiQuotaDb;
 pub use company_id::CompanyIdDb;
+pub use company_profile::CompanyProfileDb;
+pub use document::DocumentDb;
 pub use filing::FilingDb;
+pub use financial_item::FinancialItemDb;
+pub use periodic_report::PeriodicReportDb;
+
+pub(crate) use financial_item::group_by_statement;
+pub(crate) use periodic_report::periodic_report_receipt;
@@ -0,0 +1,211 @@
+use hashbrown::HashMap;
+use std::future::Future;
+use types::{company, filing};
+
+use crate::dart::FilingDb;
+use crate::model::dart::{AmendmentChain, Filing};
+use crate::query::{FilingQuery, KeysetQuery};
+use crate::DbError;
+
+/// Amendment chains of the filings of a company, resolved from `dart.filing`.
+///
+/// Nothing links an amendment to the filing it amends, so filings are chained by company and
+/// by report name without its amendment marker, see [`types::filing::ReportName::base_name`].
+/// Reports like `주요사항보고서` are filed again for every event under the same name,
+/// so every filing which isn't an amendment starts a new chain,
+/// and an amendment joins the newest chain of its report name received before it.
+/// Orders of the FSS to correct a report, `[정정명령부과]` and `[정정제출요구]`,
+/// aren't versions of the report and belong to no chain.
+pub trait AmendmentChainDb {
+    /// The amendment chains of the company with `dart_id`, ordered by their original filing.
+    fn get_amendment_chains(
+        &mut self,
+        dart_id: &company::DartId,
+    ) -> impl Future<Output = Result<Vec<AmendmentChain>, DbError>>;
+    /// The amendment chain the filing with `receipt_number` belongs to.
+    fn get_amendment_chain(
+        &mut self,
+        dart_id: &company::DartId,
+        receipt_number: &filing::ReceiptNumber,
+    ) -> impl Future<Output = Result<Option<AmendmentChain>, DbError>>;
+    /// The latest effective version of every report of the company with `dart_id`,
+    /// which is what figures should be taken from.
+    fn get_effective_filings(
+        &mut self,
+        dart_id: &company::DartId,
+    ) -> impl Future<Output = Result<Vec<Filing>, DbError>>;
+}
+
+impl<D: FilingDb> AmendmentChainDb for D {
+    #[tracing::instrument(skip(self))]
+    async fn get_amendment_chains(
+        &mut self,
+        dart_id: &company::DartId,
+    ) -> Result<Vec<AmendmentChain>, DbError> {
+        let mut filings = Vec::new();
+        let mut stream = FilingQuery::new().dart_id(dart_id.clone()).stream(self);
+        while let Some(filing) = stream.next().await {
+            filings.push(filing?);
+        }
+        Ok(amendment_chains(filings))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_amendment_chain(
+        &mut self,
+        dart_id: &company::DartId,
+        receipt_number: &filing::ReceiptNumber,
+    ) -> Result<Option<AmendmentChain>, DbError> {
+        Ok(self
+            .get_amendment_chains(dart_id)
+            .await?
+            .into_iter()
+            .find(|chain| chain.contains(receipt_number)))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_effective_filings(
+        &mut self,
+        dart_id: &company::DartId,
+    ) -> Result<Vec<Filing>, DbError> {
+        Ok(self
+            .get_amendment_chains(dart_id)
+            .await?
+            .iter()
+            .filter_map(|chain| chain.latest_effective().cloned())
+            .collect())
+    }
+}
+
+/// Chain `filings` as described in [`AmendmentChainDb`], ordered by their original filing.
+pub fn amendment_chains(mut filings: Vec<Filing>) -> Vec<AmendmentChain> {
+    filings.sort_by(|a, b| {
+        (&a.receipt_date, &a.receipt_number).cmp(&(&b.receipt_date, &b.receipt_number))
+    });
+
+    let mut chains: Vec<AmendmentChain> = Vec::new();
+    // The newest chain of each company and report name
+    let mut newest: HashMap<(company::DartId, String), usize> = HashMap::new();
+    for filing in filings {
+        let amendment_kind = filing.report_name.amendment_kind();
+        if amendment_kind.is_some_and(|kind| !kind.is_refiling()) {
+            continue;
+        }
+        let key = (
+            filing.dart_id.clone(),
+            filing.report_name.base_name().to_string(),
+        );
+        match (amendment_kind, newest.get(&key)) {
+            (Some(_), Some(&position)) => chains[position].filings.push(filing),
+            // An amendment of a filing which wasn't ingested starts a chain of its own,
+            // which callers match with what was taken from the filing by the base name
+            _ => {
+                newest.insert(key, chains.len());
+                chains.push(AmendmentChain {
+                    filings: vec![filing],
+                });
+            }
+        }
+    }
+    chains
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fake::{Fake, Faker};
+
+    fn filing(receipt_number: &str, report_name: &str, remark: &str) -> Filing {
+        let remark = filing::Remark::new(remark);
+        let flags = remark.flags();
+        Filing {
+            dart_id: "10000000".try_into().expect("Failed to create dart_id"),
+            report_name: report_name
+                .try_into()
+                .expect("Failed to create report_name"),
+            receipt_number: receipt_number
+                .try_into()
+                .expect("Failed to create receipt_number"),
+            receipt_date: receipt_number[..8]
+                .try_into()
+                .expect("Failed to create receipt_date"),
+            remark,
+            is_consolidated: flags.consolidated,
+            is_corrected: flags.corrected,
+            is_withdrawn: flags.withdrawn,
+            ..Faker.fake()
+        }
+    }
+
+    fn receipt_numbers(chain: &AmendmentChain) -> Vec<String> {
+        chain
+            .filings
+            .iter()
+            .map(|filing| filing.receipt_number.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn amendments_should_join_the_newest_chain_of_their_report() {
+        let chains = amendment_chains(vec![
+            filing("20240320000002", "[기재정정]사업보고서 (2023.12)", ""),
+            filing("20240312000001", "사업보고서 (2023.12)", "정"),
+            filing("20240401000001", "주요사항보고서(자기주식취득결정)", ""),
+            filing("20240501000001", "주요사항보고서(자기주식취득결정)", "정"),
+            filing(
+                "20240502000001",
+                "[기재정정]주요사항보고서(자기주식취득결정)",
+                "",
+            ),
+        ]);
+
+        assert_eq!(chains.len(), 3);
+        assert_eq!(
+            receipt_numbers(&chains[0]),
+            vec!["20240312000001", "20240320000002"]
+        );
+        assert_eq!(chains[0].base_name(), "사업보고서 (2023.12)");
+        // The second buyback is a report of its own, which the correction amends
+        assert_eq!(receipt_numbers(&chains[1]), vec!["20240401000001"]);
+        assert_eq!(
+            receipt_numbers(&chains[2]),
+            vec!["20240501000001", "20240502000001"]
+        );
+    }
+
+    #[test]
+    fn correction_orders_should_not_join_chains() {
+        let chains = amendment_chains(vec![
+            filing("20240312000001", "사업보고서 (2023.12)", "정"),
+            filing("20240401000001", "[정정제출요구]사업보고서 (2023.12)", ""),
+            filing("20240410000001", "[기재정정]사업보고서 (2023.12)", ""),
+            filing("20240501000001", "[정정명령부과]사업보고서 (2023.12)", ""),
+        ]);
+
+        assert_eq!(chains.len(), 1);
+        assert_eq!(
+            receipt_numbers(&chains[0]),
+            vec!["20240312000001", "20240410000001"]
+        );
+        let latest = chains[0]
+            .latest_effective()
+            .expect("The correction should be effective");
+        assert_eq!(latest.receipt_number.to_string(), "20240410000001");
+    }
+
+    #[test]
+    fn latest_effective_filing_should_skip_withdrawn_ones() {
+        let chains = amendment_chains(vec![
+            filing("20240312000001", "사업보고서 (2023.12)", "정"),
+            filing("20240320000001", "[기재정정]사업보고서 (2023.12)", "철"),
+            filing("20240401000001", "[기재정정]반기보고서 (2024.06)", ""),
+        ]);
+
+        let latest = chains[0]
+            .latest_effective()
+            .expect("The original should be effective");
+        assert_eq!(latest.receipt_number.to_string(), "20240312000001");
+        // The amended filing wasn't ingested, so the amendment is its own chain
+        assert_eq!(receipt_numbers(&chains[1]), vec!["20240401000001"]);
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use std::future::Future;
+
+use crate::model::dart::ApiQuota;
+use crate::schema::dart::api_quota::dsl;
+use crate::{DbError, PostgresDb};
+
+/// The daily request quotas of OpenDART API keys, shared by the runners.
+///
+/// OpenDART caps the requests a key sends per day, so runners reserve every request here first,
+/// which keeps runners sharing a key from exceeding the cap together.
+pub trait ApiQuotaDb {
+    /// The quotas of `day`, ordered by key.
+    fn get_api_quotas(
+        &mut self,
+        day: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<ApiQuota>, DbError>>;
+    /// Count a request with the key `key_id` on `day`,
+    /// unless `limit` requests are already counted or the key is exhausted.
+    ///
+    /// Returns whether the request was counted.
+    /// The check and the count are a single statement, so concurrent runners can't both pass the limit.
+    fn reserve_api_request(
+        &mut self,
+        key_id: &str,
+        day: NaiveDate,
+        limit: i32,
+    ) -> impl Future<Output = Result<bool, DbError>>;
+    /// Mark the key `key_id` as exhausted on `day`, so no more requests are reserved with it.
+    fn exhaust_api_quota(
+        &mut self,
+        key_id: &str,
+        day: NaiveDate,
+    ) -> impl Future<Output = Result<(), DbError>>;
+}
+
+impl ApiQuotaDb for PostgresDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_api_quotas(&mut self, day: NaiveDate) -> Result<Vec<ApiQuota>, DbError> {
+        self.run(move |conn| {
+            Ok(dsl::api_quota
+                .filter(dsl::day.eq(day))
+                .order(dsl::key_id)
+                .select(ApiQuota::as_select())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn reserve_api_request(
+        &mut self,
+        key_id: &str,
+        day: NaiveDate,
+        limit: i32,
+    ) -> Result<bool, DbError> {
+        let key_id = key_id.to_string();
+        let run_id = self.run_id;
+        self.run(move |conn| {
+            diesel::insert_into(dsl::api_quota)
+                .values((
+                    dsl::key_id.eq(&key_id),
+                    dsl::day.eq(day),
+                    dsl::run_id.eq(run_id),
+                ))
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+            let reserved = diesel::update(dsl::api_quota)
+                .filter(dsl::key_id.eq(&key_id))
+                .filter(dsl::day.eq(day))
+                .filter(dsl::request_count.lt(limit))
+                .filter(dsl::exhausted.eq(false))
+                .set((
+                    dsl::request_count.eq(dsl::request_count + 1),
+                    dsl::run_id.eq(run_id),
+                ))
+                .execute(conn)?;
+            Ok(reserved == 1)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn exhaust_api_quota(&mut self, key_id: &str, day: NaiveDate) -> Result<(), DbError> {
+        let key_id = key_id.to_string();
+        let run_id = self.run_id;
+        self.run(move |conn| {
+            diesel::insert_into(dsl::api_quota)
+                .values((
+                    dsl::key_id.eq(&key_id),
+                    dsl::day.eq(day),
+                    dsl::exhausted.eq(true),
+                    dsl::run_id.eq(run_id),
+                ))
+                .on_conflict((dsl::key_id, dsl::day))
+                .do_update()
+                .set((
+                    dsl::exhausted.eq(true),
+                    dsl::run_id.eq(excluded(dsl::run_id)),
+                ))
+                .execute(conn)?;
+            tracing::info!(%key_id, %day, "Exhausted API quota");
+            Ok(())
+        })
+        .await
+    }
+}
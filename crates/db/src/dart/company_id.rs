@@ -1,14 +1,28 @@
 use diesel::prelude::*;
 use diesel::upsert::excluded;
 use std::future::Future;
+use types::company;
 
 use crate::schema::dart::company_id::dsl;
+use crate::schema::dart::filing;
 use crate::{model, DbError, PostgresDb, POSTGRES_MAX_PARAMETERS};
 
 pub trait CompanyIdDb {
     fn get_company_ids(
         &mut self,
     ) -> impl Future<Output = Result<Vec<model::dart::CompanyId>, DbError>>;
+    /// Find company ids whose normalized name equals the normalized `name`,
+    /// e.g. "삼성전자(주)" finds "삼성전자".
+    fn find_company_ids_by_name(
+        &mut self,
+        name: &str,
+    ) -> impl Future<Output = Result<Vec<model::dart::CompanyId>, DbError>>;
+    /// The company id with `dart_id` and its filings, newest first,
+    /// or `None` if there is no such company id.
+    fn get_company_with_filings(
+        &mut self,
+        dart_id: &str,
+    ) -> impl Future<Output = Result<Option<model::dart::CompanyWithFilings>, DbError>>;
     fn insert_company_ids(
         &mut self,
         company_ids: Vec<model::dart::CompanyId>,
@@ -22,7 +36,57 @@ pub trait CompanyIdDb {
 impl CompanyIdDb for PostgresDb {
     #[tracing::instrument(skip(self))]
     async fn get_company_ids(&mut self) -> Result<Vec<model::dart::CompanyId>, DbError> {
-        Ok(dsl::company_id.load(&mut self.conn)?)
+        self.run(|conn| {
+            Ok(dsl::company_id
+                .select(model::dart::CompanyId::as_select())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_company_ids_by_name(
+        &mut self,
+        name: &str,
+    ) -> Result<Vec<model::dart::CompanyId>, DbError> {
+        let normalized_name = company::NormalizedName::from_name(name);
+        self.run(move |conn| {
+            Ok(dsl::company_id
+                .filter(dsl::normalized_company_name.eq(normalized_name))
+                .select(model::dart::CompanyId::as_select())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_company_with_filings(
+        &mut self,
+        dart_id: &str,
+    ) -> Result<Option<model::dart::CompanyWithFilings>, DbError> {
+        let dart_id = dart_id.to_string();
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                let Some(company_id) = dsl::company_id
+                    .find(&dart_id)
+                    .select(model::dart::CompanyId::as_select())
+                    .first(conn)
+                    .optional()?
+                else {
+                    return Ok(None);
+                };
+                let filings = filing::table
+                    .filter(filing::dart_id.eq(&dart_id))
+                    .order((filing::receipt_date.desc(), filing::receipt_number.desc()))
+                    .select(model::dart::Filing::as_select())
+                    .load(conn)?;
+                Ok(Some(model::dart::CompanyWithFilings {
+                    company_id,
+                    filings,
+                }))
+            })
+        })
+        .await
     }
 
     #[tracing::instrument(skip(self, company_ids))]
@@ -61,24 +125,32 @@ impl PostgresDb {
     ) -> Result<(), DbError> {
         let total_company_id_count = company_ids.len();
 
-        self.conn.transaction(|conn| {
-            let insert_count = diesel::insert_into(dsl::company_id)
-                .values(&company_ids)
-                .execute(conn)?;
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                let insert_count = diesel::insert_into(dsl::company_id)
+                    .values(
+                        company_ids
+                            .iter()
+                            .map(|c| (c, normalized_company_name(c)))
+                            .collect::<Vec<_>>(),
+                    )
+                    .execute(conn)?;
 
-            if insert_count == total_company_id_count {
-                tracing::trace!("Inserted {} company_ids", insert_count);
-                Ok(())
-            } else {
-                tracing::error!(
-                    "Inserted {}/{} company_ids. Rolling back transaction",
-                    insert_count,
-                    total_company_id_count
-                );
-                Err(diesel::result::Error::RollbackTransaction)
-            }
-        })?;
-        Ok(())
+                if insert_count == total_company_id_count {
+                    tracing::trace!("Inserted {} company_ids", insert_count);
+                    Ok(())
+                } else {
+                    tracing::error!(
+                        "Inserted {}/{} company_ids. Rolling back transaction",
+                        insert_count,
+                        total_company_id_count
+                    );
+                    Err(diesel::result::Error::RollbackTransaction)
+                }
+            })?;
+            Ok(())
+        })
+        .await
     }
 
     async fn upsert_company_ids_inner(
@@ -87,34 +159,63 @@ impl PostgresDb {
     ) -> Result<(), DbError> {
         let total_company_id_count = company_ids.len();
 
-        self.conn.transaction(|conn| {
-            for company_id in &company_ids {
-                let insert_count = diesel::insert_into(dsl::company_id)
-                    .values(company_id)
-                    .on_conflict(dsl::dart_id)
-                    .do_update()
-                    .set((
-                        dsl::company_name.eq(excluded(dsl::company_name)),
-                        dsl::stock_code.eq(excluded(dsl::stock_code)),
-                        dsl::id_modify_date.eq(excluded(dsl::id_modify_date)),
-                    ))
-                    .execute(conn)?;
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                for company_id in &company_ids {
+                    let insert_count = diesel::insert_into(dsl::company_id)
+                        .values((company_id, normalized_company_name(company_id)))
+                        .on_conflict(dsl::dart_id)
+                        .do_update()
+                        .set((
+                            dsl::company_name.eq(excluded(dsl::company_name)),
+                            dsl::normalized_company_name.eq(excluded(dsl::normalized_company_name)),
+                            dsl::stock_code.eq(excluded(dsl::stock_code)),
+                            dsl::id_modify_date.eq(excluded(dsl::id_modify_date)),
+                        ))
+                        .execute(conn)?;
 
-                if insert_count != 1 {
-                    tracing::error!(
-                        "Upserted {}/{} company_ids. Rolling back transaction",
-                        insert_count,
-                        total_company_id_count
-                    );
-                    return Err(diesel::result::Error::RollbackTransaction);
+                    if insert_count != 1 {
+                        tracing::error!(
+                            "Upserted {}/{} company_ids. Rolling back transaction",
+                            insert_count,
+                            total_company_id_count
+                        );
+                        return Err(diesel::result::Error::RollbackTransaction);
+                    }
                 }
-            }
+                Ok(())
+            })?;
             Ok(())
-        })?;
-        Ok(())
+        })
+        .await
     }
 }
 
+/// Insert placeholders for the companies of `filings` which have no company id yet,
+/// see [`model::dart::CompanyId::placeholders_for`].
+pub(crate) fn insert_placeholders(
+    conn: &mut PgConnection,
+    filings: &[model::dart::NewFiling],
+) -> QueryResult<usize> {
+    let placeholders = model::dart::CompanyId::placeholders_for(filings);
+    diesel::insert_into(dsl::company_id)
+        .values(
+            placeholders
+                .iter()
+                .map(|c| (c, normalized_company_name(c)))
+                .collect::<Vec<_>>(),
+        )
+        .on_conflict(dsl::dart_id)
+        .do_nothing()
+        .execute(conn)
+}
+
+fn normalized_company_name(
+    company_id: &model::dart::CompanyId,
+) -> diesel::dsl::Eq<dsl::normalized_company_name, company::NormalizedName> {
+    dsl::normalized_company_name.eq(company_id.company_name.normalized())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::dart::CompanyIdDb;
@@ -207,4 +308,31 @@ mod tests {
         }
         // endregion: Assert
     }
+
+    #[tokio::test]
+    async fn find_company_ids_by_name_should_match_normalized_name() {
+        tracing_setup::span!("test");
+
+        let function_id = utils::function_id!();
+        let mut ctx = PostgresTestContext::new(&function_id).await;
+
+        let ids = (0..10_u64).map(|i| 10000000 + i).collect::<Vec<_>>();
+        let company_ids = ctx.populate_company_ids(&ids).await;
+
+        let renamed_company_id = CompanyId {
+            company_name: "삼성전자(주)".try_into().expect("Failed to convert"),
+            ..company_ids[0].clone()
+        };
+
+        let db = ctx.db();
+        db.upsert_company_ids(vec![renamed_company_id.clone()])
+            .await
+            .expect("Failed to upsert company_ids");
+        let found = db
+            .find_company_ids_by_name("삼성전자")
+            .await
+            .expect("Failed to find company_ids");
+
+        assert_eq!(found, vec![renamed_company_id]);
+    }
 }
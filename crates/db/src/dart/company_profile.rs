@@ -0,0 +1,85 @@
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use std::future::Future;
+
+use crate::model::dart::{CompanyProfile, NewCompanyProfile};
+use crate::schema::dart::company_profile::dsl;
+use crate::{DbError, PostgresDb};
+
+pub trait CompanyProfileDb {
+    fn get_company_profiles(
+        &mut self,
+    ) -> impl Future<Output = Result<Vec<CompanyProfile>, DbError>>;
+    fn get_company_profile(
+        &mut self,
+        dart_id: &str,
+    ) -> impl Future<Output = Result<Option<CompanyProfile>, DbError>>;
+    /// Insert `profiles`, replacing the stored profiles of the same companies, in one transaction.
+    fn upsert_company_profiles(
+        &mut self,
+        profiles: Vec<NewCompanyProfile>,
+    ) -> impl Future<Output = Result<(), DbError>>;
+}
+
+impl CompanyProfileDb for PostgresDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_company_profiles(&mut self) -> Result<Vec<CompanyProfile>, DbError> {
+        self.run(|conn| {
+            Ok(dsl::company_profile
+                .select(CompanyProfile::as_select())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_company_profile(
+        &mut self,
+        dart_id: &str,
+    ) -> Result<Option<CompanyProfile>, DbError> {
+        let dart_id = dart_id.to_string();
+        self.run(move |conn| {
+            Ok(dsl::company_profile
+                .find(dart_id)
+                .select(CompanyProfile::as_select())
+                .first(conn)
+                .optional()?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, profiles))]
+    async fn upsert_company_profiles(
+        &mut self,
+        profiles: Vec<NewCompanyProfile>,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                for profile in &profiles {
+                    diesel::insert_into(dsl::company_profile)
+                        .values((profile, dsl::run_id.eq(run_id)))
+                        .on_conflict(dsl::dart_id)
+                        .do_update()
+                        .set((
+                            dsl::corporation_registration_number
+                                .eq(excluded(dsl::corporation_registration_number)),
+                            dsl::business_registration_number
+                                .eq(excluded(dsl::business_registration_number)),
+                            dsl::representative_name.eq(excluded(dsl::representative_name)),
+                            dsl::headquarters_address.eq(excluded(dsl::headquarters_address)),
+                            dsl::industry_code.eq(excluded(dsl::industry_code)),
+                            dsl::established_date.eq(excluded(dsl::established_date)),
+                            dsl::fiscal_month.eq(excluded(dsl::fiscal_month)),
+                            dsl::run_id.eq(excluded(dsl::run_id)),
+                        ))
+                        .execute(conn)?;
+                }
+                tracing::trace!("Upserted {} company profiles", profiles.len());
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,82 @@
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use std::future::Future;
+use types::filing;
+
+use crate::model::dart::{Document, NewDocument};
+use crate::schema::dart::{document, filing as filing_table};
+use crate::{DbError, PostgresDb};
+
+pub trait DocumentDb {
+    fn get_document(
+        &mut self,
+        receipt_number: &str,
+    ) -> impl Future<Output = Result<Option<Document>, DbError>>;
+    /// Up to `limit` filings which haven't been checked for a document yet, newest first.
+    ///
+    /// A bulk download which stops halfway picks up where it left off,
+    /// as every downloaded document drops out of the result.
+    /// So do filings recorded without a document, which OpenDART would answer the same again.
+    fn get_receipt_numbers_without_document(
+        &mut self,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<filing::ReceiptNumber>, DbError>>;
+    /// Record the downloaded archive of a filing, or that it has none,
+    /// replacing the previous record and its `checked_at`.
+    fn upsert_document(
+        &mut self,
+        document: NewDocument,
+    ) -> impl Future<Output = Result<(), DbError>>;
+}
+
+impl DocumentDb for PostgresDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_document(&mut self, receipt_number: &str) -> Result<Option<Document>, DbError> {
+        let receipt_number = receipt_number.to_string();
+        self.run(move |conn| {
+            Ok(document::table
+                .find(receipt_number)
+                .select(Document::as_select())
+                .first(conn)
+                .optional()?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_receipt_numbers_without_document(
+        &mut self,
+        limit: i64,
+    ) -> Result<Vec<filing::ReceiptNumber>, DbError> {
+        self.run(move |conn| {
+            Ok(filing_table::table
+                .left_join(document::table)
+                .filter(document::receipt_number.is_null())
+                .select(filing_table::receipt_number)
+                .order(filing_table::receipt_number.desc())
+                .limit(limit)
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn upsert_document(&mut self, new_document: NewDocument) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.run(move |conn| {
+            diesel::insert_into(document::table)
+                .values((&new_document, document::run_id.eq(run_id)))
+                .on_conflict(document::receipt_number)
+                .do_update()
+                .set((
+                    document::sha256.eq(excluded(document::sha256)),
+                    document::byte_size.eq(excluded(document::byte_size)),
+                    document::run_id.eq(excluded(document::run_id)),
+                    document::checked_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)?;
+            Ok(())
+        })
+        .await
+    }
+}
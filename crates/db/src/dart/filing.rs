@@ -1,21 +1,99 @@
 use diesel::prelude::*;
+use diesel::upsert::excluded;
+use hashbrown::HashMap;
 use std::future::Future;
 
+use super::company_id;
+use crate::query::{FilingQuery, Page};
 use crate::schema::dart::filing::dsl;
 use crate::{model, DbError, PostgresDb, POSTGRES_MAX_PARAMETERS};
+use types::filing;
 
 pub trait FilingDb {
     fn get_filings(&mut self) -> impl Future<Output = Result<Vec<model::dart::Filing>, DbError>>;
+    /// A page of the filings matching `query`, ordered by `receipt_number`.
+    ///
+    /// See [`crate::query`] to walk all matching filings page by page.
+    fn query_filings(
+        &mut self,
+        query: &FilingQuery,
+    ) -> impl Future<Output = Result<Page<model::dart::Filing, filing::ReceiptNumber>, DbError>>;
+    /// The receipt date of the newest filing, or `None` when there are no filings.
+    ///
+    /// Incremental ingestion resumes from this date.
+    fn get_latest_receipt_date(
+        &mut self,
+    ) -> impl Future<Output = Result<Option<filing::ReceiptDate>, DbError>>;
     fn insert_filings(
         &mut self,
         filings: Vec<model::dart::NewFiling>,
     ) -> impl Future<Output = Result<(), DbError>>;
+    /// Insert new filings and update the ones already stored, matched by `receipt_number`.
+    ///
+    /// When `filings` has the same receipt number more than once, the last one is kept.
+    fn upsert_filings(
+        &mut self,
+        filings: Vec<model::dart::NewFiling>,
+    ) -> impl Future<Output = Result<(), DbError>>;
 }
 
 impl FilingDb for PostgresDb {
     #[tracing::instrument(skip(self))]
     async fn get_filings(&mut self) -> Result<Vec<model::dart::Filing>, DbError> {
-        Ok(dsl::filing.load(&mut self.conn)?)
+        self.run(|conn| Ok(dsl::filing.load(conn)?)).await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn query_filings(
+        &mut self,
+        query: &FilingQuery,
+    ) -> Result<Page<model::dart::Filing, filing::ReceiptNumber>, DbError> {
+        let query = query.clone();
+        self.run(move |conn| {
+            let mut select = dsl::filing.into_boxed();
+            if let Some(dart_id) = query.dart_id.clone() {
+                select = select.filter(dsl::dart_id.eq(dart_id));
+            }
+            if let Some(from) = query.receipt_date_from.clone() {
+                select = select.filter(dsl::receipt_date.ge(from));
+            }
+            if let Some(to) = query.receipt_date_to.clone() {
+                select = select.filter(dsl::receipt_date.le(to));
+            }
+            if let Some(is_corrected) = query.is_corrected {
+                select = select.filter(dsl::is_corrected.eq(is_corrected));
+            }
+            if let Some(is_withdrawn) = query.is_withdrawn {
+                select = select.filter(dsl::is_withdrawn.eq(is_withdrawn));
+            }
+            if let Some(run_id) = query.run_id {
+                select = select.filter(dsl::run_id.eq(run_id));
+            }
+            if let Some(after) = query.after.clone() {
+                select = select.filter(dsl::receipt_number.gt(after));
+            }
+
+            let filings = select
+                .order(dsl::receipt_number.asc())
+                .limit(query.page_size as i64)
+                .load(conn)?;
+            Ok(Page::new(
+                filings,
+                query.page_size,
+                |filing: &model::dart::Filing| filing.receipt_number.clone(),
+            ))
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_latest_receipt_date(&mut self) -> Result<Option<filing::ReceiptDate>, DbError> {
+        self.run(|conn| {
+            Ok(dsl::filing
+                .select(diesel::dsl::max(dsl::receipt_date))
+                .first(conn)?)
+        })
+        .await
     }
 
     #[tracing::instrument(skip(self, filings))]
@@ -31,6 +109,22 @@ impl FilingDb for PostgresDb {
         }
         Ok(())
     }
+
+    #[tracing::instrument(skip(self, filings))]
+    async fn upsert_filings(
+        &mut self,
+        filings: Vec<model::dart::NewFiling>,
+    ) -> Result<(), DbError> {
+        const BUFFER_DIVISOR: usize = 100;
+
+        // A single `ON CONFLICT DO UPDATE` can't update the same row twice
+        let filings = dedupe_filings(filings);
+        for chunk in filings.chunks(POSTGRES_MAX_PARAMETERS / BUFFER_DIVISOR) {
+            tracing::trace!(chunk_size = chunk.len(), "Upserting chunk of filings");
+            self.upsert_filings_inner(chunk.to_vec()).await?;
+        }
+        Ok(())
+    }
 }
 
 impl PostgresDb {
@@ -39,26 +133,90 @@ impl PostgresDb {
         filings: Vec<model::dart::NewFiling>,
     ) -> Result<(), DbError> {
         let total_filing_count = filings.len();
+        let run_id = self.run_id;
+
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                company_id::insert_placeholders(conn, &filings)?;
+                let insert_count = diesel::insert_into(dsl::filing)
+                    .values(filing_rows(&filings, run_id))
+                    .execute(conn)?;
+
+                if insert_count == total_filing_count {
+                    tracing::trace!("Inserted {} filings", insert_count);
+                    Ok(())
+                } else {
+                    tracing::error!(
+                        "Inserted {}/{} filings. Rolling back transaction",
+                        insert_count,
+                        total_filing_count
+                    );
+                    Err(diesel::result::Error::RollbackTransaction)
+                }
+            })?;
+            Ok(())
+        })
+        .await
+    }
 
-        self.conn.transaction(|conn| {
-            let insert_count = diesel::insert_into(dsl::filing)
-                .values(&filings)
-                .execute(conn)?;
-
-            if insert_count == total_filing_count {
-                tracing::trace!("Inserted {} filings", insert_count);
-                Ok(())
-            } else {
-                tracing::error!(
-                    "Inserted {}/{} filings. Rolling back transaction",
-                    insert_count,
-                    total_filing_count
-                );
-                Err(diesel::result::Error::RollbackTransaction)
+    async fn upsert_filings_inner(
+        &mut self,
+        filings: Vec<model::dart::NewFiling>,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                company_id::insert_placeholders(conn, &filings)?;
+                let upsert_count = diesel::insert_into(dsl::filing)
+                    .values(filing_rows(&filings, run_id))
+                    .on_conflict(dsl::receipt_number)
+                    .do_update()
+                    .set((
+                        dsl::dart_id.eq(excluded(dsl::dart_id)),
+                        dsl::report_name.eq(excluded(dsl::report_name)),
+                        dsl::filer_name.eq(excluded(dsl::filer_name)),
+                        dsl::receipt_date.eq(excluded(dsl::receipt_date)),
+                        dsl::remark.eq(excluded(dsl::remark)),
+                        dsl::run_id.eq(excluded(dsl::run_id)),
+                    ))
+                    .execute(conn)?;
+                tracing::trace!("Upserted {} filings", upsert_count);
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+/// Pair each filing with the ingest run it is written by.
+fn filing_rows(
+    filings: &[model::dart::NewFiling],
+    run_id: Option<i32>,
+) -> Vec<(
+    &model::dart::NewFiling,
+    diesel::dsl::Eq<dsl::run_id, Option<i32>>,
+)> {
+    filings
+        .iter()
+        .map(|filing| (filing, dsl::run_id.eq(run_id)))
+        .collect()
+}
+
+/// Keep the last filing of each receipt number, in the order of their first occurrence.
+pub(crate) fn dedupe_filings(filings: Vec<model::dart::NewFiling>) -> Vec<model::dart::NewFiling> {
+    let mut positions: HashMap<filing::ReceiptNumber, usize> = HashMap::new();
+    let mut deduped: Vec<model::dart::NewFiling> = Vec::with_capacity(filings.len());
+    for filing in filings {
+        match positions.get(&filing.receipt_number) {
+            Some(&position) => deduped[position] = filing,
+            None => {
+                positions.insert(filing.receipt_number.clone(), deduped.len());
+                deduped.push(filing);
             }
-        })?;
-        Ok(())
+        }
     }
+    deduped
 }
 
 #[cfg(test)]
@@ -0,0 +1,196 @@
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use hashbrown::HashMap;
+use std::future::Future;
+use types::filing;
+
+use crate::model::dart::{
+    FinancialItem, FinancialStatementCheck, FinancialStatementKey, NewFinancialItem,
+    NewFinancialStatementCheck,
+};
+use crate::schema::dart::financial_item::dsl;
+use crate::schema::dart::financial_statement_check;
+use crate::{DbError, PostgresDb};
+
+pub trait FinancialItemDb {
+    /// The financial items of the company with `dart_id`,
+    /// ordered by statement and by the order of the items within it.
+    fn get_financial_items(
+        &mut self,
+        dart_id: &str,
+    ) -> impl Future<Output = Result<Vec<FinancialItem>, DbError>>;
+    /// The statements which have been stored, with the filing each was taken from,
+    /// in no particular order.
+    fn get_financial_statement_receipts(
+        &mut self,
+    ) -> impl Future<Output = Result<Vec<(FinancialStatementKey, filing::ReceiptNumber)>, DbError>>;
+    /// Store `items`, replacing all stored items of the statements they belong to.
+    ///
+    /// A statement is replaced as a whole, in one transaction,
+    /// so items dropped from a corrected statement don't linger.
+    fn replace_financial_statements(
+        &mut self,
+        items: Vec<NewFinancialItem>,
+    ) -> impl Future<Output = Result<(), DbError>>;
+    /// Every recorded check of a statement, in no particular order.
+    fn get_financial_statement_checks(
+        &mut self,
+    ) -> impl Future<Output = Result<Vec<FinancialStatementCheck>, DbError>>;
+    /// Record that a statement was fetched, including one OpenDART had no items for,
+    /// replacing the previous check and its `checked_at`.
+    fn upsert_financial_statement_check(
+        &mut self,
+        check: NewFinancialStatementCheck,
+    ) -> impl Future<Output = Result<(), DbError>>;
+}
+
+impl FinancialItemDb for PostgresDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_financial_items(&mut self, dart_id: &str) -> Result<Vec<FinancialItem>, DbError> {
+        let dart_id = dart_id.to_string();
+        self.run(move |conn| {
+            Ok(dsl::financial_item
+                .filter(dsl::dart_id.eq(dart_id))
+                .order((
+                    dsl::business_year,
+                    dsl::report_code,
+                    dsl::fs_div,
+                    dsl::statement,
+                    dsl::ord,
+                    dsl::account_detail,
+                ))
+                .select(FinancialItem::as_select())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_financial_statement_receipts(
+        &mut self,
+    ) -> Result<Vec<(FinancialStatementKey, filing::ReceiptNumber)>, DbError> {
+        self.run(|conn| {
+            let keys = dsl::financial_item
+                .select((
+                    dsl::dart_id,
+                    dsl::business_year,
+                    dsl::report_code,
+                    dsl::fs_div,
+                    dsl::receipt_number,
+                ))
+                .distinct()
+                .load(conn)?;
+            Ok(keys
+                .into_iter()
+                .map(
+                    |(dart_id, business_year, report_code, fs_div, receipt_number)| {
+                        let key = FinancialStatementKey {
+                            dart_id,
+                            business_year,
+                            report_code,
+                            fs_div,
+                        };
+                        (key, receipt_number)
+                    },
+                )
+                .collect())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, items))]
+    async fn replace_financial_statements(
+        &mut self,
+        items: Vec<NewFinancialItem>,
+    ) -> Result<(), DbError> {
+        for (key, items) in group_by_statement(items) {
+            tracing::trace!(?key, count = items.len(), "Replacing financial statement");
+            let run_id = self.run_id;
+            self.run(move |conn| {
+                conn.transaction(|conn| {
+                    diesel::delete(
+                        dsl::financial_item
+                            .filter(dsl::dart_id.eq(&key.dart_id))
+                            .filter(dsl::business_year.eq(key.business_year))
+                            .filter(dsl::report_code.eq(key.report_code))
+                            .filter(dsl::fs_div.eq(key.fs_div)),
+                    )
+                    .execute(conn)?;
+                    let rows: Vec<_> = items
+                        .iter()
+                        .map(|item| (item, dsl::run_id.eq(run_id)))
+                        .collect();
+                    diesel::insert_into(dsl::financial_item)
+                        .values(rows)
+                        .execute(conn)?;
+                    Ok::<_, diesel::result::Error>(())
+                })?;
+                Ok(())
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_financial_statement_checks(
+        &mut self,
+    ) -> Result<Vec<FinancialStatementCheck>, DbError> {
+        self.run(|conn| {
+            Ok(financial_statement_check::table
+                .select(FinancialStatementCheck::as_select())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn upsert_financial_statement_check(
+        &mut self,
+        check: NewFinancialStatementCheck,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.run(move |conn| {
+            diesel::insert_into(financial_statement_check::table)
+                .values((&check, financial_statement_check::run_id.eq(run_id)))
+                .on_conflict((
+                    financial_statement_check::dart_id,
+                    financial_statement_check::business_year,
+                    financial_statement_check::report_code,
+                    financial_statement_check::fs_div,
+                ))
+                .do_update()
+                .set((
+                    financial_statement_check::item_count
+                        .eq(excluded(financial_statement_check::item_count)),
+                    financial_statement_check::skipped_count
+                        .eq(excluded(financial_statement_check::skipped_count)),
+                    financial_statement_check::run_id
+                        .eq(excluded(financial_statement_check::run_id)),
+                    financial_statement_check::checked_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+/// Group `items` by their statement, in the order the statements first appear.
+pub(crate) fn group_by_statement(
+    items: Vec<NewFinancialItem>,
+) -> Vec<(FinancialStatementKey, Vec<NewFinancialItem>)> {
+    let mut positions: HashMap<FinancialStatementKey, usize> = HashMap::new();
+    let mut statements: Vec<(FinancialStatementKey, Vec<NewFinancialItem>)> = Vec::new();
+    for item in items {
+        let key = item.statement_key();
+        match positions.get(&key) {
+            Some(&position) => statements[position].1.push(item),
+            None => {
+                positions.insert(key.clone(), statements.len());
+                statements.push((key, vec![item]));
+            }
+        }
+    }
+    statements
+}
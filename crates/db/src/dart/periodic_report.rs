@@ -0,0 +1,341 @@
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use std::future::Future;
+
+use crate::model::dart::{
+    Employee, Executive, MajorShareholder, NewEmployee, NewExecutive, NewMajorShareholder,
+    NewPeriodicReportCheck, PeriodicReportCheck, PeriodicReportKey, PeriodicReportSection,
+    ReportCode,
+};
+use crate::schema::dart::{employee, executive, major_shareholder, periodic_report_check};
+use crate::{DbError, PostgresDb};
+
+use types::{company, filing};
+
+/// The major shareholder, executive and employee sections of periodic reports.
+///
+/// Like financial statements, a section is stored and replaced as a whole per report,
+/// so rows dropped from a corrected report don't linger.
+pub trait PeriodicReportDb {
+    /// The major shareholders of the company with `dart_id`, ordered by report and position.
+    fn get_major_shareholders(
+        &mut self,
+        dart_id: &str,
+    ) -> impl Future<Output = Result<Vec<MajorShareholder>, DbError>>;
+    /// The executives of the company with `dart_id`, ordered by report and position.
+    fn get_executives(
+        &mut self,
+        dart_id: &str,
+    ) -> impl Future<Output = Result<Vec<Executive>, DbError>>;
+    /// The employees of the company with `dart_id`, ordered by report and position.
+    fn get_employees(
+        &mut self,
+        dart_id: &str,
+    ) -> impl Future<Output = Result<Vec<Employee>, DbError>>;
+    /// The reports of which `section` has been stored, with the filing it was taken from,
+    /// in no particular order.
+    fn get_periodic_report_receipts(
+        &mut self,
+        section: PeriodicReportSection,
+    ) -> impl Future<Output = Result<Vec<(PeriodicReportKey, filing::ReceiptNumber)>, DbError>>;
+    /// Store `rows` as the major shareholders of the report `key`, replacing the stored ones.
+    fn replace_major_shareholders(
+        &mut self,
+        key: PeriodicReportKey,
+        rows: Vec<NewMajorShareholder>,
+    ) -> impl Future<Output = Result<(), DbError>>;
+    /// Store `rows` as the executives of the report `key`, replacing the stored ones.
+    fn replace_executives(
+        &mut self,
+        key: PeriodicReportKey,
+        rows: Vec<NewExecutive>,
+    ) -> impl Future<Output = Result<(), DbError>>;
+    /// Store `rows` as the employees of the report `key`, replacing the stored ones.
+    fn replace_employees(
+        &mut self,
+        key: PeriodicReportKey,
+        rows: Vec<NewEmployee>,
+    ) -> impl Future<Output = Result<(), DbError>>;
+    /// Every recorded check of a section, in no particular order.
+    fn get_periodic_report_checks(
+        &mut self,
+    ) -> impl Future<Output = Result<Vec<PeriodicReportCheck>, DbError>>;
+    /// Record that a section was fetched, including one OpenDART had no rows for,
+    /// replacing the previous check and its `checked_at`.
+    fn upsert_periodic_report_check(
+        &mut self,
+        check: NewPeriodicReportCheck,
+    ) -> impl Future<Output = Result<(), DbError>>;
+}
+
+impl PeriodicReportDb for PostgresDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_major_shareholders(
+        &mut self,
+        dart_id: &str,
+    ) -> Result<Vec<MajorShareholder>, DbError> {
+        let dart_id = dart_id.to_string();
+        self.run(move |conn| {
+            Ok(major_shareholder::table
+                .filter(major_shareholder::dart_id.eq(dart_id))
+                .order((
+                    major_shareholder::business_year,
+                    major_shareholder::report_code,
+                    major_shareholder::ord,
+                ))
+                .select(MajorShareholder::as_select())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_executives(&mut self, dart_id: &str) -> Result<Vec<Executive>, DbError> {
+        let dart_id = dart_id.to_string();
+        self.run(move |conn| {
+            Ok(executive::table
+                .filter(executive::dart_id.eq(dart_id))
+                .order((
+                    executive::business_year,
+                    executive::report_code,
+                    executive::ord,
+                ))
+                .select(Executive::as_select())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_employees(&mut self, dart_id: &str) -> Result<Vec<Employee>, DbError> {
+        let dart_id = dart_id.to_string();
+        self.run(move |conn| {
+            Ok(employee::table
+                .filter(employee::dart_id.eq(dart_id))
+                .order((
+                    employee::business_year,
+                    employee::report_code,
+                    employee::ord,
+                ))
+                .select(Employee::as_select())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_periodic_report_receipts(
+        &mut self,
+        section: PeriodicReportSection,
+    ) -> Result<Vec<(PeriodicReportKey, filing::ReceiptNumber)>, DbError> {
+        self.run(move |conn| {
+            let receipts: Vec<(company::DartId, i32, ReportCode, filing::ReceiptNumber)> =
+                match section {
+                    PeriodicReportSection::MajorShareholder => major_shareholder::table
+                        .select((
+                            major_shareholder::dart_id,
+                            major_shareholder::business_year,
+                            major_shareholder::report_code,
+                            major_shareholder::receipt_number,
+                        ))
+                        .distinct()
+                        .load(conn)?,
+                    PeriodicReportSection::Executive => executive::table
+                        .select((
+                            executive::dart_id,
+                            executive::business_year,
+                            executive::report_code,
+                            executive::receipt_number,
+                        ))
+                        .distinct()
+                        .load(conn)?,
+                    PeriodicReportSection::Employee => employee::table
+                        .select((
+                            employee::dart_id,
+                            employee::business_year,
+                            employee::report_code,
+                            employee::receipt_number,
+                        ))
+                        .distinct()
+                        .load(conn)?,
+                };
+            Ok(receipts.into_iter().map(periodic_report_receipt).collect())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, rows))]
+    async fn replace_major_shareholders(
+        &mut self,
+        key: PeriodicReportKey,
+        rows: Vec<NewMajorShareholder>,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                diesel::delete(
+                    major_shareholder::table
+                        .filter(major_shareholder::dart_id.eq(&key.dart_id))
+                        .filter(major_shareholder::business_year.eq(key.business_year))
+                        .filter(major_shareholder::report_code.eq(key.report_code)),
+                )
+                .execute(conn)?;
+                let rows: Vec<_> = rows
+                    .iter()
+                    .map(|row| {
+                        (
+                            major_shareholder::dart_id.eq(&key.dart_id),
+                            major_shareholder::business_year.eq(key.business_year),
+                            major_shareholder::report_code.eq(key.report_code),
+                            row,
+                            major_shareholder::run_id.eq(run_id),
+                        )
+                    })
+                    .collect();
+                if !rows.is_empty() {
+                    diesel::insert_into(major_shareholder::table)
+                        .values(rows)
+                        .execute(conn)?;
+                }
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, rows))]
+    async fn replace_executives(
+        &mut self,
+        key: PeriodicReportKey,
+        rows: Vec<NewExecutive>,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                diesel::delete(
+                    executive::table
+                        .filter(executive::dart_id.eq(&key.dart_id))
+                        .filter(executive::business_year.eq(key.business_year))
+                        .filter(executive::report_code.eq(key.report_code)),
+                )
+                .execute(conn)?;
+                let rows: Vec<_> = rows
+                    .iter()
+                    .map(|row| {
+                        (
+                            executive::dart_id.eq(&key.dart_id),
+                            executive::business_year.eq(key.business_year),
+                            executive::report_code.eq(key.report_code),
+                            row,
+                            executive::run_id.eq(run_id),
+                        )
+                    })
+                    .collect();
+                if !rows.is_empty() {
+                    diesel::insert_into(executive::table)
+                        .values(rows)
+                        .execute(conn)?;
+                }
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, rows))]
+    async fn replace_employees(
+        &mut self,
+        key: PeriodicReportKey,
+        rows: Vec<NewEmployee>,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                diesel::delete(
+                    employee::table
+                        .filter(employee::dart_id.eq(&key.dart_id))
+                        .filter(employee::business_year.eq(key.business_year))
+                        .filter(employee::report_code.eq(key.report_code)),
+                )
+                .execute(conn)?;
+                let rows: Vec<_> = rows
+                    .iter()
+                    .map(|row| {
+                        (
+                            employee::dart_id.eq(&key.dart_id),
+                            employee::business_year.eq(key.business_year),
+                            employee::report_code.eq(key.report_code),
+                            row,
+                            employee::run_id.eq(run_id),
+                        )
+                    })
+                    .collect();
+                if !rows.is_empty() {
+                    diesel::insert_into(employee::table)
+                        .values(rows)
+                        .execute(conn)?;
+                }
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_periodic_report_checks(&mut self) -> Result<Vec<PeriodicReportCheck>, DbError> {
+        self.run(|conn| {
+            Ok(periodic_report_check::table
+                .select(PeriodicReportCheck::as_select())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn upsert_periodic_report_check(
+        &mut self,
+        check: NewPeriodicReportCheck,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.run(move |conn| {
+            diesel::insert_into(periodic_report_check::table)
+                .values((&check, periodic_report_check::run_id.eq(run_id)))
+                .on_conflict((
+                    periodic_report_check::dart_id,
+                    periodic_report_check::business_year,
+                    periodic_report_check::report_code,
+                    periodic_report_check::section,
+                ))
+                .do_update()
+                .set((
+                    periodic_report_check::row_count.eq(excluded(periodic_report_check::row_count)),
+                    periodic_report_check::skipped_count
+                        .eq(excluded(periodic_report_check::skipped_count)),
+                    periodic_report_check::run_id.eq(excluded(periodic_report_check::run_id)),
+                    periodic_report_check::checked_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+pub(crate) fn periodic_report_receipt(
+    (dart_id, business_year, report_code, receipt_number): (
+        company::DartId,
+        i32,
+        ReportCode,
+        filing::ReceiptNumber,
+    ),
+) -> (PeriodicReportKey, filing::ReceiptNumber) {
+    let key = PeriodicReportKey {
+        dart_id,
+        business_year,
+        report_code,
+    };
+    (key, receipt_number)
+}
@@ -1,39 +1,312 @@
+use crate::blob::{self, Blob};
 use crate::error::DbError;
-use crate::{dart, smes};
+use crate::{dart, ingest, link, smes};
 
 use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool, R2D2Connection};
 use diesel::sql_query;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use std::fmt::Debug;
 use std::future::Future;
 use std::path::Path;
 
-pub trait Db: Sized + smes::CompanyDb + smes::HtmlDb + dart::FilingDb + dart::CompanyIdDb {
-    fn new<P: AsRef<Path> + Debug>(db_url: P) -> impl Future<Output = Self>;
+pub trait Db:
+    Sized
+    + smes::CompanyDb
+    + smes::HtmlDb
+    + dart::FilingDb
+    + dart::CompanyIdDb
+    + dart::CompanyProfileDb
+    + dart::DocumentDb
+    + dart::FinancialItemDb
+    + dart::PeriodicReportDb
+    + dart::ApiQuotaDb
+    + link::CompanyLinkDb
+    + ingest::IngestRunDb
+{
+    fn new<P: AsRef<Path> + Debug>(db_url: P) -> impl Future<Output = Result<Self, DbError>>;
     fn health_check(&mut self) -> impl Future<Output = Result<(), DbError>>;
 }
 
 // region: Postgres
+
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!("../../migrations");
+
+/// A handle to a pool of Postgres connections.
+///
+/// Diesel is blocking, so every query runs on a pooled connection within
+/// [`tokio::task::spawn_blocking`] instead of the async worker threads.
+/// Cloning the handle is cheap, and clones share the same pool,
+/// so multiple tasks can query the database concurrently.
+#[derive(Clone)]
 pub struct PostgresDb {
-    pub conn: PgConnection,
+    pool: Pool<ConnectionManager<PgConnection>>,
+    /// The ingest run rows are written with, only set on the handle
+    /// [`ingest::IngestRunDb::start_ingest_run`] returns.
+    pub(crate) run_id: Option<i32>,
 }
 
 impl Db for PostgresDb {
     #[tracing::instrument]
-    async fn new<P: AsRef<Path> + Debug>(connection_string: P) -> Self {
-        let connection_string = connection_string.as_ref().to_string_lossy();
-        let conn = PgConnection::establish(&connection_string)
-            .expect("Failed to establish connection to db");
+    async fn new<P: AsRef<Path> + Debug>(connection_string: P) -> Result<Self, DbError> {
+        let connection_string = connection_string.as_ref().to_string_lossy().to_string();
+        // Building the pool establishes its initial connections, which blocks.
+        let pool = tokio::task::spawn_blocking(move || {
+            Pool::builder().build(ConnectionManager::<PgConnection>::new(connection_string))
+        })
+        .await??;
 
-        Self { conn }
+        Ok(Self { pool, run_id: None })
     }
 
     #[tracing::instrument(skip(self))]
     async fn health_check(&mut self) -> Result<(), DbError> {
-        sql_query("SELECT 1").execute(&mut self.conn)?;
-        Ok(())
+        self.run(|conn| {
+            sql_query("SELECT 1").execute(conn)?;
+            Ok(())
+        })
+        .await
     }
 }
 
+impl PostgresDb {
+    /// Run blocking diesel code with a connection from the pool.
+    pub(crate) async fn run<T, F>(&self, f: F) -> Result<T, DbError>
+    where
+        F: FnOnce(&mut PgConnection) -> Result<T, DbError> + Send + 'static,
+        T: Send + 'static,
+    {
+        run_blocking(&self.pool, f).await
+    }
+
+    /// Connect, and apply the migrations the database doesn't have yet.
+    #[tracing::instrument]
+    pub async fn connect_and_migrate(connection_string: &str) -> Result<Self, DbError> {
+        let db = Self::new(connection_string).await?;
+        db.migrate().await?;
+        Ok(db)
+    }
+
+    /// Apply the pending migrations, returning their versions.
+    ///
+    /// When any were applied, the stored normalized company names are brought up to date as well,
+    /// see [`renormalize_company_names`], and the html bodies backfilled by a migration are compressed,
+    /// see [`compress_html_revisions`].
+    #[tracing::instrument(skip(self))]
+    pub async fn migrate(&self) -> Result<Vec<String>, DbError> {
+        self.run(|conn| {
+            let versions: Vec<String> = conn
+                .run_pending_migrations(MIGRATIONS)
+                .map_err(DbError::Migration)?
+                .iter()
+                .map(|version| version.to_string())
+                .collect();
+            tracing::info!(?versions, "Ran pending migrations");
+            if !versions.is_empty() {
+                let renormalized = renormalize_company_names(conn)?;
+                tracing::info!(renormalized, "Renormalized company names");
+                let compressed = compress_html_revisions(conn)?;
+                tracing::info!(compressed, "Compressed backfilled html revisions");
+            }
+            Ok(versions)
+        })
+        .await
+    }
+
+    /// Fail with [`DbError::PendingMigrations`] when the schema is older than this binary,
+    /// so a runner never writes to tables it doesn't know the shape of.
+    #[tracing::instrument(skip(self))]
+    pub async fn check_schema_version(&self) -> Result<(), DbError> {
+        self.run(|conn| {
+            let pending: Vec<String> = conn
+                .pending_migrations(MIGRATIONS)
+                .map_err(DbError::Migration)?
+                .iter()
+                .map(|migration| migration.name().to_string())
+                .collect();
+            if pending.is_empty() {
+                Ok(())
+            } else {
+                Err(DbError::PendingMigrations(pending))
+            }
+        })
+        .await
+    }
+}
+
+/// How many rows [`renormalize_company_names`] reads, and updates, per query.
+const RENORMALIZE_BATCH_SIZE: i64 = 10_000;
+
+/// Overwrite the normalized company names which differ from [`types::company::NormalizedName`],
+/// returning the number of rows updated.
+///
+/// The migrations backfill `normalized_company_name` with an SQL approximation,
+/// and a change of the normalization comes with a migration, so the names are recomputed in Rust
+/// whenever migrations were applied.
+fn renormalize_company_names(conn: &mut PgConnection) -> Result<usize, DbError> {
+    use crate::schema::dart::company_id;
+    use crate::schema::smes::company;
+
+    conn.transaction(|conn| {
+        // Renormalizing doesn't change a company,
+        // so it mustn't show up in `CompanyQuery::updated_since`.
+        sql_query("ALTER TABLE smes.company DISABLE TRIGGER set_updated_at").execute(conn)?;
+        let mut updated = 0;
+
+        let mut after = String::new();
+        loop {
+            let companies: Vec<(String, String, String)> = company::table
+                .filter(company::smes_id.gt(&after))
+                .order(company::smes_id.asc())
+                .limit(RENORMALIZE_BATCH_SIZE)
+                .select((
+                    company::smes_id,
+                    company::company_name,
+                    company::normalized_company_name,
+                ))
+                .load(conn)?;
+            let Some((last, _, _)) = companies.last() else {
+                break;
+            };
+            after = last.clone();
+            updated += update_normalized_names(conn, "smes.company", "smes_id", companies)?;
+        }
+
+        let mut after = String::new();
+        loop {
+            let company_ids: Vec<(String, String, String)> = company_id::table
+                .filter(company_id::dart_id.gt(&after))
+                .order(company_id::dart_id.asc())
+                .limit(RENORMALIZE_BATCH_SIZE)
+                .select((
+                    company_id::dart_id,
+                    company_id::company_name,
+                    company_id::normalized_company_name,
+                ))
+                .load(conn)?;
+            let Some((last, _, _)) = company_ids.last() else {
+                break;
+            };
+            after = last.clone();
+            updated += update_normalized_names(conn, "dart.company_id", "dart_id", company_ids)?;
+        }
+
+        sql_query("ALTER TABLE smes.company ENABLE TRIGGER set_updated_at").execute(conn)?;
+        Ok(updated)
+    })
+}
+
+/// Update the `(key, name, stored normalized name)` `rows` of `table` whose normalized name is stale,
+/// in a single query.
+fn update_normalized_names(
+    conn: &mut PgConnection,
+    table: &str,
+    key_column: &str,
+    rows: Vec<(String, String, String)>,
+) -> Result<usize, DbError> {
+    use diesel::sql_types::{Array, Text};
+
+    let (keys, normalized): (Vec<String>, Vec<String>) = rows
+        .into_iter()
+        .filter_map(|(key, name, stored)| {
+            let normalized = types::company::NormalizedName::from_name(&name).to_string();
+            (normalized != stored).then_some((key, normalized))
+        })
+        .unzip();
+    if keys.is_empty() {
+        return Ok(0);
+    }
+
+    Ok(sql_query(format!(
+        "UPDATE {table} SET normalized_company_name = renormalized.normalized_company_name \
+         FROM unnest($1, $2) AS renormalized ({key_column}, normalized_company_name) \
+         WHERE {table}.{key_column} = renormalized.{key_column}"
+    ))
+    .bind::<Array<Text>, _>(keys)
+    .bind::<Array<Text>, _>(normalized)
+    .execute(conn)?)
+}
+
+/// How many html revisions [`compress_html_revisions`] reads, and updates, per query.
+/// Pages are large, so this is far smaller than [`RENORMALIZE_BATCH_SIZE`].
+const COMPRESS_BATCH_SIZE: i64 = 100;
+
+/// Compress the bodies of `smes.html_revision` which are stored uncompressed,
+/// returning the number of rows updated.
+///
+/// The migration introducing the table backfills it with the existing pages,
+/// which Postgres can hash but not compress.
+/// Compressed rows no longer match the filter, so every batch reads the next uncompressed rows.
+fn compress_html_revisions(conn: &mut PgConnection) -> Result<usize, DbError> {
+    use crate::schema::smes::html_revision;
+    use diesel::dsl::sql;
+    use diesel::sql_types::{Array, Bool, Bytea, Text};
+
+    conn.transaction(|conn| {
+        let mut updated = 0;
+        loop {
+            let revisions: Vec<(String, String, Vec<u8>)> = html_revision::table
+                .filter(sql::<Bool>(
+                    "substring(body FROM 1 FOR 4) <> '\\x28b52ffd'::BYTEA",
+                ))
+                .limit(COMPRESS_BATCH_SIZE)
+                .select((
+                    html_revision::smes_id,
+                    html_revision::sha256,
+                    html_revision::body,
+                ))
+                .load(conn)?;
+            if revisions.is_empty() {
+                break;
+            }
+
+            let mut smes_ids = Vec::with_capacity(revisions.len());
+            let mut sha256s = Vec::with_capacity(revisions.len());
+            let mut bodies = Vec::with_capacity(revisions.len());
+            for (smes_id, sha256, body) in revisions {
+                let content = blob::decompress(&body)?;
+                smes_ids.push(smes_id);
+                sha256s.push(sha256);
+                bodies.push(Blob::compress(&content)?.body);
+            }
+
+            updated += sql_query(
+                "UPDATE smes.html_revision SET body = compressed.body \
+                 FROM unnest($1, $2, $3) AS compressed (smes_id, sha256, body) \
+                 WHERE html_revision.smes_id = compressed.smes_id \
+                   AND html_revision.sha256 = compressed.sha256",
+            )
+            .bind::<Array<Text>, _>(smes_ids)
+            .bind::<Array<Text>, _>(sha256s)
+            .bind::<Array<Bytea>, _>(bodies)
+            .execute(conn)?;
+        }
+        Ok(updated)
+    })
+}
+
+// endregion: Postgres
+
+/// Run `f` with a connection from `pool` on the blocking thread pool of tokio.
+pub(crate) async fn run_blocking<C, T, F>(
+    pool: &Pool<ConnectionManager<C>>,
+    f: F,
+) -> Result<T, DbError>
+where
+    C: R2D2Connection + Send + 'static,
+    F: FnOnce(&mut C) -> Result<T, DbError> + Send + 'static,
+    T: Send + 'static,
+{
+    let pool = pool.clone();
+    let span = tracing::Span::current();
+    tokio::task::spawn_blocking(move || {
+        let _guard = span.enter();
+        let mut conn = pool.get()?;
+        f(&mut conn)
+    })
+    .await?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,4 +319,238 @@ mod tests {
         let mut ctx = PostgresTestContext::new(&function_id).await;
         assert!(ctx.db().health_check().await.is_ok());
     }
+
+    #[tokio::test]
+    async fn cloned_handles_should_query_concurrently() {
+        tracing_setup::span!("test");
+        let function_id = utils::function_id!();
+        let mut ctx = PostgresTestContext::new(&function_id).await;
+
+        let handles = (0..4)
+            .map(|_| {
+                let mut db = ctx.db().clone();
+                tokio::spawn(async move { db.health_check().await })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            assert!(handle.await.expect("Task panicked").is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn schema_version_check_should_refuse_pending_migrations() {
+        // region: Arrange
+        tracing_setup::span!("test");
+        let function_id = utils::function_id!();
+        let mut ctx = PostgresTestContext::new(&function_id).await;
+        let db = ctx.db();
+        // endregion: Arrange
+
+        // region: Action
+        let up_to_date = db.check_schema_version().await;
+        let reverted = db
+            .run(|conn| {
+                let version = conn
+                    .revert_last_migration(MIGRATIONS)
+                    .map_err(DbError::Migration)?;
+                Ok(version.to_string())
+            })
+            .await
+            .expect("Failed to revert the last migration");
+        let out_of_date = db.check_schema_version().await;
+        let applied = db.migrate().await.expect("Failed to migrate");
+        // endregion: Action
+
+        // region: Assert
+        assert!(up_to_date.is_ok());
+        match out_of_date {
+            Err(DbError::PendingMigrations(pending)) => assert_eq!(pending.len(), 1),
+            other => panic!("Expected pending migrations, got {other:?}"),
+        }
+        assert_eq!(applied, vec![reverted]);
+        assert!(db.check_schema_version().await.is_ok());
+        // endregion: Assert
+    }
+
+    /// The normalized names of the SMES company `1000000` and the DART company `00000001`.
+    async fn normalized_names(db: &PostgresDb) -> (String, String) {
+        db.run(|conn| {
+            use crate::schema::dart::company_id;
+            use crate::schema::smes::company;
+
+            let smes_normalized: String = company::table
+                .find("1000000")
+                .select(company::normalized_company_name)
+                .first(conn)?;
+            let dart_normalized: String = company_id::table
+                .find("00000001")
+                .select(company_id::normalized_company_name)
+                .first(conn)?;
+            Ok((smes_normalized, dart_normalized))
+        })
+        .await
+        .expect("Failed to get normalized names")
+    }
+
+    /// The `updated_at` of the SMES company `1000000`.
+    async fn updated_at(db: &PostgresDb) -> time::PrimitiveDateTime {
+        db.run(|conn| {
+            use crate::schema::smes::company;
+
+            Ok(company::table
+                .find("1000000")
+                .select(company::updated_at)
+                .first(conn)?)
+        })
+        .await
+        .expect("Failed to get updated_at")
+    }
+
+    #[tokio::test]
+    async fn migrate_should_renormalize_company_names() {
+        // region: Arrange
+        tracing_setup::span!("test");
+        let function_id = utils::function_id!();
+        let mut ctx = PostgresTestContext::new(&function_id).await;
+        ctx.populate_companies(&[1000000]).await;
+        let db = ctx.db();
+
+        // Names the SQL backfill of the migrations normalizes differently
+        const SMES_NAME: &str = "ＡＢＣ Co.,Ltd";
+        const DART_NAME: &str = "Zinc. Korea Inc.";
+        db.run(|conn| {
+            sql_query(
+                "UPDATE smes.company SET company_name = $1, normalized_company_name = 'stale' \
+                 WHERE smes_id = '1000000'",
+            )
+            .bind::<diesel::sql_types::Text, _>(SMES_NAME)
+            .execute(conn)?;
+            sql_query(
+                "INSERT INTO dart.company_id (dart_id, company_name, normalized_company_name) \
+                 VALUES ('00000001', $1, 'stale')",
+            )
+            .bind::<diesel::sql_types::Text, _>(DART_NAME)
+            .execute(conn)?;
+            Ok(())
+        })
+        .await
+        .expect("Failed to store stale normalized names");
+
+        let updated_at_before = updated_at(db).await;
+        // endregion: Arrange
+
+        // region: Action
+        // Names are only recomputed when a migration is applied
+        let up_to_date = db.migrate().await.expect("Failed to migrate");
+        let (smes_stale, _) = normalized_names(db).await;
+        db.run(|conn| {
+            conn.revert_last_migration(MIGRATIONS)
+                .map_err(DbError::Migration)?;
+            Ok(())
+        })
+        .await
+        .expect("Failed to revert the last migration");
+        db.migrate().await.expect("Failed to migrate");
+        // endregion: Action
+
+        // region: Assert
+        let (smes_normalized, dart_normalized) = normalized_names(db).await;
+
+        assert!(up_to_date.is_empty());
+        assert_eq!(smes_stale, "stale");
+        assert_eq!(smes_normalized, "abc");
+        assert_eq!(dart_normalized, "zinc.korea");
+        // Renormalizing isn't an update of the company
+        assert_eq!(updated_at(db).await, updated_at_before);
+        // endregion: Assert
+    }
+
+    /// Revert the migrations down to, and including, the one introducing `smes.html_revision`.
+    async fn revert_html_revision(db: &PostgresDb) -> Result<(), DbError> {
+        db.run(|conn| loop {
+            let reverted = conn
+                .revert_last_migration(MIGRATIONS)
+                .map_err(DbError::Migration)?;
+            if reverted.to_string().replace('-', "") == "20241118064512" {
+                return Ok(());
+            }
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn migrate_should_compress_backfilled_html_revisions() {
+        // region: Arrange
+        tracing_setup::span!("test");
+        let function_id = utils::function_id!();
+        let mut ctx = PostgresTestContext::new(&function_id).await;
+        ctx.populate_companies(&[1000000]).await;
+        let db = ctx.db();
+
+        revert_html_revision(db)
+            .await
+            .expect("Failed to revert the html revision migration");
+        const CONTENT: &str = "<html><body>유동자산</body></html>";
+        db.run(|conn| {
+            sql_query("INSERT INTO smes.html (smes_id, html_content) VALUES ('1000000', $1)")
+                .bind::<diesel::sql_types::Text, _>(CONTENT)
+                .execute(conn)?;
+            Ok(())
+        })
+        .await
+        .expect("Failed to store a page before the html revision migration");
+        // endregion: Arrange
+
+        // region: Action
+        db.migrate().await.expect("Failed to migrate");
+        // endregion: Action
+
+        // region: Assert
+        let body: Vec<u8> = db
+            .run(|conn| {
+                use crate::schema::smes::html_revision;
+
+                Ok(html_revision::table
+                    .filter(html_revision::smes_id.eq("1000000"))
+                    .select(html_revision::body)
+                    .first(conn)?)
+            })
+            .await
+            .expect("Failed to get the html revision");
+        assert!(blob::is_compressed(&body));
+        assert_eq!(
+            blob::decompress(&body).expect("Failed to decompress"),
+            CONTENT
+        );
+        // endregion: Assert
+    }
+
+    #[tokio::test]
+    async fn reverting_html_revision_should_refuse_compressed_bodies() {
+        // region: Arrange
+        tracing_setup::span!("test");
+        let function_id = utils::function_id!();
+        let mut ctx = PostgresTestContext::new(&function_id).await;
+        ctx.populate_htmls(&[1000000]).await;
+        let db = ctx.db();
+        // endregion: Arrange
+
+        // region: Action
+        let reverted = revert_html_revision(db).await;
+        // endregion: Action
+
+        // region: Assert
+        assert!(reverted.is_err());
+        let html_count: i64 = db
+            .run(|conn| {
+                use crate::schema::smes::html;
+
+                Ok(html::table.count().get_result(conn)?)
+            })
+            .await
+            .expect("Failed to count the pages");
+        assert_eq!(html_count, 1);
+        // endregion: Assert
+    }
 }
@@ -6,6 +6,14 @@ pub enum DbError {
     Deserialize(#[from] serde::de::value::Error),
     #[error("Diesel error: {0}")]
     Diesel(#[from] diesel::result::Error),
+    #[error("Connection pool error: {0}")]
+    Pool(#[from] diesel::r2d2::PoolError),
+    #[error("Blocking task error: {0}")]
+    Join(#[from] tokio::task::JoinError),
+    #[error("Migration error: {0}")]
+    Migration(#[from] Box<dyn std::error::Error + Send + Sync>),
+    #[error("The schema is out of date, run `db migrate` to apply: {0:?}")]
+    PendingMigrations(Vec<String>),
     #[error("Io error: {0}")]
     Io(#[from] std::io::Error),
     #[error("Type error: {0}")]
@@ -0,0 +1,261 @@
+//! An in-memory backend, for tests and dry runs which don't need a database server.
+//!
+//! The tables are kept in ordered maps keyed by their primary keys.
+//! Constraints of the Postgres tables are enforced on every write,
+//! and violations are reported as the same [`DatabaseErrorKind`] diesel reports,
+//! so callers can't tell the backends apart by their errors.
+
+mod api_quota;
+mod company;
+mod company_id;
+mod company_link;
+mod company_profile;
+mod document;
+mod filing;
+mod financial_item;
+mod html;
+mod ingest_run;
+mod periodic_report;
+mod table;
+
+use crate::db::Db;
+use crate::error::DbError;
+use crate::model::dart::{
+    ApiQuota, CompanyId, CompanyProfile, Document, Employee, Executive, Filing, FinancialItem,
+    FinancialStatementCheck, FinancialStatementKey, MajorShareholder, PeriodicReportCheck,
+    PeriodicReportKey, PeriodicReportSection,
+};
+use crate::model::ingest::IngestRun;
+use crate::model::link::CompanyLink;
+use crate::model::smes::{Company, HtmlDeadLetter, HtmlRevision, ListSnapshot};
+
+use chrono::NaiveDate;
+use diesel::result::DatabaseErrorKind;
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use table::{Journal, Table};
+use types::{company, filing};
+
+/// A handle to an in-memory database.
+///
+/// Clones share the same tables, like clones of [`crate::PostgresDb`] share the same pool.
+#[derive(Clone, Default)]
+pub struct InMemoryDb {
+    tables: Arc<Mutex<Tables>>,
+    /// The ingest run rows are written with, only set on the handle
+    /// [`crate::ingest::IngestRunDb::start_ingest_run`] returns.
+    run_id: Option<i32>,
+}
+
+#[derive(Default)]
+struct Tables {
+    companies: Table<company::SmesId, Company>,
+    htmls: Table<company::SmesId, html::LatestHtml>,
+    /// Bodies are kept uncompressed, keyed by their hash like in `smes.html_revision`.
+    html_revisions: Table<(company::SmesId, String), HtmlRevision>,
+    html_dead_letters: Table<i32, HtmlDeadLetter>,
+    list_snapshots: Table<i32, ListSnapshot>,
+    list_snapshot_companies: Table<i32, BTreeSet<company::SmesId>>,
+    filings: Table<filing::ReceiptNumber, Filing>,
+    company_ids: Table<company::DartId, CompanyId>,
+    company_profiles: Table<company::DartId, CompanyProfile>,
+    documents: Table<filing::ReceiptNumber, Document>,
+    financial_items: Table<financial_item::FinancialItemKey, FinancialItem>,
+    financial_statement_checks: Table<FinancialStatementKey, FinancialStatementCheck>,
+    major_shareholders: Table<periodic_report::PeriodicReportRowKey, MajorShareholder>,
+    executives: Table<periodic_report::PeriodicReportRowKey, Executive>,
+    employees: Table<periodic_report::PeriodicReportRowKey, Employee>,
+    periodic_report_checks: Table<(PeriodicReportKey, PeriodicReportSection), PeriodicReportCheck>,
+    api_quotas: Table<(String, NaiveDate), ApiQuota>,
+    company_links: Table<(company::SmesId, company::DartId), CompanyLink>,
+    ingest_runs: Table<i32, IngestRun>,
+}
+
+/// The position of every table's undo log, see [`Tables::savepoint`].
+#[derive(Default)]
+struct Savepoint([usize; 19]);
+
+impl Tables {
+    fn journals(&mut self) -> [&mut dyn Journal; 19] {
+        [
+            &mut self.companies,
+            &mut self.htmls,
+            &mut self.html_revisions,
+            &mut self.html_dead_letters,
+            &mut self.list_snapshots,
+            &mut self.list_snapshot_companies,
+            &mut self.filings,
+            &mut self.company_ids,
+            &mut self.company_profiles,
+            &mut self.documents,
+            &mut self.financial_items,
+            &mut self.financial_statement_checks,
+            &mut self.major_shareholders,
+            &mut self.executives,
+            &mut self.employees,
+            &mut self.periodic_report_checks,
+            &mut self.api_quotas,
+            &mut self.company_links,
+            &mut self.ingest_runs,
+        ]
+    }
+
+    fn savepoint(&mut self) -> Savepoint {
+        Savepoint(self.journals().map(|journal| journal.savepoint()))
+    }
+
+    /// Undo every write since `savepoint` was taken.
+    fn rollback_to(&mut self, savepoint: &Savepoint) {
+        for (journal, &position) in self.journals().into_iter().zip(&savepoint.0) {
+            journal.rollback_to(position);
+        }
+    }
+
+    fn commit(&mut self) {
+        for journal in self.journals() {
+            journal.commit();
+        }
+    }
+}
+
+impl Db for InMemoryDb {
+    /// Every call creates an empty database, the path is ignored.
+    #[tracing::instrument]
+    async fn new<P: AsRef<Path> + Debug>(_path: P) -> Result<Self, DbError> {
+        Ok(Self::default())
+    }
+
+    async fn health_check(&mut self) -> Result<(), DbError> {
+        Ok(())
+    }
+}
+
+impl InMemoryDb {
+    fn tables(&self) -> MutexGuard<'_, Tables> {
+        // A panic within `transaction` leaves its writes in the undo logs,
+        // which every transaction empties once it's done, so roll them all back.
+        self.tables.lock().unwrap_or_else(|poisoned| {
+            let mut tables = poisoned.into_inner();
+            tables.rollback_to(&Savepoint::default());
+            tables
+        })
+    }
+
+    /// Run `f` against the tables, rolling back its writes if it fails.
+    fn transaction<T, F>(&self, f: F) -> Result<T, DbError>
+    where
+        F: FnOnce(&mut Tables) -> Result<T, DbError>,
+    {
+        let mut tables = self.tables();
+        let savepoint = tables.savepoint();
+        let result = f(&mut tables);
+        match &result {
+            Ok(_) => tables.commit(),
+            Err(_) => tables.rollback_to(&savepoint),
+        }
+        result
+    }
+}
+
+/// The current time, as `now()` would set it in a `TIMESTAMP` column.
+fn now() -> time::PrimitiveDateTime {
+    let now = time::OffsetDateTime::now_utc();
+    time::PrimitiveDateTime::new(now.date(), now.time())
+}
+
+fn violation(kind: DatabaseErrorKind, message: String) -> DbError {
+    DbError::Diesel(diesel::result::Error::DatabaseError(
+        kind,
+        Box::new(message),
+    ))
+}
+
+/// Mirrors the `CHECK (column ~ '^[0-9]{n}$')` constraints.
+fn check_digits(column: &str, value: &str, digits: usize) -> Result<(), DbError> {
+    if value.len() == digits && value.chars().all(|c| c.is_ascii_digit()) {
+        Ok(())
+    } else {
+        Err(violation(
+            DatabaseErrorKind::CheckViolation,
+            format!("{column} must be {digits} digits, got {value:?}"),
+        ))
+    }
+}
+
+fn unique_violation(table: &str, key: impl Debug) -> DbError {
+    violation(
+        DatabaseErrorKind::UniqueViolation,
+        format!("duplicate key {key:?} in {table}"),
+    )
+}
+
+fn foreign_key_violation(table: &str, key: impl Debug, referenced_table: &str) -> DbError {
+    violation(
+        DatabaseErrorKind::ForeignKeyViolation,
+        format!("{key:?} in {table} is not present in {referenced_table}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smes::{CompanyDb, HtmlDb};
+    use crate::test_utils::{InMemoryTestContext, TestContext};
+
+    #[tokio::test]
+    async fn clones_should_share_tables() {
+        tracing_setup::span!("test");
+        let function_id = utils::function_id!();
+        let mut ctx = InMemoryTestContext::new(&function_id).await;
+        let mut clone = ctx.db().clone();
+
+        let companies = ctx.populate_companies(&[1000000]).await;
+
+        let selected = clone
+            .get_companies()
+            .await
+            .expect("Failed to get companies");
+        assert_eq!(selected.len(), companies.len());
+    }
+
+    #[tokio::test]
+    async fn poisoned_lock_should_keep_committed_dead_letters() {
+        tracing_setup::span!("test");
+
+        // region: Arrange
+        let mut db = InMemoryDb::default();
+        db.transaction(|tables| {
+            tables.html_dead_letters.insert(
+                1,
+                HtmlDeadLetter {
+                    id: 1,
+                    smes_id: "1000000".try_into().expect("Failed to convert"),
+                    html_content: "<html></html>".try_into().expect("Failed to convert"),
+                    error: "duplicate".to_string(),
+                    created_at: now(),
+                },
+            );
+            Ok(())
+        })
+        .expect("Failed to insert dead letter");
+        // endregion: Arrange
+
+        // region: Action
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            db.transaction::<(), _>(|_| panic!("Panicking within a transaction"))
+        }));
+        // endregion: Action
+
+        // region: Assert
+        assert!(panicked.is_err());
+        assert!(db.tables.is_poisoned());
+        let dead_letters = db
+            .select_html_dead_letters()
+            .await
+            .expect("Failed to select dead letters");
+        assert_eq!(dead_letters.len(), 1);
+        // endregion: Assert
+    }
+}
@@ -0,0 +1,89 @@
+use super::{now, violation, Tables};
+use crate::dart::ApiQuotaDb;
+use crate::model::dart::ApiQuota;
+use crate::{DbError, InMemoryDb};
+
+use chrono::NaiveDate;
+use diesel::result::DatabaseErrorKind;
+
+impl ApiQuotaDb for InMemoryDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_api_quotas(&mut self, day: NaiveDate) -> Result<Vec<ApiQuota>, DbError> {
+        // Ordered by key, as the map is ordered by key and day
+        Ok(self
+            .tables()
+            .api_quotas
+            .values()
+            .filter(|quota| quota.day == day)
+            .cloned()
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn reserve_api_request(
+        &mut self,
+        key_id: &str,
+        day: NaiveDate,
+        limit: i32,
+    ) -> Result<bool, DbError> {
+        let run_id = self.run_id;
+        self.transaction(|tables| {
+            let quota = api_quota(tables, key_id, day, run_id)?;
+            if quota.request_count >= limit || quota.exhausted {
+                return Ok(false);
+            }
+            quota.request_count += 1;
+            quota.run_id = run_id;
+            quota.updated_at = now();
+            Ok(true)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn exhaust_api_quota(&mut self, key_id: &str, day: NaiveDate) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.transaction(|tables| {
+            let quota = api_quota(tables, key_id, day, run_id)?;
+            quota.exhausted = true;
+            quota.run_id = run_id;
+            quota.updated_at = now();
+            Ok(())
+        })
+    }
+}
+
+/// The quota of `key_id` on `day`, inserted with no requests if there is none.
+fn api_quota<'a>(
+    tables: &'a mut Tables,
+    key_id: &str,
+    day: NaiveDate,
+    run_id: Option<i32>,
+) -> Result<&'a mut ApiQuota, DbError> {
+    check_key_id(key_id)?;
+    Ok(tables
+        .api_quotas
+        .get_or_insert_with((key_id.to_string(), day), || {
+            let now = now();
+            ApiQuota {
+                key_id: key_id.to_string(),
+                day,
+                request_count: 0,
+                exhausted: false,
+                run_id,
+                created_at: now,
+                updated_at: now,
+            }
+        }))
+}
+
+/// Mirrors the `CHECK (key_id ~ '^[0-9a-f]{16}$')` constraint of `dart.api_quota`.
+fn check_key_id(key_id: &str) -> Result<(), DbError> {
+    if key_id.len() == 16 && key_id.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f')) {
+        Ok(())
+    } else {
+        Err(violation(
+            DatabaseErrorKind::CheckViolation,
+            format!("dart.api_quota.key_id must be 16 lowercase hex digits, got {key_id:?}"),
+        ))
+    }
+}
@@ -0,0 +1,245 @@
+use super::{check_digits, foreign_key_violation, now, unique_violation, Tables};
+use crate::model::smes::{Company, ListSnapshot, ListSnapshotDiff, NewCompany};
+use crate::query::{CompanyQuery, Page};
+use crate::smes::CompanyDb;
+use crate::{DbError, InMemoryDb};
+
+use hashbrown::HashSet;
+use std::collections::BTreeSet;
+use types::company;
+
+impl CompanyDb for InMemoryDb {
+    async fn get_companies(&mut self) -> Result<Vec<Company>, DbError> {
+        Ok(self.tables().companies.values().cloned().collect())
+    }
+
+    async fn get_smes_ids(&mut self) -> Result<HashSet<company::SmesId>, DbError> {
+        Ok(self.tables().companies.keys().cloned().collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn query_companies(
+        &mut self,
+        query: &CompanyQuery,
+    ) -> Result<Page<Company, company::SmesId>, DbError> {
+        let tables = self.tables();
+        let companies = tables
+            .companies
+            .values()
+            .filter(|c| query.matches(c, tables.htmls.contains_key(&c.smes_id)))
+            .take(query.page_size)
+            .cloned()
+            .collect();
+        Ok(Page::new(
+            companies,
+            query.page_size,
+            |company: &Company| company.smes_id.clone(),
+        ))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_companies_by_name(&mut self, name: &str) -> Result<Vec<Company>, DbError> {
+        let normalized_name = company::NormalizedName::from_name(name);
+        Ok(self
+            .tables()
+            .companies
+            .values()
+            .filter(|c| c.company_name.normalized() == normalized_name)
+            .cloned()
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self, companies))]
+    async fn insert_companies(&mut self, companies: Vec<NewCompany>) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.transaction(|tables| {
+            let now = now();
+            for company in companies {
+                check_company(&company)?;
+                if tables.companies.contains_key(&company.smes_id) {
+                    return Err(unique_violation("smes.company", &company.smes_id));
+                }
+                tables.companies.insert(
+                    company.smes_id.clone(),
+                    new_company_row(company, now, run_id),
+                );
+            }
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(skip(self, companies))]
+    async fn upsert_companies(&mut self, companies: Vec<NewCompany>) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.transaction(|tables| {
+            let now = now();
+            for company in companies {
+                check_company(&company)?;
+                match tables.companies.get_mut(&company.smes_id) {
+                    Some(existing) => {
+                        // `updated_at` is only touched when the row changes,
+                        // as the `diesel_manage_updated_at` trigger does.
+                        if NewCompany::from(existing.clone()) != company
+                            || existing.run_id != run_id
+                        {
+                            *existing = Company {
+                                created_at: existing.created_at,
+                                last_seen_at: existing.last_seen_at,
+                                delisted_at: existing.delisted_at,
+                                ..new_company_row(company, now, run_id)
+                            };
+                        }
+                    }
+                    None => {
+                        tables.companies.insert(
+                            company.smes_id.clone(),
+                            new_company_row(company, now, run_id),
+                        );
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(skip(self, smes_ids))]
+    async fn insert_list_snapshot(
+        &mut self,
+        smes_ids: HashSet<company::SmesId>,
+    ) -> Result<ListSnapshot, DbError> {
+        self.transaction(|tables| {
+            let snapshot = ListSnapshot {
+                snapshot_id: tables
+                    .list_snapshots
+                    .last_key_value()
+                    .map_or(1, |(snapshot_id, _)| snapshot_id + 1),
+                company_count: smes_ids.len() as i32,
+                created_at: now(),
+            };
+
+            for smes_id in &smes_ids {
+                check_digits(
+                    "smes.list_snapshot_company.smes_id",
+                    smes_id.as_ref().as_str(),
+                    7,
+                )?;
+                if !tables.companies.contains_key(smes_id) {
+                    return Err(foreign_key_violation(
+                        "smes.list_snapshot_company",
+                        smes_id,
+                        "smes.company",
+                    ));
+                }
+            }
+
+            let mut seen_count = 0;
+            let mut delisted_count = 0;
+            // Only the companies which change are written, to keep the undo log short
+            let changed: Vec<company::SmesId> = tables
+                .companies
+                .values()
+                .filter(|company| {
+                    smes_ids.contains(&company.smes_id) || company.delisted_at.is_none()
+                })
+                .map(|company| company.smes_id.clone())
+                .collect();
+            for smes_id in &changed {
+                let Some(company) = tables.companies.get_mut(smes_id) else {
+                    continue;
+                };
+                if smes_ids.contains(&company.smes_id) {
+                    company.last_seen_at = Some(snapshot.created_at);
+                    company.delisted_at = None;
+                    seen_count += 1;
+                } else if company.delisted_at.is_none() {
+                    company.delisted_at = Some(snapshot.created_at);
+                    delisted_count += 1;
+                }
+            }
+
+            tables
+                .list_snapshot_companies
+                .insert(snapshot.snapshot_id, smes_ids.into_iter().collect());
+            tables
+                .list_snapshots
+                .insert(snapshot.snapshot_id, snapshot.clone());
+
+            tracing::info!(
+                snapshot_id = snapshot.snapshot_id,
+                seen_count,
+                delisted_count,
+                "Recorded list snapshot"
+            );
+            Ok(snapshot)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_list_snapshots(&mut self) -> Result<Vec<ListSnapshot>, DbError> {
+        Ok(self.tables().list_snapshots.values().cloned().collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_list_snapshot_diff(
+        &mut self,
+        from_snapshot_id: i32,
+        to_snapshot_id: i32,
+    ) -> Result<ListSnapshotDiff, DbError> {
+        let tables = self.tables();
+        let from = get_list_snapshot_smes_ids(&tables, from_snapshot_id);
+        let to = get_list_snapshot_smes_ids(&tables, to_snapshot_id);
+
+        Ok(ListSnapshotDiff {
+            from_snapshot_id,
+            to_snapshot_id,
+            added: to.difference(from).cloned().collect(),
+            removed: from.difference(to).cloned().collect(),
+        })
+    }
+}
+
+/// An unknown snapshot has no companies, like an empty query result.
+fn get_list_snapshot_smes_ids(tables: &Tables, snapshot_id: i32) -> &BTreeSet<company::SmesId> {
+    static EMPTY: BTreeSet<company::SmesId> = BTreeSet::new();
+    tables
+        .list_snapshot_companies
+        .get(&snapshot_id)
+        .unwrap_or(&EMPTY)
+}
+
+fn check_company(company: &NewCompany) -> Result<(), DbError> {
+    check_digits("smes.company.smes_id", company.smes_id.as_ref().as_str(), 7)?;
+    if let Some(business_registration_number) = &company.business_registration_number {
+        check_digits(
+            "smes.company.business_registration_number",
+            business_registration_number.as_ref().as_str(),
+            10,
+        )?;
+    }
+    check_digits(
+        "smes.company.industry_code",
+        company.industry_code.as_ref().as_str(),
+        5,
+    )
+}
+
+fn new_company_row(
+    company: NewCompany,
+    now: time::PrimitiveDateTime,
+    run_id: Option<i32>,
+) -> Company {
+    Company {
+        smes_id: company.smes_id,
+        representative_name: company.representative_name,
+        headquarters_address: company.headquarters_address,
+        business_registration_number: company.business_registration_number,
+        company_name: company.company_name,
+        industry_code: company.industry_code,
+        industry_name: company.industry_name,
+        created_at: now,
+        updated_at: now,
+        last_seen_at: None,
+        delisted_at: None,
+        run_id,
+    }
+}
@@ -0,0 +1,109 @@
+use super::{check_digits, unique_violation, Tables};
+use crate::dart::CompanyIdDb;
+use crate::model::dart::{CompanyId, CompanyWithFilings, NewFiling};
+use crate::{DbError, InMemoryDb};
+
+use types::company;
+
+impl CompanyIdDb for InMemoryDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_company_ids(&mut self) -> Result<Vec<CompanyId>, DbError> {
+        Ok(self.tables().company_ids.values().cloned().collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_company_ids_by_name(&mut self, name: &str) -> Result<Vec<CompanyId>, DbError> {
+        let normalized_name = company::NormalizedName::from_name(name);
+        Ok(self
+            .tables()
+            .company_ids
+            .values()
+            .filter(|c| c.company_name.normalized() == normalized_name)
+            .cloned()
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_company_with_filings(
+        &mut self,
+        dart_id: &str,
+    ) -> Result<Option<CompanyWithFilings>, DbError> {
+        let tables = self.tables();
+        let Some(company_id) = tables
+            .company_ids
+            .values()
+            .find(|c| c.dart_id.as_ref() == dart_id)
+        else {
+            return Ok(None);
+        };
+        let mut filings: Vec<_> = tables
+            .filings
+            .values()
+            .filter(|filing| filing.dart_id == company_id.dart_id)
+            .cloned()
+            .collect();
+        filings.sort_by(|a, b| {
+            (&b.receipt_date, &b.receipt_number).cmp(&(&a.receipt_date, &a.receipt_number))
+        });
+        Ok(Some(CompanyWithFilings {
+            company_id: company_id.clone(),
+            filings,
+        }))
+    }
+
+    #[tracing::instrument(skip(self, company_ids))]
+    async fn insert_company_ids(&mut self, company_ids: Vec<CompanyId>) -> Result<(), DbError> {
+        self.transaction(|tables| {
+            for company_id in company_ids {
+                check_company_id(&company_id)?;
+                if tables.company_ids.contains_key(&company_id.dart_id) {
+                    return Err(unique_violation("dart.company_id", &company_id.dart_id));
+                }
+                tables
+                    .company_ids
+                    .insert(company_id.dart_id.clone(), company_id);
+            }
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(skip(self, company_ids))]
+    async fn upsert_company_ids(&mut self, company_ids: Vec<CompanyId>) -> Result<(), DbError> {
+        self.transaction(|tables| {
+            for company_id in company_ids {
+                check_company_id(&company_id)?;
+                tables
+                    .company_ids
+                    .insert(company_id.dart_id.clone(), company_id);
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Insert placeholders for the companies of `filings` which have no company id yet.
+pub(super) fn insert_placeholders(tables: &mut Tables, filings: &[NewFiling]) {
+    for placeholder in CompanyId::placeholders_for(filings) {
+        if !tables.company_ids.contains_key(&placeholder.dart_id) {
+            tables
+                .company_ids
+                .insert(placeholder.dart_id.clone(), placeholder);
+        }
+    }
+}
+
+fn check_company_id(company_id: &CompanyId) -> Result<(), DbError> {
+    check_digits(
+        "dart.company_id.dart_id",
+        company_id.dart_id.as_ref().as_str(),
+        8,
+    )?;
+    match &company_id.stock_code {
+        Some(stock_code) => check_digits(
+            "dart.company_id.stock_code",
+            stock_code.as_ref().as_str(),
+            6,
+        ),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,142 @@
+use super::{check_digits, foreign_key_violation, now, violation};
+use crate::link::CompanyLinkDb;
+use crate::model::link::{CompanyLink, LinkedCompany, NewCompanyLink};
+use crate::{DbError, InMemoryDb};
+
+use diesel::result::DatabaseErrorKind;
+use std::collections::BTreeSet;
+use types::company;
+
+impl CompanyLinkDb for InMemoryDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_company_links(&mut self) -> Result<Vec<CompanyLink>, DbError> {
+        Ok(self.tables().company_links.values().cloned().collect())
+    }
+
+    #[tracing::instrument(skip(self, company_links))]
+    async fn replace_company_links(
+        &mut self,
+        company_links: Vec<NewCompanyLink>,
+    ) -> Result<(), DbError> {
+        self.transaction(|tables| {
+            let now = now();
+            let keys: BTreeSet<_> = company_links
+                .iter()
+                .map(|link| (link.smes_id.clone(), link.dart_id.clone()))
+                .collect();
+            tables.company_links.retain(|key, _| keys.contains(key));
+            for link in company_links {
+                check_company_link(&link)?;
+                if !tables.companies.contains_key(&link.smes_id) {
+                    return Err(foreign_key_violation(
+                        "company_link",
+                        &link.smes_id,
+                        "smes.company",
+                    ));
+                }
+                if !tables.company_ids.contains_key(&link.dart_id) {
+                    return Err(foreign_key_violation(
+                        "company_link",
+                        &link.dart_id,
+                        "dart.company_id",
+                    ));
+                }
+
+                let key = (link.smes_id.clone(), link.dart_id.clone());
+                match tables.company_links.get_mut(&key) {
+                    Some(existing) => {
+                        if NewCompanyLink::from(existing.clone()) != link {
+                            *existing = CompanyLink {
+                                created_at: existing.created_at,
+                                ..new_company_link_row(link, now)
+                            };
+                        }
+                    }
+                    None => {
+                        tables
+                            .company_links
+                            .insert(key, new_company_link_row(link, now));
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_linked_company(
+        &mut self,
+        smes_id: &str,
+    ) -> Result<Option<LinkedCompany>, DbError> {
+        let smes_id = company::SmesId::from(smes_id.to_string());
+        let tables = self.tables();
+        let Some(company) = tables.companies.get(&smes_id).cloned() else {
+            return Ok(None);
+        };
+
+        let links: Vec<CompanyLink> = tables
+            .company_links
+            .values()
+            .filter(|link| link.smes_id == smes_id)
+            .cloned()
+            .collect();
+
+        let html = super::html::select_latest_html(&tables, &smes_id);
+
+        let filings = tables
+            .filings
+            .values()
+            .filter(|filing| links.iter().any(|link| link.dart_id == filing.dart_id))
+            .cloned()
+            .collect();
+
+        Ok(Some(LinkedCompany {
+            links,
+            company,
+            html,
+            filings,
+        }))
+    }
+}
+
+fn check_company_link(link: &NewCompanyLink) -> Result<(), DbError> {
+    check_digits("company_link.smes_id", link.smes_id.as_ref().as_str(), 7)?;
+    check_digits("company_link.dart_id", link.dart_id.as_ref().as_str(), 8)?;
+    if let Some(business_registration_number) = &link.business_registration_number {
+        check_digits(
+            "company_link.business_registration_number",
+            business_registration_number.as_ref().as_str(),
+            10,
+        )?;
+    }
+    if let Some(corporation_registration_number) = &link.corporation_registration_number {
+        check_digits(
+            "company_link.corporation_registration_number",
+            corporation_registration_number.as_ref().as_str(),
+            13,
+        )?;
+    }
+    if !(link.confidence > 0.0 && link.confidence <= 1.0) {
+        return Err(violation(
+            DatabaseErrorKind::CheckViolation,
+            format!(
+                "company_link.confidence must be in (0, 1], got {}",
+                link.confidence
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn new_company_link_row(link: NewCompanyLink, now: time::PrimitiveDateTime) -> CompanyLink {
+    CompanyLink {
+        smes_id: link.smes_id,
+        dart_id: link.dart_id,
+        business_registration_number: link.business_registration_number,
+        corporation_registration_number: link.corporation_registration_number,
+        match_method: link.match_method,
+        confidence: link.confidence,
+        created_at: now,
+        updated_at: now,
+    }
+}
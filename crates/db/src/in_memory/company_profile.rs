@@ -0,0 +1,125 @@
+use super::{check_digits, foreign_key_violation, now, violation};
+use crate::dart::CompanyProfileDb;
+use crate::model::dart::{CompanyProfile, NewCompanyProfile};
+use crate::{DbError, InMemoryDb};
+
+use diesel::result::DatabaseErrorKind;
+
+impl CompanyProfileDb for InMemoryDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_company_profiles(&mut self) -> Result<Vec<CompanyProfile>, DbError> {
+        Ok(self.tables().company_profiles.values().cloned().collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_company_profile(
+        &mut self,
+        dart_id: &str,
+    ) -> Result<Option<CompanyProfile>, DbError> {
+        Ok(self
+            .tables()
+            .company_profiles
+            .values()
+            .find(|profile| profile.dart_id.as_ref() == dart_id)
+            .cloned())
+    }
+
+    #[tracing::instrument(skip(self, profiles))]
+    async fn upsert_company_profiles(
+        &mut self,
+        profiles: Vec<NewCompanyProfile>,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.transaction(|tables| {
+            let now = now();
+            for profile in profiles {
+                check_company_profile(&profile)?;
+                if !tables.company_ids.contains_key(&profile.dart_id) {
+                    return Err(foreign_key_violation(
+                        "dart.company_profile",
+                        &profile.dart_id,
+                        "dart.company_id",
+                    ));
+                }
+                match tables.company_profiles.get_mut(&profile.dart_id) {
+                    Some(existing) => {
+                        if NewCompanyProfile::from(existing.clone()) != profile
+                            || existing.run_id != run_id
+                        {
+                            *existing = CompanyProfile {
+                                created_at: existing.created_at,
+                                ..new_company_profile_row(profile, now, run_id)
+                            };
+                        }
+                    }
+                    None => {
+                        tables.company_profiles.insert(
+                            profile.dart_id.clone(),
+                            new_company_profile_row(profile, now, run_id),
+                        );
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Mirrors the `CHECK` constraints of `dart.company_profile`.
+fn check_company_profile(profile: &NewCompanyProfile) -> Result<(), DbError> {
+    check_digits(
+        "dart.company_profile.dart_id",
+        profile.dart_id.as_ref().as_str(),
+        8,
+    )?;
+    if let Some(number) = &profile.corporation_registration_number {
+        check_digits(
+            "dart.company_profile.corporation_registration_number",
+            number.as_ref().as_str(),
+            13,
+        )?;
+    }
+    if let Some(number) = &profile.business_registration_number {
+        check_digits(
+            "dart.company_profile.business_registration_number",
+            number.as_ref().as_str(),
+            10,
+        )?;
+    }
+    if let Some(industry_code) = &profile.industry_code {
+        check_digits(
+            "dart.company_profile.industry_code",
+            industry_code.as_ref().as_str(),
+            5,
+        )?;
+    }
+    if let Some(fiscal_month) = profile.fiscal_month {
+        if !(1..=12).contains(&fiscal_month) {
+            return Err(violation(
+                DatabaseErrorKind::CheckViolation,
+                format!("fiscal_month must be between 1 and 12, got {fiscal_month}"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn new_company_profile_row(
+    profile: NewCompanyProfile,
+    now: time::PrimitiveDateTime,
+    run_id: Option<i32>,
+) -> CompanyProfile {
+    CompanyProfile {
+        dart_id: profile.dart_id,
+        corporation_registration_number: profile.corporation_registration_number,
+        business_registration_number: profile.business_registration_number,
+        representative_name: profile.representative_name,
+        headquarters_address: profile.headquarters_address,
+        industry_code: profile.industry_code,
+        established_date: profile.established_date,
+        fiscal_month: profile.fiscal_month,
+        run_id,
+        created_at: now,
+        updated_at: now,
+    }
+}
@@ -0,0 +1,91 @@
+use super::{check_digits, foreign_key_violation, now, violation};
+use crate::dart::DocumentDb;
+use crate::model::dart::{Document, NewDocument};
+use crate::{DbError, InMemoryDb};
+
+use diesel::result::DatabaseErrorKind;
+use types::filing;
+
+impl DocumentDb for InMemoryDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_document(&mut self, receipt_number: &str) -> Result<Option<Document>, DbError> {
+        Ok(self
+            .tables()
+            .documents
+            .values()
+            .find(|document| document.receipt_number.as_ref() == receipt_number)
+            .cloned())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_receipt_numbers_without_document(
+        &mut self,
+        limit: i64,
+    ) -> Result<Vec<filing::ReceiptNumber>, DbError> {
+        let tables = self.tables();
+        Ok(tables
+            .filings
+            .keys()
+            .rev()
+            .filter(|receipt_number| !tables.documents.contains_key(*receipt_number))
+            .take(usize::try_from(limit).unwrap_or_default())
+            .cloned()
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn upsert_document(&mut self, document: NewDocument) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.transaction(|tables| {
+            check_document(&document)?;
+            if !tables.filings.contains_key(&document.receipt_number) {
+                return Err(foreign_key_violation(
+                    "dart.document",
+                    &document.receipt_number,
+                    "dart.filing",
+                ));
+            }
+            tables.documents.insert(
+                document.receipt_number.clone(),
+                Document {
+                    receipt_number: document.receipt_number,
+                    sha256: document.sha256,
+                    byte_size: document.byte_size,
+                    run_id,
+                    checked_at: now(),
+                },
+            );
+            Ok(())
+        })
+    }
+}
+
+/// Mirrors the `CHECK` constraints of `dart.document`.
+fn check_document(document: &NewDocument) -> Result<(), DbError> {
+    check_digits(
+        "dart.document.receipt_number",
+        document.receipt_number.as_ref().as_str(),
+        14,
+    )?;
+    if let Some(sha256) = &document.sha256 {
+        if sha256.len() != 64 || !sha256.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f')) {
+            return Err(violation(
+                DatabaseErrorKind::CheckViolation,
+                format!("sha256 must be 64 lowercase hex digits, got {sha256:?}"),
+            ));
+        }
+    }
+    if let Some(byte_size) = document.byte_size.filter(|&byte_size| byte_size < 0) {
+        return Err(violation(
+            DatabaseErrorKind::CheckViolation,
+            format!("byte_size must not be negative, got {byte_size}"),
+        ));
+    }
+    if document.sha256.is_some() != document.byte_size.is_some() {
+        return Err(violation(
+            DatabaseErrorKind::CheckViolation,
+            "sha256 and byte_size must both be set or both be NULL".to_string(),
+        ));
+    }
+    Ok(())
+}
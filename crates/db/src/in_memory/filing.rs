@@ -0,0 +1,121 @@
+use super::company_id::insert_placeholders;
+use super::{check_digits, now, unique_violation};
+use crate::dart::FilingDb;
+use crate::model::dart::{Filing, NewFiling};
+use crate::query::{FilingQuery, Page};
+use crate::{DbError, InMemoryDb};
+
+use types::filing;
+
+impl FilingDb for InMemoryDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_filings(&mut self) -> Result<Vec<Filing>, DbError> {
+        Ok(self.tables().filings.values().cloned().collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn query_filings(
+        &mut self,
+        query: &FilingQuery,
+    ) -> Result<Page<Filing, filing::ReceiptNumber>, DbError> {
+        let filings = self
+            .tables()
+            .filings
+            .values()
+            .filter(|filing| query.matches(filing))
+            .take(query.page_size)
+            .cloned()
+            .collect();
+        Ok(Page::new(filings, query.page_size, |filing: &Filing| {
+            filing.receipt_number.clone()
+        }))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_latest_receipt_date(&mut self) -> Result<Option<filing::ReceiptDate>, DbError> {
+        Ok(self
+            .tables()
+            .filings
+            .values()
+            .map(|filing| filing.receipt_date.clone())
+            .max())
+    }
+
+    #[tracing::instrument(skip(self, filings))]
+    async fn insert_filings(&mut self, filings: Vec<NewFiling>) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.transaction(|tables| {
+            let now = now();
+            insert_placeholders(tables, &filings);
+            for filing in filings {
+                check_filing(&filing)?;
+                if tables.filings.contains_key(&filing.receipt_number) {
+                    return Err(unique_violation("dart.filing", &filing.receipt_number));
+                }
+                tables.filings.insert(
+                    filing.receipt_number.clone(),
+                    new_filing_row(filing, now, run_id),
+                );
+            }
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(skip(self, filings))]
+    async fn upsert_filings(&mut self, filings: Vec<NewFiling>) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.transaction(|tables| {
+            let now = now();
+            insert_placeholders(tables, &filings);
+            for filing in filings {
+                check_filing(&filing)?;
+                match tables.filings.get_mut(&filing.receipt_number) {
+                    Some(existing) => {
+                        if NewFiling::from(existing.clone()) != filing || existing.run_id != run_id
+                        {
+                            *existing = Filing {
+                                created_at: existing.created_at,
+                                ..new_filing_row(filing, now, run_id)
+                            };
+                        }
+                    }
+                    None => {
+                        tables.filings.insert(
+                            filing.receipt_number.clone(),
+                            new_filing_row(filing, now, run_id),
+                        );
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+fn check_filing(filing: &NewFiling) -> Result<(), DbError> {
+    check_digits("dart.filing.dart_id", filing.dart_id.as_ref().as_str(), 8)?;
+    check_digits(
+        "dart.filing.receipt_number",
+        filing.receipt_number.as_ref().as_str(),
+        14,
+    )
+}
+
+/// The generated flags are derived from the remark, like the generated columns of `dart.filing`.
+fn new_filing_row(filing: NewFiling, now: time::PrimitiveDateTime, run_id: Option<i32>) -> Filing {
+    let flags = filing.remark.flags();
+    Filing {
+        dart_id: filing.dart_id,
+        report_name: filing.report_name,
+        receipt_number: filing.receipt_number,
+        filer_name: filing.filer_name,
+        receipt_date: filing.receipt_date,
+        remark: filing.remark,
+        created_at: now,
+        updated_at: now,
+        run_id,
+        is_consolidated: flags.consolidated,
+        is_corrected: flags.corrected,
+        is_withdrawn: flags.withdrawn,
+    }
+}
@@ -0,0 +1,208 @@
+use super::{check_digits, foreign_key_violation, now, unique_violation, violation};
+use crate::dart::{group_by_statement, FinancialItemDb};
+use crate::model::dart::{
+    FinancialItem, FinancialStatementCheck, FinancialStatementKey, NewFinancialItem,
+    NewFinancialStatementCheck, Statement,
+};
+use crate::{DbError, InMemoryDb};
+
+use diesel::result::DatabaseErrorKind;
+
+/// The primary key of `dart.financial_item`.
+pub(super) type FinancialItemKey = (FinancialStatementKey, Statement, i32, String);
+
+impl FinancialItemDb for InMemoryDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_financial_items(&mut self, dart_id: &str) -> Result<Vec<FinancialItem>, DbError> {
+        Ok(self
+            .tables()
+            .financial_items
+            .values()
+            .filter(|item| item.dart_id.as_ref() == dart_id)
+            .cloned()
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_financial_statement_receipts(
+        &mut self,
+    ) -> Result<Vec<(FinancialStatementKey, filing::ReceiptNumber)>, DbError> {
+        let mut receipts: Vec<_> = self
+            .tables()
+            .financial_items
+            .iter()
+            .map(|((key, ..), item)| (key.clone(), item.receipt_number.clone()))
+            .collect();
+        receipts.dedup();
+        Ok(receipts)
+    }
+
+    #[tracing::instrument(skip(self, items))]
+    async fn replace_financial_statements(
+        &mut self,
+        items: Vec<NewFinancialItem>,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        for (key, items) in group_by_statement(items) {
+            tracing::trace!(?key, count = items.len(), "Replacing financial statement");
+            self.transaction(|tables| {
+                tables
+                    .financial_items
+                    .retain(|(statement_key, ..), _| statement_key != &key);
+                let now = now();
+                for item in items {
+                    check_financial_item(&item)?;
+                    if !tables.company_ids.contains_key(&item.dart_id) {
+                        return Err(foreign_key_violation(
+                            "dart.financial_item",
+                            &item.dart_id,
+                            "dart.company_id",
+                        ));
+                    }
+                    let item_key = (
+                        key.clone(),
+                        item.statement,
+                        item.ord,
+                        item.account_detail.clone(),
+                    );
+                    if tables.financial_items.contains_key(&item_key) {
+                        return Err(unique_violation("dart.financial_item", &item_key));
+                    }
+                    tables
+                        .financial_items
+                        .insert(item_key, new_financial_item_row(item, now, run_id));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_financial_statement_checks(
+        &mut self,
+    ) -> Result<Vec<FinancialStatementCheck>, DbError> {
+        Ok(self
+            .tables()
+            .financial_statement_checks
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn upsert_financial_statement_check(
+        &mut self,
+        check: NewFinancialStatementCheck,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.transaction(|tables| {
+            check_financial_statement_check(&check)?;
+            if !tables.company_ids.contains_key(&check.dart_id) {
+                return Err(foreign_key_violation(
+                    "dart.financial_statement_check",
+                    &check.dart_id,
+                    "dart.company_id",
+                ));
+            }
+            let key = FinancialStatementKey {
+                dart_id: check.dart_id.clone(),
+                business_year: check.business_year,
+                report_code: check.report_code,
+                fs_div: check.fs_div,
+            };
+            tables.financial_statement_checks.insert(
+                key,
+                FinancialStatementCheck {
+                    dart_id: check.dart_id,
+                    business_year: check.business_year,
+                    report_code: check.report_code,
+                    fs_div: check.fs_div,
+                    item_count: check.item_count,
+                    skipped_count: check.skipped_count,
+                    run_id,
+                    checked_at: now(),
+                },
+            );
+            Ok(())
+        })
+    }
+}
+
+/// Mirrors the `CHECK` constraints of `dart.financial_item`
+/// which the types of [`NewFinancialItem`] don't enforce.
+fn check_financial_item(item: &NewFinancialItem) -> Result<(), DbError> {
+    check_digits(
+        "dart.financial_item.dart_id",
+        item.dart_id.as_ref().as_str(),
+        8,
+    )?;
+    check_digits(
+        "dart.financial_item.receipt_number",
+        item.receipt_number.as_ref().as_str(),
+        14,
+    )?;
+    if !(2015..=9999).contains(&item.business_year) {
+        return Err(violation(
+            DatabaseErrorKind::CheckViolation,
+            format!(
+                "business_year must be between 2015 and 9999, got {}",
+                item.business_year
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Mirrors the `CHECK` constraints of `dart.financial_statement_check`.
+fn check_financial_statement_check(check: &NewFinancialStatementCheck) -> Result<(), DbError> {
+    check_digits(
+        "dart.financial_statement_check.dart_id",
+        check.dart_id.as_ref().as_str(),
+        8,
+    )?;
+    if !(2015..=9999).contains(&check.business_year) {
+        return Err(violation(
+            DatabaseErrorKind::CheckViolation,
+            format!(
+                "business_year must be between 2015 and 9999, got {}",
+                check.business_year
+            ),
+        ));
+    }
+    if check.item_count < 0 || check.skipped_count < 0 {
+        return Err(violation(
+            DatabaseErrorKind::CheckViolation,
+            format!(
+                "item_count and skipped_count must not be negative, got {} and {}",
+                check.item_count, check.skipped_count
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn new_financial_item_row(
+    item: NewFinancialItem,
+    now: time::PrimitiveDateTime,
+    run_id: Option<i32>,
+) -> FinancialItem {
+    FinancialItem {
+        dart_id: item.dart_id,
+        business_year: item.business_year,
+        report_code: item.report_code,
+        fs_div: item.fs_div,
+        statement: item.statement,
+        ord: item.ord,
+        account_detail: item.account_detail,
+        account_id: item.account_id,
+        account_name: item.account_name,
+        current_amount: item.current_amount,
+        previous_amount: item.previous_amount,
+        before_previous_amount: item.before_previous_amount,
+        currency: item.currency,
+        receipt_number: item.receipt_number,
+        run_id,
+        created_at: now,
+    }
+}
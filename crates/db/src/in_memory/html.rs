@@ -0,0 +1,218 @@
+use super::{check_digits, foreign_key_violation, now, unique_violation, Tables};
+use crate::batch::{self, BATCH_SIZE, BATCH_TIMEOUT};
+use crate::blob::sha256_hex;
+use crate::model::smes::{Html, HtmlDeadLetter, HtmlRevision, NewHtml};
+use crate::smes::HtmlDb;
+use crate::{DbError, InMemoryDb};
+
+use hashbrown::HashSet;
+use tokio::sync::mpsc::UnboundedReceiver;
+use types::company;
+
+/// A row of `smes.html`, which points to the latest revision.
+#[derive(Clone)]
+pub(super) struct LatestHtml {
+    sha256: String,
+    created_at: time::PrimitiveDateTime,
+    updated_at: time::PrimitiveDateTime,
+    run_id: Option<i32>,
+}
+
+impl HtmlDb for InMemoryDb {
+    async fn select_html(&mut self, smes_id: &str) -> Result<Option<Html>, DbError> {
+        Ok(select_latest_html(
+            &self.tables(),
+            &company::SmesId::from(smes_id.to_string()),
+        ))
+    }
+
+    async fn select_htmls(&mut self) -> Result<Vec<Html>, DbError> {
+        let tables = self.tables();
+        Ok(tables
+            .htmls
+            .keys()
+            .filter_map(|smes_id| select_latest_html(&tables, smes_id))
+            .collect())
+    }
+
+    async fn select_html_ids(&mut self) -> Result<HashSet<company::SmesId>, DbError> {
+        Ok(self.tables().htmls.keys().cloned().collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn select_html_revisions(&mut self, smes_id: &str) -> Result<Vec<HtmlRevision>, DbError> {
+        let smes_id = company::SmesId::from(smes_id.to_string());
+        let mut revisions: Vec<HtmlRevision> = self
+            .tables()
+            .html_revisions
+            .values()
+            .filter(|revision| revision.smes_id == smes_id)
+            .cloned()
+            .collect();
+        revisions.sort_by_key(|revision| revision.fetched_at);
+        Ok(revisions)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn select_html_dead_letters(&mut self) -> Result<Vec<HtmlDeadLetter>, DbError> {
+        Ok(self.tables().html_dead_letters.values().cloned().collect())
+    }
+
+    #[tracing::instrument(skip(self, htmls))]
+    async fn insert_html_channel(
+        &mut self,
+        mut htmls: UnboundedReceiver<NewHtml>,
+    ) -> Result<(), DbError> {
+        while let Some(batch) = batch::recv_batch(&mut htmls, BATCH_SIZE, BATCH_TIMEOUT).await {
+            tracing::trace!(count = batch.len(), "Inserting htmls");
+            self.transaction(|tables| write_batch(tables, batch, self.run_id, insert_html))?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, htmls))]
+    async fn upsert_html_channel(
+        &mut self,
+        mut htmls: UnboundedReceiver<NewHtml>,
+    ) -> Result<(), DbError> {
+        while let Some(batch) = batch::recv_batch(&mut htmls, BATCH_SIZE, BATCH_TIMEOUT).await {
+            tracing::trace!(count = batch.len(), "Upserting htmls");
+            self.transaction(|tables| write_batch(tables, batch, self.run_id, upsert_html))?;
+        }
+        Ok(())
+    }
+}
+
+/// Write `htmls`, moving the pages which violate a constraint to the dead-letter table.
+fn write_batch(
+    tables: &mut Tables,
+    htmls: Vec<NewHtml>,
+    run_id: Option<i32>,
+    write: fn(&mut Tables, NewHtml, Option<i32>) -> Result<(), DbError>,
+) -> Result<(), DbError> {
+    for html in htmls {
+        // Roll back to a savepoint, so a rejected page leaves the batch intact
+        let savepoint = tables.savepoint();
+        match write(tables, html.clone(), run_id) {
+            Ok(()) => {}
+            Err(e) if batch::is_rejected_row(&e) => {
+                tables.rollback_to(&savepoint);
+                tracing::warn!(smes_id = %html.smes_id, error = %e, "Moving html to the dead-letter table");
+                let id = tables
+                    .html_dead_letters
+                    .last_key_value()
+                    .map_or(1, |(id, _)| id + 1);
+                tables.html_dead_letters.insert(
+                    id,
+                    HtmlDeadLetter {
+                        id,
+                        smes_id: html.smes_id,
+                        html_content: html.html_content,
+                        error: e.to_string(),
+                        created_at: now(),
+                    },
+                );
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+fn insert_html(tables: &mut Tables, html: NewHtml, run_id: Option<i32>) -> Result<(), DbError> {
+    let smes_id = html.smes_id.clone();
+    let sha256 = insert_revision(tables, html)?;
+    if tables.htmls.contains_key(&smes_id) {
+        return Err(unique_violation("smes.html", &smes_id));
+    }
+    let now = now();
+    tables.htmls.insert(
+        smes_id,
+        LatestHtml {
+            sha256,
+            created_at: now,
+            updated_at: now,
+            run_id,
+        },
+    );
+    Ok(())
+}
+
+fn upsert_html(tables: &mut Tables, html: NewHtml, run_id: Option<i32>) -> Result<(), DbError> {
+    let smes_id = html.smes_id.clone();
+    let sha256 = sha256_hex(html.html_content.as_ref().as_str());
+    if tables
+        .htmls
+        .get(&smes_id)
+        .is_some_and(|latest| latest.sha256 == sha256)
+    {
+        tracing::trace!(?smes_id, "Skipping unchanged html");
+        return Ok(());
+    }
+
+    let sha256 = insert_revision(tables, html)?;
+    let now = now();
+    match tables.htmls.get_mut(&smes_id) {
+        Some(existing) => {
+            existing.sha256 = sha256;
+            existing.updated_at = now;
+            existing.run_id = run_id;
+        }
+        None => {
+            tables.htmls.insert(
+                smes_id,
+                LatestHtml {
+                    sha256,
+                    created_at: now,
+                    updated_at: now,
+                    run_id,
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Join `smes.html` with the revision it points to.
+pub(super) fn select_latest_html(tables: &Tables, smes_id: &company::SmesId) -> Option<Html> {
+    let latest = tables.htmls.get(smes_id)?;
+    let revision = tables
+        .html_revisions
+        .get(&(smes_id.clone(), latest.sha256.clone()))?;
+    Some(Html {
+        smes_id: smes_id.clone(),
+        html_content: revision.html_content.clone(),
+        created_at: latest.created_at,
+        updated_at: latest.updated_at,
+        run_id: latest.run_id,
+    })
+}
+
+/// Insert the revision, or mark the same content as fetched again, and return its hash.
+fn insert_revision(tables: &mut Tables, html: NewHtml) -> Result<String, DbError> {
+    check_digits(
+        "smes.html_revision.smes_id",
+        html.smes_id.as_ref().as_str(),
+        7,
+    )?;
+    if !tables.companies.contains_key(&html.smes_id) {
+        return Err(foreign_key_violation(
+            "smes.html_revision",
+            &html.smes_id,
+            "smes.company",
+        ));
+    }
+
+    let sha256 = sha256_hex(html.html_content.as_ref().as_str());
+    let key = (html.smes_id.clone(), sha256.clone());
+    let revision = tables
+        .html_revisions
+        .get_or_insert_with(key, || HtmlRevision {
+            smes_id: html.smes_id,
+            sha256: sha256.clone(),
+            fetched_at: now(),
+            html_content: html.html_content,
+        });
+    revision.fetched_at = now();
+    Ok(sha256)
+}
@@ -0,0 +1,93 @@
+use super::{now, violation};
+use crate::ingest::IngestRunDb;
+use crate::model::ingest::{IngestCounts, IngestRun, NewIngestRun};
+use crate::{DbError, InMemoryDb};
+
+use diesel::result::DatabaseErrorKind;
+
+impl IngestRunDb for InMemoryDb {
+    #[tracing::instrument(skip(self))]
+    async fn start_ingest_run(&mut self, run: NewIngestRun) -> Result<(IngestRun, Self), DbError> {
+        let run = self.transaction(|tables| {
+            // Like `SERIAL`, as runs are never deleted.
+            let run_id = tables
+                .ingest_runs
+                .last_key_value()
+                .map_or(1, |(run_id, _)| run_id + 1);
+            let run = IngestRun {
+                run_id,
+                runner: run.runner,
+                git_rev: run.git_rev,
+                config: run.config,
+                started_at: now(),
+                finished_at: None,
+                inserted_count: None,
+                updated_count: None,
+                unchanged_count: None,
+                source_url: run.source_url,
+                error: None,
+            };
+            tables.ingest_runs.insert(run_id, run.clone());
+            Ok(run)
+        })?;
+        tracing::info!(run_id = run.run_id, runner = %run.runner, "Started ingest run");
+        let run_db = Self {
+            run_id: Some(run.run_id),
+            ..self.clone()
+        };
+        Ok((run, run_db))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn finish_ingest_run(
+        &mut self,
+        run_id: i32,
+        counts: IngestCounts,
+    ) -> Result<IngestRun, DbError> {
+        let run = self.transaction(|tables| {
+            check_counts(&counts)?;
+            let run = tables
+                .ingest_runs
+                .get_mut(&run_id)
+                .ok_or(DbError::Diesel(diesel::result::Error::NotFound))?;
+            run.finished_at = Some(now());
+            run.inserted_count = Some(counts.inserted);
+            run.updated_count = Some(counts.updated);
+            run.unchanged_count = Some(counts.unchanged);
+            Ok(run.clone())
+        })?;
+        tracing::info!(run_id, ?counts, "Finished ingest run");
+        Ok(run)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn fail_ingest_run(&mut self, run_id: i32, error: String) -> Result<IngestRun, DbError> {
+        let run = self.transaction(|tables| {
+            let run = tables
+                .ingest_runs
+                .get_mut(&run_id)
+                .ok_or(DbError::Diesel(diesel::result::Error::NotFound))?;
+            run.finished_at = Some(now());
+            run.error = Some(error);
+            Ok(run.clone())
+        })?;
+        tracing::warn!(run_id, error = ?run.error, "Failed ingest run");
+        Ok(run)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_ingest_runs(&mut self) -> Result<Vec<IngestRun>, DbError> {
+        Ok(self.tables().ingest_runs.values().cloned().collect())
+    }
+}
+
+/// Mirrors the `CHECK (... >= 0)` constraints on the counts.
+fn check_counts(counts: &IngestCounts) -> Result<(), DbError> {
+    if counts.inserted < 0 || counts.updated < 0 || counts.unchanged < 0 {
+        return Err(violation(
+            DatabaseErrorKind::CheckViolation,
+            format!("counts of ingest_run must not be negative, got {counts:?}"),
+        ));
+    }
+    Ok(())
+}
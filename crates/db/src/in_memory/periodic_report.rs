@@ -0,0 +1,299 @@
+use super::{check_digits, foreign_key_violation, now, unique_violation, violation, Tables};
+use crate::dart::PeriodicReportDb;
+use crate::model::dart::{
+    Employee, Executive, MajorShareholder, NewEmployee, NewExecutive, NewMajorShareholder,
+    NewPeriodicReportCheck, PeriodicReportCheck, PeriodicReportKey, PeriodicReportSection,
+};
+use crate::{DbError, InMemoryDb};
+
+use diesel::result::DatabaseErrorKind;
+use std::collections::BTreeMap;
+use types::filing;
+
+/// The primary key of `dart.major_shareholder`, `dart.executive` and `dart.employee`.
+pub(super) type PeriodicReportRowKey = (PeriodicReportKey, i32);
+
+impl PeriodicReportDb for InMemoryDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_major_shareholders(
+        &mut self,
+        dart_id: &str,
+    ) -> Result<Vec<MajorShareholder>, DbError> {
+        Ok(rows_of(&self.tables().major_shareholders, dart_id))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_executives(&mut self, dart_id: &str) -> Result<Vec<Executive>, DbError> {
+        Ok(rows_of(&self.tables().executives, dart_id))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_employees(&mut self, dart_id: &str) -> Result<Vec<Employee>, DbError> {
+        Ok(rows_of(&self.tables().employees, dart_id))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_periodic_report_receipts(
+        &mut self,
+        section: PeriodicReportSection,
+    ) -> Result<Vec<(PeriodicReportKey, filing::ReceiptNumber)>, DbError> {
+        let tables = self.tables();
+        let mut receipts: Vec<_> = match section {
+            PeriodicReportSection::MajorShareholder => tables
+                .major_shareholders
+                .iter()
+                .map(|((key, _), row)| (key.clone(), row.receipt_number.clone()))
+                .collect(),
+            PeriodicReportSection::Executive => tables
+                .executives
+                .iter()
+                .map(|((key, _), row)| (key.clone(), row.receipt_number.clone()))
+                .collect(),
+            PeriodicReportSection::Employee => tables
+                .employees
+                .iter()
+                .map(|((key, _), row)| (key.clone(), row.receipt_number.clone()))
+                .collect(),
+        };
+        receipts.dedup();
+        Ok(receipts)
+    }
+
+    #[tracing::instrument(skip(self, rows))]
+    async fn replace_major_shareholders(
+        &mut self,
+        key: PeriodicReportKey,
+        rows: Vec<NewMajorShareholder>,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.transaction(|tables| {
+            tables
+                .major_shareholders
+                .retain(|(report_key, _), _| report_key != &key);
+            let now = now();
+            for row in rows {
+                check_report(tables, "dart.major_shareholder", &key)?;
+                check_row("dart.major_shareholder", row.ord, &row.receipt_number)?;
+                let row_key = (key.clone(), row.ord);
+                if tables.major_shareholders.contains_key(&row_key) {
+                    return Err(unique_violation("dart.major_shareholder", &row_key));
+                }
+                tables.major_shareholders.insert(
+                    row_key,
+                    MajorShareholder {
+                        dart_id: key.dart_id.clone(),
+                        business_year: key.business_year,
+                        report_code: key.report_code,
+                        ord: row.ord,
+                        name: row.name,
+                        relation: row.relation,
+                        stock_kind: row.stock_kind,
+                        shares_at_start: row.shares_at_start,
+                        stake_at_start: row.stake_at_start,
+                        shares_at_end: row.shares_at_end,
+                        stake_at_end: row.stake_at_end,
+                        remark: row.remark,
+                        settlement_date: row.settlement_date,
+                        receipt_number: row.receipt_number,
+                        run_id,
+                        created_at: now,
+                    },
+                );
+            }
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(skip(self, rows))]
+    async fn replace_executives(
+        &mut self,
+        key: PeriodicReportKey,
+        rows: Vec<NewExecutive>,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.transaction(|tables| {
+            tables
+                .executives
+                .retain(|(report_key, _), _| report_key != &key);
+            let now = now();
+            for row in rows {
+                check_report(tables, "dart.executive", &key)?;
+                check_row("dart.executive", row.ord, &row.receipt_number)?;
+                let row_key = (key.clone(), row.ord);
+                if tables.executives.contains_key(&row_key) {
+                    return Err(unique_violation("dart.executive", &row_key));
+                }
+                tables.executives.insert(
+                    row_key,
+                    Executive {
+                        dart_id: key.dart_id.clone(),
+                        business_year: key.business_year,
+                        report_code: key.report_code,
+                        ord: row.ord,
+                        name: row.name,
+                        gender: row.gender,
+                        birth_month: row.birth_month,
+                        position: row.position,
+                        registration: row.registration,
+                        is_full_time: row.is_full_time,
+                        responsibility: row.responsibility,
+                        main_career: row.main_career,
+                        relation_to_major_shareholder: row.relation_to_major_shareholder,
+                        tenure: row.tenure,
+                        term_end: row.term_end,
+                        settlement_date: row.settlement_date,
+                        receipt_number: row.receipt_number,
+                        run_id,
+                        created_at: now,
+                    },
+                );
+            }
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(skip(self, rows))]
+    async fn replace_employees(
+        &mut self,
+        key: PeriodicReportKey,
+        rows: Vec<NewEmployee>,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.transaction(|tables| {
+            tables
+                .employees
+                .retain(|(report_key, _), _| report_key != &key);
+            let now = now();
+            for row in rows {
+                check_report(tables, "dart.employee", &key)?;
+                check_row("dart.employee", row.ord, &row.receipt_number)?;
+                let row_key = (key.clone(), row.ord);
+                if tables.employees.contains_key(&row_key) {
+                    return Err(unique_violation("dart.employee", &row_key));
+                }
+                tables.employees.insert(
+                    row_key,
+                    Employee {
+                        dart_id: key.dart_id.clone(),
+                        business_year: key.business_year,
+                        report_code: key.report_code,
+                        ord: row.ord,
+                        business_segment: row.business_segment,
+                        gender: row.gender,
+                        regular_count: row.regular_count,
+                        contract_count: row.contract_count,
+                        total_count: row.total_count,
+                        average_tenure: row.average_tenure,
+                        annual_salary_total: row.annual_salary_total,
+                        average_salary: row.average_salary,
+                        remark: row.remark,
+                        settlement_date: row.settlement_date,
+                        receipt_number: row.receipt_number,
+                        run_id,
+                        created_at: now,
+                    },
+                );
+            }
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_periodic_report_checks(&mut self) -> Result<Vec<PeriodicReportCheck>, DbError> {
+        Ok(self
+            .tables()
+            .periodic_report_checks
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn upsert_periodic_report_check(
+        &mut self,
+        check: NewPeriodicReportCheck,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        self.transaction(|tables| {
+            let key = PeriodicReportKey {
+                dart_id: check.dart_id.clone(),
+                business_year: check.business_year,
+                report_code: check.report_code,
+            };
+            check_report(tables, "dart.periodic_report_check", &key)?;
+            if check.row_count < 0 || check.skipped_count < 0 {
+                return Err(violation(
+                    DatabaseErrorKind::CheckViolation,
+                    format!(
+                        "row_count and skipped_count must not be negative, got {} and {}",
+                        check.row_count, check.skipped_count
+                    ),
+                ));
+            }
+            tables.periodic_report_checks.insert(
+                (key, check.section),
+                PeriodicReportCheck {
+                    dart_id: check.dart_id,
+                    business_year: check.business_year,
+                    report_code: check.report_code,
+                    section: check.section,
+                    row_count: check.row_count,
+                    skipped_count: check.skipped_count,
+                    run_id,
+                    checked_at: now(),
+                },
+            );
+            Ok(())
+        })
+    }
+}
+
+/// The rows of the company with `dart_id`, which the keys order by report and position.
+fn rows_of<T: Clone>(rows: &BTreeMap<PeriodicReportRowKey, T>, dart_id: &str) -> Vec<T> {
+    rows.iter()
+        .filter(|((key, _), _)| key.dart_id.as_ref() == dart_id)
+        .map(|(_, row)| row.clone())
+        .collect()
+}
+
+/// Mirrors the `CHECK` and `FOREIGN KEY` constraints on the report columns of `table`.
+fn check_report(tables: &Tables, table: &str, key: &PeriodicReportKey) -> Result<(), DbError> {
+    check_digits(
+        &format!("{table}.dart_id"),
+        key.dart_id.as_ref().as_str(),
+        8,
+    )?;
+    if !(2015..=9999).contains(&key.business_year) {
+        return Err(violation(
+            DatabaseErrorKind::CheckViolation,
+            format!(
+                "business_year must be between 2015 and 9999, got {}",
+                key.business_year
+            ),
+        ));
+    }
+    if !tables.company_ids.contains_key(&key.dart_id) {
+        return Err(foreign_key_violation(
+            table,
+            &key.dart_id,
+            "dart.company_id",
+        ));
+    }
+    Ok(())
+}
+
+/// Mirrors the `CHECK` constraints on the row columns of `table`.
+fn check_row(table: &str, ord: i32, receipt_number: &filing::ReceiptNumber) -> Result<(), DbError> {
+    check_digits(
+        &format!("{table}.receipt_number"),
+        receipt_number.as_ref().as_str(),
+        14,
+    )?;
+    if ord <= 0 {
+        return Err(violation(
+            DatabaseErrorKind::CheckViolation,
+            format!("ord must be positive, got {ord}"),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+use std::ops::Deref;
+
+/// Something a transaction writes to, which can roll its writes back.
+pub(super) trait Journal {
+    /// The position [`Journal::rollback_to`] returns to.
+    fn savepoint(&self) -> usize;
+    /// Undo the writes since `savepoint`, the newest first.
+    fn rollback_to(&mut self, savepoint: usize);
+    /// Keep the writes, forgetting how to undo them.
+    fn commit(&mut self);
+}
+
+/// Rows ordered by their primary key, which log the row every write replaces.
+///
+/// Reads go through [`BTreeMap`], writes through the methods of the table,
+/// so a transaction costs as much as the rows it writes rather than the whole table.
+pub(super) struct Table<K, V> {
+    rows: BTreeMap<K, V>,
+    /// The key and previous row of every write, oldest first, `None` when the key was vacant.
+    undo_log: Vec<(K, Option<V>)>,
+}
+
+impl<K, V> Default for Table<K, V> {
+    fn default() -> Self {
+        Self {
+            rows: BTreeMap::new(),
+            undo_log: Vec::new(),
+        }
+    }
+}
+
+impl<K, V> Deref for Table<K, V> {
+    type Target = BTreeMap<K, V>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rows
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> Table<K, V> {
+    pub(super) fn insert(&mut self, key: K, row: V) {
+        let previous = self.rows.insert(key.clone(), row);
+        self.undo_log.push((key, previous));
+    }
+
+    pub(super) fn remove(&mut self, key: &K) {
+        if let Some(previous) = self.rows.remove(key) {
+            self.undo_log.push((key.clone(), Some(previous)));
+        }
+    }
+
+    pub(super) fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let previous = self.rows.get(key)?.clone();
+        self.undo_log.push((key.clone(), Some(previous)));
+        self.rows.get_mut(key)
+    }
+
+    /// The row of `key`, inserting `default()` when there is none.
+    pub(super) fn get_or_insert_with(&mut self, key: K, default: impl FnOnce() -> V) -> &mut V {
+        let previous = self.rows.get(&key).cloned();
+        self.undo_log.push((key.clone(), previous));
+        self.rows.entry(key).or_insert_with(default)
+    }
+
+    /// Remove the rows `keep` returns `false` for.
+    pub(super) fn retain(&mut self, mut keep: impl FnMut(&K, &V) -> bool) {
+        let removed: Vec<K> = self
+            .rows
+            .iter()
+            .filter(|(key, row)| !keep(key, row))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &removed {
+            self.remove(key);
+        }
+    }
+}
+
+impl<K: Ord, V> Journal for Table<K, V> {
+    fn savepoint(&self) -> usize {
+        self.undo_log.len()
+    }
+
+    fn rollback_to(&mut self, savepoint: usize) {
+        while self.undo_log.len() > savepoint {
+            let Some((key, previous)) = self.undo_log.pop() else {
+                break;
+            };
+            match previous {
+                Some(previous) => {
+                    self.rows.insert(key, previous);
+                }
+                None => {
+                    self.rows.remove(&key);
+                }
+            }
+        }
+    }
+
+    fn commit(&mut self) {
+        self.undo_log.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rollback_should_restore_rows_in_reverse() {
+        let mut table = Table::default();
+        table.insert(1, "a");
+        table.commit();
+
+        let savepoint = table.savepoint();
+        table.insert(1, "b");
+        table.insert(2, "c");
+        *table.get_mut(&2).expect("Row should exist") = "d";
+        table.remove(&1);
+        table.rollback_to(savepoint);
+
+        assert_eq!(table.iter().collect::<Vec<_>>(), vec![(&1, &"a")]);
+    }
+}
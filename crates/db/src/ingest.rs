@@ -0,0 +1,113 @@
+//! Provenance of stored rows.
+//!
+//! A runner records each of its runs in `ingest_run`,
+//! and the rows it writes to `smes.company`, `smes.html` and `dart.filing` reference the run by `run_id`.
+//! A bad batch can then be traced with e.g. [`crate::query::CompanyQuery::run_id`].
+//!
+//! Starting a run returns a handle of its own, and only rows written through that handle reference the run:
+//!
+//! ```ignore
+//! let (run, mut run_db) = db.start_ingest_run(NewIngestRun { runner: "smes_list".to_string(), .. }).await?;
+//! run_db.upsert_companies(companies).await?;
+//! db.finish_ingest_run(run.run_id, IngestCounts { inserted, ..Default::default() }).await?;
+//! ```
+
+use diesel::prelude::*;
+use std::future::Future;
+
+use crate::model::ingest::{IngestCounts, IngestRun, NewIngestRun};
+use crate::schema::ingest_run::dsl;
+use crate::{DbError, PostgresDb};
+
+pub trait IngestRunDb: Sized {
+    /// Record the start of a run, returning it and a handle whose writes reference it.
+    ///
+    /// The handle the run is started with is left as is.
+    /// Clones of the returned handle share its connections and reference the run as well.
+    fn start_ingest_run(
+        &mut self,
+        run: NewIngestRun,
+    ) -> impl Future<Output = Result<(IngestRun, Self), DbError>>;
+    /// Record the end of the run with `run_id` and the counts of the runner.
+    fn finish_ingest_run(
+        &mut self,
+        run_id: i32,
+        counts: IngestCounts,
+    ) -> impl Future<Output = Result<IngestRun, DbError>>;
+    /// Record the end of the run with `run_id`, which failed with `error`.
+    fn fail_ingest_run(
+        &mut self,
+        run_id: i32,
+        error: String,
+    ) -> impl Future<Output = Result<IngestRun, DbError>>;
+    /// Runs ordered from the oldest to the newest.
+    fn get_ingest_runs(&mut self) -> impl Future<Output = Result<Vec<IngestRun>, DbError>>;
+}
+
+impl IngestRunDb for PostgresDb {
+    #[tracing::instrument(skip(self))]
+    async fn start_ingest_run(&mut self, run: NewIngestRun) -> Result<(IngestRun, Self), DbError> {
+        let run: IngestRun = self
+            .run(move |conn| {
+                Ok(diesel::insert_into(dsl::ingest_run)
+                    .values(&run)
+                    .returning(IngestRun::as_returning())
+                    .get_result(conn)?)
+            })
+            .await?;
+        tracing::info!(run_id = run.run_id, runner = %run.runner, "Started ingest run");
+        let run_db = Self {
+            run_id: Some(run.run_id),
+            ..self.clone()
+        };
+        Ok((run, run_db))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn finish_ingest_run(
+        &mut self,
+        run_id: i32,
+        counts: IngestCounts,
+    ) -> Result<IngestRun, DbError> {
+        let run: IngestRun = self
+            .run(move |conn| {
+                Ok(diesel::update(dsl::ingest_run.find(run_id))
+                    .set((
+                        dsl::finished_at.eq(diesel::dsl::now),
+                        dsl::inserted_count.eq(counts.inserted),
+                        dsl::updated_count.eq(counts.updated),
+                        dsl::unchanged_count.eq(counts.unchanged),
+                    ))
+                    .returning(IngestRun::as_returning())
+                    .get_result(conn)?)
+            })
+            .await?;
+        tracing::info!(run_id, ?counts, "Finished ingest run");
+        Ok(run)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn fail_ingest_run(&mut self, run_id: i32, error: String) -> Result<IngestRun, DbError> {
+        let run: IngestRun = self
+            .run(move |conn| {
+                Ok(diesel::update(dsl::ingest_run.find(run_id))
+                    .set((dsl::finished_at.eq(diesel::dsl::now), dsl::error.eq(error)))
+                    .returning(IngestRun::as_returning())
+                    .get_result(conn)?)
+            })
+            .await?;
+        tracing::warn!(run_id, error = ?run.error, "Failed ingest run");
+        Ok(run)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_ingest_runs(&mut self) -> Result<Vec<IngestRun>, DbError> {
+        self.run(|conn| {
+            Ok(dsl::ingest_run
+                .order(dsl::run_id.asc())
+                .select(IngestRun::as_select())
+                .load(conn)?)
+        })
+        .await
+    }
+}
@@ -1,14 +1,26 @@
+mod batch;
+mod blob;
 mod db;
 mod error;
+mod in_memory;
+mod sqlite;
 
 pub(crate) mod schema;
 pub(crate) mod test_utils;
 
+#[cfg(test)]
+mod conformance;
+
 pub mod dart;
+pub mod ingest;
+pub mod link;
 pub mod model;
+pub mod query;
 pub mod smes;
 
 pub use db::{Db, PostgresDb};
 pub use error::DbError;
+pub use in_memory::InMemoryDb;
+pub use sqlite::SqliteDb;
 
 pub(crate) const POSTGRES_MAX_PARAMETERS: usize = 65535;
@@ -0,0 +1,5 @@
+mod company_link;
+mod matcher;
+
+pub use company_link::CompanyLinkDb;
+pub use matcher::{dart_identities, match_companies, CompanyIdentity};
@@ -0,0 +1,198 @@
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use hashbrown::HashSet;
+use std::future::Future;
+use types::company;
+
+use crate::schema::company_link::dsl;
+use crate::schema::{dart, smes};
+use crate::{model, DbError, PostgresDb, POSTGRES_MAX_PARAMETERS};
+
+pub trait CompanyLinkDb {
+    fn get_company_links(
+        &mut self,
+    ) -> impl Future<Output = Result<Vec<model::link::CompanyLink>, DbError>>;
+    /// Replace the stored links with `company_links`.
+    ///
+    /// Links are recomputed for every company at once, see [`crate::link::match_companies`],
+    /// so a stored link which isn't among them is stale, e.g. one matched by name
+    /// when the registration number now matches another company, and is removed.
+    /// Links which are kept keep their `created_at`.
+    fn replace_company_links(
+        &mut self,
+        company_links: Vec<model::link::NewCompanyLink>,
+    ) -> impl Future<Output = Result<(), DbError>>;
+    /// Get the SMES company together with its BSPL HTML and the filings of the linked DART companies.
+    ///
+    /// Returns `None` when the company does not exist.
+    fn get_linked_company(
+        &mut self,
+        smes_id: &str,
+    ) -> impl Future<Output = Result<Option<model::link::LinkedCompany>, DbError>>;
+}
+
+impl CompanyLinkDb for PostgresDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_company_links(&mut self) -> Result<Vec<model::link::CompanyLink>, DbError> {
+        self.run(|conn| Ok(dsl::company_link.load(conn)?)).await
+    }
+
+    #[tracing::instrument(skip(self, company_links))]
+    async fn replace_company_links(
+        &mut self,
+        company_links: Vec<model::link::NewCompanyLink>,
+    ) -> Result<(), DbError> {
+        const BUFFER_DIVISOR: usize = 100;
+
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                let stored: Vec<(company::SmesId, company::DartId)> = dsl::company_link
+                    .select((dsl::smes_id, dsl::dart_id))
+                    .load(conn)?;
+                let keys: HashSet<_> = company_links
+                    .iter()
+                    .map(|link| (&link.smes_id, &link.dart_id))
+                    .collect();
+                let mut delete_count = 0;
+                for (smes_id, dart_id) in &stored {
+                    if !keys.contains(&(smes_id, dart_id)) {
+                        delete_count += diesel::delete(dsl::company_link.find((smes_id, dart_id)))
+                            .execute(conn)?;
+                    }
+                }
+                tracing::trace!("Deleted {} stale company_links", delete_count);
+
+                for chunk in company_links.chunks(POSTGRES_MAX_PARAMETERS / BUFFER_DIVISOR) {
+                    tracing::trace!(chunk_size = chunk.len(), "Upserting chunk of company_links");
+                    upsert_company_links(conn, chunk)?;
+                }
+                Ok::<_, DbError>(())
+            })
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_linked_company(
+        &mut self,
+        smes_id: &str,
+    ) -> Result<Option<model::link::LinkedCompany>, DbError> {
+        let smes_id = smes_id.to_string();
+        self.run(move |conn| {
+            let company: Option<model::smes::Company> = smes::company::table
+                .filter(smes::company::smes_id.eq(&smes_id))
+                .select(model::smes::Company::as_select())
+                .first(conn)
+                .optional()?;
+            let Some(company) = company else {
+                return Ok(None);
+            };
+
+            let links: Vec<model::link::CompanyLink> = dsl::company_link
+                .filter(dsl::smes_id.eq(&smes_id))
+                .load(conn)?;
+
+            let html = crate::smes::select_latest_htmls(conn, Some(&smes_id))?
+                .into_iter()
+                .next();
+
+            let dart_ids = links
+                .iter()
+                .map(|link| link.dart_id.clone())
+                .collect::<Vec<_>>();
+            let filings = dart::filing::table
+                .filter(dart::filing::dart_id.eq_any(dart_ids))
+                .load(conn)?;
+
+            Ok(Some(model::link::LinkedCompany {
+                links,
+                company,
+                html,
+                filings,
+            }))
+        })
+        .await
+    }
+}
+
+fn upsert_company_links(
+    conn: &mut PgConnection,
+    company_links: &[model::link::NewCompanyLink],
+) -> Result<(), DbError> {
+    let insert_count = diesel::insert_into(dsl::company_link)
+        .values(company_links)
+        .on_conflict((dsl::smes_id, dsl::dart_id))
+        .do_update()
+        .set((
+            dsl::business_registration_number.eq(excluded(dsl::business_registration_number)),
+            dsl::corporation_registration_number.eq(excluded(dsl::corporation_registration_number)),
+            dsl::match_method.eq(excluded(dsl::match_method)),
+            dsl::confidence.eq(excluded(dsl::confidence)),
+        ))
+        .execute(conn)?;
+
+    if insert_count == company_links.len() {
+        tracing::trace!("Upserted {} company_links", insert_count);
+        Ok(())
+    } else {
+        tracing::error!(
+            "Upserted {}/{} company_links. Rolling back transaction",
+            insert_count,
+            company_links.len()
+        );
+        Err(diesel::result::Error::RollbackTransaction.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::link::CompanyLinkDb;
+    use crate::model::link::{MatchMethod, NewCompanyLink};
+    use crate::test_utils::{PostgresTestContext, TestContext};
+
+    #[tokio::test]
+    async fn replace_and_get_linked_company_should_work() {
+        // region: Arrange
+        tracing_setup::span!("test");
+
+        let function_id = utils::function_id!();
+        let mut ctx = PostgresTestContext::new(&function_id).await;
+
+        let companies = ctx.populate_htmls(&[1000000]).await;
+        let company_ids = ctx.populate_company_ids(&[10000000]).await;
+        let filings = ctx.populate_filings(&[10000000]).await;
+
+        let link = NewCompanyLink {
+            smes_id: companies[0].smes_id.clone(),
+            dart_id: company_ids[0].dart_id.clone(),
+            business_registration_number: None,
+            corporation_registration_number: None,
+            match_method: MatchMethod::NameAndRepresentative,
+            confidence: 0.8,
+        };
+        // endregion: Arrange
+
+        // region: Action
+        let db = ctx.db();
+        db.replace_company_links(vec![link.clone()])
+            .await
+            .expect("Failed to replace company links");
+        // Replacing twice should not fail
+        db.replace_company_links(vec![link.clone()])
+            .await
+            .expect("Failed to replace company links");
+        let linked = db
+            .get_linked_company(companies[0].smes_id.as_ref().as_str())
+            .await
+            .expect("Failed to get linked company")
+            .expect("Company should exist");
+        // endregion: Action
+
+        // region: Assert
+        assert_eq!(linked.links.len(), 1);
+        assert_eq!(NewCompanyLink::from(linked.links[0].clone()), link);
+        assert!(linked.html.is_some());
+        assert_eq!(linked.filings.len(), filings.len());
+        // endregion: Assert
+    }
+}
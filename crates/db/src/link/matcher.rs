@@ -0,0 +1,316 @@
+use crate::model::dart::{CompanyId, CompanyProfile};
+use crate::model::link::{MatchMethod, NewCompanyLink};
+use crate::model::smes::Company;
+use hashbrown::HashMap;
+use std::hash::Hash;
+use types::company;
+
+const REGISTRATION_NUMBER_CONFIDENCE: f64 = 1.0;
+const NAME_AND_REPRESENTATIVE_CONFIDENCE: f64 = 0.8;
+
+/// The identifying facts of a company, as known by a single source.
+#[derive(Clone, Debug)]
+pub struct CompanyIdentity<K> {
+    pub key: K,
+    pub business_registration_number: Option<company::BusinessRegistrationNumber>,
+    pub corporation_registration_number: Option<company::CorporationRegistrationNumber>,
+    pub name: company::Name,
+    pub representative_name: Option<company::RepresentativeName>,
+}
+
+impl From<&Company> for CompanyIdentity<company::SmesId> {
+    fn from(company: &Company) -> Self {
+        CompanyIdentity {
+            key: company.smes_id.clone(),
+            business_registration_number: company.business_registration_number.clone(),
+            corporation_registration_number: None,
+            name: company.company_name.clone(),
+            representative_name: Some(company.representative_name.clone()),
+        }
+    }
+}
+
+impl CompanyIdentity<company::DartId> {
+    /// The identity of a DART company, with the registration numbers and representative of its `profile`.
+    ///
+    /// Without a profile only the name is known, which matches nothing on its own.
+    pub fn dart(company_id: &CompanyId, profile: Option<&CompanyProfile>) -> Self {
+        CompanyIdentity {
+            key: company_id.dart_id.clone(),
+            business_registration_number: profile
+                .and_then(|profile| profile.business_registration_number.clone()),
+            corporation_registration_number: profile
+                .and_then(|profile| profile.corporation_registration_number.clone()),
+            name: company_id.company_name.clone(),
+            representative_name: profile.and_then(|profile| profile.representative_name.clone()),
+        }
+    }
+}
+
+/// The identities of `company_ids`, each with its profile among `profiles` if it has one.
+pub fn dart_identities(
+    company_ids: &[CompanyId],
+    profiles: &[CompanyProfile],
+) -> Vec<CompanyIdentity<company::DartId>> {
+    let profiles: HashMap<_, _> = profiles
+        .iter()
+        .map(|profile| (&profile.dart_id, profile))
+        .collect();
+    company_ids
+        .iter()
+        .map(|company_id| {
+            CompanyIdentity::dart(company_id, profiles.get(&company_id.dart_id).copied())
+        })
+        .collect()
+}
+
+/// Match SMES companies with DART companies.
+///
+/// Each SMES company is matched with at most one DART company, trying in order:
+/// 1. The business registration number
+/// 2. The corporation registration number
+/// 3. The normalized company name together with the representative name
+///
+/// A key which is shared by more than one company is considered ambiguous and is not matched.
+/// Joint representatives, e.g. `한종희, 경계현`, are matched by any one of their names.
+#[tracing::instrument(skip_all)]
+pub fn match_companies(
+    smes: &[CompanyIdentity<company::SmesId>],
+    dart: &[CompanyIdentity<company::DartId>],
+) -> Vec<NewCompanyLink> {
+    let by_business_registration_number = index(dart, |c| c.business_registration_number.clone());
+    let by_corporation_registration_number =
+        index(dart, |c| c.corporation_registration_number.clone());
+    let by_name = index(dart, name_keys);
+    let smes_by_name = index(smes, name_keys);
+
+    let links: Vec<_> = smes
+        .iter()
+        .filter_map(|smes_company| {
+            if let Some(dart_company) = smes_company
+                .business_registration_number
+                .as_ref()
+                .and_then(|number| unique(&by_business_registration_number, number))
+            {
+                return Some(link(
+                    smes_company,
+                    dart_company,
+                    MatchMethod::BusinessRegistrationNumber,
+                    REGISTRATION_NUMBER_CONFIDENCE,
+                ));
+            }
+
+            if let Some(dart_company) = smes_company
+                .corporation_registration_number
+                .as_ref()
+                .and_then(|number| unique(&by_corporation_registration_number, number))
+            {
+                return Some(link(
+                    smes_company,
+                    dart_company,
+                    MatchMethod::CorporationRegistrationNumber,
+                    REGISTRATION_NUMBER_CONFIDENCE,
+                ));
+            }
+
+            // The representatives may name more than one DART company
+            let mut dart_companies = name_keys(smes_company)
+                .into_iter()
+                .filter(|key| unique(&smes_by_name, key).is_some())
+                .filter_map(|key| unique(&by_name, &key));
+            let dart_company = dart_companies.next()?;
+            if dart_companies.any(|other| other.key != dart_company.key) {
+                return None;
+            }
+            Some(link(
+                smes_company,
+                dart_company,
+                MatchMethod::NameAndRepresentative,
+                NAME_AND_REPRESENTATIVE_CONFIDENCE,
+            ))
+        })
+        .collect();
+
+    tracing::info!(
+        smes_count = smes.len(),
+        dart_count = dart.len(),
+        link_count = links.len(),
+        "Matched companies"
+    );
+    links
+}
+
+fn index<K, V, I, F>(
+    identities: &[CompanyIdentity<K>],
+    keys: F,
+) -> HashMap<V, Vec<&CompanyIdentity<K>>>
+where
+    V: Eq + Hash,
+    I: IntoIterator<Item = V>,
+    F: Fn(&CompanyIdentity<K>) -> I,
+{
+    let mut index = HashMap::<V, Vec<_>>::new();
+    for identity in identities {
+        for key in keys(identity) {
+            index.entry(key).or_default().push(identity);
+        }
+    }
+    index
+}
+
+fn unique<'a, K, V>(
+    index: &HashMap<V, Vec<&'a CompanyIdentity<K>>>,
+    key: &V,
+) -> Option<&'a CompanyIdentity<K>>
+where
+    V: Eq + Hash,
+{
+    match index.get(key).map(Vec::as_slice) {
+        Some([identity]) => Some(identity),
+        _ => None,
+    }
+}
+
+fn link(
+    smes: &CompanyIdentity<company::SmesId>,
+    dart: &CompanyIdentity<company::DartId>,
+    match_method: MatchMethod,
+    confidence: f64,
+) -> NewCompanyLink {
+    NewCompanyLink {
+        smes_id: smes.key.clone(),
+        dart_id: dart.key.clone(),
+        business_registration_number: smes
+            .business_registration_number
+            .clone()
+            .or_else(|| dart.business_registration_number.clone()),
+        corporation_registration_number: smes
+            .corporation_registration_number
+            .clone()
+            .or_else(|| dart.corporation_registration_number.clone()),
+        match_method,
+        confidence,
+    }
+}
+
+/// A key per representative, as DART lists joint representatives in a single `ceo_nm`.
+fn name_keys<K>(identity: &CompanyIdentity<K>) -> Vec<(String, String)> {
+    let Some(representative_name) = identity.representative_name.as_ref() else {
+        return Vec::new();
+    };
+    let name = identity.name.normalized().into_inner();
+    let mut keys: Vec<_> = representative_name
+        .as_ref()
+        .split([',', '·', 'ㆍ'])
+        .map(|representative| {
+            representative
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect::<String>()
+        })
+        .filter(|representative| !representative.is_empty())
+        .map(|representative| (name.clone(), representative))
+        .collect();
+    // A company listed twice under a key would be ambiguous with itself
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn smes(
+        key: &str,
+        brn: Option<&str>,
+        name: &str,
+        rep: &str,
+    ) -> CompanyIdentity<company::SmesId> {
+        CompanyIdentity {
+            key: key.try_into().unwrap(),
+            business_registration_number: brn.map(|brn| brn.try_into().unwrap()),
+            corporation_registration_number: None,
+            name: name.try_into().unwrap(),
+            representative_name: Some(rep.try_into().unwrap()),
+        }
+    }
+
+    fn dart(
+        key: &str,
+        brn: Option<&str>,
+        name: &str,
+        rep: Option<&str>,
+    ) -> CompanyIdentity<company::DartId> {
+        CompanyIdentity {
+            key: key.try_into().unwrap(),
+            business_registration_number: brn.map(|brn| brn.try_into().unwrap()),
+            corporation_registration_number: None,
+            name: name.try_into().unwrap(),
+            representative_name: rep.map(|rep| rep.try_into().unwrap()),
+        }
+    }
+
+    #[test]
+    fn business_registration_number_should_match_first() {
+        let smes = [smes("1071180", Some("5632000760"), "루키게임즈", "김성국")];
+        let dart = [
+            dart("00000001", Some("5632000760"), "다른이름", None),
+            dart("00000002", None, "루키게임즈", Some("김성국")),
+        ];
+
+        let links = match_companies(&smes, &dart);
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].dart_id.as_ref(), "00000001");
+        assert_eq!(
+            links[0].match_method,
+            MatchMethod::BusinessRegistrationNumber
+        );
+    }
+
+    #[test]
+    fn normalized_name_and_representative_should_match() {
+        let smes = [smes("1071180", None, "(주)루키게임즈", "김성국")];
+        let dart = [dart(
+            "00000002",
+            None,
+            "주식회사 루키게임즈",
+            Some("김 성국"),
+        )];
+
+        let links = match_companies(&smes, &dart);
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].match_method, MatchMethod::NameAndRepresentative);
+        assert_eq!(links[0].confidence, NAME_AND_REPRESENTATIVE_CONFIDENCE);
+    }
+
+    #[test]
+    fn any_of_joint_representatives_should_match() {
+        let smes = [smes("1071180", None, "삼성전자", "경계현")];
+        let dart = [dart(
+            "00126380",
+            None,
+            "삼성전자(주)",
+            Some("한종희, 경계현"),
+        )];
+
+        let links = match_companies(&smes, &dart);
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].dart_id.as_ref(), "00126380");
+        assert_eq!(links[0].match_method, MatchMethod::NameAndRepresentative);
+    }
+
+    #[test]
+    fn ambiguous_names_should_not_match() {
+        let smes = [smes("1071180", None, "루키게임즈", "김성국")];
+        let dart = [
+            dart("00000001", None, "루키게임즈", Some("김성국")),
+            dart("00000002", None, "(주)루키게임즈", Some("김성국")),
+        ];
+
+        assert!(match_companies(&smes, &dart).is_empty());
+    }
+}
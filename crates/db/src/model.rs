@@ -1,2 +1,4 @@
 pub mod dart;
+pub mod ingest;
+pub mod link;
 pub mod smes;
@@ -1,10 +1,18 @@
 use chrono::NaiveDate;
+use diesel::deserialize::{FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use diesel::sqlite::Sqlite;
 use diesel::{Insertable, Queryable, Selectable};
 use fake::faker::name::ja_jp::Name;
 use fake::faker::number::raw::NumberWithFormat;
 use fake::locales::EN;
 use fake::{Dummy, Fake};
+use hashbrown::{HashMap, HashSet};
 use rand::Rng;
+use std::io::Write;
 use types::{company, filing, YYYYMMDD};
 // region: Table filing
 
@@ -20,6 +28,14 @@ pub struct Filing {
     pub remark: filing::Remark,
     pub created_at: time::PrimitiveDateTime,
     pub updated_at: time::PrimitiveDateTime,
+    /// The ingest run which last wrote the filing, `None` when written outside a run.
+    pub run_id: Option<i32>,
+    /// Generated from `remark`, see [`filing::RemarkFlags`]
+    pub is_consolidated: bool,
+    /// Generated from `remark`, whether the filing is superseded by a correction
+    pub is_corrected: bool,
+    /// Generated from `remark`
+    pub is_withdrawn: bool,
 }
 
 impl<T> Dummy<T> for Filing {
@@ -28,6 +44,7 @@ impl<T> Dummy<T> for Filing {
             fake::faker::time::en::DateTime().fake_with_rng::<time::PrimitiveDateTime, R>(rng);
 
         let new_filing = NewFiling::dummy_with_rng(_config, rng);
+        let flags = new_filing.remark.flags();
 
         Filing {
             dart_id: new_filing.dart_id,
@@ -38,6 +55,10 @@ impl<T> Dummy<T> for Filing {
             remark: new_filing.remark,
             created_at: fake_time,
             updated_at: fake_time,
+            run_id: None,
+            is_consolidated: flags.consolidated,
+            is_corrected: flags.corrected,
+            is_withdrawn: flags.withdrawn,
         }
     }
 }
@@ -52,6 +73,12 @@ pub struct NewFiling {
     pub filer_name: filing::FilerName,
     pub receipt_date: filing::ReceiptDate,
     pub remark: filing::Remark,
+    /// The name of the company as listed with the filing, `corp_name` of the OpenDART list,
+    /// which names its placeholder, see [`CompanyId::placeholders_for`].
+    ///
+    /// Not stored, so `None` for filings read back, and ignored when comparing filings.
+    #[diesel(skip_insertion)]
+    pub company_name: Option<company::Name>,
 }
 
 impl<T> Dummy<T> for NewFiling {
@@ -73,6 +100,7 @@ impl<T> Dummy<T> for NewFiling {
                 NaiveDate::from_ymd_opt(2021, 1, 1).expect("invalid date passed"),
             ),
             remark: filing::Remark::new("Remark"),
+            company_name: None,
         }
     }
 }
@@ -86,6 +114,7 @@ impl From<Filing> for NewFiling {
             filer_name: filing.filer_name,
             receipt_date: filing.receipt_date,
             remark: filing.remark,
+            company_name: None,
         }
     }
 }
@@ -101,6 +130,43 @@ impl PartialEq for NewFiling {
     }
 }
 
+/// A filing and the amendments filed after it, like `[기재정정]`, in the order they were received.
+///
+/// See [`crate::dart::AmendmentChainDb`] for how filings are chained.
+#[derive(Clone)]
+pub struct AmendmentChain {
+    /// Never empty, the first filing is the one the others amend.
+    pub filings: Vec<Filing>,
+}
+
+impl AmendmentChain {
+    /// The amended filing, or the oldest amendment when the amended filing wasn't ingested.
+    pub fn original(&self) -> &Filing {
+        &self.filings[0]
+    }
+
+    /// The report name shared by the filings of the chain, without amendment markers.
+    pub fn base_name(&self) -> &str {
+        self.original().report_name.base_name()
+    }
+
+    /// The newest filing which wasn't withdrawn, which supersedes the others.
+    ///
+    /// `None` when every filing of the chain was withdrawn.
+    pub fn latest_effective(&self) -> Option<&Filing> {
+        self.filings
+            .iter()
+            .rev()
+            .find(|filing| !filing.is_withdrawn)
+    }
+
+    pub fn contains(&self, receipt_number: &filing::ReceiptNumber) -> bool {
+        self.filings
+            .iter()
+            .any(|filing| &filing.receipt_number == receipt_number)
+    }
+}
+
 // endregion: Table filing
 
 // region: Table company_id
@@ -111,8 +177,10 @@ impl PartialEq for NewFiling {
 pub struct CompanyId {
     pub dart_id: company::DartId,
     pub company_name: company::Name,
-    pub stock_code: company::StockCode,
-    pub id_modify_date: YYYYMMDD,
+    /// `None` for companies which aren't listed.
+    pub stock_code: Option<company::StockCode>,
+    /// `None` for placeholders, which stand in for companies missing from the corp code list.
+    pub id_modify_date: Option<YYYYMMDD>,
 }
 
 impl<T> Dummy<T> for CompanyId {
@@ -124,16 +192,796 @@ impl<T> Dummy<T> for CompanyId {
                 .try_into()
                 .expect("dummy creation logic needs to be fixed within the source code"),
             company_name: Name().fake_with_rng::<String, R>(rng).into(),
-            stock_code: NumberWithFormat(EN, "^#####")
-                .fake::<String>()
-                .as_str()
-                .try_into()
-                .expect("dummy creation logic needs to be fixed within the source code"),
-            id_modify_date: YYYYMMDD::new(
-                NaiveDate::from_ymd_opt(2021, 1, 1).expect("invalid date passed"),
+            stock_code: Some(
+                NumberWithFormat(EN, "^#####")
+                    .fake::<String>()
+                    .as_str()
+                    .try_into()
+                    .expect("dummy creation logic needs to be fixed within the source code"),
             ),
+            id_modify_date: Some(YYYYMMDD::new(
+                NaiveDate::from_ymd_opt(2021, 1, 1).expect("invalid date passed"),
+            )),
         }
     }
 }
 
+impl CompanyId {
+    /// Placeholders for the companies of `filings`, one per `dart_id`,
+    /// named after the company the filings were listed with.
+    ///
+    /// Filings can be fetched before the corp code list which contains their company.
+    /// Backends insert these where a company id is missing, so `dart.filing` can reference it,
+    /// and the next [`crate::dart::CompanyIdDb::upsert_company_ids`] completes them.
+    /// A filer may be someone other than the company, e.g. a shareholder reporting its stake,
+    /// so the first filer only names companies none of whose filings carry `company_name`.
+    pub(crate) fn placeholders_for(filings: &[NewFiling]) -> Vec<CompanyId> {
+        let mut company_names = HashMap::new();
+        for filing in filings {
+            if let Some(company_name) = &filing.company_name {
+                company_names
+                    .entry(&filing.dart_id)
+                    .or_insert_with(|| company_name.clone());
+            }
+        }
+
+        let mut seen = HashSet::new();
+        filings
+            .iter()
+            .filter(|filing| seen.insert(&filing.dart_id))
+            .filter_map(|filing| {
+                let company_name = match company_names.get(&filing.dart_id) {
+                    Some(company_name) => company_name.clone(),
+                    None => company::Name::try_from(filing.filer_name.as_ref().as_str()).ok()?,
+                };
+                Some(CompanyId {
+                    dart_id: filing.dart_id.clone(),
+                    company_name,
+                    stock_code: None,
+                    id_modify_date: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Whether this is a placeholder which no corp code list has completed yet.
+    pub fn is_placeholder(&self) -> bool {
+        self.id_modify_date.is_none()
+    }
+}
+
+/// A company id with its filings, newest first.
+#[derive(Clone)]
+pub struct CompanyWithFilings {
+    pub company_id: CompanyId,
+    pub filings: Vec<Filing>,
+}
+
 // endregion: Table company_id
+
+// region: Table company_profile
+
+/// The overview of a company in OpenDART, `None` where OpenDART leaves a field blank.
+#[derive(Queryable, Selectable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::company_profile)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct CompanyProfile {
+    pub dart_id: company::DartId,
+    pub corporation_registration_number: Option<company::CorporationRegistrationNumber>,
+    pub business_registration_number: Option<company::BusinessRegistrationNumber>,
+    pub representative_name: Option<company::RepresentativeName>,
+    pub headquarters_address: Option<company::HeadquartersAddress>,
+    /// `None` also for codes coarser than the 5 digits SMES uses
+    pub industry_code: Option<company::IndustryCode>,
+    pub established_date: Option<YYYYMMDD>,
+    /// The month the fiscal year ends in, 1-12
+    pub fiscal_month: Option<i32>,
+    /// The ingest run which last wrote the profile, `None` when written outside a run.
+    pub run_id: Option<i32>,
+    pub created_at: time::PrimitiveDateTime,
+    pub updated_at: time::PrimitiveDateTime,
+}
+
+#[derive(Insertable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::company_profile)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewCompanyProfile {
+    pub dart_id: company::DartId,
+    pub corporation_registration_number: Option<company::CorporationRegistrationNumber>,
+    pub business_registration_number: Option<company::BusinessRegistrationNumber>,
+    pub representative_name: Option<company::RepresentativeName>,
+    pub headquarters_address: Option<company::HeadquartersAddress>,
+    pub industry_code: Option<company::IndustryCode>,
+    pub established_date: Option<YYYYMMDD>,
+    pub fiscal_month: Option<i32>,
+}
+
+impl NewCompanyProfile {
+    /// A company OpenDART answered has no overview, status `013`,
+    /// recorded without fields so it isn't requested again until the corp code list modifies it.
+    pub fn missing(dart_id: company::DartId) -> Self {
+        NewCompanyProfile {
+            dart_id,
+            corporation_registration_number: None,
+            business_registration_number: None,
+            representative_name: None,
+            headquarters_address: None,
+            industry_code: None,
+            established_date: None,
+            fiscal_month: None,
+        }
+    }
+}
+
+impl From<CompanyProfile> for NewCompanyProfile {
+    fn from(profile: CompanyProfile) -> Self {
+        NewCompanyProfile {
+            dart_id: profile.dart_id,
+            corporation_registration_number: profile.corporation_registration_number,
+            business_registration_number: profile.business_registration_number,
+            representative_name: profile.representative_name,
+            headquarters_address: profile.headquarters_address,
+            industry_code: profile.industry_code,
+            established_date: profile.established_date,
+            fiscal_month: profile.fiscal_month,
+        }
+    }
+}
+
+// endregion: Table company_profile
+
+// region: Table financial_item
+
+/// An account of a single-company financial statement of OpenDART.
+///
+/// Like the cells of SMES tables, an account is identified by where it sits in its statement,
+/// the statement kind, its order and its groups, rather than by its name, which varies between companies.
+#[derive(Queryable, Selectable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::financial_item)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct FinancialItem {
+    pub dart_id: company::DartId,
+    pub business_year: i32,
+    pub report_code: ReportCode,
+    pub fs_div: FsDiv,
+    pub statement: Statement,
+    pub ord: i32,
+    /// The `|`-separated groups of the account, `-` when it has none
+    pub account_detail: String,
+    /// The IFRS taxonomy id, or `-표준계정코드 미사용-` for accounts outside the taxonomy
+    pub account_id: String,
+    pub account_name: String,
+    /// 당기금액
+    pub current_amount: Option<i64>,
+    /// 전기금액
+    pub previous_amount: Option<i64>,
+    /// 전전기금액
+    pub before_previous_amount: Option<i64>,
+    pub currency: String,
+    /// The filing the statement was taken from
+    pub receipt_number: filing::ReceiptNumber,
+    /// The ingest run which wrote the item, `None` when written outside a run.
+    pub run_id: Option<i32>,
+    pub created_at: time::PrimitiveDateTime,
+}
+
+#[derive(Insertable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::financial_item)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewFinancialItem {
+    pub dart_id: company::DartId,
+    pub business_year: i32,
+    pub report_code: ReportCode,
+    pub fs_div: FsDiv,
+    pub statement: Statement,
+    pub ord: i32,
+    pub account_detail: String,
+    pub account_id: String,
+    pub account_name: String,
+    pub current_amount: Option<i64>,
+    pub previous_amount: Option<i64>,
+    pub before_previous_amount: Option<i64>,
+    pub currency: String,
+    pub receipt_number: filing::ReceiptNumber,
+}
+
+impl From<FinancialItem> for NewFinancialItem {
+    fn from(item: FinancialItem) -> Self {
+        NewFinancialItem {
+            dart_id: item.dart_id,
+            business_year: item.business_year,
+            report_code: item.report_code,
+            fs_div: item.fs_div,
+            statement: item.statement,
+            ord: item.ord,
+            account_detail: item.account_detail,
+            account_id: item.account_id,
+            account_name: item.account_name,
+            current_amount: item.current_amount,
+            previous_amount: item.previous_amount,
+            before_previous_amount: item.before_previous_amount,
+            currency: item.currency,
+            receipt_number: item.receipt_number,
+        }
+    }
+}
+
+impl NewFinancialItem {
+    /// The statement the item belongs to.
+    pub fn statement_key(&self) -> FinancialStatementKey {
+        FinancialStatementKey {
+            dart_id: self.dart_id.clone(),
+            business_year: self.business_year,
+            report_code: self.report_code,
+            fs_div: self.fs_div,
+        }
+    }
+}
+
+/// The financial statements of a company for a report, consolidated or separate.
+///
+/// Statements are stored and replaced as a whole, see [`crate::dart::FinancialItemDb`].
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct FinancialStatementKey {
+    pub dart_id: company::DartId,
+    pub business_year: i32,
+    pub report_code: ReportCode,
+    pub fs_div: FsDiv,
+}
+
+/// When a financial statement was last fetched, and how many of its items were stored and skipped.
+///
+/// Statements without items are only recorded here, see [`crate::dart::FinancialItemDb`].
+#[derive(Queryable, Selectable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::financial_statement_check)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct FinancialStatementCheck {
+    pub dart_id: company::DartId,
+    pub business_year: i32,
+    pub report_code: ReportCode,
+    pub fs_div: FsDiv,
+    /// The items which were stored, `0` when OpenDART had none
+    pub item_count: i32,
+    /// The items which were left out as invalid, so the stored statement is incomplete
+    pub skipped_count: i32,
+    /// The ingest run which checked the statement, `None` when checked outside a run.
+    pub run_id: Option<i32>,
+    pub checked_at: time::PrimitiveDateTime,
+}
+
+impl FinancialStatementCheck {
+    pub fn statement_key(&self) -> FinancialStatementKey {
+        FinancialStatementKey {
+            dart_id: self.dart_id.clone(),
+            business_year: self.business_year,
+            report_code: self.report_code,
+            fs_div: self.fs_div,
+        }
+    }
+}
+
+#[derive(Insertable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::financial_statement_check)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewFinancialStatementCheck {
+    pub dart_id: company::DartId,
+    pub business_year: i32,
+    pub report_code: ReportCode,
+    pub fs_div: FsDiv,
+    pub item_count: i32,
+    pub skipped_count: i32,
+}
+
+impl NewFinancialStatementCheck {
+    /// The check of the statement of `key`, which had `item_count` valid and `skipped_count` invalid items.
+    pub fn new(key: FinancialStatementKey, item_count: i32, skipped_count: i32) -> Self {
+        NewFinancialStatementCheck {
+            dart_id: key.dart_id,
+            business_year: key.business_year,
+            report_code: key.report_code,
+            fs_div: key.fs_div,
+            item_count,
+            skipped_count,
+        }
+    }
+}
+
+/// Enums stored as the codes OpenDART uses, in `TEXT` columns with a `CHECK (... IN (...))` constraint.
+///
+/// Variants are declared in the order of their codes, so they sort like the stored codes.
+macro_rules! dart_code {
+    ($(#[$meta:meta])* $name:ident { $($(#[$variant_meta:meta])* $variant:ident => $code:literal,)+ }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord, AsExpression, FromSqlRow)]
+        #[diesel(sql_type = Text)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+        }
+
+        impl $name {
+            pub const ALL: &'static [$name] = &[$($name::$variant,)+];
+
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $code,)+
+                }
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = String;
+
+            fn try_from(code: &str) -> Result<Self, Self::Error> {
+                match code {
+                    $($code => Ok($name::$variant),)+
+                    other => Err(format!("Unrecognized {}: {other}", stringify!($name))),
+                }
+            }
+        }
+
+        impl ToSql<Text, Pg> for $name {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+                out.write_all(self.as_str().as_bytes())?;
+                Ok(IsNull::No)
+            }
+        }
+
+        impl FromSql<Text, Pg> for $name {
+            fn from_sql(bytes: PgValue<'_>) -> diesel::deserialize::Result<Self> {
+                Ok($name::try_from(std::str::from_utf8(bytes.as_bytes())?)?)
+            }
+        }
+
+        impl ToSql<Text, Sqlite> for $name {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {
+                out.set_value(self.as_str());
+                Ok(IsNull::No)
+            }
+        }
+
+        impl FromSql<Text, Sqlite> for $name {
+            fn from_sql(
+                value: <Sqlite as diesel::backend::Backend>::RawValue<'_>,
+            ) -> diesel::deserialize::Result<Self> {
+                let value = <String as FromSql<Text, Sqlite>>::from_sql(value)?;
+                Ok($name::try_from(value.as_str())?)
+            }
+        }
+    };
+}
+
+dart_code!(
+    /// ## 보고서 코드
+    ReportCode {
+        /// 사업보고서
+        Annual => "11011",
+        /// 반기보고서
+        HalfYear => "11012",
+        /// 1분기보고서
+        FirstQuarter => "11013",
+        /// 3분기보고서
+        ThirdQuarter => "11014",
+    }
+);
+
+dart_code!(
+    /// ## 개별/연결구분
+    FsDiv {
+        /// 연결재무제표
+        Consolidated => "CFS",
+        /// 재무제표, for companies without subsidiaries or alongside the consolidated one
+        Separate => "OFS",
+    }
+);
+
+dart_code!(
+    /// ## 재무제표구분
+    Statement {
+        /// 재무상태표
+        BalanceSheet => "BS",
+        /// 현금흐름표
+        CashFlow => "CF",
+        /// 포괄손익계산서
+        ComprehensiveIncome => "CIS",
+        /// 손익계산서
+        Income => "IS",
+        /// 자본변동표
+        ChangesInEquity => "SCE",
+    }
+);
+
+// endregion: Table financial_item
+
+// region: Tables major_shareholder, executive and employee
+
+/// A report a section of which is stored, see [`crate::dart::PeriodicReportDb`].
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct PeriodicReportKey {
+    pub dart_id: company::DartId,
+    pub business_year: i32,
+    pub report_code: ReportCode,
+}
+
+dart_code!(
+    /// The sections of periodic reports which are stored, each in its own table,
+    /// as the OpenDART endpoints which serve them.
+    PeriodicReportSection {
+        /// 직원 현황, `dart.employee`
+        Employee => "empSttus",
+        /// 임원 현황, `dart.executive`
+        Executive => "exctvSttus",
+        /// 최대주주 현황, `dart.major_shareholder`
+        MajorShareholder => "hyslrSttus",
+    }
+);
+
+/// When a section of a periodic report was last fetched, and how many of its rows were stored and skipped.
+///
+/// Sections without rows are only recorded here, see [`crate::dart::PeriodicReportDb`].
+#[derive(Queryable, Selectable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::periodic_report_check)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PeriodicReportCheck {
+    pub dart_id: company::DartId,
+    pub business_year: i32,
+    pub report_code: ReportCode,
+    pub section: PeriodicReportSection,
+    /// The rows which were stored, `0` when OpenDART had none or only a total row
+    pub row_count: i32,
+    /// The rows which were left out as invalid, so the stored section is incomplete
+    pub skipped_count: i32,
+    /// The ingest run which checked the section, `None` when checked outside a run.
+    pub run_id: Option<i32>,
+    pub checked_at: time::PrimitiveDateTime,
+}
+
+impl PeriodicReportCheck {
+    pub fn report_key(&self) -> PeriodicReportKey {
+        PeriodicReportKey {
+            dart_id: self.dart_id.clone(),
+            business_year: self.business_year,
+            report_code: self.report_code,
+        }
+    }
+}
+
+#[derive(Insertable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::periodic_report_check)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewPeriodicReportCheck {
+    pub dart_id: company::DartId,
+    pub business_year: i32,
+    pub report_code: ReportCode,
+    pub section: PeriodicReportSection,
+    pub row_count: i32,
+    pub skipped_count: i32,
+}
+
+impl NewPeriodicReportCheck {
+    /// The check of `section` of the report `key`, which had `row_count` valid and `skipped_count` invalid rows.
+    pub fn new(
+        key: PeriodicReportKey,
+        section: PeriodicReportSection,
+        row_count: i32,
+        skipped_count: i32,
+    ) -> Self {
+        NewPeriodicReportCheck {
+            dart_id: key.dart_id,
+            business_year: key.business_year,
+            report_code: key.report_code,
+            section,
+            row_count,
+            skipped_count,
+        }
+    }
+}
+
+/// A row of 최대주주 현황, the largest shareholder and its related parties.
+#[derive(Queryable, Selectable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::major_shareholder)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct MajorShareholder {
+    pub dart_id: company::DartId,
+    pub business_year: i32,
+    pub report_code: ReportCode,
+    /// The position of the row within the section, from 1
+    pub ord: i32,
+    pub name: String,
+    /// 관계, e.g. `본인` or `계열회사`
+    pub relation: Option<String>,
+    /// 주식 종류, e.g. `보통주`
+    pub stock_kind: Option<String>,
+    /// 기초 소유 주식 수
+    pub shares_at_start: Option<i64>,
+    /// 기초 지분율, in percent
+    pub stake_at_start: Option<f64>,
+    /// 기말 소유 주식 수
+    pub shares_at_end: Option<i64>,
+    /// 기말 지분율, in percent
+    pub stake_at_end: Option<f64>,
+    pub remark: Option<String>,
+    /// 결산기준일
+    pub settlement_date: Option<YYYYMMDD>,
+    /// The filing the section was taken from
+    pub receipt_number: filing::ReceiptNumber,
+    /// The ingest run which wrote the row, `None` when written outside a run.
+    pub run_id: Option<i32>,
+    pub created_at: time::PrimitiveDateTime,
+}
+
+/// A row of a major shareholder section, without the report it belongs to.
+#[derive(Insertable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::major_shareholder)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewMajorShareholder {
+    pub ord: i32,
+    pub name: String,
+    pub relation: Option<String>,
+    pub stock_kind: Option<String>,
+    pub shares_at_start: Option<i64>,
+    pub stake_at_start: Option<f64>,
+    pub shares_at_end: Option<i64>,
+    pub stake_at_end: Option<f64>,
+    pub remark: Option<String>,
+    pub settlement_date: Option<YYYYMMDD>,
+    pub receipt_number: filing::ReceiptNumber,
+}
+
+impl From<MajorShareholder> for NewMajorShareholder {
+    fn from(row: MajorShareholder) -> Self {
+        NewMajorShareholder {
+            ord: row.ord,
+            name: row.name,
+            relation: row.relation,
+            stock_kind: row.stock_kind,
+            shares_at_start: row.shares_at_start,
+            stake_at_start: row.stake_at_start,
+            shares_at_end: row.shares_at_end,
+            stake_at_end: row.stake_at_end,
+            remark: row.remark,
+            settlement_date: row.settlement_date,
+            receipt_number: row.receipt_number,
+        }
+    }
+}
+
+/// A row of 임원 현황, registered and unregistered executives alike.
+#[derive(Queryable, Selectable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::executive)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Executive {
+    pub dart_id: company::DartId,
+    pub business_year: i32,
+    pub report_code: ReportCode,
+    /// The position of the row within the section, from 1
+    pub ord: i32,
+    pub name: String,
+    pub gender: Option<Gender>,
+    /// 출생 년월, as reported, e.g. `1968년 11월`
+    pub birth_month: Option<String>,
+    /// 직위
+    pub position: Option<String>,
+    /// 등기 임원 여부, e.g. `사내이사`, `사외이사` or `미등기`
+    pub registration: Option<String>,
+    /// 상근 여부
+    pub is_full_time: Option<bool>,
+    /// 담당 업무
+    pub responsibility: Option<String>,
+    /// 주요 경력
+    pub main_career: Option<String>,
+    /// 최대 주주와의 관계
+    pub relation_to_major_shareholder: Option<String>,
+    /// 재직 기간, as reported
+    pub tenure: Option<String>,
+    /// 임기 만료일, as reported
+    pub term_end: Option<String>,
+    /// 결산기준일
+    pub settlement_date: Option<YYYYMMDD>,
+    /// The filing the section was taken from
+    pub receipt_number: filing::ReceiptNumber,
+    /// The ingest run which wrote the row, `None` when written outside a run.
+    pub run_id: Option<i32>,
+    pub created_at: time::PrimitiveDateTime,
+}
+
+/// A row of an executive section, without the report it belongs to.
+#[derive(Insertable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::executive)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewExecutive {
+    pub ord: i32,
+    pub name: String,
+    pub gender: Option<Gender>,
+    pub birth_month: Option<String>,
+    pub position: Option<String>,
+    pub registration: Option<String>,
+    pub is_full_time: Option<bool>,
+    pub responsibility: Option<String>,
+    pub main_career: Option<String>,
+    pub relation_to_major_shareholder: Option<String>,
+    pub tenure: Option<String>,
+    pub term_end: Option<String>,
+    pub settlement_date: Option<YYYYMMDD>,
+    pub receipt_number: filing::ReceiptNumber,
+}
+
+impl From<Executive> for NewExecutive {
+    fn from(row: Executive) -> Self {
+        NewExecutive {
+            ord: row.ord,
+            name: row.name,
+            gender: row.gender,
+            birth_month: row.birth_month,
+            position: row.position,
+            registration: row.registration,
+            is_full_time: row.is_full_time,
+            responsibility: row.responsibility,
+            main_career: row.main_career,
+            relation_to_major_shareholder: row.relation_to_major_shareholder,
+            tenure: row.tenure,
+            term_end: row.term_end,
+            settlement_date: row.settlement_date,
+            receipt_number: row.receipt_number,
+        }
+    }
+}
+
+/// A row of 직원 현황, the employees of a business segment and gender.
+#[derive(Queryable, Selectable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::employee)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Employee {
+    pub dart_id: company::DartId,
+    pub business_year: i32,
+    pub report_code: ReportCode,
+    /// The position of the row within the section, from 1
+    pub ord: i32,
+    /// 사업부문
+    pub business_segment: Option<String>,
+    pub gender: Option<Gender>,
+    /// 정규직 수
+    pub regular_count: Option<i64>,
+    /// 계약직 수
+    pub contract_count: Option<i64>,
+    /// 합계
+    pub total_count: Option<i64>,
+    /// 평균 근속 연수, as reported, e.g. `12.5` or `12년 6개월`
+    pub average_tenure: Option<String>,
+    /// 연간 급여 총액
+    pub annual_salary_total: Option<i64>,
+    /// 1인평균 급여 액
+    pub average_salary: Option<i64>,
+    pub remark: Option<String>,
+    /// 결산기준일
+    pub settlement_date: Option<YYYYMMDD>,
+    /// The filing the section was taken from
+    pub receipt_number: filing::ReceiptNumber,
+    /// The ingest run which wrote the row, `None` when written outside a run.
+    pub run_id: Option<i32>,
+    pub created_at: time::PrimitiveDateTime,
+}
+
+/// A row of an employee section, without the report it belongs to.
+#[derive(Insertable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::employee)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewEmployee {
+    pub ord: i32,
+    pub business_segment: Option<String>,
+    pub gender: Option<Gender>,
+    pub regular_count: Option<i64>,
+    pub contract_count: Option<i64>,
+    pub total_count: Option<i64>,
+    pub average_tenure: Option<String>,
+    pub annual_salary_total: Option<i64>,
+    pub average_salary: Option<i64>,
+    pub remark: Option<String>,
+    pub settlement_date: Option<YYYYMMDD>,
+    pub receipt_number: filing::ReceiptNumber,
+}
+
+impl From<Employee> for NewEmployee {
+    fn from(row: Employee) -> Self {
+        NewEmployee {
+            ord: row.ord,
+            business_segment: row.business_segment,
+            gender: row.gender,
+            regular_count: row.regular_count,
+            contract_count: row.contract_count,
+            total_count: row.total_count,
+            average_tenure: row.average_tenure,
+            annual_salary_total: row.annual_salary_total,
+            average_salary: row.average_salary,
+            remark: row.remark,
+            settlement_date: row.settlement_date,
+            receipt_number: row.receipt_number,
+        }
+    }
+}
+
+dart_code!(
+    /// ## 성별
+    Gender {
+        /// 남
+        Male => "남",
+        /// 여
+        Female => "여",
+    }
+);
+
+// endregion: Tables major_shareholder, executive and employee
+
+// region: Table document
+
+/// Where the document archive of a filing is stored, and what it contains.
+///
+/// The archive itself is kept outside the database, addressed by `sha256`.
+/// Filings OpenDART has no document for are recorded without one.
+#[derive(Queryable, Selectable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::document)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Document {
+    pub receipt_number: filing::ReceiptNumber,
+    /// The lowercase hex SHA-256 of the archive, `None` when the filing has no document
+    pub sha256: Option<String>,
+    /// `None` when the filing has no document
+    pub byte_size: Option<i64>,
+    /// The ingest run which checked the filing, `None` when checked outside a run.
+    pub run_id: Option<i32>,
+    /// When OpenDART was last asked for the archive
+    pub checked_at: time::PrimitiveDateTime,
+}
+
+#[derive(Insertable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::document)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewDocument {
+    pub receipt_number: filing::ReceiptNumber,
+    pub sha256: Option<String>,
+    pub byte_size: Option<i64>,
+}
+
+impl NewDocument {
+    /// A filing OpenDART answered has no document, status `013` or `014`.
+    pub fn missing(receipt_number: filing::ReceiptNumber) -> Self {
+        NewDocument {
+            receipt_number,
+            sha256: None,
+            byte_size: None,
+        }
+    }
+}
+
+impl From<Document> for NewDocument {
+    fn from(document: Document) -> Self {
+        NewDocument {
+            receipt_number: document.receipt_number,
+            sha256: document.sha256,
+            byte_size: document.byte_size,
+        }
+    }
+}
+
+// endregion: Table document
+
+// region: Table api_quota
+
+/// The OpenDART requests sent with an API key on a day, see [`crate::dart::ApiQuotaDb`].
+#[derive(Queryable, Selectable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::dart::api_quota)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ApiQuota {
+    /// The first 16 hex digits of the SHA-256 of the key
+    pub key_id: String,
+    /// The date in KST, when OpenDART resets the counts
+    pub day: NaiveDate,
+    pub request_count: i32,
+    /// Whether OpenDART answered that the key exceeded its limit for the day
+    pub exhausted: bool,
+    /// The ingest run which last sent a request with the key.
+    pub run_id: Option<i32>,
+    pub created_at: time::PrimitiveDateTime,
+    pub updated_at: time::PrimitiveDateTime,
+}
+
+// endregion: Table api_quota
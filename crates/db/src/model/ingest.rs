@@ -0,0 +1,52 @@
+use diesel::{Insertable, Queryable, Selectable};
+
+// region: Table ingest_run
+/// A single run of a runner.
+///
+/// Rows written through the handle of a run reference it by `run_id`,
+/// see [`crate::ingest::IngestRunDb`].
+#[derive(Queryable, Selectable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::ingest_run)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct IngestRun {
+    pub run_id: i32,
+    pub runner: String,
+    /// The git revision the runner was built from
+    pub git_rev: Option<String>,
+    /// The settings the runner was started with, as the runner formats them
+    pub config: Option<String>,
+    pub started_at: time::PrimitiveDateTime,
+    /// `None` while the run is in progress, or when it was killed
+    pub finished_at: Option<time::PrimitiveDateTime>,
+    pub inserted_count: Option<i32>,
+    pub updated_count: Option<i32>,
+    pub unchanged_count: Option<i32>,
+    /// The server the runner fetched from, see [`NewIngestRun::source_url`]
+    pub source_url: Option<String>,
+    /// Why the run failed, see [`crate::ingest::IngestRunDb::fail_ingest_run`]
+    pub error: Option<String>,
+}
+
+#[derive(Insertable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::ingest_run)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewIngestRun {
+    /// The name of the runner, e.g. `smes_list`
+    pub runner: String,
+    pub git_rev: Option<String>,
+    pub config: Option<String>,
+    /// The server the runner fetched from, e.g. `https://opendart.fss.or.kr`.
+    ///
+    /// Rows reference their run, so this is where a row was fetched from,
+    /// e.g. to tell rows fetched from a mock apart.
+    pub source_url: Option<String>,
+}
+
+/// How many rows a run wrote, as counted by the runner.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct IngestCounts {
+    pub inserted: i32,
+    pub updated: i32,
+    pub unchanged: i32,
+}
+// endregion: Table ingest_run
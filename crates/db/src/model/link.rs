@@ -0,0 +1,127 @@
+use diesel::deserialize::{FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use diesel::sqlite::Sqlite;
+use diesel::{Insertable, Queryable, Selectable};
+use std::io::Write;
+use types::company;
+
+// region: Table company_link
+
+#[derive(Queryable, Selectable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::company_link)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct CompanyLink {
+    pub smes_id: company::SmesId,
+    pub dart_id: company::DartId,
+    pub business_registration_number: Option<company::BusinessRegistrationNumber>,
+    /// The key used by the data.go.kr financial statement APIs
+    pub corporation_registration_number: Option<company::CorporationRegistrationNumber>,
+    pub match_method: MatchMethod,
+    pub confidence: f64,
+    pub created_at: time::PrimitiveDateTime,
+    pub updated_at: time::PrimitiveDateTime,
+}
+
+#[derive(Insertable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::company_link)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewCompanyLink {
+    pub smes_id: company::SmesId,
+    pub dart_id: company::DartId,
+    pub business_registration_number: Option<company::BusinessRegistrationNumber>,
+    pub corporation_registration_number: Option<company::CorporationRegistrationNumber>,
+    pub match_method: MatchMethod,
+    pub confidence: f64,
+}
+
+impl From<CompanyLink> for NewCompanyLink {
+    fn from(link: CompanyLink) -> Self {
+        NewCompanyLink {
+            smes_id: link.smes_id,
+            dart_id: link.dart_id,
+            business_registration_number: link.business_registration_number,
+            corporation_registration_number: link.corporation_registration_number,
+            match_method: link.match_method,
+            confidence: link.confidence,
+        }
+    }
+}
+
+/// How the companies of a [`CompanyLink`] were matched, in the order they are tried.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, AsExpression, FromSqlRow)]
+#[diesel(sql_type = Text)]
+pub enum MatchMethod {
+    BusinessRegistrationNumber,
+    CorporationRegistrationNumber,
+    /// Normalized company name together with the representative name
+    NameAndRepresentative,
+}
+
+impl MatchMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchMethod::BusinessRegistrationNumber => "business_registration_number",
+            MatchMethod::CorporationRegistrationNumber => "corporation_registration_number",
+            MatchMethod::NameAndRepresentative => "name_and_representative",
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> diesel::deserialize::Result<Self> {
+        match bytes {
+            b"business_registration_number" => Ok(MatchMethod::BusinessRegistrationNumber),
+            b"corporation_registration_number" => Ok(MatchMethod::CorporationRegistrationNumber),
+            b"name_and_representative" => Ok(MatchMethod::NameAndRepresentative),
+            other => Err(format!(
+                "Unrecognized match method: {}",
+                String::from_utf8_lossy(other)
+            )
+            .into()),
+        }
+    }
+}
+
+impl ToSql<Text, Pg> for MatchMethod {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.as_str().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Pg> for MatchMethod {
+    fn from_sql(bytes: PgValue<'_>) -> diesel::deserialize::Result<Self> {
+        MatchMethod::from_bytes(bytes.as_bytes())
+    }
+}
+
+impl ToSql<Text, Sqlite> for MatchMethod {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {
+        out.set_value(self.as_str());
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Sqlite> for MatchMethod {
+    fn from_sql(
+        value: <Sqlite as diesel::backend::Backend>::RawValue<'_>,
+    ) -> diesel::deserialize::Result<Self> {
+        let value = <String as FromSql<Text, Sqlite>>::from_sql(value)?;
+        MatchMethod::from_bytes(value.as_bytes())
+    }
+}
+
+// endregion: Table company_link
+
+/// A linked company with the data stored from each source.
+///
+/// FSC financial statements are not stored,
+/// and can be fetched from data.go.kr with the `corporation_registration_number` of the links.
+#[derive(Clone)]
+pub struct LinkedCompany {
+    pub links: Vec<CompanyLink>,
+    pub company: crate::model::smes::Company,
+    pub html: Option<crate::model::smes::Html>,
+    pub filings: Vec<crate::model::dart::Filing>,
+}
@@ -1,3 +1,4 @@
+use crate::{blob, DbError};
 use diesel::{Insertable, Queryable, Selectable};
 use fake::faker::address::ja_jp::CityName;
 use fake::faker::company::ja_jp::{CompanyName, Industry};
@@ -5,6 +6,7 @@ use fake::faker::name::ja_jp::Name;
 use fake::faker::number::raw::NumberWithFormat;
 use fake::locales::EN;
 use fake::{Dummy, Fake};
+use hashbrown::HashSet;
 use rand::Rng;
 use types::company;
 
@@ -16,12 +18,18 @@ pub struct Company {
     pub smes_id: company::SmesId,
     pub representative_name: company::RepresentativeName,
     pub headquarters_address: company::HeadquartersAddress,
-    pub business_registration_number: company::BusinessRegistrationNumber,
+    pub business_registration_number: Option<company::BusinessRegistrationNumber>,
     pub company_name: company::Name,
     pub industry_code: company::IndustryCode,
     pub industry_name: company::IndustryName,
     pub created_at: time::PrimitiveDateTime,
     pub updated_at: time::PrimitiveDateTime,
+    /// Creation time of the latest list snapshot the company was seen in.
+    pub last_seen_at: Option<time::PrimitiveDateTime>,
+    /// Set when the company disappears from a list snapshot, cleared when it reappears.
+    pub delisted_at: Option<time::PrimitiveDateTime>,
+    /// The ingest run which last wrote the company, `None` when written outside a run.
+    pub run_id: Option<i32>,
 }
 
 impl<T> Dummy<T> for Company {
@@ -41,6 +49,9 @@ impl<T> Dummy<T> for Company {
             industry_name: new_company.industry_name,
             created_at: fake_time,
             updated_at: fake_time,
+            last_seen_at: None,
+            delisted_at: None,
+            run_id: None,
         }
     }
 }
@@ -52,7 +63,7 @@ pub struct NewCompany {
     pub smes_id: company::SmesId,
     pub representative_name: company::RepresentativeName,
     pub headquarters_address: company::HeadquartersAddress,
-    pub business_registration_number: company::BusinessRegistrationNumber,
+    pub business_registration_number: Option<company::BusinessRegistrationNumber>,
     pub company_name: company::Name,
     pub industry_code: company::IndustryCode,
     pub industry_name: company::IndustryName,
@@ -72,11 +83,13 @@ impl<T> Dummy<T> for NewCompany {
                 CityName().fake_with_rng::<String, R>(rng)
             )
             .into(),
-            business_registration_number: NumberWithFormat(EN, "^#########")
-                .fake::<String>()
-                .as_str()
-                .try_into()
+            // Random digits rarely have a valid check digit
+            business_registration_number: Some(
+                company::BusinessRegistrationNumber::try_new_without_checksum(
+                    NumberWithFormat(EN, "^#########").fake::<String>().as_str(),
+                )
                 .expect("dummy creation logic needs to be fixed within the source code"),
+            ),
             company_name: CompanyName().fake_with_rng::<String, R>(rng).into(),
             industry_code: NumberWithFormat(EN, "^####")
                 .fake::<String>()
@@ -116,14 +129,15 @@ impl PartialEq for NewCompany {
 // endregion: Table company
 
 // region: Table html
-#[derive(Queryable, Selectable, Clone)]
-#[diesel(table_name = crate::schema::smes::html)]
-#[diesel(check_for_backend(diesel::pg::Pg))]
+/// The latest page of a company, which `smes.html` points to in `smes.html_revision`.
+#[derive(Clone)]
 pub struct Html {
     pub smes_id: company::SmesId,
     pub html_content: company::SmesHtmlContent,
     pub created_at: time::PrimitiveDateTime,
     pub updated_at: time::PrimitiveDateTime,
+    /// The ingest run which stored the latest page, `None` when stored outside a run.
+    pub run_id: Option<i32>,
 }
 
 impl<T> Dummy<T> for Html {
@@ -137,13 +151,12 @@ impl<T> Dummy<T> for Html {
             html_content: new_html.html_content,
             created_at: fake_time,
             updated_at: fake_time,
+            run_id: None,
         }
     }
 }
 
-#[derive(Insertable, Clone, PartialEq, Debug)]
-#[diesel(table_name = crate::schema::smes::html)]
-#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Clone, PartialEq, Debug)]
 pub struct NewHtml {
     pub smes_id: company::SmesId,
     pub html_content: company::SmesHtmlContent,
@@ -170,4 +183,113 @@ impl From<Html> for NewHtml {
         }
     }
 }
+
+/// A row of `smes.html` joined with the body of the revision it points to.
+#[derive(Queryable)]
+pub(crate) struct HtmlWithBody {
+    pub smes_id: company::SmesId,
+    pub body: Vec<u8>,
+    pub created_at: time::PrimitiveDateTime,
+    pub updated_at: time::PrimitiveDateTime,
+    pub run_id: Option<i32>,
+}
+
+impl TryFrom<HtmlWithBody> for Html {
+    type Error = DbError;
+
+    fn try_from(row: HtmlWithBody) -> Result<Self, Self::Error> {
+        Ok(Html {
+            smes_id: row.smes_id,
+            html_content: blob::decompress(&row.body)?.into(),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            run_id: row.run_id,
+        })
+    }
+}
 // endregion: Table html
+
+// region: Table html_revision
+/// A page of a company as it was fetched.
+///
+/// Pages are stored once per content, so a page which reappears moves to its latest `fetched_at`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct HtmlRevision {
+    pub smes_id: company::SmesId,
+    /// Lowercase hex SHA-256 of `html_content`
+    pub sha256: String,
+    pub fetched_at: time::PrimitiveDateTime,
+    pub html_content: company::SmesHtmlContent,
+}
+
+#[derive(Queryable)]
+pub(crate) struct HtmlRevisionRow {
+    pub smes_id: company::SmesId,
+    pub sha256: String,
+    pub fetched_at: time::PrimitiveDateTime,
+    pub body: Vec<u8>,
+}
+
+impl TryFrom<HtmlRevisionRow> for HtmlRevision {
+    type Error = DbError;
+
+    fn try_from(row: HtmlRevisionRow) -> Result<Self, Self::Error> {
+        Ok(HtmlRevision {
+            smes_id: row.smes_id,
+            sha256: row.sha256,
+            fetched_at: row.fetched_at,
+            html_content: blob::decompress(&row.body)?.into(),
+        })
+    }
+}
+// endregion: Table html_revision
+
+// region: Table html_dead_letter
+/// A page which failed a constraint while being stored, with the error the database reported.
+#[derive(Queryable, Clone, PartialEq, Debug)]
+pub struct HtmlDeadLetter {
+    pub id: i32,
+    pub smes_id: company::SmesId,
+    pub html_content: company::SmesHtmlContent,
+    pub error: String,
+    pub created_at: time::PrimitiveDateTime,
+}
+// endregion: Table html_dead_letter
+
+// region: Table list_snapshot
+/// A single run of the SMES list crawl.
+#[derive(Queryable, Selectable, Clone, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::smes::list_snapshot)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ListSnapshot {
+    pub snapshot_id: i32,
+    pub company_count: i32,
+    pub created_at: time::PrimitiveDateTime,
+}
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = crate::schema::smes::list_snapshot)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub(crate) struct NewListSnapshot {
+    pub company_count: i32,
+}
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = crate::schema::smes::list_snapshot_company)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub(crate) struct ListSnapshotCompany {
+    pub snapshot_id: i32,
+    pub smes_id: company::SmesId,
+}
+
+/// Companies which were added or removed between two list snapshots.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ListSnapshotDiff {
+    pub from_snapshot_id: i32,
+    pub to_snapshot_id: i32,
+    /// Present in `to`, but not in `from`
+    pub added: HashSet<company::SmesId>,
+    /// Present in `from`, but not in `to`
+    pub removed: HashSet<company::SmesId>,
+}
+// endregion: Table list_snapshot
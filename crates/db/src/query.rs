@@ -0,0 +1,432 @@
+//! Filtered queries which page through a table by its primary key.
+//!
+//! A query is built with chained setters and run with e.g. [`CompanyDb::query_companies`],
+//! which returns a single [`Page`].
+//! [`KeysetQuery::stream`] runs the query page by page,
+//! so a whole table can be walked without loading it into memory at once.
+//!
+//! ```ignore
+//! let query = CompanyQuery::new().region("서울").has_html(true).page_size(500);
+//! let mut companies = query.stream(&mut db);
+//! while let Some(company) = companies.next().await {
+//!     let company = company?;
+//! }
+//! ```
+
+use crate::dart::FilingDb;
+use crate::model::dart::Filing;
+use crate::model::smes::Company;
+use crate::smes::CompanyDb;
+use crate::DbError;
+
+use std::collections::VecDeque;
+use std::future::Future;
+use types::{company, filing};
+
+/// The number of rows in a page, unless set with `page_size`.
+pub const DEFAULT_PAGE_SIZE: usize = 1000;
+
+/// Rows of a query in key order, and the key to continue after when there may be more.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Page<T, K> {
+    pub items: Vec<T>,
+    /// `None` once the last page has been returned.
+    pub next: Option<K>,
+}
+
+impl<T, K> Page<T, K> {
+    /// A page is full when it has `page_size` rows, which means there may be more.
+    pub(crate) fn new(items: Vec<T>, page_size: usize, key: impl Fn(&T) -> K) -> Self {
+        let next = if items.len() < page_size {
+            None
+        } else {
+            items.last().map(key)
+        };
+        Page { items, next }
+    }
+}
+
+/// A query which pages through its rows in the order of their keys.
+pub trait KeysetQuery: Clone {
+    type Row;
+    type Key: Clone;
+
+    /// The key of `row`, which the next page starts after.
+    fn key(row: &Self::Row) -> Self::Key;
+    /// The same query, starting after the row with `key`.
+    fn after(self, key: Self::Key) -> Self;
+
+    /// Run the query page by page, see [`RowStream`].
+    fn stream<D: RunQuery<Self>>(self, db: &mut D) -> RowStream<'_, D, Self> {
+        RowStream {
+            db,
+            next_query: Some(self),
+            rows: VecDeque::new(),
+        }
+    }
+}
+
+/// Backends which can run a [`KeysetQuery`].
+pub trait RunQuery<Q: KeysetQuery> {
+    fn run_query(
+        &mut self,
+        query: &Q,
+    ) -> impl Future<Output = Result<Page<Q::Row, Q::Key>, DbError>>;
+}
+
+/// Rows of a query, fetched a page at a time as they are consumed.
+pub struct RowStream<'a, D, Q: KeysetQuery> {
+    db: &'a mut D,
+    /// The query for the next page, `None` after the last page.
+    next_query: Option<Q>,
+    rows: VecDeque<Q::Row>,
+}
+
+impl<D: RunQuery<Q>, Q: KeysetQuery> RowStream<'_, D, Q> {
+    /// The next row, or `None` when all rows have been returned.
+    ///
+    /// After an error, the stream ends.
+    pub async fn next(&mut self) -> Option<Result<Q::Row, DbError>> {
+        while self.rows.is_empty() {
+            let query = self.next_query.take()?;
+            let page = match self.db.run_query(&query).await {
+                Ok(page) => page,
+                Err(e) => return Some(Err(e)),
+            };
+            self.next_query = page.next.map(|key| query.after(key));
+            self.rows = page.items.into();
+        }
+        self.rows.pop_front().map(Ok)
+    }
+}
+
+// region: Companies
+/// Filters on `smes.company`, paged by `smes_id`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CompanyQuery {
+    pub(crate) industry_code: Option<company::IndustryCode>,
+    pub(crate) region: Option<String>,
+    pub(crate) name_prefix: Option<company::NormalizedName>,
+    pub(crate) created_since: Option<time::PrimitiveDateTime>,
+    pub(crate) created_before: Option<time::PrimitiveDateTime>,
+    pub(crate) updated_since: Option<time::PrimitiveDateTime>,
+    pub(crate) updated_before: Option<time::PrimitiveDateTime>,
+    pub(crate) has_html: Option<bool>,
+    pub(crate) run_id: Option<i32>,
+    pub(crate) after: Option<company::SmesId>,
+    pub(crate) page_size: usize,
+}
+
+impl Default for CompanyQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompanyQuery {
+    /// All companies, a page of [`DEFAULT_PAGE_SIZE`] at a time.
+    pub fn new() -> Self {
+        CompanyQuery {
+            industry_code: None,
+            region: None,
+            name_prefix: None,
+            created_since: None,
+            created_before: None,
+            updated_since: None,
+            updated_before: None,
+            has_html: None,
+            run_id: None,
+            after: None,
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+
+    pub fn industry_code(mut self, industry_code: company::IndustryCode) -> Self {
+        self.industry_code = Some(industry_code);
+        self
+    }
+
+    /// Companies whose headquarters address starts with `region`, e.g. "서울특별시" or "경기".
+    pub fn region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    /// Companies whose normalized name starts with the normalized `prefix`,
+    /// e.g. "(주)루키" finds "주식회사 루키게임즈".
+    pub fn name_prefix(mut self, prefix: &str) -> Self {
+        self.name_prefix = Some(company::NormalizedName::from_name(prefix));
+        self
+    }
+
+    /// Companies created at or after `since`.
+    pub fn created_since(mut self, since: time::PrimitiveDateTime) -> Self {
+        self.created_since = Some(since);
+        self
+    }
+
+    /// Companies created before `before`.
+    pub fn created_before(mut self, before: time::PrimitiveDateTime) -> Self {
+        self.created_before = Some(before);
+        self
+    }
+
+    /// Companies updated at or after `since`.
+    pub fn updated_since(mut self, since: time::PrimitiveDateTime) -> Self {
+        self.updated_since = Some(since);
+        self
+    }
+
+    /// Companies updated before `before`.
+    pub fn updated_before(mut self, before: time::PrimitiveDateTime) -> Self {
+        self.updated_before = Some(before);
+        self
+    }
+
+    /// Companies which have, or with `false` don't have, a page in `smes.html`.
+    pub fn has_html(mut self, has_html: bool) -> Self {
+        self.has_html = Some(has_html);
+        self
+    }
+
+    /// Companies last written by the ingest run with `run_id`.
+    pub fn run_id(mut self, run_id: i32) -> Self {
+        self.run_id = Some(run_id);
+        self
+    }
+
+    /// At most `page_size` companies per page, at least one.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Whether `company` passes the filters, for backends which filter in memory.
+    pub(crate) fn matches(&self, company: &Company, has_html: bool) -> bool {
+        self.industry_code
+            .as_ref()
+            .is_none_or(|code| &company.industry_code == code)
+            && self.region.as_ref().is_none_or(|region| {
+                company
+                    .headquarters_address
+                    .as_ref()
+                    .starts_with(region.as_str())
+            })
+            && self.name_prefix.as_ref().is_none_or(|prefix| {
+                company
+                    .company_name
+                    .normalized()
+                    .as_ref()
+                    .starts_with(prefix.as_ref().as_str())
+            })
+            && self
+                .created_since
+                .is_none_or(|since| company.created_at >= since)
+            && self
+                .created_before
+                .is_none_or(|before| company.created_at < before)
+            && self
+                .updated_since
+                .is_none_or(|since| company.updated_at >= since)
+            && self
+                .updated_before
+                .is_none_or(|before| company.updated_at < before)
+            && self.has_html.is_none_or(|expected| has_html == expected)
+            && self
+                .run_id
+                .is_none_or(|run_id| company.run_id == Some(run_id))
+            && self
+                .after
+                .as_ref()
+                .is_none_or(|after| &company.smes_id > after)
+    }
+}
+
+impl KeysetQuery for CompanyQuery {
+    type Row = Company;
+    type Key = company::SmesId;
+
+    fn key(row: &Company) -> company::SmesId {
+        row.smes_id.clone()
+    }
+
+    fn after(mut self, key: company::SmesId) -> Self {
+        self.after = Some(key);
+        self
+    }
+}
+
+impl<D: CompanyDb> RunQuery<CompanyQuery> for D {
+    async fn run_query(
+        &mut self,
+        query: &CompanyQuery,
+    ) -> Result<Page<Company, company::SmesId>, DbError> {
+        self.query_companies(query).await
+    }
+}
+// endregion: Companies
+
+// region: Filings
+/// Filters on `dart.filing`, paged by `receipt_number`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FilingQuery {
+    pub(crate) dart_id: Option<company::DartId>,
+    pub(crate) receipt_date_from: Option<filing::ReceiptDate>,
+    pub(crate) receipt_date_to: Option<filing::ReceiptDate>,
+    pub(crate) is_corrected: Option<bool>,
+    pub(crate) is_withdrawn: Option<bool>,
+    pub(crate) run_id: Option<i32>,
+    pub(crate) after: Option<filing::ReceiptNumber>,
+    pub(crate) page_size: usize,
+}
+
+impl Default for FilingQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FilingQuery {
+    /// All filings, a page of [`DEFAULT_PAGE_SIZE`] at a time.
+    pub fn new() -> Self {
+        FilingQuery {
+            dart_id: None,
+            receipt_date_from: None,
+            receipt_date_to: None,
+            is_corrected: None,
+            is_withdrawn: None,
+            run_id: None,
+            after: None,
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+
+    /// Filings of the company with `dart_id`.
+    pub fn dart_id(mut self, dart_id: company::DartId) -> Self {
+        self.dart_id = Some(dart_id);
+        self
+    }
+
+    /// Filings received on or after `from`.
+    pub fn receipt_date_from(mut self, from: filing::ReceiptDate) -> Self {
+        self.receipt_date_from = Some(from);
+        self
+    }
+
+    /// Filings received on or before `to`.
+    pub fn receipt_date_to(mut self, to: filing::ReceiptDate) -> Self {
+        self.receipt_date_to = Some(to);
+        self
+    }
+
+    /// Filings which were, or with `false` weren't, superseded by a correction, marked `정` in their remark.
+    pub fn is_corrected(mut self, is_corrected: bool) -> Self {
+        self.is_corrected = Some(is_corrected);
+        self
+    }
+
+    /// Filings which were, or with `false` weren't, withdrawn, marked `철` in their remark.
+    pub fn is_withdrawn(mut self, is_withdrawn: bool) -> Self {
+        self.is_withdrawn = Some(is_withdrawn);
+        self
+    }
+
+    /// Filings last written by the ingest run with `run_id`.
+    pub fn run_id(mut self, run_id: i32) -> Self {
+        self.run_id = Some(run_id);
+        self
+    }
+
+    /// At most `page_size` filings per page, at least one.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Whether `filing` passes the filters, for backends which filter in memory.
+    pub(crate) fn matches(&self, filing: &Filing) -> bool {
+        self.dart_id
+            .as_ref()
+            .is_none_or(|dart_id| &filing.dart_id == dart_id)
+            && self
+                .receipt_date_from
+                .as_ref()
+                .is_none_or(|from| &filing.receipt_date >= from)
+            && self
+                .receipt_date_to
+                .as_ref()
+                .is_none_or(|to| &filing.receipt_date <= to)
+            && self
+                .is_corrected
+                .is_none_or(|is_corrected| filing.is_corrected == is_corrected)
+            && self
+                .is_withdrawn
+                .is_none_or(|is_withdrawn| filing.is_withdrawn == is_withdrawn)
+            && self
+                .run_id
+                .is_none_or(|run_id| filing.run_id == Some(run_id))
+            && self
+                .after
+                .as_ref()
+                .is_none_or(|after| &filing.receipt_number > after)
+    }
+}
+
+impl KeysetQuery for FilingQuery {
+    type Row = Filing;
+    type Key = filing::ReceiptNumber;
+
+    fn key(row: &Filing) -> filing::ReceiptNumber {
+        row.receipt_number.clone()
+    }
+
+    fn after(mut self, key: filing::ReceiptNumber) -> Self {
+        self.after = Some(key);
+        self
+    }
+}
+
+impl<D: FilingDb> RunQuery<FilingQuery> for D {
+    async fn run_query(
+        &mut self,
+        query: &FilingQuery,
+    ) -> Result<Page<Filing, filing::ReceiptNumber>, DbError> {
+        self.query_filings(query).await
+    }
+}
+// endregion: Filings
+
+/// A `LIKE` pattern matching values which start with `prefix`.
+///
+/// `%`, `_` and `\` in `prefix` are escaped, so use the pattern with `.escape('\\')`.
+pub(crate) fn prefix_pattern(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_pattern_should_escape_wildcards() {
+        assert_eq!(prefix_pattern("루키"), "루키%");
+        assert_eq!(prefix_pattern("100%_a\\"), "100\\%\\_a\\\\%");
+    }
+
+    #[test]
+    fn page_should_continue_only_when_full() {
+        let full = Page::new(vec![1, 2], 2, |n: &i32| *n);
+        let partial = Page::new(vec![3], 2, |n: &i32| *n);
+
+        assert_eq!(full.next, Some(2));
+        assert_eq!(partial.next, None);
+    }
+}
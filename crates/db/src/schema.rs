@@ -1,6 +1,9 @@
 mod schema_dart;
 mod schema_public;
 mod schema_smes;
+mod schema_sqlite;
 
 pub use schema_dart::*;
+pub use schema_public::*;
 pub use schema_smes::*;
+pub use schema_sqlite::*;
@@ -1,17 +1,136 @@
 // @generated automatically by Diesel CLI.
 
 pub mod dart {
+    diesel::table! {
+        dart.api_quota (key_id, day) {
+            key_id -> Text,
+            day -> Date,
+            request_count -> Int4,
+            exhausted -> Bool,
+            run_id -> Nullable<Int4>,
+            created_at -> Timestamp,
+            updated_at -> Timestamp,
+        }
+    }
+
     diesel::table! {
         dart.company_id (dart_id) {
             dart_id -> Text,
             company_name -> Text,
-            stock_code -> Text,
-            id_modify_date -> Date,
+            stock_code -> Nullable<Text>,
+            id_modify_date -> Nullable<Date>,
+            normalized_company_name -> Text,
         }
     }
 
     diesel::table! {
-        dart.filing (dart_id) {
+        dart.company_profile (dart_id) {
+            dart_id -> Text,
+            corporation_registration_number -> Nullable<Text>,
+            business_registration_number -> Nullable<Text>,
+            representative_name -> Nullable<Text>,
+            headquarters_address -> Nullable<Text>,
+            industry_code -> Nullable<Text>,
+            established_date -> Nullable<Date>,
+            fiscal_month -> Nullable<Int4>,
+            run_id -> Nullable<Int4>,
+            created_at -> Timestamp,
+            updated_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart.document (receipt_number) {
+            receipt_number -> Text,
+            sha256 -> Nullable<Text>,
+            byte_size -> Nullable<Int8>,
+            run_id -> Nullable<Int4>,
+            checked_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart.financial_item (dart_id, business_year, report_code, fs_div, statement, ord, account_detail) {
+            dart_id -> Text,
+            business_year -> Int4,
+            report_code -> Text,
+            fs_div -> Text,
+            statement -> Text,
+            ord -> Int4,
+            account_detail -> Text,
+            account_id -> Text,
+            account_name -> Text,
+            current_amount -> Nullable<Int8>,
+            previous_amount -> Nullable<Int8>,
+            before_previous_amount -> Nullable<Int8>,
+            currency -> Text,
+            receipt_number -> Text,
+            run_id -> Nullable<Int4>,
+            created_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart.financial_statement_check (dart_id, business_year, report_code, fs_div) {
+            dart_id -> Text,
+            business_year -> Int4,
+            report_code -> Text,
+            fs_div -> Text,
+            item_count -> Int4,
+            skipped_count -> Int4,
+            run_id -> Nullable<Int4>,
+            checked_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart.employee (dart_id, business_year, report_code, ord) {
+            dart_id -> Text,
+            business_year -> Int4,
+            report_code -> Text,
+            ord -> Int4,
+            business_segment -> Nullable<Text>,
+            gender -> Nullable<Text>,
+            regular_count -> Nullable<Int8>,
+            contract_count -> Nullable<Int8>,
+            total_count -> Nullable<Int8>,
+            average_tenure -> Nullable<Text>,
+            annual_salary_total -> Nullable<Int8>,
+            average_salary -> Nullable<Int8>,
+            remark -> Nullable<Text>,
+            settlement_date -> Nullable<Date>,
+            receipt_number -> Text,
+            run_id -> Nullable<Int4>,
+            created_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart.executive (dart_id, business_year, report_code, ord) {
+            dart_id -> Text,
+            business_year -> Int4,
+            report_code -> Text,
+            ord -> Int4,
+            name -> Text,
+            gender -> Nullable<Text>,
+            birth_month -> Nullable<Text>,
+            position -> Nullable<Text>,
+            registration -> Nullable<Text>,
+            is_full_time -> Nullable<Bool>,
+            responsibility -> Nullable<Text>,
+            main_career -> Nullable<Text>,
+            relation_to_major_shareholder -> Nullable<Text>,
+            tenure -> Nullable<Text>,
+            term_end -> Nullable<Text>,
+            settlement_date -> Nullable<Date>,
+            receipt_number -> Text,
+            run_id -> Nullable<Int4>,
+            created_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart.filing (receipt_number) {
             dart_id -> Text,
             report_name -> Text,
             receipt_number -> Text,
@@ -20,8 +139,68 @@ pub mod dart {
             remark -> Text,
             created_at -> Timestamp,
             updated_at -> Timestamp,
+            run_id -> Nullable<Int4>,
+            is_consolidated -> Bool,
+            is_corrected -> Bool,
+            is_withdrawn -> Bool,
+        }
+    }
+
+    diesel::table! {
+        dart.periodic_report_check (dart_id, business_year, report_code, section) {
+            dart_id -> Text,
+            business_year -> Int4,
+            report_code -> Text,
+            section -> Text,
+            row_count -> Int4,
+            skipped_count -> Int4,
+            run_id -> Nullable<Int4>,
+            checked_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart.major_shareholder (dart_id, business_year, report_code, ord) {
+            dart_id -> Text,
+            business_year -> Int4,
+            report_code -> Text,
+            ord -> Int4,
+            name -> Text,
+            relation -> Nullable<Text>,
+            stock_kind -> Nullable<Text>,
+            shares_at_start -> Nullable<Int8>,
+            stake_at_start -> Nullable<Float8>,
+            shares_at_end -> Nullable<Int8>,
+            stake_at_end -> Nullable<Float8>,
+            remark -> Nullable<Text>,
+            settlement_date -> Nullable<Date>,
+            receipt_number -> Text,
+            run_id -> Nullable<Int4>,
+            created_at -> Timestamp,
         }
     }
 
-    diesel::allow_tables_to_appear_in_same_query!(company_id, filing,);
+    diesel::joinable!(company_profile -> company_id (dart_id));
+    diesel::joinable!(document -> filing (receipt_number));
+    diesel::joinable!(employee -> company_id (dart_id));
+    diesel::joinable!(executive -> company_id (dart_id));
+    diesel::joinable!(financial_item -> company_id (dart_id));
+    diesel::joinable!(financial_statement_check -> company_id (dart_id));
+    diesel::joinable!(filing -> company_id (dart_id));
+    diesel::joinable!(major_shareholder -> company_id (dart_id));
+    diesel::joinable!(periodic_report_check -> company_id (dart_id));
+
+    diesel::allow_tables_to_appear_in_same_query!(
+        api_quota,
+        company_id,
+        company_profile,
+        document,
+        employee,
+        executive,
+        financial_item,
+        financial_statement_check,
+        filing,
+        major_shareholder,
+        periodic_report_check,
+    );
 }
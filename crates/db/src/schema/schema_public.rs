@@ -1 +1,32 @@
 // @generated automatically by Diesel CLI.
+
+diesel::table! {
+    company_link (smes_id, dart_id) {
+        smes_id -> Text,
+        dart_id -> Text,
+        business_registration_number -> Nullable<Text>,
+        corporation_registration_number -> Nullable<Text>,
+        match_method -> Text,
+        confidence -> Float8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    ingest_run (run_id) {
+        run_id -> Int4,
+        runner -> Text,
+        git_rev -> Nullable<Text>,
+        config -> Nullable<Text>,
+        started_at -> Timestamp,
+        finished_at -> Nullable<Timestamp>,
+        inserted_count -> Nullable<Int4>,
+        updated_count -> Nullable<Int4>,
+        unchanged_count -> Nullable<Int4>,
+        source_url -> Nullable<Text>,
+        error -> Nullable<Text>,
+    }
+}
+
+diesel::allow_tables_to_appear_in_same_query!(company_link, ingest_run,);
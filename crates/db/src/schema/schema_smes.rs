@@ -6,25 +6,74 @@ pub mod smes {
             smes_id -> Text,
             representative_name -> Text,
             headquarters_address -> Text,
-            business_registration_number -> Text,
+            business_registration_number -> Nullable<Text>,
             company_name -> Text,
             industry_code -> Text,
             industry_name -> Text,
             created_at -> Timestamp,
             updated_at -> Timestamp,
+            last_seen_at -> Nullable<Timestamp>,
+            delisted_at -> Nullable<Timestamp>,
+            normalized_company_name -> Text,
+            run_id -> Nullable<Int4>,
         }
     }
 
     diesel::table! {
         smes.html (smes_id) {
             smes_id -> Text,
-            html_content -> Text,
             created_at -> Timestamp,
             updated_at -> Timestamp,
+            sha256 -> Text,
+            run_id -> Nullable<Int4>,
+        }
+    }
+
+    diesel::table! {
+        smes.html_dead_letter (id) {
+            id -> Int4,
+            smes_id -> Text,
+            html_content -> Text,
+            error -> Text,
+            created_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        smes.html_revision (smes_id, sha256) {
+            smes_id -> Text,
+            sha256 -> Text,
+            fetched_at -> Timestamp,
+            body -> Bytea,
+        }
+    }
+
+    diesel::table! {
+        smes.list_snapshot (snapshot_id) {
+            snapshot_id -> Int4,
+            company_count -> Int4,
+            created_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        smes.list_snapshot_company (snapshot_id, smes_id) {
+            snapshot_id -> Int4,
+            smes_id -> Text,
         }
     }
 
     diesel::joinable!(html -> company (smes_id));
+    diesel::joinable!(html_revision -> company (smes_id));
+    diesel::joinable!(list_snapshot_company -> company (smes_id));
+    diesel::joinable!(list_snapshot_company -> list_snapshot (snapshot_id));
 
-    diesel::allow_tables_to_appear_in_same_query!(company, html,);
+    diesel::allow_tables_to_appear_in_same_query!(
+        company,
+        html,
+        html_dead_letter,
+        html_revision,
+        list_snapshot,
+        list_snapshot_company,
+    );
 }
@@ -0,0 +1,314 @@
+// Mirrors the Postgres schema in `migrations_sqlite`.
+//
+// SQLite has no schemas, so the tables are prefixed with their Postgres schema instead,
+// e.g. `smes.company` is `smes_company`.
+
+pub mod sqlite {
+    diesel::table! {
+        smes_company (smes_id) {
+            smes_id -> Text,
+            representative_name -> Text,
+            headquarters_address -> Text,
+            business_registration_number -> Nullable<Text>,
+            company_name -> Text,
+            industry_code -> Text,
+            industry_name -> Text,
+            created_at -> Timestamp,
+            updated_at -> Timestamp,
+            last_seen_at -> Nullable<Timestamp>,
+            delisted_at -> Nullable<Timestamp>,
+            normalized_company_name -> Text,
+            run_id -> Nullable<Integer>,
+        }
+    }
+
+    diesel::table! {
+        smes_html (smes_id) {
+            smes_id -> Text,
+            created_at -> Timestamp,
+            updated_at -> Timestamp,
+            sha256 -> Text,
+            run_id -> Nullable<Integer>,
+        }
+    }
+
+    diesel::table! {
+        smes_html_dead_letter (id) {
+            id -> Integer,
+            smes_id -> Text,
+            html_content -> Text,
+            error -> Text,
+            created_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        smes_html_revision (smes_id, sha256) {
+            smes_id -> Text,
+            sha256 -> Text,
+            fetched_at -> Timestamp,
+            body -> Binary,
+        }
+    }
+
+    diesel::table! {
+        smes_list_snapshot (snapshot_id) {
+            snapshot_id -> Integer,
+            company_count -> Integer,
+            created_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        smes_list_snapshot_company (snapshot_id, smes_id) {
+            snapshot_id -> Integer,
+            smes_id -> Text,
+        }
+    }
+
+    diesel::table! {
+        dart_company_id (dart_id) {
+            dart_id -> Text,
+            company_name -> Text,
+            stock_code -> Nullable<Text>,
+            id_modify_date -> Nullable<Date>,
+            normalized_company_name -> Text,
+        }
+    }
+
+    diesel::table! {
+        dart_api_quota (key_id, day) {
+            key_id -> Text,
+            day -> Date,
+            request_count -> Integer,
+            exhausted -> Bool,
+            run_id -> Nullable<Integer>,
+            created_at -> Timestamp,
+            updated_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart_company_profile (dart_id) {
+            dart_id -> Text,
+            corporation_registration_number -> Nullable<Text>,
+            business_registration_number -> Nullable<Text>,
+            representative_name -> Nullable<Text>,
+            headquarters_address -> Nullable<Text>,
+            industry_code -> Nullable<Text>,
+            established_date -> Nullable<Date>,
+            fiscal_month -> Nullable<Integer>,
+            run_id -> Nullable<Integer>,
+            created_at -> Timestamp,
+            updated_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart_document (receipt_number) {
+            receipt_number -> Text,
+            sha256 -> Nullable<Text>,
+            byte_size -> Nullable<BigInt>,
+            run_id -> Nullable<Integer>,
+            checked_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart_employee (dart_id, business_year, report_code, ord) {
+            dart_id -> Text,
+            business_year -> Integer,
+            report_code -> Text,
+            ord -> Integer,
+            business_segment -> Nullable<Text>,
+            gender -> Nullable<Text>,
+            regular_count -> Nullable<BigInt>,
+            contract_count -> Nullable<BigInt>,
+            total_count -> Nullable<BigInt>,
+            average_tenure -> Nullable<Text>,
+            annual_salary_total -> Nullable<BigInt>,
+            average_salary -> Nullable<BigInt>,
+            remark -> Nullable<Text>,
+            settlement_date -> Nullable<Date>,
+            receipt_number -> Text,
+            run_id -> Nullable<Integer>,
+            created_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart_executive (dart_id, business_year, report_code, ord) {
+            dart_id -> Text,
+            business_year -> Integer,
+            report_code -> Text,
+            ord -> Integer,
+            name -> Text,
+            gender -> Nullable<Text>,
+            birth_month -> Nullable<Text>,
+            position -> Nullable<Text>,
+            registration -> Nullable<Text>,
+            is_full_time -> Nullable<Bool>,
+            responsibility -> Nullable<Text>,
+            main_career -> Nullable<Text>,
+            relation_to_major_shareholder -> Nullable<Text>,
+            tenure -> Nullable<Text>,
+            term_end -> Nullable<Text>,
+            settlement_date -> Nullable<Date>,
+            receipt_number -> Text,
+            run_id -> Nullable<Integer>,
+            created_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart_financial_item (dart_id, business_year, report_code, fs_div, statement, ord, account_detail) {
+            dart_id -> Text,
+            business_year -> Integer,
+            report_code -> Text,
+            fs_div -> Text,
+            statement -> Text,
+            ord -> Integer,
+            account_detail -> Text,
+            account_id -> Text,
+            account_name -> Text,
+            current_amount -> Nullable<BigInt>,
+            previous_amount -> Nullable<BigInt>,
+            before_previous_amount -> Nullable<BigInt>,
+            currency -> Text,
+            receipt_number -> Text,
+            run_id -> Nullable<Integer>,
+            created_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart_financial_statement_check (dart_id, business_year, report_code, fs_div) {
+            dart_id -> Text,
+            business_year -> Integer,
+            report_code -> Text,
+            fs_div -> Text,
+            item_count -> Integer,
+            skipped_count -> Integer,
+            run_id -> Nullable<Integer>,
+            checked_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart_filing (receipt_number) {
+            dart_id -> Text,
+            report_name -> Text,
+            receipt_number -> Text,
+            filer_name -> Text,
+            receipt_date -> Date,
+            remark -> Text,
+            created_at -> Timestamp,
+            updated_at -> Timestamp,
+            run_id -> Nullable<Integer>,
+            is_consolidated -> Bool,
+            is_corrected -> Bool,
+            is_withdrawn -> Bool,
+        }
+    }
+
+    diesel::table! {
+        dart_major_shareholder (dart_id, business_year, report_code, ord) {
+            dart_id -> Text,
+            business_year -> Integer,
+            report_code -> Text,
+            ord -> Integer,
+            name -> Text,
+            relation -> Nullable<Text>,
+            stock_kind -> Nullable<Text>,
+            shares_at_start -> Nullable<BigInt>,
+            stake_at_start -> Nullable<Double>,
+            shares_at_end -> Nullable<BigInt>,
+            stake_at_end -> Nullable<Double>,
+            remark -> Nullable<Text>,
+            settlement_date -> Nullable<Date>,
+            receipt_number -> Text,
+            run_id -> Nullable<Integer>,
+            created_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        dart_periodic_report_check (dart_id, business_year, report_code, section) {
+            dart_id -> Text,
+            business_year -> Integer,
+            report_code -> Text,
+            section -> Text,
+            row_count -> Integer,
+            skipped_count -> Integer,
+            run_id -> Nullable<Integer>,
+            checked_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        company_link (smes_id, dart_id) {
+            smes_id -> Text,
+            dart_id -> Text,
+            business_registration_number -> Nullable<Text>,
+            corporation_registration_number -> Nullable<Text>,
+            match_method -> Text,
+            confidence -> Double,
+            created_at -> Timestamp,
+            updated_at -> Timestamp,
+        }
+    }
+
+    diesel::table! {
+        ingest_run (run_id) {
+            run_id -> Integer,
+            runner -> Text,
+            git_rev -> Nullable<Text>,
+            config -> Nullable<Text>,
+            started_at -> Timestamp,
+            finished_at -> Nullable<Timestamp>,
+            inserted_count -> Nullable<Integer>,
+            updated_count -> Nullable<Integer>,
+            unchanged_count -> Nullable<Integer>,
+            source_url -> Nullable<Text>,
+            error -> Nullable<Text>,
+        }
+    }
+
+    diesel::joinable!(smes_html -> smes_company (smes_id));
+    diesel::joinable!(smes_html_revision -> smes_company (smes_id));
+    diesel::joinable!(smes_list_snapshot_company -> smes_company (smes_id));
+    diesel::joinable!(smes_list_snapshot_company -> smes_list_snapshot (snapshot_id));
+    diesel::joinable!(company_link -> smes_company (smes_id));
+    diesel::joinable!(company_link -> dart_company_id (dart_id));
+    diesel::joinable!(dart_filing -> dart_company_id (dart_id));
+    diesel::joinable!(dart_financial_item -> dart_company_id (dart_id));
+    diesel::joinable!(dart_financial_statement_check -> dart_company_id (dart_id));
+    diesel::joinable!(dart_document -> dart_filing (receipt_number));
+    diesel::joinable!(dart_company_profile -> dart_company_id (dart_id));
+    diesel::joinable!(dart_major_shareholder -> dart_company_id (dart_id));
+    diesel::joinable!(dart_executive -> dart_company_id (dart_id));
+    diesel::joinable!(dart_employee -> dart_company_id (dart_id));
+    diesel::joinable!(dart_periodic_report_check -> dart_company_id (dart_id));
+
+    diesel::allow_tables_to_appear_in_same_query!(
+        smes_company,
+        smes_html,
+        smes_html_dead_letter,
+        smes_html_revision,
+        smes_list_snapshot,
+        smes_list_snapshot_company,
+        dart_api_quota,
+        dart_company_id,
+        dart_company_profile,
+        dart_document,
+        dart_employee,
+        dart_executive,
+        dart_filing,
+        dart_financial_item,
+        dart_financial_statement_check,
+        dart_major_shareholder,
+        dart_periodic_report_check,
+        company_link,
+        ingest_run,
+    );
+}
@@ -3,3 +3,5 @@ mod html;
 
 pub use company::CompanyDb;
 pub use html::HtmlDb;
+
+pub(crate) use html::select_latest_htmls;
@@ -1,6 +1,11 @@
+use crate::model::smes::{
+    Company, ListSnapshot, ListSnapshotCompany, ListSnapshotDiff, NewListSnapshot,
+};
+use crate::query::{prefix_pattern, CompanyQuery, Page};
 use crate::{DbError, PostgresDb, POSTGRES_MAX_PARAMETERS};
 
 use crate::schema::smes::company::dsl;
+use crate::schema::smes::{html, list_snapshot, list_snapshot_company};
 use diesel::prelude::*;
 use diesel::upsert::excluded;
 use hashbrown::HashSet;
@@ -12,6 +17,19 @@ pub trait CompanyDb {
         &mut self,
     ) -> impl Future<Output = Result<Vec<crate::model::smes::Company>, DbError>>;
     fn get_smes_ids(&mut self) -> impl Future<Output = Result<HashSet<company::SmesId>, DbError>>;
+    /// A page of the companies matching `query`, ordered by `smes_id`.
+    ///
+    /// See [`crate::query`] to walk all matching companies page by page.
+    fn query_companies(
+        &mut self,
+        query: &CompanyQuery,
+    ) -> impl Future<Output = Result<Page<Company, company::SmesId>, DbError>>;
+    /// Find companies whose normalized name equals the normalized `name`,
+    /// e.g. "(주)루키게임즈" finds "주식회사 루키게임즈".
+    fn find_companies_by_name(
+        &mut self,
+        name: &str,
+    ) -> impl Future<Output = Result<Vec<crate::model::smes::Company>, DbError>>;
     fn insert_companies(
         &mut self,
         companies: Vec<crate::model::smes::NewCompany>,
@@ -20,20 +38,127 @@ pub trait CompanyDb {
         &mut self,
         companies: Vec<crate::model::smes::NewCompany>,
     ) -> impl Future<Output = Result<(), DbError>>;
+    /// Record the ids returned by a single list crawl as a new snapshot.
+    ///
+    /// The companies should already be stored, e.g. with `upsert_companies`.
+    /// Companies in the snapshot get `last_seen_at` set and `delisted_at` cleared,
+    /// while listed companies missing from the snapshot get `delisted_at` set.
+    fn insert_list_snapshot(
+        &mut self,
+        smes_ids: HashSet<company::SmesId>,
+    ) -> impl Future<Output = Result<ListSnapshot, DbError>>;
+    /// Snapshots ordered from the oldest to the newest.
+    fn get_list_snapshots(&mut self) -> impl Future<Output = Result<Vec<ListSnapshot>, DbError>>;
+    fn get_list_snapshot_diff(
+        &mut self,
+        from_snapshot_id: i32,
+        to_snapshot_id: i32,
+    ) -> impl Future<Output = Result<ListSnapshotDiff, DbError>>;
 }
 
 impl CompanyDb for PostgresDb {
     async fn get_companies(&mut self) -> Result<Vec<crate::model::smes::Company>, DbError> {
-        Ok(dsl::company.load(&mut self.conn)?)
+        self.run(|conn| {
+            Ok(dsl::company
+                .select(crate::model::smes::Company::as_select())
+                .load(conn)?)
+        })
+        .await
     }
 
     async fn get_smes_ids(&mut self) -> Result<HashSet<company::SmesId>, DbError> {
-        dsl::company
-            .select(dsl::smes_id)
-            .load::<String>(&mut self.conn)?
-            .into_iter()
-            .map(|id| company::SmesId::try_from(id.as_str()).map_err(DbError::from))
-            .collect::<Result<HashSet<_>, _>>()
+        self.run(|conn| {
+            dsl::company
+                .select(dsl::smes_id)
+                .load::<String>(conn)?
+                .into_iter()
+                .map(|id| company::SmesId::try_from(id.as_str()).map_err(DbError::from))
+                .collect::<Result<HashSet<_>, _>>()
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn query_companies(
+        &mut self,
+        query: &CompanyQuery,
+    ) -> Result<Page<Company, company::SmesId>, DbError> {
+        let query = query.clone();
+        self.run(move |conn| {
+            let mut select = dsl::company.select(Company::as_select()).into_boxed();
+            if let Some(industry_code) = query.industry_code.clone() {
+                select = select.filter(dsl::industry_code.eq(industry_code));
+            }
+            if let Some(region) = &query.region {
+                select = select.filter(
+                    dsl::headquarters_address
+                        .like(prefix_pattern(region))
+                        .escape('\\'),
+                );
+            }
+            if let Some(name_prefix) = &query.name_prefix {
+                select = select.filter(
+                    dsl::normalized_company_name
+                        .like(prefix_pattern(name_prefix.as_ref()))
+                        .escape('\\'),
+                );
+            }
+            if let Some(since) = query.created_since {
+                select = select.filter(dsl::created_at.ge(since));
+            }
+            if let Some(before) = query.created_before {
+                select = select.filter(dsl::created_at.lt(before));
+            }
+            if let Some(since) = query.updated_since {
+                select = select.filter(dsl::updated_at.ge(since));
+            }
+            if let Some(before) = query.updated_before {
+                select = select.filter(dsl::updated_at.lt(before));
+            }
+            match query.has_html {
+                Some(true) => {
+                    select = select.filter(dsl::smes_id.eq_any(html::table.select(html::smes_id)));
+                }
+                Some(false) => {
+                    select = select.filter(diesel::dsl::not(
+                        dsl::smes_id.eq_any(html::table.select(html::smes_id)),
+                    ));
+                }
+                None => {}
+            }
+            if let Some(run_id) = query.run_id {
+                select = select.filter(dsl::run_id.eq(run_id));
+            }
+            if let Some(after) = query.after.clone() {
+                select = select.filter(dsl::smes_id.gt(after));
+            }
+
+            let companies = select
+                .order(dsl::smes_id.asc())
+                .limit(query.page_size as i64)
+                .load(conn)?;
+            Ok(Page::new(
+                companies,
+                query.page_size,
+                |company: &Company| company.smes_id.clone(),
+            ))
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_companies_by_name(
+        &mut self,
+        name: &str,
+    ) -> Result<Vec<crate::model::smes::Company>, DbError> {
+        let normalized_name = company::NormalizedName::from_name(name);
+        self.run(move |conn| {
+            Ok(dsl::company
+                .filter(dsl::normalized_company_name.eq(normalized_name))
+                .select(crate::model::smes::Company::as_select())
+                .load(conn)?)
+        })
+        .await
     }
 
     #[tracing::instrument(skip(self, companies))]
@@ -69,6 +194,102 @@ impl CompanyDb for PostgresDb {
         }
         Ok(())
     }
+
+    #[tracing::instrument(skip(self, smes_ids))]
+    async fn insert_list_snapshot(
+        &mut self,
+        smes_ids: HashSet<company::SmesId>,
+    ) -> Result<ListSnapshot, DbError> {
+        const BUFFER_DIVISOR: usize = 100;
+        let smes_ids: Vec<_> = smes_ids.into_iter().collect();
+
+        self.run(move |conn| {
+            let snapshot = conn.transaction(|conn| {
+                let snapshot: ListSnapshot = diesel::insert_into(list_snapshot::table)
+                    .values(NewListSnapshot {
+                        company_count: smes_ids.len() as i32,
+                    })
+                    .returning(ListSnapshot::as_returning())
+                    .get_result(conn)?;
+
+                for chunk in smes_ids.chunks(POSTGRES_MAX_PARAMETERS / BUFFER_DIVISOR) {
+                    let rows = chunk
+                        .iter()
+                        .map(|smes_id| ListSnapshotCompany {
+                            snapshot_id: snapshot.snapshot_id,
+                            smes_id: smes_id.clone(),
+                        })
+                        .collect::<Vec<_>>();
+                    diesel::insert_into(list_snapshot_company::table)
+                        .values(&rows)
+                        .execute(conn)?;
+                }
+
+                let seen_ids = || {
+                    list_snapshot_company::table
+                        .filter(list_snapshot_company::snapshot_id.eq(snapshot.snapshot_id))
+                        .select(list_snapshot_company::smes_id)
+                };
+
+                let seen_count =
+                    diesel::update(dsl::company.filter(dsl::smes_id.eq_any(seen_ids())))
+                        .set((
+                            dsl::last_seen_at.eq(snapshot.created_at),
+                            dsl::delisted_at.eq(None::<time::PrimitiveDateTime>),
+                        ))
+                        .execute(conn)?;
+
+                let delisted_count = diesel::update(
+                    dsl::company
+                        .filter(dsl::delisted_at.is_null())
+                        .filter(diesel::dsl::not(dsl::smes_id.eq_any(seen_ids()))),
+                )
+                .set(dsl::delisted_at.eq(snapshot.created_at))
+                .execute(conn)?;
+
+                tracing::info!(
+                    snapshot_id = snapshot.snapshot_id,
+                    seen_count,
+                    delisted_count,
+                    "Recorded list snapshot"
+                );
+                Ok::<_, diesel::result::Error>(snapshot)
+            })?;
+            Ok(snapshot)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_list_snapshots(&mut self) -> Result<Vec<ListSnapshot>, DbError> {
+        self.run(|conn| {
+            Ok(list_snapshot::table
+                .order(list_snapshot::snapshot_id.asc())
+                .select(ListSnapshot::as_select())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_list_snapshot_diff(
+        &mut self,
+        from_snapshot_id: i32,
+        to_snapshot_id: i32,
+    ) -> Result<ListSnapshotDiff, DbError> {
+        self.run(move |conn| {
+            let from = get_list_snapshot_smes_ids(conn, from_snapshot_id)?;
+            let to = get_list_snapshot_smes_ids(conn, to_snapshot_id)?;
+
+            Ok(ListSnapshotDiff {
+                from_snapshot_id,
+                to_snapshot_id,
+                added: to.difference(&from).cloned().collect(),
+                removed: from.difference(&to).cloned().collect(),
+            })
+        })
+        .await
+    }
 }
 
 impl PostgresDb {
@@ -78,25 +299,31 @@ impl PostgresDb {
         companies: Vec<crate::model::smes::NewCompany>,
     ) -> Result<(), DbError> {
         let total_company_count = companies.len() as u64;
-
-        self.conn.transaction::<(), _, _>(|conn| {
-            let insert_count = diesel::insert_into(dsl::company)
-                .values(&companies)
-                .execute(conn)?;
-
-            if insert_count == total_company_count as usize {
-                tracing::trace!("Inserted {} companies", insert_count);
-                Ok(())
-            } else {
-                tracing::error!(
-                    "Inserted {}/{} companies. Rolling back transaction",
-                    insert_count,
-                    total_company_count
-                );
-                Err(diesel::result::Error::RollbackTransaction)
-            }
-        })?;
-        Ok(())
+        let run_id = self.run_id;
+
+        self.run(move |conn| {
+            let companies = company_rows(&companies, run_id);
+
+            conn.transaction::<(), _, _>(|conn| {
+                let insert_count = diesel::insert_into(dsl::company)
+                    .values(companies)
+                    .execute(conn)?;
+
+                if insert_count == total_company_count as usize {
+                    tracing::trace!("Inserted {} companies", insert_count);
+                    Ok(())
+                } else {
+                    tracing::error!(
+                        "Inserted {}/{} companies. Rolling back transaction",
+                        insert_count,
+                        total_company_count
+                    );
+                    Err(diesel::result::Error::RollbackTransaction)
+                }
+            })?;
+            Ok(())
+        })
+        .await
     }
 
     #[tracing::instrument(skip(self, companies))]
@@ -104,38 +331,82 @@ impl PostgresDb {
         &mut self,
         companies: Vec<crate::model::smes::NewCompany>,
     ) -> Result<(), DbError> {
-        self.conn.transaction(|conn| {
-            let insert_count = diesel::insert_into(dsl::company)
-                .values(&companies)
-                .on_conflict(dsl::smes_id)
-                .do_update()
-                .set((
-                    dsl::representative_name.eq(excluded(dsl::representative_name)),
-                    dsl::headquarters_address.eq(excluded(dsl::headquarters_address)),
-                    dsl::business_registration_number
-                        .eq(excluded(dsl::business_registration_number)),
-                    dsl::company_name.eq(excluded(dsl::company_name)),
-                    dsl::industry_code.eq(excluded(dsl::industry_code)),
-                    dsl::industry_name.eq(excluded(dsl::industry_name)),
-                ))
-                .execute(conn)?;
-
-            if insert_count == companies.len() {
-                tracing::trace!("Upserted {} companies", insert_count);
-                Ok(())
-            } else {
-                tracing::error!(
-                    "Upserted {}/{} companies. Rolling back transaction",
-                    insert_count,
-                    companies.len()
-                );
-                Err(diesel::result::Error::RollbackTransaction)
-            }
-        })?;
-        Ok(())
+        let total_company_count = companies.len();
+        let run_id = self.run_id;
+
+        self.run(move |conn| {
+            let companies = company_rows(&companies, run_id);
+
+            conn.transaction(|conn| {
+                let insert_count = diesel::insert_into(dsl::company)
+                    .values(companies)
+                    .on_conflict(dsl::smes_id)
+                    .do_update()
+                    .set((
+                        dsl::representative_name.eq(excluded(dsl::representative_name)),
+                        dsl::headquarters_address.eq(excluded(dsl::headquarters_address)),
+                        dsl::business_registration_number
+                            .eq(excluded(dsl::business_registration_number)),
+                        dsl::company_name.eq(excluded(dsl::company_name)),
+                        dsl::normalized_company_name.eq(excluded(dsl::normalized_company_name)),
+                        dsl::industry_code.eq(excluded(dsl::industry_code)),
+                        dsl::industry_name.eq(excluded(dsl::industry_name)),
+                        dsl::run_id.eq(excluded(dsl::run_id)),
+                    ))
+                    .execute(conn)?;
+
+                if insert_count == total_company_count {
+                    tracing::trace!("Upserted {} companies", insert_count);
+                    Ok(())
+                } else {
+                    tracing::error!(
+                        "Upserted {}/{} companies. Rolling back transaction",
+                        insert_count,
+                        total_company_count
+                    );
+                    Err(diesel::result::Error::RollbackTransaction)
+                }
+            })?;
+            Ok(())
+        })
+        .await
     }
 }
 
+fn get_list_snapshot_smes_ids(
+    conn: &mut PgConnection,
+    snapshot_id: i32,
+) -> Result<HashSet<company::SmesId>, DbError> {
+    Ok(list_snapshot_company::table
+        .filter(list_snapshot_company::snapshot_id.eq(snapshot_id))
+        .select(list_snapshot_company::smes_id)
+        .load::<company::SmesId>(conn)?
+        .into_iter()
+        .collect())
+}
+
+/// Pair each company with its normalized name, which is stored for matching and search,
+/// and the ingest run it is written by.
+fn company_rows(
+    companies: &[crate::model::smes::NewCompany],
+    run_id: Option<i32>,
+) -> Vec<(
+    &crate::model::smes::NewCompany,
+    diesel::dsl::Eq<dsl::normalized_company_name, company::NormalizedName>,
+    diesel::dsl::Eq<dsl::run_id, Option<i32>>,
+)> {
+    companies
+        .iter()
+        .map(|c| {
+            (
+                c,
+                dsl::normalized_company_name.eq(c.company_name.normalized()),
+                dsl::run_id.eq(run_id),
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use crate::model::smes::NewCompany;
@@ -262,4 +533,92 @@ mod test {
         }
         // endregion: Assert
     }
+
+    #[tokio::test]
+    async fn list_snapshots_should_track_delisted_companies() {
+        // region: Arrange
+        tracing_setup::span!("test");
+
+        let function_id = utils::function_id!();
+        let mut ctx = PostgresTestContext::new(&function_id).await;
+
+        let ids = (0..10_u64).map(|i| 1000000 + i).collect::<Vec<_>>();
+        let companies = ctx.populate_companies(&ids).await;
+        let all_ids: HashSet<_> = companies.iter().map(|c| c.smes_id.clone()).collect();
+
+        // The last company disappears from the second crawl
+        let delisted_id = companies.last().unwrap().smes_id.clone();
+        let mut remaining_ids = all_ids.clone();
+        remaining_ids.remove(&delisted_id);
+        // endregion: Arrange
+
+        // region: Action
+        let db = ctx.db();
+        let first = db
+            .insert_list_snapshot(all_ids)
+            .await
+            .expect("Failed to insert first snapshot");
+        let second = db
+            .insert_list_snapshot(remaining_ids)
+            .await
+            .expect("Failed to insert second snapshot");
+        // endregion: Action
+
+        // region: Assert
+        let snapshots = db.get_list_snapshots().await.unwrap();
+        assert_eq!(snapshots, vec![first.clone(), second.clone()]);
+        assert_eq!(second.company_count, 9);
+
+        let diff = db
+            .get_list_snapshot_diff(first.snapshot_id, second.snapshot_id)
+            .await
+            .unwrap();
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed, HashSet::from([delisted_id.clone()]));
+
+        for company in db.get_companies().await.unwrap() {
+            if company.smes_id == delisted_id {
+                assert_eq!(company.delisted_at, Some(second.created_at));
+                assert_eq!(company.last_seen_at, Some(first.created_at));
+            } else {
+                assert_eq!(company.delisted_at, None);
+                assert_eq!(company.last_seen_at, Some(second.created_at));
+            }
+        }
+        // endregion: Assert
+    }
+
+    #[tokio::test]
+    async fn find_companies_by_name_should_match_normalized_name() {
+        // region: Arrange
+        tracing_setup::span!("test");
+
+        let function_id = utils::function_id!();
+        let mut ctx = PostgresTestContext::new(&function_id).await;
+
+        let ids = (0..10_u64).map(|i| 1000000 + i).collect::<Vec<_>>();
+        let companies = ctx.populate_companies(&ids).await;
+
+        let renamed_company = NewCompany {
+            company_name: "주식회사 루키게임즈".try_into().expect("Failed to convert"),
+            ..companies[0].clone()
+        };
+        // endregion: Arrange
+
+        // region: Action
+        let db = ctx.db();
+        db.upsert_companies(vec![renamed_company.clone()])
+            .await
+            .expect("Failed to upsert companies");
+        let found = db
+            .find_companies_by_name("(주)루키 게임즈")
+            .await
+            .expect("Failed to find companies");
+        // endregion: Action
+
+        // region: Assert
+        assert_eq!(found.len(), 1);
+        assert_eq!(NewCompany::from(found[0].clone()), renamed_company);
+        // endregion: Assert
+    }
 }
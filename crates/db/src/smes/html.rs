@@ -1,45 +1,79 @@
+use crate::batch::{self, BATCH_SIZE, BATCH_TIMEOUT};
+use crate::blob::Blob;
+use crate::model::smes::{
+    Html, HtmlDeadLetter, HtmlRevision, HtmlRevisionRow, HtmlWithBody, NewHtml,
+};
 use crate::schema::smes::html::dsl;
+use crate::schema::smes::{html_dead_letter, html_revision};
 use crate::{DbError, PostgresDb};
+use diesel::prelude::*;
 use diesel::upsert::excluded;
-use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 use hashbrown::HashSet;
 use std::future::Future;
 use tokio::sync::mpsc::UnboundedReceiver;
 use types::company;
 
 impl HtmlDb for PostgresDb {
-    async fn select_html(
-        &mut self,
-        smes_id: &str,
-    ) -> Result<Option<crate::model::smes::Html>, DbError> {
-        Ok(dsl::html
-            .filter(dsl::smes_id.eq(smes_id))
-            .first(&mut self.conn)
-            .optional()?)
+    async fn select_html(&mut self, smes_id: &str) -> Result<Option<Html>, DbError> {
+        let smes_id = smes_id.to_string();
+        self.run(move |conn| {
+            Ok(select_latest_htmls(conn, Some(&smes_id))?
+                .into_iter()
+                .next())
+        })
+        .await
     }
 
-    async fn select_htmls(&mut self) -> Result<Vec<crate::model::smes::Html>, DbError> {
-        Ok(dsl::html.load(&mut self.conn)?)
+    async fn select_htmls(&mut self) -> Result<Vec<Html>, DbError> {
+        self.run(|conn| select_latest_htmls(conn, None)).await
     }
 
     async fn select_html_ids(&mut self) -> Result<HashSet<company::SmesId>, DbError> {
-        Ok(dsl::html
-            .select(dsl::smes_id)
-            .load(&mut self.conn)?
-            .into_iter()
-            .collect())
+        self.run(|conn| {
+            Ok(dsl::html
+                .select(dsl::smes_id)
+                .load(conn)?
+                .into_iter()
+                .collect())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn select_html_revisions(&mut self, smes_id: &str) -> Result<Vec<HtmlRevision>, DbError> {
+        let smes_id = smes_id.to_string();
+        self.run(move |conn| {
+            html_revision::table
+                .filter(html_revision::smes_id.eq(smes_id))
+                .order(html_revision::fetched_at.asc())
+                .load::<HtmlRevisionRow>(conn)?
+                .into_iter()
+                .map(HtmlRevision::try_from)
+                .collect()
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn select_html_dead_letters(&mut self) -> Result<Vec<HtmlDeadLetter>, DbError> {
+        self.run(|conn| {
+            Ok(html_dead_letter::table
+                .order(html_dead_letter::id.asc())
+                .load(conn)?)
+        })
+        .await
     }
 
     #[tracing::instrument(skip(self, htmls))]
     async fn insert_html_channel(
         &mut self,
-        mut htmls: UnboundedReceiver<crate::model::smes::NewHtml>,
+        mut htmls: UnboundedReceiver<NewHtml>,
     ) -> Result<(), DbError> {
-        let query = diesel::insert_into(dsl::html);
-
-        while let Some(html) = htmls.recv().await {
-            tracing::trace!(?html, "Inserting html");
-            query.values(&html).execute(&mut self.conn)?;
+        while let Some(batch) = batch::recv_batch(&mut htmls, BATCH_SIZE, BATCH_TIMEOUT).await {
+            tracing::trace!(count = batch.len(), "Inserting htmls");
+            let run_id = self.run_id;
+            self.run(move |conn| write_batch(conn, batch, run_id, insert_html))
+                .await?;
         }
         Ok(())
     }
@@ -47,18 +81,13 @@ impl HtmlDb for PostgresDb {
     #[tracing::instrument(skip(self, htmls))]
     async fn upsert_html_channel(
         &mut self,
-        mut htmls: UnboundedReceiver<crate::model::smes::NewHtml>,
+        mut htmls: UnboundedReceiver<NewHtml>,
     ) -> Result<(), DbError> {
-        let query = diesel::insert_into(dsl::html);
-
-        while let Some(html) = htmls.recv().await {
-            tracing::trace!(?html, "Upserting html");
-            query
-                .values(&html)
-                .on_conflict(dsl::smes_id)
-                .do_update()
-                .set((dsl::html_content.eq(excluded(dsl::html_content)),))
-                .execute(&mut self.conn)?;
+        while let Some(batch) = batch::recv_batch(&mut htmls, BATCH_SIZE, BATCH_TIMEOUT).await {
+            tracing::trace!(count = batch.len(), "Upserting htmls");
+            let run_id = self.run_id;
+            self.run(move |conn| write_batch(conn, batch, run_id, upsert_html))
+                .await?;
         }
         Ok(())
     }
@@ -75,16 +104,166 @@ pub trait HtmlDb {
     fn select_html_ids(
         &mut self,
     ) -> impl Future<Output = Result<HashSet<company::SmesId>, DbError>>;
+    /// Every distinct page fetched for the company, oldest first.
+    fn select_html_revisions(
+        &mut self,
+        smes_id: &str,
+    ) -> impl Future<Output = Result<Vec<crate::model::smes::HtmlRevision>, DbError>>;
+    /// Pages which were rejected by the database, oldest first.
+    fn select_html_dead_letters(
+        &mut self,
+    ) -> impl Future<Output = Result<Vec<crate::model::smes::HtmlDeadLetter>, DbError>>;
+    /// Store the pages as new revisions.
+    ///
+    /// The channel is written in batches, one transaction each.
+    /// A page the database rejects, e.g. as the company already has a page,
+    /// is moved to the dead-letter table and the rest of the channel is still consumed.
     fn insert_html_channel(
         &mut self,
         htmls: UnboundedReceiver<crate::model::smes::NewHtml>,
     ) -> impl Future<Output = Result<(), DbError>>;
+    /// Store the pages as new revisions and point the companies to them.
+    ///
+    /// A page with the same content as the latest revision isn't written at all.
+    /// Batches and rejected pages are handled like in [`HtmlDb::insert_html_channel`].
     fn upsert_html_channel(
         &mut self,
         htmls: UnboundedReceiver<crate::model::smes::NewHtml>,
     ) -> impl Future<Output = Result<(), DbError>>;
 }
 
+/// The latest pages of all companies, or of the company with `smes_id`.
+pub(crate) fn select_latest_htmls(
+    conn: &mut PgConnection,
+    smes_id: Option<&str>,
+) -> Result<Vec<Html>, DbError> {
+    let mut query = dsl::html
+        .inner_join(
+            html_revision::table.on(html_revision::smes_id
+                .eq(dsl::smes_id)
+                .and(html_revision::sha256.eq(dsl::sha256))),
+        )
+        .select((
+            dsl::smes_id,
+            html_revision::body,
+            dsl::created_at,
+            dsl::updated_at,
+            dsl::run_id,
+        ))
+        .into_boxed();
+    if let Some(smes_id) = smes_id {
+        query = query.filter(dsl::smes_id.eq(smes_id.to_string()));
+    }
+
+    query
+        .load::<HtmlWithBody>(conn)?
+        .into_iter()
+        .map(Html::try_from)
+        .collect()
+}
+
+/// Write `htmls` in one transaction, moving the pages the database rejects to the dead-letter table.
+fn write_batch(
+    conn: &mut PgConnection,
+    htmls: Vec<NewHtml>,
+    run_id: Option<i32>,
+    write: fn(&mut PgConnection, &NewHtml, &Blob, Option<i32>) -> Result<(), DbError>,
+) -> Result<(), DbError> {
+    let blobs = htmls
+        .iter()
+        .map(|html| Blob::compress(html.html_content.as_ref().as_str()))
+        .collect::<Result<Vec<_>, _>>()?;
+    conn.transaction(|conn| {
+        for (html, blob) in htmls.iter().zip(&blobs) {
+            // Nested transactions are savepoints, so a rejected page leaves the batch intact
+            match conn.transaction(|conn| write(conn, html, blob, run_id)) {
+                Ok(()) => {}
+                Err(e) if batch::is_rejected_row(&e) => {
+                    tracing::warn!(smes_id = %html.smes_id, error = %e, "Moving html to the dead-letter table");
+                    diesel::insert_into(html_dead_letter::table)
+                        .values((
+                            html_dead_letter::smes_id.eq(&html.smes_id),
+                            html_dead_letter::html_content.eq(&html.html_content),
+                            html_dead_letter::error.eq(e.to_string()),
+                        ))
+                        .execute(conn)?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    })
+}
+
+fn insert_html(
+    conn: &mut PgConnection,
+    html: &NewHtml,
+    blob: &Blob,
+    run_id: Option<i32>,
+) -> Result<(), DbError> {
+    insert_revision(conn, &html.smes_id, blob)?;
+    diesel::insert_into(dsl::html)
+        .values((
+            dsl::smes_id.eq(&html.smes_id),
+            dsl::sha256.eq(blob.sha256.as_str()),
+            dsl::run_id.eq(run_id),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+fn upsert_html(
+    conn: &mut PgConnection,
+    html: &NewHtml,
+    blob: &Blob,
+    run_id: Option<i32>,
+) -> Result<(), DbError> {
+    let latest_sha256: Option<String> = dsl::html
+        .filter(dsl::smes_id.eq(&html.smes_id))
+        .select(dsl::sha256)
+        .first(conn)
+        .optional()?;
+    if latest_sha256.as_ref() == Some(&blob.sha256) {
+        tracing::trace!(smes_id = %html.smes_id, "Html is unchanged");
+        return Ok(());
+    }
+
+    insert_revision(conn, &html.smes_id, blob)?;
+    diesel::insert_into(dsl::html)
+        .values((
+            dsl::smes_id.eq(&html.smes_id),
+            dsl::sha256.eq(blob.sha256.as_str()),
+            dsl::run_id.eq(run_id),
+        ))
+        .on_conflict(dsl::smes_id)
+        .do_update()
+        .set((
+            dsl::sha256.eq(excluded(dsl::sha256)),
+            dsl::run_id.eq(excluded(dsl::run_id)),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Store a revision, or mark a page the company already had with the same content as fetched again,
+/// so a page which changes back shows up as the latest revision.
+fn insert_revision(
+    conn: &mut PgConnection,
+    smes_id: &company::SmesId,
+    blob: &Blob,
+) -> QueryResult<usize> {
+    diesel::insert_into(html_revision::table)
+        .values((
+            html_revision::smes_id.eq(smes_id),
+            html_revision::sha256.eq(blob.sha256.as_str()),
+            html_revision::body.eq(blob.body.as_slice()),
+        ))
+        .on_conflict((html_revision::smes_id, html_revision::sha256))
+        .do_update()
+        .set(html_revision::fetched_at.eq(diesel::dsl::now))
+        .execute(conn)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::model::smes::NewHtml;
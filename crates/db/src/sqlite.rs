@@ -0,0 +1,259 @@
+//! A single-file SQLite backend, for running the runners locally without Postgres.
+//!
+//! The SQLite tables mirror the Postgres tables, see `migrations_sqlite`.
+//! Pending migrations are run when the database is opened,
+//! and normalized company names are recomputed when any were applied,
+//! as are the html bodies a migration backfilled uncompressed.
+
+mod api_quota;
+mod company;
+mod company_id;
+mod company_link;
+mod company_profile;
+mod document;
+mod filing;
+mod financial_item;
+mod html;
+mod ingest_run;
+mod periodic_report;
+
+use crate::blob::{self, Blob};
+use crate::db::{run_blocking, Db};
+use crate::error::DbError;
+
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, CustomizeConnection, Pool};
+use diesel::sql_query;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use std::fmt::Debug;
+use std::path::Path;
+use types::company;
+
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!("../../migrations_sqlite");
+
+/// Functions registered on every connection, as SQLite lacks ones the Postgres migrations use.
+///
+/// They're only called from SQL, so the Rust side of them is unused.
+#[allow(dead_code)]
+mod functions {
+    use diesel::prelude::*;
+    use diesel::sql_types::Text;
+
+    define_sql_function!(fn sha256_hex(content: Text) -> Text);
+    define_sql_function!(fn normalize_company_name(name: Text) -> Text);
+}
+
+/// A handle to a pool of SQLite connections to a single database file.
+///
+/// Like [`crate::PostgresDb`], queries run within [`tokio::task::spawn_blocking`],
+/// and clones share the same pool.
+#[derive(Clone)]
+pub struct SqliteDb {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+    /// The ingest run rows are written with, only set on the handle
+    /// [`crate::ingest::IngestRunDb::start_ingest_run`] returns.
+    run_id: Option<i32>,
+}
+
+impl Db for SqliteDb {
+    #[tracing::instrument]
+    async fn new<P: AsRef<Path> + Debug>(path: P) -> Result<Self, DbError> {
+        let path = path.as_ref().to_string_lossy().to_string();
+        let pool = tokio::task::spawn_blocking(move || {
+            Pool::builder()
+                .connection_customizer(Box::new(SqliteConnectionOptions))
+                .build(ConnectionManager::<SqliteConnection>::new(path))
+        })
+        .await??;
+        let db = Self { pool, run_id: None };
+
+        db.run(|conn| {
+            // WAL lets readers proceed while another connection of the pool is writing.
+            // It is persisted in the database file, so it only needs to be set once.
+            conn.batch_execute("PRAGMA journal_mode = WAL;")?;
+            let migration_versions = conn
+                .run_pending_migrations(MIGRATIONS)
+                .map_err(DbError::Migration)?;
+            tracing::debug!(?migration_versions, "Ran pending sqlite migrations");
+            // A change of the normalization comes with a migration,
+            // so the names are only recomputed when one was applied.
+            if !migration_versions.is_empty() {
+                renormalize_company_names(conn)?;
+                compress_html_revisions(conn)?;
+            }
+            Ok(())
+        })
+        .await?;
+
+        Ok(db)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn health_check(&mut self) -> Result<(), DbError> {
+        self.run(|conn| {
+            sql_query("SELECT 1").execute(conn)?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+impl SqliteDb {
+    /// Run blocking diesel code with a connection from the pool.
+    pub(crate) async fn run<T, F>(&self, f: F) -> Result<T, DbError>
+    where
+        F: FnOnce(&mut SqliteConnection) -> Result<T, DbError> + Send + 'static,
+        T: Send + 'static,
+    {
+        run_blocking(&self.pool, f).await
+    }
+}
+
+/// Overwrite the normalized company names which differ from [`company::NormalizedName`].
+///
+/// SQLite can't disable a trigger, so the one maintaining `updated_at` is dropped meanwhile,
+/// as renormalizing doesn't change a company, see [`crate::query::CompanyQuery::updated_since`].
+/// It's restored from its own definition, so it stays as the migrations left it.
+fn renormalize_company_names(conn: &mut SqliteConnection) -> Result<(), DbError> {
+    use diesel::dsl::sql;
+    use diesel::sql_types::Text;
+
+    conn.immediate_transaction(|conn| {
+        let trigger: String = diesel::select(sql::<Text>(
+            "(SELECT sql FROM sqlite_master \
+             WHERE type = 'trigger' AND name = 'smes_company_updated_at')",
+        ))
+        .get_result(conn)?;
+        conn.batch_execute(
+            "DROP TRIGGER smes_company_updated_at; \
+             UPDATE smes_company SET normalized_company_name = normalize_company_name(company_name) \
+             WHERE normalized_company_name <> normalize_company_name(company_name);",
+        )?;
+        conn.batch_execute(&trigger)?;
+        conn.batch_execute(
+            "UPDATE dart_company_id SET normalized_company_name = normalize_company_name(company_name) \
+             WHERE normalized_company_name <> normalize_company_name(company_name);",
+        )?;
+        Ok(())
+    })
+}
+
+/// Compress the bodies of `smes_html_revision` which are stored uncompressed,
+/// like [`crate::PostgresDb::migrate`] does.
+fn compress_html_revisions(conn: &mut SqliteConnection) -> Result<(), DbError> {
+    use crate::schema::sqlite::smes_html_revision::dsl;
+    use diesel::dsl::sql;
+    use diesel::sql_types::Bool;
+
+    conn.immediate_transaction(|conn| {
+        let keys: Vec<(String, String)> = dsl::smes_html_revision
+            .filter(sql::<Bool>("substr(body, 1, 4) <> X'28B52FFD'"))
+            .select((dsl::smes_id, dsl::sha256))
+            .load(conn)?;
+        for (smes_id, sha256) in &keys {
+            let revision = dsl::smes_html_revision.find((smes_id, sha256));
+            let body: Vec<u8> = revision.select(dsl::body).first(conn)?;
+            let content = blob::decompress(&body)?;
+            diesel::update(revision)
+                .set(dsl::body.eq(Blob::compress(&content)?.body))
+                .execute(conn)?;
+        }
+        tracing::info!(
+            compressed = keys.len(),
+            "Compressed backfilled html revisions"
+        );
+        Ok(())
+    })
+}
+
+/// Connection level settings and functions, which SQLite doesn't persist in the database file.
+#[derive(Debug)]
+struct SqliteConnectionOptions;
+
+impl CustomizeConnection<SqliteConnection, diesel::r2d2::Error> for SqliteConnectionOptions {
+    fn on_acquire(&self, conn: &mut SqliteConnection) -> Result<(), diesel::r2d2::Error> {
+        conn.batch_execute("PRAGMA foreign_keys = ON; PRAGMA busy_timeout = 5000;")
+            .map_err(diesel::r2d2::Error::QueryError)?;
+        functions::sha256_hex_utils::register_impl(conn, |content: String| {
+            blob::sha256_hex(&content)
+        })
+        .map_err(diesel::r2d2::Error::QueryError)?;
+        functions::normalize_company_name_utils::register_impl(conn, |name: String| {
+            company::NormalizedName::from_name(&name).to_string()
+        })
+        .map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{SqliteTestContext, TestContext};
+
+    #[tokio::test]
+    async fn sqlite_health_check() {
+        tracing_setup::span!("test");
+        let function_id = utils::function_id!();
+        let mut ctx = SqliteTestContext::new(&function_id).await;
+        assert!(ctx.db().health_check().await.is_ok());
+    }
+
+    /// The normalized name and `updated_at` of the company `1000000`.
+    async fn normalized_name(db: &SqliteDb) -> (String, time::PrimitiveDateTime) {
+        db.run(|conn| {
+            use crate::schema::sqlite::smes_company::dsl;
+
+            Ok(dsl::smes_company
+                .find("1000000")
+                .select((dsl::normalized_company_name, dsl::updated_at))
+                .first(conn)?)
+        })
+        .await
+        .expect("Failed to get the normalized name")
+    }
+
+    #[tokio::test]
+    async fn opening_should_renormalize_company_names() {
+        // region: Arrange
+        tracing_setup::span!("test");
+        let function_id = utils::function_id!();
+        let mut ctx = SqliteTestContext::new(&function_id).await;
+        ctx.populate_companies(&[1000000]).await;
+        ctx.db()
+            .run(|conn| {
+                sql_query(
+                    "UPDATE smes_company SET company_name = 'ＡＢＣ Co.,Ltd', \
+                     normalized_company_name = 'stale' WHERE smes_id = '1000000'",
+                )
+                .execute(conn)?;
+                Ok(())
+            })
+            .await
+            .expect("Failed to store a stale normalized name");
+        let (_, updated_at_before) = normalized_name(ctx.db()).await;
+        // Names are only recomputed when a migration is applied
+        ctx.db()
+            .run(|conn| {
+                conn.revert_last_migration(MIGRATIONS)
+                    .map_err(DbError::Migration)?;
+                Ok(())
+            })
+            .await
+            .expect("Failed to revert the last migration");
+        // endregion: Arrange
+
+        // region: Action
+        // The same file `SqliteTestContext` opened
+        let path = std::env::temp_dir().join(format!("{}.sqlite", function_id));
+        let db = SqliteDb::new(&path).await.expect("Failed to reopen db");
+        // endregion: Action
+
+        // region: Assert
+        let (normalized, updated_at) = normalized_name(&db).await;
+        assert_eq!(normalized, "abc");
+        // Renormalizing isn't an update of the company
+        assert_eq!(updated_at, updated_at_before);
+        // endregion: Assert
+    }
+}
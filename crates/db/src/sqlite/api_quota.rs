@@ -0,0 +1,82 @@
+use crate::dart::ApiQuotaDb;
+use crate::model::dart::ApiQuota;
+use crate::schema::sqlite::dart_api_quota::dsl;
+use crate::{DbError, SqliteDb};
+
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+
+impl ApiQuotaDb for SqliteDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_api_quotas(&mut self, day: NaiveDate) -> Result<Vec<ApiQuota>, DbError> {
+        self.run(move |conn| {
+            Ok(dsl::dart_api_quota
+                .filter(dsl::day.eq(day))
+                .order(dsl::key_id)
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn reserve_api_request(
+        &mut self,
+        key_id: &str,
+        day: NaiveDate,
+        limit: i32,
+    ) -> Result<bool, DbError> {
+        let key_id = key_id.to_string();
+        let run_id = self.run_id;
+        self.run(move |conn| {
+            let reserved = conn.immediate_transaction(|conn| {
+                diesel::insert_into(dsl::dart_api_quota)
+                    .values((
+                        dsl::key_id.eq(&key_id),
+                        dsl::day.eq(day),
+                        dsl::run_id.eq(run_id),
+                    ))
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+                let reserved = diesel::update(dsl::dart_api_quota)
+                    .filter(dsl::key_id.eq(&key_id))
+                    .filter(dsl::day.eq(day))
+                    .filter(dsl::request_count.lt(limit))
+                    .filter(dsl::exhausted.eq(false))
+                    .set((
+                        dsl::request_count.eq(dsl::request_count + 1),
+                        dsl::run_id.eq(run_id),
+                    ))
+                    .execute(conn)?;
+                Ok::<_, diesel::result::Error>(reserved == 1)
+            })?;
+            Ok(reserved)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn exhaust_api_quota(&mut self, key_id: &str, day: NaiveDate) -> Result<(), DbError> {
+        let key_id = key_id.to_string();
+        let run_id = self.run_id;
+        self.run(move |conn| {
+            diesel::insert_into(dsl::dart_api_quota)
+                .values((
+                    dsl::key_id.eq(&key_id),
+                    dsl::day.eq(day),
+                    dsl::exhausted.eq(true),
+                    dsl::run_id.eq(run_id),
+                ))
+                .on_conflict((dsl::key_id, dsl::day))
+                .do_update()
+                .set((
+                    dsl::exhausted.eq(true),
+                    dsl::run_id.eq(excluded(dsl::run_id)),
+                ))
+                .execute(conn)?;
+            tracing::info!(%key_id, %day, "Exhausted API quota");
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,340 @@
+use crate::model::smes::{Company, ListSnapshot, ListSnapshotDiff, NewCompany};
+use crate::query::{prefix_pattern, CompanyQuery, Page};
+use crate::schema::sqlite::smes_company::dsl;
+use crate::schema::sqlite::{smes_html, smes_list_snapshot, smes_list_snapshot_company};
+use crate::smes::CompanyDb;
+use crate::{DbError, SqliteDb};
+
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use hashbrown::HashSet;
+use types::company;
+
+/// Columns in the field order of [`Company`].
+pub(super) const COMPANY_COLUMNS: (
+    dsl::smes_id,
+    dsl::representative_name,
+    dsl::headquarters_address,
+    dsl::business_registration_number,
+    dsl::company_name,
+    dsl::industry_code,
+    dsl::industry_name,
+    dsl::created_at,
+    dsl::updated_at,
+    dsl::last_seen_at,
+    dsl::delisted_at,
+    dsl::run_id,
+) = (
+    dsl::smes_id,
+    dsl::representative_name,
+    dsl::headquarters_address,
+    dsl::business_registration_number,
+    dsl::company_name,
+    dsl::industry_code,
+    dsl::industry_name,
+    dsl::created_at,
+    dsl::updated_at,
+    dsl::last_seen_at,
+    dsl::delisted_at,
+    dsl::run_id,
+);
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sqlite::smes_company)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct CompanyRow {
+    smes_id: company::SmesId,
+    representative_name: company::RepresentativeName,
+    headquarters_address: company::HeadquartersAddress,
+    business_registration_number: Option<company::BusinessRegistrationNumber>,
+    company_name: company::Name,
+    industry_code: company::IndustryCode,
+    industry_name: company::IndustryName,
+    normalized_company_name: company::NormalizedName,
+    run_id: Option<i32>,
+}
+
+impl CompanyRow {
+    fn new(company: NewCompany, run_id: Option<i32>) -> Self {
+        CompanyRow {
+            normalized_company_name: company.company_name.normalized(),
+            run_id,
+            smes_id: company.smes_id,
+            representative_name: company.representative_name,
+            headquarters_address: company.headquarters_address,
+            business_registration_number: company.business_registration_number,
+            company_name: company.company_name,
+            industry_code: company.industry_code,
+            industry_name: company.industry_name,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = smes_list_snapshot_company)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct ListSnapshotCompanyRow {
+    snapshot_id: i32,
+    smes_id: company::SmesId,
+}
+
+impl CompanyDb for SqliteDb {
+    async fn get_companies(&mut self) -> Result<Vec<Company>, DbError> {
+        self.run(|conn| Ok(dsl::smes_company.select(COMPANY_COLUMNS).load(conn)?))
+            .await
+    }
+
+    async fn get_smes_ids(&mut self) -> Result<HashSet<company::SmesId>, DbError> {
+        self.run(|conn| {
+            Ok(dsl::smes_company
+                .select(dsl::smes_id)
+                .load::<company::SmesId>(conn)?
+                .into_iter()
+                .collect())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn query_companies(
+        &mut self,
+        query: &CompanyQuery,
+    ) -> Result<Page<Company, company::SmesId>, DbError> {
+        let query = query.clone();
+        self.run(move |conn| {
+            let mut select = dsl::smes_company.select(COMPANY_COLUMNS).into_boxed();
+            if let Some(industry_code) = query.industry_code.clone() {
+                select = select.filter(dsl::industry_code.eq(industry_code));
+            }
+            if let Some(region) = &query.region {
+                select = select.filter(
+                    dsl::headquarters_address
+                        .like(prefix_pattern(region))
+                        .escape('\\'),
+                );
+            }
+            if let Some(name_prefix) = &query.name_prefix {
+                select = select.filter(
+                    dsl::normalized_company_name
+                        .like(prefix_pattern(name_prefix.as_ref()))
+                        .escape('\\'),
+                );
+            }
+            if let Some(since) = query.created_since {
+                select = select.filter(dsl::created_at.ge(since));
+            }
+            if let Some(before) = query.created_before {
+                select = select.filter(dsl::created_at.lt(before));
+            }
+            if let Some(since) = query.updated_since {
+                select = select.filter(dsl::updated_at.ge(since));
+            }
+            if let Some(before) = query.updated_before {
+                select = select.filter(dsl::updated_at.lt(before));
+            }
+            match query.has_html {
+                Some(true) => {
+                    select = select
+                        .filter(dsl::smes_id.eq_any(smes_html::table.select(smes_html::smes_id)));
+                }
+                Some(false) => {
+                    select = select.filter(diesel::dsl::not(
+                        dsl::smes_id.eq_any(smes_html::table.select(smes_html::smes_id)),
+                    ));
+                }
+                None => {}
+            }
+            if let Some(run_id) = query.run_id {
+                select = select.filter(dsl::run_id.eq(run_id));
+            }
+            if let Some(after) = query.after.clone() {
+                select = select.filter(dsl::smes_id.gt(after));
+            }
+
+            let companies = select
+                .order(dsl::smes_id.asc())
+                .limit(query.page_size as i64)
+                .load(conn)?;
+            Ok(Page::new(
+                companies,
+                query.page_size,
+                |company: &Company| company.smes_id.clone(),
+            ))
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_companies_by_name(&mut self, name: &str) -> Result<Vec<Company>, DbError> {
+        let normalized_name = company::NormalizedName::from_name(name);
+        self.run(move |conn| {
+            Ok(dsl::smes_company
+                .filter(dsl::normalized_company_name.eq(normalized_name))
+                .select(COMPANY_COLUMNS)
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, companies))]
+    async fn insert_companies(&mut self, companies: Vec<NewCompany>) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        let rows: Vec<CompanyRow> = companies
+            .into_iter()
+            .map(|company| CompanyRow::new(company, run_id))
+            .collect();
+
+        self.run(move |conn| {
+            conn.immediate_transaction(|conn| {
+                for row in &rows {
+                    diesel::insert_into(dsl::smes_company)
+                        .values(row)
+                        .execute(conn)?;
+                }
+                tracing::trace!("Inserted {} companies", rows.len());
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, companies))]
+    async fn upsert_companies(&mut self, companies: Vec<NewCompany>) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        let rows: Vec<CompanyRow> = companies
+            .into_iter()
+            .map(|company| CompanyRow::new(company, run_id))
+            .collect();
+
+        self.run(move |conn| {
+            conn.immediate_transaction(|conn| {
+                // SQLite supports upserts of a single row only
+                for row in &rows {
+                    diesel::insert_into(dsl::smes_company)
+                        .values(row)
+                        .on_conflict(dsl::smes_id)
+                        .do_update()
+                        .set((
+                            dsl::representative_name.eq(excluded(dsl::representative_name)),
+                            dsl::headquarters_address.eq(excluded(dsl::headquarters_address)),
+                            dsl::business_registration_number
+                                .eq(excluded(dsl::business_registration_number)),
+                            dsl::company_name.eq(excluded(dsl::company_name)),
+                            dsl::normalized_company_name.eq(excluded(dsl::normalized_company_name)),
+                            dsl::industry_code.eq(excluded(dsl::industry_code)),
+                            dsl::industry_name.eq(excluded(dsl::industry_name)),
+                            dsl::run_id.eq(excluded(dsl::run_id)),
+                        ))
+                        .execute(conn)?;
+                }
+                tracing::trace!("Upserted {} companies", rows.len());
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, smes_ids))]
+    async fn insert_list_snapshot(
+        &mut self,
+        smes_ids: HashSet<company::SmesId>,
+    ) -> Result<ListSnapshot, DbError> {
+        self.run(move |conn| {
+            let snapshot = conn.immediate_transaction(|conn| {
+                let snapshot: ListSnapshot = diesel::insert_into(smes_list_snapshot::table)
+                    .values(smes_list_snapshot::company_count.eq(smes_ids.len() as i32))
+                    .returning((
+                        smes_list_snapshot::snapshot_id,
+                        smes_list_snapshot::company_count,
+                        smes_list_snapshot::created_at,
+                    ))
+                    .get_result(conn)?;
+
+                for smes_id in smes_ids {
+                    diesel::insert_into(smes_list_snapshot_company::table)
+                        .values(ListSnapshotCompanyRow {
+                            snapshot_id: snapshot.snapshot_id,
+                            smes_id,
+                        })
+                        .execute(conn)?;
+                }
+
+                let seen_ids = || {
+                    smes_list_snapshot_company::table
+                        .filter(smes_list_snapshot_company::snapshot_id.eq(snapshot.snapshot_id))
+                        .select(smes_list_snapshot_company::smes_id)
+                };
+
+                let seen_count =
+                    diesel::update(dsl::smes_company.filter(dsl::smes_id.eq_any(seen_ids())))
+                        .set((
+                            dsl::last_seen_at.eq(snapshot.created_at),
+                            dsl::delisted_at.eq(None::<time::PrimitiveDateTime>),
+                        ))
+                        .execute(conn)?;
+
+                let delisted_count = diesel::update(
+                    dsl::smes_company
+                        .filter(dsl::delisted_at.is_null())
+                        .filter(diesel::dsl::not(dsl::smes_id.eq_any(seen_ids()))),
+                )
+                .set(dsl::delisted_at.eq(snapshot.created_at))
+                .execute(conn)?;
+
+                tracing::info!(
+                    snapshot_id = snapshot.snapshot_id,
+                    seen_count,
+                    delisted_count,
+                    "Recorded list snapshot"
+                );
+                Ok::<_, diesel::result::Error>(snapshot)
+            })?;
+            Ok(snapshot)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_list_snapshots(&mut self) -> Result<Vec<ListSnapshot>, DbError> {
+        self.run(|conn| {
+            Ok(smes_list_snapshot::table
+                .order(smes_list_snapshot::snapshot_id.asc())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_list_snapshot_diff(
+        &mut self,
+        from_snapshot_id: i32,
+        to_snapshot_id: i32,
+    ) -> Result<ListSnapshotDiff, DbError> {
+        self.run(move |conn| {
+            let from = get_list_snapshot_smes_ids(conn, from_snapshot_id)?;
+            let to = get_list_snapshot_smes_ids(conn, to_snapshot_id)?;
+
+            Ok(ListSnapshotDiff {
+                from_snapshot_id,
+                to_snapshot_id,
+                added: to.difference(&from).cloned().collect(),
+                removed: from.difference(&to).cloned().collect(),
+            })
+        })
+        .await
+    }
+}
+
+fn get_list_snapshot_smes_ids(
+    conn: &mut SqliteConnection,
+    snapshot_id: i32,
+) -> Result<HashSet<company::SmesId>, DbError> {
+    Ok(smes_list_snapshot_company::table
+        .filter(smes_list_snapshot_company::snapshot_id.eq(snapshot_id))
+        .select(smes_list_snapshot_company::smes_id)
+        .load::<company::SmesId>(conn)?
+        .into_iter()
+        .collect())
+}
@@ -0,0 +1,158 @@
+use crate::dart::CompanyIdDb;
+use crate::model::dart::{CompanyId, CompanyWithFilings, Filing};
+use crate::schema::sqlite::dart_company_id::dsl;
+use crate::schema::sqlite::dart_filing;
+use crate::{DbError, SqliteDb};
+
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use types::{company, YYYYMMDD};
+
+/// Columns in the field order of [`CompanyId`].
+const COMPANY_ID_COLUMNS: (
+    dsl::dart_id,
+    dsl::company_name,
+    dsl::stock_code,
+    dsl::id_modify_date,
+) = (
+    dsl::dart_id,
+    dsl::company_name,
+    dsl::stock_code,
+    dsl::id_modify_date,
+);
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sqlite::dart_company_id)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct CompanyIdRow {
+    dart_id: company::DartId,
+    company_name: company::Name,
+    stock_code: Option<company::StockCode>,
+    id_modify_date: Option<YYYYMMDD>,
+    normalized_company_name: company::NormalizedName,
+}
+
+impl From<CompanyId> for CompanyIdRow {
+    fn from(company_id: CompanyId) -> Self {
+        CompanyIdRow {
+            normalized_company_name: company_id.company_name.normalized(),
+            dart_id: company_id.dart_id,
+            company_name: company_id.company_name,
+            stock_code: company_id.stock_code,
+            id_modify_date: company_id.id_modify_date,
+        }
+    }
+}
+
+impl CompanyIdDb for SqliteDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_company_ids(&mut self) -> Result<Vec<CompanyId>, DbError> {
+        self.run(|conn| Ok(dsl::dart_company_id.select(COMPANY_ID_COLUMNS).load(conn)?))
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_company_ids_by_name(&mut self, name: &str) -> Result<Vec<CompanyId>, DbError> {
+        let normalized_name = company::NormalizedName::from_name(name);
+        self.run(move |conn| {
+            Ok(dsl::dart_company_id
+                .filter(dsl::normalized_company_name.eq(normalized_name))
+                .select(COMPANY_ID_COLUMNS)
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_company_with_filings(
+        &mut self,
+        dart_id: &str,
+    ) -> Result<Option<CompanyWithFilings>, DbError> {
+        let dart_id = dart_id.to_string();
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                let Some(company_id) = dsl::dart_company_id
+                    .find(&dart_id)
+                    .select(COMPANY_ID_COLUMNS)
+                    .first::<CompanyId>(conn)
+                    .optional()?
+                else {
+                    return Ok(None);
+                };
+                let filings = dart_filing::table
+                    .filter(dart_filing::dart_id.eq(&dart_id))
+                    .order((
+                        dart_filing::receipt_date.desc(),
+                        dart_filing::receipt_number.desc(),
+                    ))
+                    .load::<Filing>(conn)?;
+                Ok(Some(CompanyWithFilings {
+                    company_id,
+                    filings,
+                }))
+            })
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, company_ids))]
+    async fn insert_company_ids(&mut self, company_ids: Vec<CompanyId>) -> Result<(), DbError> {
+        let rows: Vec<CompanyIdRow> = company_ids.into_iter().map(CompanyIdRow::from).collect();
+
+        self.run(move |conn| {
+            conn.immediate_transaction(|conn| {
+                for row in &rows {
+                    diesel::insert_into(dsl::dart_company_id)
+                        .values(row)
+                        .execute(conn)?;
+                }
+                tracing::trace!("Inserted {} company_ids", rows.len());
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, company_ids))]
+    async fn upsert_company_ids(&mut self, company_ids: Vec<CompanyId>) -> Result<(), DbError> {
+        let rows: Vec<CompanyIdRow> = company_ids.into_iter().map(CompanyIdRow::from).collect();
+
+        self.run(move |conn| {
+            conn.immediate_transaction(|conn| {
+                for row in &rows {
+                    diesel::insert_into(dsl::dart_company_id)
+                        .values(row)
+                        .on_conflict(dsl::dart_id)
+                        .do_update()
+                        .set((
+                            dsl::company_name.eq(excluded(dsl::company_name)),
+                            dsl::normalized_company_name.eq(excluded(dsl::normalized_company_name)),
+                            dsl::stock_code.eq(excluded(dsl::stock_code)),
+                            dsl::id_modify_date.eq(excluded(dsl::id_modify_date)),
+                        ))
+                        .execute(conn)?;
+                }
+                tracing::trace!("Upserted {} company_ids", rows.len());
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+/// Insert the `placeholders` of [`CompanyId::placeholders_for`] which have no company id yet.
+pub(super) fn insert_placeholders(
+    conn: &mut SqliteConnection,
+    placeholders: &[CompanyId],
+) -> QueryResult<()> {
+    for placeholder in placeholders {
+        diesel::insert_into(dsl::dart_company_id)
+            .values(CompanyIdRow::from(placeholder.clone()))
+            .on_conflict(dsl::dart_id)
+            .do_nothing()
+            .execute(conn)?;
+    }
+    Ok(())
+}
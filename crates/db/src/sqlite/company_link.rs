@@ -0,0 +1,134 @@
+use super::company::COMPANY_COLUMNS;
+use super::html::select_latest_htmls;
+use crate::link::CompanyLinkDb;
+use crate::model::link::{CompanyLink, LinkedCompany, MatchMethod, NewCompanyLink};
+use crate::schema::sqlite::company_link::dsl;
+use crate::schema::sqlite::{dart_filing, smes_company};
+use crate::{DbError, SqliteDb};
+
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use hashbrown::HashSet;
+use types::company;
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sqlite::company_link)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct CompanyLinkRow {
+    smes_id: company::SmesId,
+    dart_id: company::DartId,
+    business_registration_number: Option<company::BusinessRegistrationNumber>,
+    corporation_registration_number: Option<company::CorporationRegistrationNumber>,
+    match_method: MatchMethod,
+    confidence: f64,
+}
+
+impl From<NewCompanyLink> for CompanyLinkRow {
+    fn from(link: NewCompanyLink) -> Self {
+        CompanyLinkRow {
+            smes_id: link.smes_id,
+            dart_id: link.dart_id,
+            business_registration_number: link.business_registration_number,
+            corporation_registration_number: link.corporation_registration_number,
+            match_method: link.match_method,
+            confidence: link.confidence,
+        }
+    }
+}
+
+impl CompanyLinkDb for SqliteDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_company_links(&mut self) -> Result<Vec<CompanyLink>, DbError> {
+        self.run(|conn| Ok(dsl::company_link.load(conn)?)).await
+    }
+
+    #[tracing::instrument(skip(self, company_links))]
+    async fn replace_company_links(
+        &mut self,
+        company_links: Vec<NewCompanyLink>,
+    ) -> Result<(), DbError> {
+        let rows: Vec<CompanyLinkRow> = company_links
+            .into_iter()
+            .map(CompanyLinkRow::from)
+            .collect();
+
+        self.run(move |conn| {
+            conn.immediate_transaction(|conn| {
+                let stored: Vec<(company::SmesId, company::DartId)> = dsl::company_link
+                    .select((dsl::smes_id, dsl::dart_id))
+                    .load(conn)?;
+                let keys: HashSet<_> = rows
+                    .iter()
+                    .map(|row| (&row.smes_id, &row.dart_id))
+                    .collect();
+                for (smes_id, dart_id) in &stored {
+                    if !keys.contains(&(smes_id, dart_id)) {
+                        diesel::delete(dsl::company_link.find((smes_id, dart_id))).execute(conn)?;
+                    }
+                }
+
+                for row in &rows {
+                    diesel::insert_into(dsl::company_link)
+                        .values(row)
+                        .on_conflict((dsl::smes_id, dsl::dart_id))
+                        .do_update()
+                        .set((
+                            dsl::business_registration_number
+                                .eq(excluded(dsl::business_registration_number)),
+                            dsl::corporation_registration_number
+                                .eq(excluded(dsl::corporation_registration_number)),
+                            dsl::match_method.eq(excluded(dsl::match_method)),
+                            dsl::confidence.eq(excluded(dsl::confidence)),
+                        ))
+                        .execute(conn)?;
+                }
+                tracing::trace!("Upserted {} company_links", rows.len());
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_linked_company(
+        &mut self,
+        smes_id: &str,
+    ) -> Result<Option<LinkedCompany>, DbError> {
+        let smes_id = smes_id.to_string();
+        self.run(move |conn| {
+            let company = smes_company::table
+                .filter(smes_company::smes_id.eq(&smes_id))
+                .select(COMPANY_COLUMNS)
+                .first(conn)
+                .optional()?;
+            let Some(company) = company else {
+                return Ok(None);
+            };
+
+            let links: Vec<CompanyLink> = dsl::company_link
+                .filter(dsl::smes_id.eq(&smes_id))
+                .load(conn)?;
+
+            let html = select_latest_htmls(conn, Some(&smes_id))?
+                .into_iter()
+                .next();
+
+            let dart_ids = links
+                .iter()
+                .map(|link| link.dart_id.clone())
+                .collect::<Vec<_>>();
+            let filings = dart_filing::table
+                .filter(dart_filing::dart_id.eq_any(dart_ids))
+                .load(conn)?;
+
+            Ok(Some(LinkedCompany {
+                links,
+                company,
+                html,
+                filings,
+            }))
+        })
+        .await
+    }
+}
@@ -0,0 +1,101 @@
+use crate::dart::CompanyProfileDb;
+use crate::model::dart::{CompanyProfile, NewCompanyProfile};
+use crate::schema::sqlite::dart_company_profile::dsl;
+use crate::{DbError, SqliteDb};
+
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use types::{company, YYYYMMDD};
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sqlite::dart_company_profile)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct CompanyProfileRow {
+    dart_id: company::DartId,
+    corporation_registration_number: Option<company::CorporationRegistrationNumber>,
+    business_registration_number: Option<company::BusinessRegistrationNumber>,
+    representative_name: Option<company::RepresentativeName>,
+    headquarters_address: Option<company::HeadquartersAddress>,
+    industry_code: Option<company::IndustryCode>,
+    established_date: Option<YYYYMMDD>,
+    fiscal_month: Option<i32>,
+    run_id: Option<i32>,
+}
+
+impl CompanyProfileRow {
+    fn new(profile: NewCompanyProfile, run_id: Option<i32>) -> Self {
+        CompanyProfileRow {
+            run_id,
+            dart_id: profile.dart_id,
+            corporation_registration_number: profile.corporation_registration_number,
+            business_registration_number: profile.business_registration_number,
+            representative_name: profile.representative_name,
+            headquarters_address: profile.headquarters_address,
+            industry_code: profile.industry_code,
+            established_date: profile.established_date,
+            fiscal_month: profile.fiscal_month,
+        }
+    }
+}
+
+impl CompanyProfileDb for SqliteDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_company_profiles(&mut self) -> Result<Vec<CompanyProfile>, DbError> {
+        self.run(|conn| Ok(dsl::dart_company_profile.load(conn)?))
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_company_profile(
+        &mut self,
+        dart_id: &str,
+    ) -> Result<Option<CompanyProfile>, DbError> {
+        let dart_id = dart_id.to_string();
+        self.run(move |conn| {
+            Ok(dsl::dart_company_profile
+                .find(dart_id)
+                .first(conn)
+                .optional()?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, profiles))]
+    async fn upsert_company_profiles(
+        &mut self,
+        profiles: Vec<NewCompanyProfile>,
+    ) -> Result<(), DbError> {
+        let run_id = self.run_id;
+        let rows: Vec<CompanyProfileRow> = profiles
+            .into_iter()
+            .map(|profile| CompanyProfileRow::new(profile, run_id))
+            .collect();
+        self.run(move |conn| {
+            conn.immediate_transaction(|conn| {
+                for row in &rows {
+                    diesel::insert_into(dsl::dart_company_profile)
+                        .values(row)
+                        .on_conflict(dsl::dart_id)
+                        .do_update()
+                        .set((
+                            dsl::corporation_registration_number
+                                .eq(excluded(dsl::corporation_registration_number)),
+                            dsl::business_registration_number
+                                .eq(excluded(dsl::business_registration_number)),
+                            dsl::representative_name.eq(excluded(dsl::representative_name)),
+                            dsl::headquarters_address.eq(excluded(dsl::headquarters_address)),
+                            dsl::industry_code.eq(excluded(dsl::industry_code)),
+                            dsl::established_date.eq(excluded(dsl::established_date)),
+                            dsl::fiscal_month.eq(excluded(dsl::fiscal_month)),
+                            dsl::run_id.eq(excluded(dsl::run_id)),
+                        ))
+                        .execute(conn)?;
+                }
+                tracing::trace!("Upserted {} company profiles", rows.len());
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,80 @@
+use crate::dart::DocumentDb;
+use crate::model::dart::{Document, NewDocument};
+use crate::schema::sqlite::{dart_document, dart_filing};
+use crate::{DbError, SqliteDb};
+
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use types::filing;
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sqlite::dart_document)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct DocumentRow {
+    receipt_number: filing::ReceiptNumber,
+    sha256: Option<String>,
+    byte_size: Option<i64>,
+    run_id: Option<i32>,
+}
+
+impl DocumentRow {
+    fn new(document: NewDocument, run_id: Option<i32>) -> Self {
+        DocumentRow {
+            run_id,
+            receipt_number: document.receipt_number,
+            sha256: document.sha256,
+            byte_size: document.byte_size,
+        }
+    }
+}
+
+impl DocumentDb for SqliteDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_document(&mut self, receipt_number: &str) -> Result<Option<Document>, DbError> {
+        let receipt_number = receipt_number.to_string();
+        self.run(move |conn| {
+            Ok(dart_document::table
+                .find(receipt_number)
+                .first(conn)
+                .optional()?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_receipt_numbers_without_document(
+        &mut self,
+        limit: i64,
+    ) -> Result<Vec<filing::ReceiptNumber>, DbError> {
+        self.run(move |conn| {
+            Ok(dart_filing::table
+                .left_join(dart_document::table)
+                .filter(dart_document::receipt_number.is_null())
+                .select(dart_filing::receipt_number)
+                .order(dart_filing::receipt_number.desc())
+                .limit(limit)
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn upsert_document(&mut self, document: NewDocument) -> Result<(), DbError> {
+        let row = DocumentRow::new(document, self.run_id);
+        self.run(move |conn| {
+            diesel::insert_into(dart_document::table)
+                .values(&row)
+                .on_conflict(dart_document::receipt_number)
+                .do_update()
+                .set((
+                    dart_document::sha256.eq(excluded(dart_document::sha256)),
+                    dart_document::byte_size.eq(excluded(dart_document::byte_size)),
+                    dart_document::run_id.eq(excluded(dart_document::run_id)),
+                    dart_document::checked_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)?;
+            Ok(())
+        })
+        .await
+    }
+}
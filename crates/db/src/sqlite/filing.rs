@@ -0,0 +1,156 @@
+use super::company_id;
+use crate::dart::FilingDb;
+use crate::model::dart::{CompanyId, Filing, NewFiling};
+use crate::query::{FilingQuery, Page};
+use crate::schema::sqlite::dart_filing::dsl;
+use crate::{DbError, SqliteDb};
+
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use types::{company, filing};
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sqlite::dart_filing)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct FilingRow {
+    dart_id: company::DartId,
+    report_name: filing::ReportName,
+    receipt_number: filing::ReceiptNumber,
+    filer_name: filing::FilerName,
+    receipt_date: filing::ReceiptDate,
+    remark: filing::Remark,
+    run_id: Option<i32>,
+}
+
+impl FilingRow {
+    fn new(filing: NewFiling, run_id: Option<i32>) -> Self {
+        FilingRow {
+            run_id,
+            dart_id: filing.dart_id,
+            report_name: filing.report_name,
+            receipt_number: filing.receipt_number,
+            filer_name: filing.filer_name,
+            receipt_date: filing.receipt_date,
+            remark: filing.remark,
+        }
+    }
+}
+
+impl FilingDb for SqliteDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_filings(&mut self) -> Result<Vec<Filing>, DbError> {
+        self.run(|conn| Ok(dsl::dart_filing.load(conn)?)).await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn query_filings(
+        &mut self,
+        query: &FilingQuery,
+    ) -> Result<Page<Filing, filing::ReceiptNumber>, DbError> {
+        let query = query.clone();
+        self.run(move |conn| {
+            let mut select = dsl::dart_filing.into_boxed();
+            if let Some(dart_id) = query.dart_id.clone() {
+                select = select.filter(dsl::dart_id.eq(dart_id));
+            }
+            if let Some(from) = query.receipt_date_from.clone() {
+                select = select.filter(dsl::receipt_date.ge(from));
+            }
+            if let Some(to) = query.receipt_date_to.clone() {
+                select = select.filter(dsl::receipt_date.le(to));
+            }
+            if let Some(is_corrected) = query.is_corrected {
+                select = select.filter(dsl::is_corrected.eq(is_corrected));
+            }
+            if let Some(is_withdrawn) = query.is_withdrawn {
+                select = select.filter(dsl::is_withdrawn.eq(is_withdrawn));
+            }
+            if let Some(run_id) = query.run_id {
+                select = select.filter(dsl::run_id.eq(run_id));
+            }
+            if let Some(after) = query.after.clone() {
+                select = select.filter(dsl::receipt_number.gt(after));
+            }
+
+            let filings = select
+                .order(dsl::receipt_number.asc())
+                .limit(query.page_size as i64)
+                .load(conn)?;
+            Ok(Page::new(filings, query.page_size, |filing: &Filing| {
+                filing.receipt_number.clone()
+            }))
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_latest_receipt_date(&mut self) -> Result<Option<filing::ReceiptDate>, DbError> {
+        self.run(|conn| {
+            Ok(dsl::dart_filing
+                .select(diesel::dsl::max(dsl::receipt_date))
+                .first(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, filings))]
+    async fn insert_filings(&mut self, filings: Vec<NewFiling>) -> Result<(), DbError> {
+        let placeholders = CompanyId::placeholders_for(&filings);
+        let run_id = self.run_id;
+        let rows: Vec<FilingRow> = filings
+            .into_iter()
+            .map(|filing| FilingRow::new(filing, run_id))
+            .collect();
+
+        self.run(move |conn| {
+            conn.immediate_transaction(|conn| {
+                company_id::insert_placeholders(conn, &placeholders)?;
+                for row in &rows {
+                    diesel::insert_into(dsl::dart_filing)
+                        .values(row)
+                        .execute(conn)?;
+                }
+                tracing::trace!("Inserted {} filings", rows.len());
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, filings))]
+    async fn upsert_filings(&mut self, filings: Vec<NewFiling>) -> Result<(), DbError> {
+        let placeholders = CompanyId::placeholders_for(&filings);
+        let run_id = self.run_id;
+        let rows: Vec<FilingRow> = filings
+            .into_iter()
+            .map(|filing| FilingRow::new(filing, run_id))
+            .collect();
+
+        self.run(move |conn| {
+            conn.immediate_transaction(|conn| {
+                company_id::insert_placeholders(conn, &placeholders)?;
+                // SQLite supports upserts of a single row only
+                for row in &rows {
+                    diesel::insert_into(dsl::dart_filing)
+                        .values(row)
+                        .on_conflict(dsl::receipt_number)
+                        .do_update()
+                        .set((
+                            dsl::dart_id.eq(excluded(dsl::dart_id)),
+                            dsl::report_name.eq(excluded(dsl::report_name)),
+                            dsl::filer_name.eq(excluded(dsl::filer_name)),
+                            dsl::receipt_date.eq(excluded(dsl::receipt_date)),
+                            dsl::remark.eq(excluded(dsl::remark)),
+                            dsl::run_id.eq(excluded(dsl::run_id)),
+                        ))
+                        .execute(conn)?;
+                }
+                tracing::trace!("Upserted {} filings", rows.len());
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+}
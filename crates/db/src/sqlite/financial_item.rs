@@ -0,0 +1,209 @@
+use crate::dart::{group_by_statement, FinancialItemDb};
+use crate::model::dart::{
+    FinancialItem, FinancialStatementCheck, FinancialStatementKey, FsDiv, NewFinancialItem,
+    NewFinancialStatementCheck, ReportCode, Statement,
+};
+use crate::schema::sqlite::dart_financial_item::dsl;
+use crate::schema::sqlite::dart_financial_statement_check;
+use crate::{DbError, SqliteDb};
+
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use types::{company, filing};
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sqlite::dart_financial_item)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct FinancialItemRow {
+    dart_id: company::DartId,
+    business_year: i32,
+    report_code: ReportCode,
+    fs_div: FsDiv,
+    statement: Statement,
+    ord: i32,
+    account_detail: String,
+    account_id: String,
+    account_name: String,
+    current_amount: Option<i64>,
+    previous_amount: Option<i64>,
+    before_previous_amount: Option<i64>,
+    currency: String,
+    receipt_number: filing::ReceiptNumber,
+    run_id: Option<i32>,
+}
+
+impl FinancialItemRow {
+    fn new(item: NewFinancialItem, run_id: Option<i32>) -> Self {
+        FinancialItemRow {
+            run_id,
+            dart_id: item.dart_id,
+            business_year: item.business_year,
+            report_code: item.report_code,
+            fs_div: item.fs_div,
+            statement: item.statement,
+            ord: item.ord,
+            account_detail: item.account_detail,
+            account_id: item.account_id,
+            account_name: item.account_name,
+            current_amount: item.current_amount,
+            previous_amount: item.previous_amount,
+            before_previous_amount: item.before_previous_amount,
+            currency: item.currency,
+            receipt_number: item.receipt_number,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sqlite::dart_financial_statement_check)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct FinancialStatementCheckRow {
+    dart_id: company::DartId,
+    business_year: i32,
+    report_code: ReportCode,
+    fs_div: FsDiv,
+    item_count: i32,
+    skipped_count: i32,
+    run_id: Option<i32>,
+}
+
+impl FinancialStatementCheckRow {
+    fn new(check: NewFinancialStatementCheck, run_id: Option<i32>) -> Self {
+        FinancialStatementCheckRow {
+            run_id,
+            dart_id: check.dart_id,
+            business_year: check.business_year,
+            report_code: check.report_code,
+            fs_div: check.fs_div,
+            item_count: check.item_count,
+            skipped_count: check.skipped_count,
+        }
+    }
+}
+
+impl FinancialItemDb for SqliteDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_financial_items(&mut self, dart_id: &str) -> Result<Vec<FinancialItem>, DbError> {
+        let dart_id = dart_id.to_string();
+        self.run(move |conn| {
+            Ok(dsl::dart_financial_item
+                .filter(dsl::dart_id.eq(dart_id))
+                .order((
+                    dsl::business_year,
+                    dsl::report_code,
+                    dsl::fs_div,
+                    dsl::statement,
+                    dsl::ord,
+                    dsl::account_detail,
+                ))
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_financial_statement_receipts(
+        &mut self,
+    ) -> Result<Vec<(FinancialStatementKey, filing::ReceiptNumber)>, DbError> {
+        self.run(|conn| {
+            let keys = dsl::dart_financial_item
+                .select((
+                    dsl::dart_id,
+                    dsl::business_year,
+                    dsl::report_code,
+                    dsl::fs_div,
+                    dsl::receipt_number,
+                ))
+                .distinct()
+                .load(conn)?;
+            Ok(keys
+                .into_iter()
+                .map(
+                    |(dart_id, business_year, report_code, fs_div, receipt_number)| {
+                        let key = FinancialStatementKey {
+                            dart_id,
+                            business_year,
+                            report_code,
+                            fs_div,
+                        };
+                        (key, receipt_number)
+                    },
+                )
+                .collect())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, items))]
+    async fn replace_financial_statements(
+        &mut self,
+        items: Vec<NewFinancialItem>,
+    ) -> Result<(), DbError> {
+        for (key, items) in group_by_statement(items) {
+            tracing::trace!(?key, count = items.len(), "Replacing financial statement");
+            let rows: Vec<FinancialItemRow> = items
+                .into_iter()
+                .map(|item| FinancialItemRow::new(item, self.run_id))
+                .collect();
+            self.run(move |conn| {
+                conn.immediate_transaction(|conn| {
+                    diesel::delete(
+                        dsl::dart_financial_item
+                            .filter(dsl::dart_id.eq(&key.dart_id))
+                            .filter(dsl::business_year.eq(key.business_year))
+                            .filter(dsl::report_code.eq(key.report_code))
+                            .filter(dsl::fs_div.eq(key.fs_div)),
+                    )
+                    .execute(conn)?;
+                    for row in &rows {
+                        diesel::insert_into(dsl::dart_financial_item)
+                            .values(row)
+                            .execute(conn)?;
+                    }
+                    Ok::<_, diesel::result::Error>(())
+                })?;
+                Ok(())
+            })
+            .await?;
+        }
+        Ok(())
+    }
+    #[tracing::instrument(skip(self))]
+    async fn get_financial_statement_checks(
+        &mut self,
+    ) -> Result<Vec<FinancialStatementCheck>, DbError> {
+        self.run(|conn| Ok(dart_financial_statement_check::table.load(conn)?))
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn upsert_financial_statement_check(
+        &mut self,
+        check: NewFinancialStatementCheck,
+    ) -> Result<(), DbError> {
+        let row = FinancialStatementCheckRow::new(check, self.run_id);
+        self.run(move |conn| {
+            diesel::insert_into(dart_financial_statement_check::table)
+                .values(&row)
+                .on_conflict((
+                    dart_financial_statement_check::dart_id,
+                    dart_financial_statement_check::business_year,
+                    dart_financial_statement_check::report_code,
+                    dart_financial_statement_check::fs_div,
+                ))
+                .do_update()
+                .set((
+                    dart_financial_statement_check::item_count
+                        .eq(excluded(dart_financial_statement_check::item_count)),
+                    dart_financial_statement_check::skipped_count
+                        .eq(excluded(dart_financial_statement_check::skipped_count)),
+                    dart_financial_statement_check::run_id
+                        .eq(excluded(dart_financial_statement_check::run_id)),
+                    dart_financial_statement_check::checked_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)?;
+            Ok(())
+        })
+        .await
+    }
+}
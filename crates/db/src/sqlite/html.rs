@@ -0,0 +1,231 @@
+use crate::batch::{self, BATCH_SIZE, BATCH_TIMEOUT};
+use crate::blob::Blob;
+use crate::model::smes::{
+    Html, HtmlDeadLetter, HtmlRevision, HtmlRevisionRow, HtmlWithBody, NewHtml,
+};
+use crate::schema::sqlite::smes_html::dsl;
+use crate::schema::sqlite::{smes_html_dead_letter, smes_html_revision};
+use crate::smes::HtmlDb;
+use crate::{DbError, SqliteDb};
+
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use hashbrown::HashSet;
+use tokio::sync::mpsc::UnboundedReceiver;
+use types::company;
+
+impl HtmlDb for SqliteDb {
+    async fn select_html(&mut self, smes_id: &str) -> Result<Option<Html>, DbError> {
+        let smes_id = smes_id.to_string();
+        self.run(move |conn| {
+            Ok(select_latest_htmls(conn, Some(&smes_id))?
+                .into_iter()
+                .next())
+        })
+        .await
+    }
+
+    async fn select_htmls(&mut self) -> Result<Vec<Html>, DbError> {
+        self.run(|conn| select_latest_htmls(conn, None)).await
+    }
+
+    async fn select_html_ids(&mut self) -> Result<HashSet<company::SmesId>, DbError> {
+        self.run(|conn| {
+            Ok(dsl::smes_html
+                .select(dsl::smes_id)
+                .load(conn)?
+                .into_iter()
+                .collect())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn select_html_revisions(&mut self, smes_id: &str) -> Result<Vec<HtmlRevision>, DbError> {
+        let smes_id = smes_id.to_string();
+        self.run(move |conn| {
+            smes_html_revision::table
+                .filter(smes_html_revision::smes_id.eq(smes_id))
+                .order(smes_html_revision::fetched_at.asc())
+                .load::<HtmlRevisionRow>(conn)?
+                .into_iter()
+                .map(HtmlRevision::try_from)
+                .collect()
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn select_html_dead_letters(&mut self) -> Result<Vec<HtmlDeadLetter>, DbError> {
+        self.run(|conn| {
+            Ok(smes_html_dead_letter::table
+                .order(smes_html_dead_letter::id.asc())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, htmls))]
+    async fn insert_html_channel(
+        &mut self,
+        mut htmls: UnboundedReceiver<NewHtml>,
+    ) -> Result<(), DbError> {
+        while let Some(batch) = batch::recv_batch(&mut htmls, BATCH_SIZE, BATCH_TIMEOUT).await {
+            tracing::trace!(count = batch.len(), "Inserting htmls");
+            let run_id = self.run_id;
+            self.run(move |conn| write_batch(conn, batch, run_id, insert_html))
+                .await?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, htmls))]
+    async fn upsert_html_channel(
+        &mut self,
+        mut htmls: UnboundedReceiver<NewHtml>,
+    ) -> Result<(), DbError> {
+        while let Some(batch) = batch::recv_batch(&mut htmls, BATCH_SIZE, BATCH_TIMEOUT).await {
+            tracing::trace!(count = batch.len(), "Upserting htmls");
+            let run_id = self.run_id;
+            self.run(move |conn| write_batch(conn, batch, run_id, upsert_html))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Write `htmls` in one transaction, moving the pages the database rejects to the dead-letter table.
+fn write_batch(
+    conn: &mut SqliteConnection,
+    htmls: Vec<NewHtml>,
+    run_id: Option<i32>,
+    write: fn(&mut SqliteConnection, &NewHtml, &Blob, Option<i32>) -> Result<(), DbError>,
+) -> Result<(), DbError> {
+    let blobs = htmls
+        .iter()
+        .map(|html| Blob::compress(html.html_content.as_ref().as_str()))
+        .collect::<Result<Vec<_>, _>>()?;
+    conn.immediate_transaction(|conn| {
+        for (html, blob) in htmls.iter().zip(&blobs) {
+            // Nested transactions are savepoints, so a rejected page leaves the batch intact
+            match conn.transaction(|conn| write(conn, html, blob, run_id)) {
+                Ok(()) => {}
+                Err(e) if batch::is_rejected_row(&e) => {
+                    tracing::warn!(smes_id = %html.smes_id, error = %e, "Moving html to the dead-letter table");
+                    diesel::insert_into(smes_html_dead_letter::table)
+                        .values((
+                            smes_html_dead_letter::smes_id.eq(&html.smes_id),
+                            smes_html_dead_letter::html_content.eq(&html.html_content),
+                            smes_html_dead_letter::error.eq(e.to_string()),
+                        ))
+                        .execute(conn)?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    })
+}
+
+fn insert_html(
+    conn: &mut SqliteConnection,
+    html: &NewHtml,
+    blob: &Blob,
+    run_id: Option<i32>,
+) -> Result<(), DbError> {
+    insert_revision(conn, &html.smes_id, blob)?;
+    diesel::insert_into(dsl::smes_html)
+        .values((
+            dsl::smes_id.eq(&html.smes_id),
+            dsl::sha256.eq(blob.sha256.as_str()),
+            dsl::run_id.eq(run_id),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+fn upsert_html(
+    conn: &mut SqliteConnection,
+    html: &NewHtml,
+    blob: &Blob,
+    run_id: Option<i32>,
+) -> Result<(), DbError> {
+    let latest_sha256: Option<String> = dsl::smes_html
+        .filter(dsl::smes_id.eq(&html.smes_id))
+        .select(dsl::sha256)
+        .first(conn)
+        .optional()?;
+    if latest_sha256.as_ref() == Some(&blob.sha256) {
+        tracing::trace!(smes_id = %html.smes_id, "Html is unchanged");
+        return Ok(());
+    }
+
+    insert_revision(conn, &html.smes_id, blob)?;
+    diesel::insert_into(dsl::smes_html)
+        .values((
+            dsl::smes_id.eq(&html.smes_id),
+            dsl::sha256.eq(blob.sha256.as_str()),
+            dsl::run_id.eq(run_id),
+        ))
+        .on_conflict(dsl::smes_id)
+        .do_update()
+        .set((
+            dsl::sha256.eq(excluded(dsl::sha256)),
+            dsl::run_id.eq(excluded(dsl::run_id)),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// The latest pages of all companies, or of the company with `smes_id`.
+pub(super) fn select_latest_htmls(
+    conn: &mut SqliteConnection,
+    smes_id: Option<&str>,
+) -> Result<Vec<Html>, DbError> {
+    let mut query = dsl::smes_html
+        .inner_join(
+            smes_html_revision::table.on(smes_html_revision::smes_id
+                .eq(dsl::smes_id)
+                .and(smes_html_revision::sha256.eq(dsl::sha256))),
+        )
+        .select((
+            dsl::smes_id,
+            smes_html_revision::body,
+            dsl::created_at,
+            dsl::updated_at,
+            dsl::run_id,
+        ))
+        .into_boxed();
+    if let Some(smes_id) = smes_id {
+        query = query.filter(dsl::smes_id.eq(smes_id.to_string()));
+    }
+
+    query
+        .load::<HtmlWithBody>(conn)?
+        .into_iter()
+        .map(Html::try_from)
+        .collect()
+}
+
+/// Store a revision, or mark a page the company already had with the same content as fetched again,
+/// so a page which changes back shows up as the latest revision.
+fn insert_revision(
+    conn: &mut SqliteConnection,
+    smes_id: &company::SmesId,
+    blob: &Blob,
+) -> QueryResult<usize> {
+    // `current_timestamp` only has a resolution of seconds,
+    // which would leave revisions fetched within a second unordered.
+    let now = time::OffsetDateTime::now_utc();
+    diesel::insert_into(smes_html_revision::table)
+        .values((
+            smes_html_revision::smes_id.eq(smes_id),
+            smes_html_revision::sha256.eq(blob.sha256.as_str()),
+            smes_html_revision::fetched_at.eq(time::PrimitiveDateTime::new(now.date(), now.time())),
+            smes_html_revision::body.eq(blob.body.as_slice()),
+        ))
+        .on_conflict((smes_html_revision::smes_id, smes_html_revision::sha256))
+        .do_update()
+        .set(smes_html_revision::fetched_at.eq(excluded(smes_html_revision::fetched_at)))
+        .execute(conn)
+}
@@ -0,0 +1,88 @@
+use crate::ingest::IngestRunDb;
+use crate::model::ingest::{IngestCounts, IngestRun, NewIngestRun};
+use crate::schema::sqlite::ingest_run::dsl;
+use crate::{DbError, SqliteDb};
+
+use diesel::prelude::*;
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sqlite::ingest_run)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct IngestRunRow {
+    runner: String,
+    git_rev: Option<String>,
+    config: Option<String>,
+    source_url: Option<String>,
+}
+
+impl From<NewIngestRun> for IngestRunRow {
+    fn from(run: NewIngestRun) -> Self {
+        IngestRunRow {
+            runner: run.runner,
+            git_rev: run.git_rev,
+            config: run.config,
+            source_url: run.source_url,
+        }
+    }
+}
+
+impl IngestRunDb for SqliteDb {
+    #[tracing::instrument(skip(self))]
+    async fn start_ingest_run(&mut self, run: NewIngestRun) -> Result<(IngestRun, Self), DbError> {
+        let row = IngestRunRow::from(run);
+        let run: IngestRun = self
+            .run(move |conn| {
+                Ok(diesel::insert_into(dsl::ingest_run)
+                    .values(&row)
+                    .get_result(conn)?)
+            })
+            .await?;
+        tracing::info!(run_id = run.run_id, runner = %run.runner, "Started ingest run");
+        let run_db = Self {
+            run_id: Some(run.run_id),
+            ..self.clone()
+        };
+        Ok((run, run_db))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn finish_ingest_run(
+        &mut self,
+        run_id: i32,
+        counts: IngestCounts,
+    ) -> Result<IngestRun, DbError> {
+        let run: IngestRun = self
+            .run(move |conn| {
+                Ok(diesel::update(dsl::ingest_run.find(run_id))
+                    .set((
+                        dsl::finished_at.eq(diesel::dsl::now),
+                        dsl::inserted_count.eq(counts.inserted),
+                        dsl::updated_count.eq(counts.updated),
+                        dsl::unchanged_count.eq(counts.unchanged),
+                    ))
+                    .get_result(conn)?)
+            })
+            .await?;
+        tracing::info!(run_id, ?counts, "Finished ingest run");
+        Ok(run)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn fail_ingest_run(&mut self, run_id: i32, error: String) -> Result<IngestRun, DbError> {
+        let run: IngestRun = self
+            .run(move |conn| {
+                Ok(diesel::update(dsl::ingest_run.find(run_id))
+                    .set((dsl::finished_at.eq(diesel::dsl::now), dsl::error.eq(error)))
+                    .get_result(conn)?)
+            })
+            .await?;
+        tracing::warn!(run_id, error = ?run.error, "Failed ingest run");
+        Ok(run)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_ingest_runs(&mut self) -> Result<Vec<IngestRun>, DbError> {
+        self.run(|conn| Ok(dsl::ingest_run.order(dsl::run_id.asc()).load(conn)?))
+            .await
+    }
+}
@@ -0,0 +1,402 @@
+use crate::dart::{periodic_report_receipt, PeriodicReportDb};
+use crate::model::dart::{
+    Employee, Executive, Gender, MajorShareholder, NewEmployee, NewExecutive, NewMajorShareholder,
+    NewPeriodicReportCheck, PeriodicReportCheck, PeriodicReportKey, PeriodicReportSection,
+    ReportCode,
+};
+use crate::schema::sqlite::{
+    dart_employee, dart_executive, dart_major_shareholder, dart_periodic_report_check,
+};
+use crate::{DbError, SqliteDb};
+
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use types::{company, filing, YYYYMMDD};
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sqlite::dart_major_shareholder)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct MajorShareholderRow {
+    dart_id: company::DartId,
+    business_year: i32,
+    report_code: ReportCode,
+    ord: i32,
+    name: String,
+    relation: Option<String>,
+    stock_kind: Option<String>,
+    shares_at_start: Option<i64>,
+    stake_at_start: Option<f64>,
+    shares_at_end: Option<i64>,
+    stake_at_end: Option<f64>,
+    remark: Option<String>,
+    settlement_date: Option<YYYYMMDD>,
+    receipt_number: filing::ReceiptNumber,
+    run_id: Option<i32>,
+}
+
+impl MajorShareholderRow {
+    fn new(key: &PeriodicReportKey, row: NewMajorShareholder, run_id: Option<i32>) -> Self {
+        MajorShareholderRow {
+            run_id,
+            dart_id: key.dart_id.clone(),
+            business_year: key.business_year,
+            report_code: key.report_code,
+            ord: row.ord,
+            name: row.name,
+            relation: row.relation,
+            stock_kind: row.stock_kind,
+            shares_at_start: row.shares_at_start,
+            stake_at_start: row.stake_at_start,
+            shares_at_end: row.shares_at_end,
+            stake_at_end: row.stake_at_end,
+            remark: row.remark,
+            settlement_date: row.settlement_date,
+            receipt_number: row.receipt_number,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sqlite::dart_executive)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct ExecutiveRow {
+    dart_id: company::DartId,
+    business_year: i32,
+    report_code: ReportCode,
+    ord: i32,
+    name: String,
+    gender: Option<Gender>,
+    birth_month: Option<String>,
+    position: Option<String>,
+    registration: Option<String>,
+    is_full_time: Option<bool>,
+    responsibility: Option<String>,
+    main_career: Option<String>,
+    relation_to_major_shareholder: Option<String>,
+    tenure: Option<String>,
+    term_end: Option<String>,
+    settlement_date: Option<YYYYMMDD>,
+    receipt_number: filing::ReceiptNumber,
+    run_id: Option<i32>,
+}
+
+impl ExecutiveRow {
+    fn new(key: &PeriodicReportKey, row: NewExecutive, run_id: Option<i32>) -> Self {
+        ExecutiveRow {
+            run_id,
+            dart_id: key.dart_id.clone(),
+            business_year: key.business_year,
+            report_code: key.report_code,
+            ord: row.ord,
+            name: row.name,
+            gender: row.gender,
+            birth_month: row.birth_month,
+            position: row.position,
+            registration: row.registration,
+            is_full_time: row.is_full_time,
+            responsibility: row.responsibility,
+            main_career: row.main_career,
+            relation_to_major_shareholder: row.relation_to_major_shareholder,
+            tenure: row.tenure,
+            term_end: row.term_end,
+            settlement_date: row.settlement_date,
+            receipt_number: row.receipt_number,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sqlite::dart_employee)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct EmployeeRow {
+    dart_id: company::DartId,
+    business_year: i32,
+    report_code: ReportCode,
+    ord: i32,
+    business_segment: Option<String>,
+    gender: Option<Gender>,
+    regular_count: Option<i64>,
+    contract_count: Option<i64>,
+    total_count: Option<i64>,
+    average_tenure: Option<String>,
+    annual_salary_total: Option<i64>,
+    average_salary: Option<i64>,
+    remark: Option<String>,
+    settlement_date: Option<YYYYMMDD>,
+    receipt_number: filing::ReceiptNumber,
+    run_id: Option<i32>,
+}
+
+impl EmployeeRow {
+    fn new(key: &PeriodicReportKey, row: NewEmployee, run_id: Option<i32>) -> Self {
+        EmployeeRow {
+            run_id,
+            dart_id: key.dart_id.clone(),
+            business_year: key.business_year,
+            report_code: key.report_code,
+            ord: row.ord,
+            business_segment: row.business_segment,
+            gender: row.gender,
+            regular_count: row.regular_count,
+            contract_count: row.contract_count,
+            total_count: row.total_count,
+            average_tenure: row.average_tenure,
+            annual_salary_total: row.annual_salary_total,
+            average_salary: row.average_salary,
+            remark: row.remark,
+            settlement_date: row.settlement_date,
+            receipt_number: row.receipt_number,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sqlite::dart_periodic_report_check)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct PeriodicReportCheckRow {
+    dart_id: company::DartId,
+    business_year: i32,
+    report_code: ReportCode,
+    section: PeriodicReportSection,
+    row_count: i32,
+    skipped_count: i32,
+    run_id: Option<i32>,
+}
+
+impl PeriodicReportCheckRow {
+    fn new(check: NewPeriodicReportCheck, run_id: Option<i32>) -> Self {
+        PeriodicReportCheckRow {
+            run_id,
+            dart_id: check.dart_id,
+            business_year: check.business_year,
+            report_code: check.report_code,
+            section: check.section,
+            row_count: check.row_count,
+            skipped_count: check.skipped_count,
+        }
+    }
+}
+
+impl PeriodicReportDb for SqliteDb {
+    #[tracing::instrument(skip(self))]
+    async fn get_major_shareholders(
+        &mut self,
+        dart_id: &str,
+    ) -> Result<Vec<MajorShareholder>, DbError> {
+        let dart_id = dart_id.to_string();
+        self.run(move |conn| {
+            Ok(dart_major_shareholder::table
+                .filter(dart_major_shareholder::dart_id.eq(dart_id))
+                .order((
+                    dart_major_shareholder::business_year,
+                    dart_major_shareholder::report_code,
+                    dart_major_shareholder::ord,
+                ))
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_executives(&mut self, dart_id: &str) -> Result<Vec<Executive>, DbError> {
+        let dart_id = dart_id.to_string();
+        self.run(move |conn| {
+            Ok(dart_executive::table
+                .filter(dart_executive::dart_id.eq(dart_id))
+                .order((
+                    dart_executive::business_year,
+                    dart_executive::report_code,
+                    dart_executive::ord,
+                ))
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_employees(&mut self, dart_id: &str) -> Result<Vec<Employee>, DbError> {
+        let dart_id = dart_id.to_string();
+        self.run(move |conn| {
+            Ok(dart_employee::table
+                .filter(dart_employee::dart_id.eq(dart_id))
+                .order((
+                    dart_employee::business_year,
+                    dart_employee::report_code,
+                    dart_employee::ord,
+                ))
+                .load(conn)?)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_periodic_report_receipts(
+        &mut self,
+        section: PeriodicReportSection,
+    ) -> Result<Vec<(PeriodicReportKey, filing::ReceiptNumber)>, DbError> {
+        self.run(move |conn| {
+            let receipts: Vec<(company::DartId, i32, ReportCode, filing::ReceiptNumber)> =
+                match section {
+                    PeriodicReportSection::MajorShareholder => dart_major_shareholder::table
+                        .select((
+                            dart_major_shareholder::dart_id,
+                            dart_major_shareholder::business_year,
+                            dart_major_shareholder::report_code,
+                            dart_major_shareholder::receipt_number,
+                        ))
+                        .distinct()
+                        .load(conn)?,
+                    PeriodicReportSection::Executive => dart_executive::table
+                        .select((
+                            dart_executive::dart_id,
+                            dart_executive::business_year,
+                            dart_executive::report_code,
+                            dart_executive::receipt_number,
+                        ))
+                        .distinct()
+                        .load(conn)?,
+                    PeriodicReportSection::Employee => dart_employee::table
+                        .select((
+                            dart_employee::dart_id,
+                            dart_employee::business_year,
+                            dart_employee::report_code,
+                            dart_employee::receipt_number,
+                        ))
+                        .distinct()
+                        .load(conn)?,
+                };
+            Ok(receipts.into_iter().map(periodic_report_receipt).collect())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, rows))]
+    async fn replace_major_shareholders(
+        &mut self,
+        key: PeriodicReportKey,
+        rows: Vec<NewMajorShareholder>,
+    ) -> Result<(), DbError> {
+        let rows: Vec<MajorShareholderRow> = rows
+            .into_iter()
+            .map(|row| MajorShareholderRow::new(&key, row, self.run_id))
+            .collect();
+        self.run(move |conn| {
+            conn.immediate_transaction(|conn| {
+                diesel::delete(
+                    dart_major_shareholder::table
+                        .filter(dart_major_shareholder::dart_id.eq(&key.dart_id))
+                        .filter(dart_major_shareholder::business_year.eq(key.business_year))
+                        .filter(dart_major_shareholder::report_code.eq(key.report_code)),
+                )
+                .execute(conn)?;
+                for row in &rows {
+                    diesel::insert_into(dart_major_shareholder::table)
+                        .values(row)
+                        .execute(conn)?;
+                }
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, rows))]
+    async fn replace_executives(
+        &mut self,
+        key: PeriodicReportKey,
+        rows: Vec<NewExecutive>,
+    ) -> Result<(), DbError> {
+        let rows: Vec<ExecutiveRow> = rows
+            .into_iter()
+            .map(|row| ExecutiveRow::new(&key, row, self.run_id))
+            .collect();
+        self.run(move |conn| {
+            conn.immediate_transaction(|conn| {
+                diesel::delete(
+                    dart_executive::table
+                        .filter(dart_executive::dart_id.eq(&key.dart_id))
+                        .filter(dart_executive::business_year.eq(key.business_year))
+                        .filter(dart_executive::report_code.eq(key.report_code)),
+                )
+                .execute(conn)?;
+                for row in &rows {
+                    diesel::insert_into(dart_executive::table)
+                        .values(row)
+                        .execute(conn)?;
+                }
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, rows))]
+    async fn replace_employees(
+        &mut self,
+        key: PeriodicReportKey,
+        rows: Vec<NewEmployee>,
+    ) -> Result<(), DbError> {
+        let rows: Vec<EmployeeRow> = rows
+            .into_iter()
+            .map(|row| EmployeeRow::new(&key, row, self.run_id))
+            .collect();
+        self.run(move |conn| {
+            conn.immediate_transaction(|conn| {
+                diesel::delete(
+                    dart_employee::table
+                        .filter(dart_employee::dart_id.eq(&key.dart_id))
+                        .filter(dart_employee::business_year.eq(key.business_year))
+                        .filter(dart_employee::report_code.eq(key.report_code)),
+                )
+                .execute(conn)?;
+                for row in &rows {
+                    diesel::insert_into(dart_employee::table)
+                        .values(row)
+                        .execute(conn)?;
+                }
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_periodic_report_checks(&mut self) -> Result<Vec<PeriodicReportCheck>, DbError> {
+        self.run(|conn| Ok(dart_periodic_report_check::table.load(conn)?))
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn upsert_periodic_report_check(
+        &mut self,
+        check: NewPeriodicReportCheck,
+    ) -> Result<(), DbError> {
+        let row = PeriodicReportCheckRow::new(check, self.run_id);
+        self.run(move |conn| {
+            diesel::insert_into(dart_periodic_report_check::table)
+                .values(&row)
+                .on_conflict((
+                    dart_periodic_report_check::dart_id,
+                    dart_periodic_report_check::business_year,
+                    dart_periodic_report_check::report_code,
+                    dart_periodic_report_check::section,
+                ))
+                .do_update()
+                .set((
+                    dart_periodic_report_check::row_count
+                        .eq(excluded(dart_periodic_report_check::row_count)),
+                    dart_periodic_report_check::skipped_count
+                        .eq(excluded(dart_periodic_report_check::skipped_count)),
+                    dart_periodic_report_check::run_id
+                        .eq(excluded(dart_periodic_report_check::run_id)),
+                    dart_periodic_report_check::checked_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)?;
+            Ok(())
+        })
+        .await
+    }
+}
@@ -1,13 +1,17 @@
 #![cfg(test)]
 
+mod in_memory;
 mod postgres;
+mod sqlite;
 
 use crate::db::Db;
 use fake::{Fake, Faker};
 use tokio::sync::mpsc;
 
 use crate::model::smes::NewHtml;
+pub(crate) use in_memory::InMemoryTestContext;
 pub(crate) use postgres::PostgresTestContext;
+pub(crate) use sqlite::SqliteTestContext;
 
 pub(crate) trait TestContext<D: Db> {
     async fn new(function_id: &str) -> Self;
@@ -101,6 +105,7 @@ pub(crate) trait TestContext<D: Db> {
                     filer_name: filing.filer_name,
                     receipt_date: filing.receipt_date,
                     remark: filing.remark,
+                    company_name: None,
                 }
             })
             .collect();
@@ -0,0 +1,21 @@
+use crate::test_utils::TestContext;
+use crate::{Db, InMemoryDb};
+
+pub(crate) struct InMemoryTestContext {
+    db: InMemoryDb,
+}
+
+impl TestContext<InMemoryDb> for InMemoryTestContext {
+    #[tracing::instrument]
+    async fn new(function_id: &str) -> Self {
+        let db = InMemoryDb::new(function_id)
+            .await
+            .expect("Failed to create in-memory test db");
+
+        Self { db }
+    }
+
+    fn db(&mut self) -> &mut InMemoryDb {
+        &mut self.db
+    }
+}
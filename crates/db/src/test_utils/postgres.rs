@@ -1,7 +1,5 @@
 use crate::test_utils::TestContext;
 use crate::PostgresDb;
-use diesel::{Connection, PgConnection};
-use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use testcontainers_modules::postgres::Postgres;
 use testcontainers_modules::testcontainers::runners::AsyncRunner;
 use testcontainers_modules::testcontainers::{ContainerAsync, ImageExt};
@@ -29,17 +27,9 @@ impl TestContext<PostgresDb> for PostgresTestContext {
         );
         tracing::trace!(%connection_string, "Connection string");
 
-        // Run migrations via diesel
-        tracing::trace!("Running migrations");
-        let mut conn = PgConnection::establish(&connection_string)
-            .expect("Failed to establish connection to test db");
-        const MIGRATIONS: EmbeddedMigrations = embed_migrations!("../../migrations");
-        let migration_versions = conn
-            .run_pending_migrations(MIGRATIONS)
-            .expect("Failed to run migrations");
-        tracing::debug!(?migration_versions, "Migrations ran successfully");
-
-        let db = PostgresDb { conn };
+        let db = PostgresDb::connect_and_migrate(&connection_string)
+            .await
+            .expect("Failed to migrate test db");
 
         Self { db, _node: node }
     }
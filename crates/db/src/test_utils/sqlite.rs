@@ -0,0 +1,43 @@
+use crate::test_utils::TestContext;
+use crate::{Db, SqliteDb};
+use std::path::{Path, PathBuf};
+
+pub(crate) struct SqliteTestContext {
+    db: SqliteDb,
+    path: PathBuf,
+}
+
+impl TestContext<SqliteDb> for SqliteTestContext {
+    #[tracing::instrument]
+    async fn new(function_id: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("{}.sqlite", function_id));
+        remove_database_files(&path);
+        tracing::trace!(?path, "Creating sqlite test db");
+
+        // Migrations are run by `SqliteDb::new`
+        let db = SqliteDb::new(&path)
+            .await
+            .expect("Failed to create sqlite test db");
+
+        Self { db, path }
+    }
+
+    fn db(&mut self) -> &mut SqliteDb {
+        &mut self.db
+    }
+}
+
+impl Drop for SqliteTestContext {
+    fn drop(&mut self) {
+        remove_database_files(&self.path);
+    }
+}
+
+/// Remove the database file along with its WAL files.
+fn remove_database_files(path: &Path) {
+    for suffix in ["", "-wal", "-shm"] {
+        let mut file = path.as_os_str().to_owned();
+        file.push(suffix);
+        let _ = std::fs::remove_file(file);
+    }
+}
@@ -0,0 +1,39 @@
+//! Embed the git revision the runners are built from, recorded with every ingest run.
+
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=GIT_REV");
+    // Builds without a checkout, e.g. in a container, can set `GIT_REV` instead.
+    let git_rev = std::env::var("GIT_REV")
+        .ok()
+        .or_else(|| git(&["rev-parse", "HEAD"]));
+    if let Some(git_rev) = git_rev {
+        println!("cargo:rustc-env=GIT_REV={git_rev}");
+    }
+
+    // A commit moves the branch HEAD points to rather than HEAD itself,
+    // and the branch may only be in `packed-refs`, so all of them are watched.
+    let mut watched = vec!["HEAD".to_string(), "packed-refs".to_string()];
+    if let Some(branch) = git(&["symbolic-ref", "-q", "HEAD"]) {
+        watched.push(branch);
+    }
+    for path in watched {
+        // Watching a missing file would rerun the script on every build
+        if let Some(path) =
+            git(&["rev-parse", "--git-path", &path]).filter(|path| Path::new(path).exists())
+        {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+}
+
+/// The trimmed output of `git` with `args`, `None` when it fails, e.g. outside a checkout.
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,47 @@
+use db::Db;
+use figment::providers::{Format, Toml};
+use figment::Figment;
+use runners::{
+    ingest_company_profiles, new_ingest_run, with_ingest_run, AppConfig, DartQuota, Database,
+};
+use tracing::Instrument;
+
+#[tokio::main]
+async fn main() {
+    tracing_setup::span!("main");
+
+    let app: AppConfig = Figment::new()
+        .merge(Toml::file("Settings.toml"))
+        .extract()
+        .expect("Failed to load settings");
+
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
+    match Database::connect(&database_url)
+        .in_current_span()
+        .await
+        .expect("Failed to connect to db")
+    {
+        Database::Postgres(db) => run(db, app).in_current_span().await,
+        Database::Sqlite(db) => run(db, app).in_current_span().await,
+    }
+}
+
+async fn run<D: Db>(mut db: D, app: AppConfig) {
+    let quota = DartQuota::from_env(app.dart_daily_request_limit)
+        .expect("Failed to create DART quota")
+        .with_rate_limit(app.dart_requests_per_minute);
+
+    let run = new_ingest_run(
+        "dart_company_profiles",
+        quota.domain(),
+        Some(format!("{app:?}")),
+    );
+    with_ingest_run(&mut db, run, |mut db| async move {
+        ingest_company_profiles(&mut db, &quota)
+            .in_current_span()
+            .await
+            .expect("Failed to ingest company profiles")
+    })
+    .in_current_span()
+    .await;
+}
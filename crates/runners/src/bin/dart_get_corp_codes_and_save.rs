@@ -1,14 +1,41 @@
-use open_dart::client::OpenDartApi;
+use db::Db;
+use figment::providers::{Format, Toml};
+use figment::Figment;
+use runners::{ingest_corp_codes, new_ingest_run, with_ingest_run, AppConfig, DartQuota, Database};
+use tracing::Instrument;
 
 #[tokio::main]
 async fn main() {
     tracing_setup::span!("main");
 
-    let api = OpenDartApi::default();
-    let corp_codes = api
-        .get_corp_codes()
+    let app: AppConfig = Figment::new()
+        .merge(Toml::file("Settings.toml"))
+        .extract()
+        .expect("Failed to load settings");
+
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
+    match Database::connect(&database_url)
+        .in_current_span()
         .await
-        .expect("Failed to get corp codes");
+        .expect("Failed to connect to db")
+    {
+        Database::Postgres(db) => run(db, app).in_current_span().await,
+        Database::Sqlite(db) => run(db, app).in_current_span().await,
+    }
+}
+
+async fn run<D: Db>(mut db: D, app: AppConfig) {
+    let quota = DartQuota::from_env(app.dart_daily_request_limit)
+        .expect("Failed to create DART quota")
+        .with_rate_limit(app.dart_requests_per_minute);
 
-    println!("Got {} corp codes", corp_codes.iter().len());
+    let run = new_ingest_run("dart_get_corp_codes_and_save", quota.domain(), None);
+    with_ingest_run(&mut db, run, |mut db| async move {
+        ingest_corp_codes(&mut db, &quota)
+            .in_current_span()
+            .await
+            .expect("Failed to ingest corp codes")
+    })
+    .in_current_span()
+    .await;
 }
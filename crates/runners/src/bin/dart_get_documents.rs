@@ -0,0 +1,111 @@
+use dart::ArchiveStore;
+use db::dart::DocumentDb;
+use db::model::dart::NewDocument;
+use db::model::ingest::IngestCounts;
+use db::Db;
+use figment::providers::{Format, Toml};
+use figment::Figment;
+use runners::{new_ingest_run, with_ingest_run, AppConfig, DartQuota, Database};
+use tracing::Instrument;
+
+#[tokio::main]
+async fn main() {
+    tracing_setup::span!("main");
+
+    let app: AppConfig = Figment::new()
+        .merge(Toml::file("Settings.toml"))
+        .extract()
+        .expect("Failed to load settings");
+
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
+    match Database::connect(&database_url)
+        .in_current_span()
+        .await
+        .expect("Failed to connect to db")
+    {
+        Database::Postgres(db) => run(db, app).in_current_span().await,
+        Database::Sqlite(db) => run(db, app).in_current_span().await,
+    }
+}
+
+async fn run<D: Db>(mut db: D, app: AppConfig) {
+    let quota = DartQuota::from_env(app.dart_daily_request_limit)
+        .expect("Failed to create DART quota")
+        .with_rate_limit(app.dart_requests_per_minute);
+    let store = ArchiveStore::new(&app.document_dir);
+
+    let run = new_ingest_run(
+        "dart_get_documents",
+        quota.domain(),
+        Some(format!("{app:?}")),
+    );
+    with_ingest_run(&mut db, run, |db| ingest(db, app, quota, store))
+        .in_current_span()
+        .await;
+}
+
+async fn ingest<D: Db>(
+    mut db: D,
+    app: AppConfig,
+    quota: DartQuota,
+    store: ArchiveStore,
+) -> IngestCounts {
+    // Every stored document is recorded right away,
+    // so an interrupted run resumes with the filings it didn't get to.
+    let receipt_numbers = db
+        .get_receipt_numbers_without_document(app.document_limit)
+        .in_current_span()
+        .await
+        .expect("Failed to get receipt numbers without document");
+    tracing::info!(
+        count = receipt_numbers.len(),
+        document_dir = ?app.document_dir,
+        "Downloading documents"
+    );
+
+    let mut counts = IngestCounts::default();
+    for (i, receipt_number) in receipt_numbers.into_iter().enumerate() {
+        let Some(archive) = quota
+            .request(&mut db, async |api| {
+                api.get_document(receipt_number.as_ref().as_str()).await
+            })
+            .in_current_span()
+            .await
+            .expect("Failed to get document")
+        else {
+            // The next run picks up the filings which are still without document
+            break;
+        };
+        let Some(archive) = archive else {
+            // Recorded so the next run doesn't ask again
+            tracing::debug!(%receipt_number, "Filing has no document");
+            db.upsert_document(NewDocument::missing(receipt_number))
+                .in_current_span()
+                .await
+                .expect("Failed to upsert document");
+            counts.unchanged += 1;
+            continue;
+        };
+
+        let stored = store
+            .put(&archive)
+            .in_current_span()
+            .await
+            .expect("Failed to store archive");
+        db.upsert_document(NewDocument {
+            receipt_number,
+            sha256: Some(stored.sha256),
+            byte_size: Some(stored.byte_size as i64),
+        })
+        .in_current_span()
+        .await
+        .expect("Failed to upsert document");
+        counts.inserted += 1;
+
+        if (i + 1) % 100 == 0 {
+            tracing::info!(downloaded = counts.inserted, "Downloading documents");
+        }
+    }
+
+    counts
+}
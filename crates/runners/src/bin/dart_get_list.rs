@@ -1,26 +1,69 @@
-use open_dart::client::OpenDartApi;
-use open_dart::endpoints::list;
+use chrono::{Days, NaiveDate};
+use db::dart::FilingDb;
+use db::Db;
+use figment::providers::{Format, Toml};
+use figment::Figment;
+use runners::{ingest_filings, new_ingest_run, with_ingest_run, AppConfig, DartQuota, Database};
 use tracing::Instrument;
 
+/// Where ingestion starts when `dart.filing` is empty.
+const FIRST_RECEIPT_DATE: &str = "20241001";
+
 #[tokio::main]
 async fn main() {
     tracing_setup::span!("main");
 
-    let params = list::ParamsBuilder::default()
-        .bgn_de("20241001".to_string())
-        .build()
-        .expect("Failed to build ListRequestParams");
-    tracing::info!(?params, "Request parameters");
+    let app: AppConfig = Figment::new()
+        .merge(Toml::file("Settings.toml"))
+        .extract()
+        .expect("Failed to load settings");
 
-    let list = OpenDartApi::default()
-        .get_list(params)
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
+    match Database::connect(&database_url)
         .in_current_span()
         .await
-        .unwrap();
-
-    if let Some(body) = list.body {
-        tracing::info!("{:?}", body);
-    } else {
-        tracing::warn!("No content");
+        .expect("Failed to connect to db")
+    {
+        Database::Postgres(db) => run(db, app).in_current_span().await,
+        Database::Sqlite(db) => run(db, app).in_current_span().await,
     }
 }
+
+async fn run<D: Db>(mut db: D, app: AppConfig) {
+    let quota = DartQuota::from_env(app.dart_daily_request_limit)
+        .expect("Failed to create DART quota")
+        .with_rate_limit(app.dart_requests_per_minute);
+
+    let first = NaiveDate::parse_from_str(FIRST_RECEIPT_DATE, "%Y%m%d")
+        .expect("FIRST_RECEIPT_DATE is not a valid date");
+    // The newest day is fetched again, as it may have been ingested before it was over,
+    // and so are the days before it, as OpenDART marks a filing `정` or `철` when it's amended or withdrawn.
+    let from = match db
+        .get_latest_receipt_date()
+        .in_current_span()
+        .await
+        .expect("Failed to get latest receipt date")
+    {
+        Some(date) => date
+            .as_ref()
+            .checked_sub_days(Days::new(app.dart_list_refetch_days))
+            .map_or(first, |from| from.max(first)),
+        None => first,
+    };
+    let to = chrono::Local::now().date_naive();
+    tracing::info!(%from, %to, "Ingesting filings");
+
+    let run = new_ingest_run(
+        "dart_get_list",
+        quota.domain(),
+        Some(format!("from={from} to={to}")),
+    );
+    with_ingest_run(&mut db, run, |mut db| async move {
+        ingest_filings(&mut db, &quota, from, to)
+            .in_current_span()
+            .await
+            .expect("Failed to ingest filings")
+    })
+    .in_current_span()
+    .await;
+}
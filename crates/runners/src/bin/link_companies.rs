@@ -0,0 +1,25 @@
+use db::Db;
+use runners::{link_companies, Database};
+use tracing::Instrument;
+
+#[tokio::main]
+async fn main() {
+    tracing_setup::span!("main");
+
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
+    match Database::connect(&database_url)
+        .in_current_span()
+        .await
+        .expect("Failed to connect to db")
+    {
+        Database::Postgres(db) => run(db).in_current_span().await,
+        Database::Sqlite(db) => run(db).in_current_span().await,
+    }
+}
+
+async fn run<D: Db>(mut db: D) {
+    link_companies(&mut db)
+        .in_current_span()
+        .await
+        .expect("Failed to link companies");
+}
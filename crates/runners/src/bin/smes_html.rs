@@ -1,9 +1,10 @@
+use db::model::ingest::IngestCounts;
 use db::smes::{CompanyDb, HtmlDb};
-use db::{Db, PostgresDb};
+use db::Db;
 use figment::providers::{Format, Toml};
 use figment::Figment;
-use runners::AppConfig;
-use smes::get_bspl_htmls;
+use runners::{new_ingest_run, with_ingest_run, AppConfig, Database};
+use smes::{get_bspl_htmls, BsplApi};
 use tracing::Instrument;
 
 #[tokio::main]
@@ -15,9 +16,29 @@ async fn main() {
         .extract()
         .expect("Failed to load settings");
 
-    let connection_string = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
-    let mut db = PostgresDb::new(connection_string).in_current_span().await;
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
+    match Database::connect(&database_url)
+        .in_current_span()
+        .await
+        .expect("Failed to connect to db")
+    {
+        Database::Postgres(db) => run(db, app).in_current_span().await,
+        Database::Sqlite(db) => run(db, app).in_current_span().await,
+    }
+}
 
+async fn run<D: Db>(mut db: D, app: AppConfig) {
+    let run = new_ingest_run(
+        "smes_html",
+        &BsplApi::default().domain,
+        Some(format!("{app:?}")),
+    );
+    with_ingest_run(&mut db, run, |db| ingest(db, app))
+        .in_current_span()
+        .await;
+}
+
+async fn ingest<D: Db>(mut db: D, app: AppConfig) -> IngestCounts {
     // 1. Get all companies from the database
     let all_ids_to_query = db
         .get_smes_ids()
@@ -60,6 +81,20 @@ async fn main() {
             .await
             .expect("Failed to upsert htmls");
     }
+
+    // Only new pages are counted,
+    // as the channels don't report which pages were changed and which were skipped.
+    let inserted = db
+        .select_html_ids()
+        .in_current_span()
+        .await
+        .expect("Failed to get html ids")
+        .difference(&ids_with_htmls)
+        .count();
+    IngestCounts {
+        inserted: i32::try_from(inserted).expect("Inserted pages should fit in i32"),
+        ..Default::default()
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,8 @@
+use db::model::ingest::IngestCounts;
 use db::smes::CompanyDb;
-use db::{Db, PostgresDb};
+use db::Db;
+use hashbrown::HashSet;
+use runners::{is_complete_crawl, new_ingest_run, with_ingest_run, Database};
 use smes::{ListApi, ListPayloadBuilder};
 use tracing::Instrument;
 
@@ -7,11 +10,26 @@ use tracing::Instrument;
 async fn main() {
     tracing_setup::span!("main");
 
-    let connection_string = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
-    let mut db = PostgresDb::new(connection_string).await;
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
+    match Database::connect(&database_url)
+        .await
+        .expect("Failed to connect to db")
+    {
+        Database::Postgres(db) => run(db).in_current_span().await,
+        Database::Sqlite(db) => run(db).in_current_span().await,
+    }
+}
+
+async fn run<D: Db>(mut db: D) {
+    let api = ListApi::new();
 
-    let mut api = ListApi::new();
+    let run = new_ingest_run("smes_list", &api.domain, None);
+    with_ingest_run(&mut db, run, |db| ingest(db, api))
+        .in_current_span()
+        .await;
+}
 
+async fn ingest<D: Db>(mut db: D, mut api: ListApi) -> IngestCounts {
     let total_count = api
         .get_company_list_count()
         .in_current_span()
@@ -35,8 +53,64 @@ async fn main() {
         .into_iter()
         .collect();
 
+    let smes_ids: HashSet<_> = companies.iter().map(|c| c.smes_id.clone()).collect();
+
+    let existing_ids = db
+        .get_smes_ids()
+        .in_current_span()
+        .await
+        .expect("Failed to get companies");
+    let inserted = companies
+        .iter()
+        .filter(|c| !existing_ids.contains(&c.smes_id))
+        .count();
+    // Every listed company is written, so the others count as updated.
+    let counts = IngestCounts {
+        inserted: i32::try_from(inserted).expect("Inserted companies should fit in i32"),
+        updated: i32::try_from(companies.len() - inserted)
+            .expect("Updated companies should fit in i32"),
+        unchanged: 0,
+    };
+
     db.upsert_companies(companies)
         .in_current_span()
         .await
         .expect("Failed to upsert companies");
+
+    // Record which companies were listed in this run,
+    // so companies that disappeared from the list can be detected.
+    let snapshots = db
+        .get_list_snapshots()
+        .in_current_span()
+        .await
+        .expect("Failed to get list snapshots");
+    let previous = snapshots.last();
+    if !is_complete_crawl(smes_ids.len(), previous) {
+        // Every company missing from a snapshot would be marked as delisted
+        tracing::warn!(
+            listed = smes_ids.len(),
+            previous_count = ?previous.map(|previous| previous.company_count),
+            "Skipping the list snapshot of an incomplete crawl"
+        );
+    } else {
+        let snapshot = db
+            .insert_list_snapshot(smes_ids)
+            .in_current_span()
+            .await
+            .expect("Failed to insert list snapshot");
+        if let Some(previous) = previous {
+            let diff = db
+                .get_list_snapshot_diff(previous.snapshot_id, snapshot.snapshot_id)
+                .in_current_span()
+                .await
+                .expect("Failed to get list snapshot diff");
+            tracing::info!(
+                added = diff.added.len(),
+                removed = diff.removed.len(),
+                ?diff.removed,
+                "Companies changed since the previous list snapshot"
+            );
+        }
+    }
+    counts
 }
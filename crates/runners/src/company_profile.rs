@@ -0,0 +1,248 @@
+use crate::DartQuota;
+use chrono::NaiveDate;
+use dart::CompanyOverview;
+use db::dart::{CompanyIdDb, CompanyProfileDb};
+use db::model::dart::{CompanyId, CompanyProfile, NewCompanyProfile};
+use db::model::ingest::IngestCounts;
+use db::Db;
+use hashbrown::HashMap;
+use std::error::Error;
+use tracing::Instrument;
+use types::{company, YYYYMMDD};
+
+/// The profile of a company from its `company.json` overview.
+///
+/// Blank fields become `None`, and so do industry codes coarser than 5 digits.
+/// Registration numbers are kept even when their check digit is wrong, as they are in SMES.
+pub fn new_company_profile(
+    overview: &CompanyOverview,
+) -> Result<NewCompanyProfile, Box<dyn Error>> {
+    let industry_code = match non_blank(&overview.induty_code) {
+        Some(code) if code.len() == 5 => Some(company::IndustryCode::try_from(code)?),
+        _ => None,
+    };
+    Ok(NewCompanyProfile {
+        dart_id: company::DartId::try_from(overview.corp_code.trim())?,
+        corporation_registration_number: non_blank(&overview.jurir_no)
+            .map(|number| {
+                company::CorporationRegistrationNumber::try_new_without_checksum(
+                    &number.replace('-', ""),
+                )
+            })
+            .transpose()?,
+        business_registration_number: non_blank(&overview.bizr_no)
+            .map(|number| {
+                company::BusinessRegistrationNumber::try_new_without_checksum(
+                    &number.replace('-', ""),
+                )
+            })
+            .transpose()?,
+        representative_name: non_blank(&overview.ceo_nm)
+            .map(company::RepresentativeName::try_from)
+            .transpose()?,
+        headquarters_address: non_blank(&overview.adres)
+            .map(company::HeadquartersAddress::try_from)
+            .transpose()?,
+        industry_code,
+        established_date: non_blank(&overview.est_dt)
+            .map(YYYYMMDD::try_from)
+            .transpose()?,
+        fiscal_month: non_blank(&overview.acc_mt).map(str::parse).transpose()?,
+    })
+}
+
+/// Whether the profile of `company_id` should be fetched,
+/// because it was never fetched or the corp code list modified the company after it was fetched.
+///
+/// Companies without an overview are recorded with [`NewCompanyProfile::missing`], so they aren't stale either.
+pub fn is_profile_stale(company_id: &CompanyId, profile: Option<&CompanyProfile>) -> bool {
+    let Some(profile) = profile else {
+        return true;
+    };
+    let fetched_on = profile.updated_at.date();
+    let fetched_on = NaiveDate::from_ymd_opt(
+        fetched_on.year(),
+        u8::from(fetched_on.month()).into(),
+        fetched_on.day().into(),
+    );
+    match (&company_id.id_modify_date, fetched_on) {
+        (Some(modify_date), Some(fetched_on)) => modify_date.as_ref() > &fetched_on,
+        _ => false,
+    }
+}
+
+/// Store the profiles of the companies whose profile is stale, see [`is_profile_stale`], listed companies first.
+///
+/// Companies OpenDART has no overview for are recorded as checked, keeping a stored profile,
+/// and count as unchanged.
+pub async fn ingest_company_profiles<D: Db>(
+    db: &mut D,
+    quota: &DartQuota,
+) -> Result<IngestCounts, Box<dyn Error>> {
+    let profiles: HashMap<_, _> = db
+        .get_company_profiles()
+        .in_current_span()
+        .await?
+        .into_iter()
+        .map(|profile| (profile.dart_id.clone(), profile))
+        .collect();
+    let mut company_ids = db.get_company_ids().in_current_span().await?;
+    let total = company_ids.len();
+    company_ids
+        .retain(|company_id| is_profile_stale(company_id, profiles.get(&company_id.dart_id)));
+    // Listed companies first, as they are the ones with filings to analyze
+    company_ids.sort_by_key(|company_id| company_id.stock_code.is_none());
+    tracing::info!(
+        stale = company_ids.len(),
+        total,
+        "Fetching company profiles"
+    );
+
+    let mut counts = IngestCounts {
+        unchanged: i32::try_from(total - company_ids.len())?,
+        ..Default::default()
+    };
+    // Profiles are stored in chunks, so an interrupted run keeps most of its work
+    let mut quota_used = false;
+    for chunk in company_ids.chunks(100) {
+        let mut new_profiles = Vec::with_capacity(chunk.len());
+        for company_id in chunk {
+            let dart_id = company_id.dart_id.as_ref().as_str();
+            let Some(overview) = quota
+                .request(db, async |api| api.get_company_overview(dart_id).await)
+                .in_current_span()
+                .await?
+            else {
+                quota_used = true;
+                break;
+            };
+            let Some(overview) = overview else {
+                tracing::debug!(dart_id = %company_id.dart_id, "Company has no overview");
+                let checked = match profiles.get(&company_id.dart_id) {
+                    Some(profile) => NewCompanyProfile::from(profile.clone()),
+                    None => NewCompanyProfile::missing(company_id.dart_id.clone()),
+                };
+                new_profiles.push(checked);
+                counts.unchanged += 1;
+                continue;
+            };
+            match new_company_profile(&overview) {
+                Ok(profile) => {
+                    if profiles.contains_key(&profile.dart_id) {
+                        counts.updated += 1;
+                    } else {
+                        counts.inserted += 1;
+                    }
+                    new_profiles.push(profile);
+                }
+                Err(e) => {
+                    tracing::warn!(dart_id = %company_id.dart_id, ?e, "Skipping invalid overview");
+                }
+            }
+        }
+
+        db.upsert_company_profiles(new_profiles)
+            .in_current_span()
+            .await?;
+        tracing::info!(
+            inserted = counts.inserted,
+            updated = counts.updated,
+            "Fetching company profiles"
+        );
+        if quota_used {
+            // The next run picks up the companies which are still stale
+            break;
+        }
+    }
+    Ok(counts)
+}
+
+fn non_blank(value: &str) -> Option<&str> {
+    Some(value.trim()).filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn overview() -> CompanyOverview {
+        CompanyOverview {
+            corp_code: "00126380".to_string(),
+            corp_name: "삼성전자(주)".to_string(),
+            stock_code: "005930".to_string(),
+            corp_cls: "Y".to_string(),
+            ceo_nm: "한종희, 경계현".to_string(),
+            jurir_no: "1301110006246".to_string(),
+            bizr_no: "124-81-00998".to_string(),
+            adres: "경기도 수원시 영통구  삼성로 129 (매탄동)".to_string(),
+            induty_code: "26410".to_string(),
+            est_dt: "19690113".to_string(),
+            acc_mt: "12".to_string(),
+        }
+    }
+
+    #[test]
+    fn new_company_profile_should_validate_fields() {
+        let profile = new_company_profile(&overview()).expect("Failed to convert overview");
+
+        assert_eq!(
+            profile.business_registration_number,
+            Some(company::BusinessRegistrationNumber::try_new("1248100998").unwrap())
+        );
+        assert_eq!(
+            profile.industry_code,
+            Some(company::IndustryCode::try_new("26410").unwrap())
+        );
+        assert_eq!(profile.fiscal_month, Some(12));
+    }
+
+    #[test]
+    fn profiles_should_be_stale_when_modified_after_fetched() {
+        let company_id = CompanyId {
+            dart_id: company::DartId::try_new("00126380").unwrap(),
+            company_name: company::Name::try_new("삼성전자").unwrap(),
+            stock_code: None,
+            id_modify_date: Some(YYYYMMDD::try_from("20241120").unwrap()),
+        };
+        let profile = |updated_at| CompanyProfile {
+            dart_id: company_id.dart_id.clone(),
+            corporation_registration_number: None,
+            business_registration_number: None,
+            representative_name: None,
+            headquarters_address: None,
+            industry_code: None,
+            established_date: None,
+            fiscal_month: None,
+            run_id: None,
+            created_at: updated_at,
+            updated_at,
+        };
+
+        assert!(is_profile_stale(&company_id, None));
+        assert!(is_profile_stale(
+            &company_id,
+            Some(&profile(datetime!(2024-11-19 12:00)))
+        ));
+        assert!(!is_profile_stale(
+            &company_id,
+            Some(&profile(datetime!(2024-11-20 12:00)))
+        ));
+    }
+
+    #[test]
+    fn blank_fields_should_become_none() {
+        let overview = CompanyOverview {
+            bizr_no: "".to_string(),
+            induty_code: "264".to_string(),
+            est_dt: " ".to_string(),
+            ..overview()
+        };
+
+        let profile = new_company_profile(&overview).expect("Failed to convert overview");
+
+        assert_eq!(profile.business_registration_number, None);
+        assert_eq!(profile.industry_code, None);
+        assert_eq!(profile.established_date, None);
+    }
+}
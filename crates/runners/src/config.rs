@@ -1,6 +1,58 @@
+use crate::DART_DAILY_REQUEST_LIMIT;
 use serde::Deserialize;
+use std::path::PathBuf;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Debug)]
 pub struct AppConfig {
     pub update_all_html: bool,
+    /// The business years `dart_financial_statements` fetches, e.g. `[2022, 2023]`
+    #[serde(default)]
+    pub financial_statement_years: Vec<i32>,
+    /// The business years `dart_periodic_reports` fetches, e.g. `[2022, 2023]`
+    #[serde(default)]
+    pub periodic_report_years: Vec<i32>,
+    /// Where `dart_get_documents` stores the document archives
+    #[serde(default = "default_document_dir")]
+    pub document_dir: PathBuf,
+    /// How many documents `dart_get_documents` downloads per run, at most
+    #[serde(default = "default_document_limit")]
+    pub document_limit: i64,
+    /// The rate limit of OpenDART requests, which blocks keys sending too many
+    #[serde(default = "default_dart_requests_per_minute")]
+    pub dart_requests_per_minute: u32,
+    /// The OpenDART requests per key and day the runners send together, at most
+    #[serde(default = "default_dart_daily_request_limit")]
+    pub dart_daily_request_limit: i32,
+    /// The days before the newest stored filing `dart_get_list` fetches again,
+    /// to pick up the remarks OpenDART adds to a filing when it's amended or withdrawn
+    #[serde(default = "default_dart_list_refetch_days")]
+    pub dart_list_refetch_days: u64,
+    /// The days after which `dart_financial_statements` and `dart_periodic_reports` fetch a statement or section
+    /// which OpenDART had no rows for, or which lost rows as invalid, again
+    #[serde(default = "default_dart_recheck_days")]
+    pub dart_recheck_days: i64,
+}
+
+fn default_document_dir() -> PathBuf {
+    PathBuf::from("documents")
+}
+
+fn default_document_limit() -> i64 {
+    1_000
+}
+
+fn default_dart_requests_per_minute() -> u32 {
+    600
+}
+
+fn default_dart_daily_request_limit() -> i32 {
+    DART_DAILY_REQUEST_LIMIT
+}
+
+fn default_dart_list_refetch_days() -> u64 {
+    7
+}
+
+fn default_dart_recheck_days() -> i64 {
+    30
 }
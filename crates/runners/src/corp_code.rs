@@ -0,0 +1,189 @@
+use crate::DartQuota;
+use db::dart::{ApiQuotaDb, CompanyIdDb};
+use db::model::dart::CompanyId;
+use db::model::ingest::IngestCounts;
+use hashbrown::HashMap;
+use std::error::Error;
+use tracing::Instrument;
+use types::{company, TypeError, YYYYMMDD};
+
+/// The number of company ids upserted at once, so progress is logged on the way.
+const CHUNK_SIZE: usize = 10_000;
+
+/// The company id of an entry of the OpenDART corp code list.
+///
+/// Unlisted companies have a blank stock code, which becomes `None`,
+/// while a blank `corp_name` is an error.
+/// `modify_date` is formatted as YYYYMMDD.
+pub fn company_id_from_corp_code(
+    corp_code: &str,
+    corp_name: &str,
+    stock_code: &str,
+    modify_date: &str,
+) -> Result<CompanyId, TypeError> {
+    let stock_code = match stock_code.trim() {
+        "" => None,
+        stock_code => Some(company::StockCode::try_from(stock_code)?),
+    };
+    Ok(CompanyId {
+        dart_id: company::DartId::try_from(corp_code.trim())?,
+        company_name: company::Name::try_from(corp_name.trim())?,
+        stock_code,
+        id_modify_date: Some(YYYYMMDD::try_from(modify_date.trim())?),
+    })
+}
+
+/// The company ids which differ from the stored ones, and how many are new, changed or unchanged.
+///
+/// Placeholders count as updated, as the corp code list completes them.
+pub fn changed_company_ids(
+    stored: Vec<CompanyId>,
+    company_ids: Vec<CompanyId>,
+) -> (Vec<CompanyId>, IngestCounts) {
+    let stored: HashMap<company::DartId, CompanyId> = stored
+        .into_iter()
+        .map(|company_id| (company_id.dart_id.clone(), company_id))
+        .collect();
+
+    let mut counts = IngestCounts::default();
+    let changed = company_ids
+        .into_iter()
+        .filter(|company_id| match stored.get(&company_id.dart_id) {
+            None => {
+                counts.inserted += 1;
+                true
+            }
+            Some(stored) if stored == company_id => {
+                counts.unchanged += 1;
+                false
+            }
+            Some(_) => {
+                counts.updated += 1;
+                true
+            }
+        })
+        .collect();
+    (changed, counts)
+}
+
+/// Store the companies of the OpenDART corp code list which are new or changed.
+///
+/// Stores nothing when the quota of OpenDART is used.
+pub async fn ingest_corp_codes<D: CompanyIdDb + ApiQuotaDb>(
+    db: &mut D,
+    quota: &DartQuota,
+) -> Result<IngestCounts, Box<dyn Error>> {
+    let Some(corp_codes) = quota
+        .request(db, async |api| api.get_corp_codes().await)
+        .in_current_span()
+        .await?
+    else {
+        return Ok(IngestCounts::default());
+    };
+
+    let mut company_ids = Vec::new();
+    for corp in &corp_codes {
+        match company_id_from_corp_code(
+            &corp.corp_code,
+            &corp.corp_name,
+            &corp.stock_code,
+            &corp.modify_date,
+        ) {
+            Ok(company_id) => company_ids.push(company_id),
+            Err(e) => {
+                tracing::warn!(?e, corp_code = %corp.corp_code, "Skipping invalid corp code");
+            }
+        }
+    }
+    tracing::info!(count = company_ids.len(), "Converted corp codes");
+
+    let stored = db.get_company_ids().in_current_span().await?;
+    let (changed, counts) = changed_company_ids(stored, company_ids);
+
+    for (i, chunk) in changed.chunks(CHUNK_SIZE).enumerate() {
+        db.upsert_company_ids(chunk.to_vec())
+            .in_current_span()
+            .await?;
+        tracing::info!(
+            upserted = i * CHUNK_SIZE + chunk.len(),
+            total = changed.len(),
+            "Upserted company ids"
+        );
+    }
+
+    tracing::info!(
+        inserted = counts.inserted,
+        updated = counts.updated,
+        unchanged = counts.unchanged,
+        "Saved corp codes"
+    );
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn company_id(dart_id: &str, company_name: &str) -> CompanyId {
+        company_id_from_corp_code(dart_id, company_name, "", "20240101")
+            .expect("Failed to convert corp code")
+    }
+
+    #[test]
+    fn company_id_from_corp_code_should_treat_blank_stock_code_as_unlisted() {
+        let unlisted = company_id_from_corp_code("00434003", "다코", " ", "20170630")
+            .expect("Failed to convert corp code");
+        let listed = company_id_from_corp_code("00126380", "삼성전자", "005930", "20230110")
+            .expect("Failed to convert corp code");
+
+        assert_eq!(unlisted.stock_code, None);
+        assert_eq!(
+            listed.stock_code.as_ref().map(|s| s.as_ref().to_string()),
+            Some("005930".to_string())
+        );
+        assert_eq!(
+            listed.id_modify_date,
+            Some("20230110".try_into().expect("Failed to create date"))
+        );
+    }
+
+    #[test]
+    fn company_id_from_corp_code_should_reject_malformed_dates() {
+        assert!(company_id_from_corp_code("00126380", "삼성전자", "005930", "2023-01-10").is_err());
+    }
+
+    #[test]
+    fn company_id_from_corp_code_should_reject_blank_names() {
+        assert!(company_id_from_corp_code("00126380", " ", "005930", "20230110").is_err());
+    }
+
+    #[test]
+    fn changed_company_ids_should_count_by_stored_state() {
+        let placeholder = CompanyId {
+            id_modify_date: None,
+            ..company_id("00000002", "루키게임즈")
+        };
+        let stored = vec![company_id("00000001", "다코"), placeholder];
+        let company_ids = vec![
+            company_id("00000001", "다코"),
+            company_id("00000002", "루키게임즈"),
+            company_id("00000003", "삼성전자"),
+        ];
+
+        let (changed, counts) = changed_company_ids(stored, company_ids);
+
+        let changed_ids: Vec<String> = changed
+            .iter()
+            .map(|c| c.dart_id.as_ref().to_string())
+            .collect();
+        assert_eq!(changed_ids, vec!["00000002", "00000003"]);
+        assert_eq!(
+            counts,
+            IngestCounts {
+                inserted: 1,
+                updated: 1,
+                unchanged: 1,
+            }
+        );
+    }
+}
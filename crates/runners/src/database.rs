@@ -0,0 +1,54 @@
+use db::{Db, DbError, PostgresDb, SqliteDb};
+
+/// The database backend of a runner, chosen by the scheme of `DATABASE_URL`.
+///
+/// `postgres://` and `postgresql://` URLs connect to Postgres.
+/// Anything else is opened as a SQLite file, optionally prefixed with `sqlite://`,
+/// which is created and migrated when it doesn't exist.
+pub enum Database {
+    Postgres(PostgresDb),
+    Sqlite(SqliteDb),
+}
+
+impl Database {
+    /// Connect to the database of a runner.
+    ///
+    /// Fails with [`DbError::PendingMigrations`] when a Postgres schema is out of date,
+    /// which is fixed by running `db migrate`.
+    #[tracing::instrument(skip_all)]
+    pub async fn connect(database_url: &str) -> Result<Self, DbError> {
+        match sqlite_path(database_url) {
+            None => {
+                let db = PostgresDb::new(database_url).await?;
+                db.check_schema_version().await?;
+                Ok(Self::Postgres(db))
+            }
+            Some(path) => Ok(Self::Sqlite(SqliteDb::new(path).await?)),
+        }
+    }
+
+    /// Connect to the database, and apply the pending migrations.
+    #[tracing::instrument(skip_all)]
+    pub async fn connect_and_migrate(database_url: &str) -> Result<Self, DbError> {
+        match sqlite_path(database_url) {
+            None => Ok(Self::Postgres(
+                PostgresDb::connect_and_migrate(database_url).await?,
+            )),
+            // SQLite is migrated whenever it's opened
+            Some(path) => Ok(Self::Sqlite(SqliteDb::new(path).await?)),
+        }
+    }
+}
+
+/// The path of the SQLite file, or `None` for a Postgres URL.
+fn sqlite_path(database_url: &str) -> Option<&str> {
+    if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+        None
+    } else {
+        Some(
+            database_url
+                .strip_prefix("sqlite://")
+                .unwrap_or(database_url),
+        )
+    }
+}
@@ -0,0 +1,351 @@
+use crate::DartQuota;
+use chrono::NaiveDate;
+use db::dart::FilingDb;
+use db::model::dart::{AmendmentChain, NewFiling, ReportCode};
+use db::model::ingest::IngestCounts;
+use db::query::{FilingQuery, KeysetQuery};
+use db::{Db, DbError};
+use hashbrown::{HashMap, HashSet};
+use std::error::Error;
+use tracing::Instrument;
+use types::{company, filing, TypeError};
+
+/// The largest page OpenDART serves.
+const PAGE_COUNT: u32 = 100;
+
+/// The filing of an item of the OpenDART filing list.
+pub fn new_filing_from_list_item(
+    corp_code: &str,
+    corp_name: &str,
+    report_nm: &str,
+    rcept_no: &str,
+    flr_nm: &str,
+    rcept_dt: &str,
+    rm: &str,
+) -> Result<NewFiling, TypeError> {
+    Ok(NewFiling {
+        dart_id: company::DartId::try_from(corp_code.trim())?,
+        report_name: filing::ReportName::try_from(report_nm.trim())?,
+        receipt_number: filing::ReceiptNumber::try_from(rcept_no.trim())?,
+        filer_name: filing::FilerName::try_from(flr_nm.trim())?,
+        receipt_date: filing::ReceiptDate::try_from(rcept_dt.trim())?,
+        remark: filing::Remark::new(rm.trim()),
+        company_name: Some(company::Name::try_from(corp_name.trim())?),
+    })
+}
+
+/// The filings which differ from the stored ones, and how many are new, changed or unchanged.
+///
+/// A receipt number listed more than once is counted once.
+pub fn changed_filings(
+    stored: Vec<NewFiling>,
+    filings: Vec<NewFiling>,
+) -> (Vec<NewFiling>, IngestCounts) {
+    let stored: HashMap<filing::ReceiptNumber, NewFiling> = stored
+        .into_iter()
+        .map(|filing| (filing.receipt_number.clone(), filing))
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut counts = IngestCounts::default();
+    let changed = filings
+        .into_iter()
+        .filter(|filing| seen.insert(filing.receipt_number.clone()))
+        .filter(|filing| match stored.get(&filing.receipt_number) {
+            None => {
+                counts.inserted += 1;
+                true
+            }
+            Some(stored) if stored == filing => {
+                counts.unchanged += 1;
+                false
+            }
+            Some(_) => {
+                counts.updated += 1;
+                true
+            }
+        })
+        .collect();
+    (changed, counts)
+}
+
+/// Whether a newer version of the filing with `receipt_number`, the `report_code` report of `business_year`,
+/// is in `chains`, so what was taken from it is out of date and should be fetched again.
+///
+/// A correction of a report filed before the filings were ingested starts a chain of its own,
+/// which the filing is in no chain with, see [`db::dart::amendment_chains`].
+/// So a filing in no chain is superseded by such a chain of the same report with a later receipt number.
+/// Reports are told apart by their base name, e.g. `사업보고서 (2023.12)`,
+/// so only those of companies whose fiscal year is the calendar year are matched.
+///
+/// `false` when the filing is in no chain otherwise, e.g. as it wasn't ingested,
+/// and when every filing of its chain was withdrawn, as there is nothing newer to fetch.
+pub fn is_superseded(
+    chains: &[AmendmentChain],
+    receipt_number: &filing::ReceiptNumber,
+    report_code: ReportCode,
+    business_year: i32,
+) -> bool {
+    if let Some(chain) = chains.iter().find(|chain| chain.contains(receipt_number)) {
+        return chain
+            .latest_effective()
+            .is_some_and(|latest| &latest.receipt_number != receipt_number);
+    }
+    let base_name = report_base_name(report_code, business_year);
+    chains
+        .iter()
+        .filter(|chain| chain.original().report_name.amendment_kind().is_some())
+        .filter(|chain| chain.base_name() == base_name)
+        .filter(|chain| &chain.original().receipt_number > receipt_number)
+        .any(|chain| chain.latest_effective().is_some())
+}
+
+/// The name of the `report_code` report of `business_year` without amendment markers,
+/// for a company whose fiscal year is the calendar year.
+fn report_base_name(report_code: ReportCode, business_year: i32) -> String {
+    let (title, month) = match report_code {
+        ReportCode::Annual => ("사업보고서", 12),
+        ReportCode::HalfYear => ("반기보고서", 6),
+        ReportCode::FirstQuarter => ("분기보고서", 3),
+        ReportCode::ThirdQuarter => ("분기보고서", 9),
+    };
+    format!("{title} ({business_year}.{month:02})")
+}
+
+/// Store the filings received from `from` to `to`, both inclusive.
+///
+/// One day at a time, so an interrupted run loses at most a day of work.
+/// Stops early when the quota of OpenDART is used, storing only the days fetched in full.
+pub async fn ingest_filings<D: Db>(
+    db: &mut D,
+    quota: &DartQuota,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<IngestCounts, Box<dyn Error>> {
+    let mut counts = IngestCounts::default();
+    let mut day = from;
+    while day <= to {
+        let Some(filings) = get_filings_of_day(db, quota, day).in_current_span().await? else {
+            tracing::warn!(%day, "Stopping before the day, as the quota is used");
+            break;
+        };
+        let stored = get_stored_filings_of_day(db, day).in_current_span().await?;
+        let (changed, day_counts) = changed_filings(stored, filings);
+        tracing::info!(%day, ?day_counts, "Fetched filings");
+
+        db.upsert_filings(changed).in_current_span().await?;
+
+        counts.inserted += day_counts.inserted;
+        counts.updated += day_counts.updated;
+        counts.unchanged += day_counts.unchanged;
+        day = day.succ_opt().ok_or("Ran out of dates")?;
+    }
+    Ok(counts)
+}
+
+/// All pages of the filings received on `day`, `None` when the quota ran out on the way.
+async fn get_filings_of_day<D: Db>(
+    db: &mut D,
+    quota: &DartQuota,
+    day: NaiveDate,
+) -> Result<Option<Vec<NewFiling>>, Box<dyn Error>> {
+    let date = day.format("%Y%m%d").to_string();
+    let mut filings = Vec::new();
+    let mut page_no = 1;
+    loop {
+        let Some(page) = quota
+            .request(db, async |api| {
+                api.get_list(&date, &date, page_no, PAGE_COUNT).await
+            })
+            .in_current_span()
+            .await?
+        else {
+            return Ok(None);
+        };
+        // Days without filings have no content
+        let Some(page) = page else {
+            break;
+        };
+
+        for item in &page.list {
+            match new_filing_from_list_item(
+                &item.corp_code,
+                &item.corp_name,
+                &item.report_nm,
+                &item.rcept_no,
+                &item.flr_nm,
+                &item.rcept_dt,
+                &item.rm,
+            ) {
+                Ok(filing) => filings.push(filing),
+                Err(e) => {
+                    tracing::warn!(?e, rcept_no = %item.rcept_no, "Skipping invalid filing");
+                }
+            }
+        }
+
+        if page_no >= page.total_page {
+            break;
+        }
+        page_no += 1;
+    }
+    Ok(Some(filings))
+}
+
+/// The stored filings received on `day`, to tell new and changed filings apart.
+async fn get_stored_filings_of_day<D: Db>(
+    db: &mut D,
+    day: NaiveDate,
+) -> Result<Vec<NewFiling>, DbError> {
+    let date = filing::ReceiptDate::new(day);
+    let mut stream = FilingQuery::new()
+        .receipt_date_from(date.clone())
+        .receipt_date_to(date)
+        .stream(db);
+    let mut stored = Vec::new();
+    while let Some(filing) = stream.next().await {
+        stored.push(NewFiling::from(filing?));
+    }
+    Ok(stored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::dart::amendment_chains;
+    use db::model::dart::Filing;
+    use time::macros::datetime;
+
+    fn new_filing(receipt_number: &str, report_nm: &str) -> NewFiling {
+        new_filing_from_list_item(
+            "00126380",
+            "삼성전자",
+            report_nm,
+            receipt_number,
+            "삼성전자",
+            "20241001",
+            "",
+        )
+        .expect("Failed to convert list item")
+    }
+
+    fn stored_filing(receipt_number: &str, report_nm: &str) -> Filing {
+        let filing = new_filing(receipt_number, report_nm);
+        let flags = filing.remark.flags();
+        Filing {
+            dart_id: filing.dart_id,
+            report_name: filing.report_name,
+            receipt_number: filing.receipt_number,
+            filer_name: filing.filer_name,
+            receipt_date: filing.receipt_date,
+            remark: filing.remark,
+            created_at: datetime!(2024-10-01 0:00),
+            updated_at: datetime!(2024-10-01 0:00),
+            run_id: None,
+            is_consolidated: flags.consolidated,
+            is_corrected: flags.corrected,
+            is_withdrawn: flags.withdrawn,
+        }
+    }
+
+    #[test]
+    fn new_filing_from_list_item_should_validate_fields() {
+        let filing = new_filing_from_list_item(
+            "00126380",
+            "삼성전자",
+            "분기보고서 (2024.09)",
+            "20241114002642",
+            "삼성전자",
+            "20241114",
+            "유",
+        )
+        .expect("Failed to convert list item");
+
+        assert_eq!(filing.dart_id.as_ref().to_string(), "00126380");
+        assert_eq!(
+            filing.receipt_date,
+            "20241114".try_into().expect("Failed to create date")
+        );
+        assert!(new_filing_from_list_item(
+            "00126380",
+            "삼성전자",
+            "",
+            "20241114002642",
+            "삼성전자",
+            "20241114",
+            ""
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn changed_filings_should_count_by_stored_state() {
+        let stored = vec![
+            new_filing("20241001000001", "분기보고서"),
+            new_filing("20241001000002", "분기보고서"),
+        ];
+        let filings = vec![
+            new_filing("20241001000001", "분기보고서"),
+            new_filing("20241001000002", "[기재정정]분기보고서"),
+            new_filing("20241001000003", "분기보고서"),
+            new_filing("20241001000003", "분기보고서"),
+        ];
+
+        let (changed, counts) = changed_filings(stored, filings);
+
+        assert_eq!(changed.len(), 2);
+        assert_eq!(
+            counts,
+            IngestCounts {
+                inserted: 1,
+                updated: 1,
+                unchanged: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn filings_should_be_superseded_by_their_corrections_only() {
+        let chains = amendment_chains(vec![
+            stored_filing("20241001000001", "사업보고서 (2023.12)"),
+            stored_filing("20241001000002", "[정정제출요구]사업보고서 (2023.12)"),
+            stored_filing("20241001000003", "[기재정정]사업보고서 (2023.12)"),
+        ]);
+        let superseded = |receipt_number: &str| {
+            is_superseded(
+                &chains,
+                &filing::ReceiptNumber::try_from(receipt_number).unwrap(),
+                ReportCode::Annual,
+                2023,
+            )
+        };
+
+        assert!(superseded("20241001000001"));
+        assert!(!superseded("20241001000003"));
+        // Filings which weren't ingested have nothing to compare with
+        assert!(!superseded("20241001000004"));
+    }
+
+    #[test]
+    fn filings_before_the_first_receipt_date_should_be_superseded_by_their_corrections() {
+        // The annual report itself was filed in March, before filings were ingested
+        let chains = amendment_chains(vec![
+            stored_filing("20241105000001", "[기재정정]사업보고서 (2023.12)"),
+            stored_filing("20241105000002", "[기재정정]반기보고서 (2024.06)"),
+        ]);
+        let original = filing::ReceiptNumber::try_from("20240312000736").unwrap();
+        let later = filing::ReceiptNumber::try_from("20241201000001").unwrap();
+
+        assert!(is_superseded(&chains, &original, ReportCode::Annual, 2023));
+        // Other reports and years aren't corrected
+        assert!(!is_superseded(&chains, &original, ReportCode::Annual, 2022));
+        assert!(!is_superseded(
+            &chains,
+            &original,
+            ReportCode::FirstQuarter,
+            2023
+        ));
+        // Nor are filings after the correction
+        assert!(!is_superseded(&chains, &later, ReportCode::Annual, 2023));
+    }
+}
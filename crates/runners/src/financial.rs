@@ -0,0 +1,394 @@
+use crate::recheck::{is_report_part_due, Check};
+use crate::DartQuota;
+use dart::FinancialStatementItem;
+use db::dart::{AmendmentChainDb, CompanyIdDb, FinancialItemDb};
+use db::model::dart::{
+    AmendmentChain, FinancialStatementCheck, FinancialStatementKey, FsDiv, NewFinancialItem,
+    NewFinancialStatementCheck, ReportCode, Statement,
+};
+use db::model::ingest::IngestCounts;
+use db::Db;
+use hashbrown::HashMap;
+use std::error::Error;
+use tracing::Instrument;
+use types::{company, filing};
+
+/// The financial item of an account of `fnlttSinglAcntAll`, requested with `fs_div`.
+///
+/// Blank amounts become `None`.
+pub fn new_financial_item(
+    item: &FinancialStatementItem,
+    fs_div: FsDiv,
+) -> Result<NewFinancialItem, Box<dyn Error>> {
+    Ok(NewFinancialItem {
+        dart_id: company::DartId::try_from(item.corp_code.trim())?,
+        business_year: item.bsns_year.trim().parse()?,
+        report_code: ReportCode::try_from(item.reprt_code.trim())?,
+        fs_div,
+        statement: Statement::try_from(item.sj_div.trim())?,
+        ord: item.ord.trim().parse()?,
+        account_detail: item
+            .account_detail
+            .as_deref()
+            .map(str::trim)
+            .filter(|detail| !detail.is_empty())
+            .unwrap_or("-")
+            .to_string(),
+        account_id: item.account_id.trim().to_string(),
+        account_name: item.account_nm.trim().to_string(),
+        current_amount: parse_amount(item.thstrm_amount.as_deref())?,
+        previous_amount: parse_amount(item.frmtrm_amount.as_deref())?,
+        before_previous_amount: parse_amount(item.bfefrmtrm_amount.as_deref())?,
+        currency: item.currency.trim().to_string(),
+        receipt_number: filing::ReceiptNumber::try_from(item.rcept_no.trim())?,
+    })
+}
+
+/// Whether the statement of `key`, stored from `receipt_number` and last checked by `check`, should be fetched.
+///
+/// A statement is fetched when there is neither, and again once the filing it was taken from is superseded.
+/// A statement OpenDART had no items for, or which lost items as invalid, is fetched again
+/// once an amendment of the company was stored after the check, or the check is `recheck_days` old.
+pub fn is_statement_due(
+    key: &FinancialStatementKey,
+    receipt_number: Option<&filing::ReceiptNumber>,
+    check: Option<&FinancialStatementCheck>,
+    chains: &[AmendmentChain],
+    now: time::PrimitiveDateTime,
+    recheck_days: i64,
+) -> bool {
+    let check = check.map(|check| Check {
+        checked_at: check.checked_at,
+        is_complete: check.item_count > 0 && check.skipped_count == 0,
+    });
+    is_report_part_due(
+        receipt_number,
+        check,
+        chains,
+        (key.report_code, key.business_year),
+        now,
+        recheck_days,
+    )
+}
+
+/// Store the financial statements of listed companies of `years`, every report and both divisions.
+///
+/// Statements which aren't due, see [`is_statement_due`], are skipped,
+/// so the next run picks up where the quota ran out.
+/// Every fetched statement is recorded as checked, including those OpenDART has no items for.
+/// Counts are of statements rather than of items, and a statement without items counts as unchanged.
+pub async fn ingest_financial_statements<D: Db>(
+    db: &mut D,
+    quota: &DartQuota,
+    years: &[i32],
+    recheck_days: i64,
+) -> Result<IngestCounts, Box<dyn Error>> {
+    // Only listed companies are covered by `fnlttSinglAcntAll`
+    let company_ids: Vec<_> = db
+        .get_company_ids()
+        .in_current_span()
+        .await?
+        .into_iter()
+        .filter(|company_id| company_id.stock_code.is_some())
+        .collect();
+    // Statements are replaced as a whole, so a stored statement is complete
+    let stored: HashMap<_, _> = db
+        .get_financial_statement_receipts()
+        .in_current_span()
+        .await?
+        .into_iter()
+        .collect();
+    let checks: HashMap<_, _> = db
+        .get_financial_statement_checks()
+        .in_current_span()
+        .await?
+        .into_iter()
+        .map(|check| (check.statement_key(), check))
+        .collect();
+    tracing::info!(
+        companies = company_ids.len(),
+        stored = stored.len(),
+        checked = checks.len(),
+        "Fetching financial statements"
+    );
+
+    let now = time::OffsetDateTime::now_utc();
+    let now = time::PrimitiveDateTime::new(now.date(), now.time());
+    let mut counts = IngestCounts::default();
+    'companies: for company_id in &company_ids {
+        let chains = db
+            .get_amendment_chains(&company_id.dart_id)
+            .in_current_span()
+            .await?;
+        for &business_year in years {
+            for &report_code in ReportCode::ALL {
+                for &fs_div in FsDiv::ALL {
+                    let key = FinancialStatementKey {
+                        dart_id: company_id.dart_id.clone(),
+                        business_year,
+                        report_code,
+                        fs_div,
+                    };
+                    let receipt_number = stored.get(&key);
+                    let check = checks.get(&key);
+                    if !is_statement_due(&key, receipt_number, check, &chains, now, recheck_days) {
+                        counts.unchanged += 1;
+                        continue;
+                    }
+                    tracing::debug!(?key, ?receipt_number, ?check, "Statement is due");
+
+                    let Some(items) = quota
+                        .request(db, async |api| {
+                            api.get_single_company_financial_statements(
+                                company_id.dart_id.as_ref().as_str(),
+                                &business_year.to_string(),
+                                report_code.as_str(),
+                                fs_div.as_str(),
+                            )
+                            .await
+                        })
+                        .in_current_span()
+                        .await?
+                    else {
+                        tracing::warn!(?key, "Stopping before the statement, as the quota is used");
+                        break 'companies;
+                    };
+
+                    let fetched = items.len();
+                    let items: Vec<_> = items
+                        .iter()
+                        .filter_map(|item| match new_financial_item(item, fs_div) {
+                            Ok(item) => Some(item),
+                            Err(e) => {
+                                tracing::warn!(?e, ?item, "Skipping invalid financial item");
+                                None
+                            }
+                        })
+                        .collect();
+                    let check = NewFinancialStatementCheck::new(
+                        key.clone(),
+                        i32::try_from(items.len())?,
+                        i32::try_from(fetched - items.len())?,
+                    );
+                    tracing::debug!(?check, "Storing financial statement");
+                    // Companies without subsidiaries have no consolidated statements,
+                    // and reports which aren't due yet have none at all.
+                    if items.is_empty() {
+                        counts.unchanged += 1;
+                    } else if receipt_number.is_some() {
+                        counts.updated += 1;
+                    } else {
+                        counts.inserted += 1;
+                    }
+                    db.replace_financial_statements(items)
+                        .in_current_span()
+                        .await?;
+                    db.upsert_financial_statement_check(check)
+                        .in_current_span()
+                        .await?;
+                }
+            }
+        }
+    }
+    Ok(counts)
+}
+
+/// Amounts may be formatted with commas, and are blank when there is no value.
+pub(crate) fn parse_amount(amount: Option<&str>) -> Result<Option<i64>, Box<dyn Error>> {
+    let amount = amount.unwrap_or_default().trim().replace(',', "");
+    if amount.is_empty() || amount == "-" {
+        return Ok(None);
+    }
+    Ok(Some(amount.parse()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::new_filing_from_list_item;
+    use db::dart::amendment_chains;
+    use db::model::dart::Filing;
+    use time::macros::datetime;
+
+    fn item() -> FinancialStatementItem {
+        FinancialStatementItem {
+            rcept_no: "20240312000736".to_string(),
+            reprt_code: "11011".to_string(),
+            bsns_year: "2023".to_string(),
+            corp_code: "00126380".to_string(),
+            sj_div: "BS".to_string(),
+            sj_nm: "재무상태표".to_string(),
+            account_id: "ifrs-full_CurrentAssets".to_string(),
+            account_nm: "유동자산".to_string(),
+            account_detail: Some("-".to_string()),
+            thstrm_amount: Some("195,936,557,000,000".to_string()),
+            frmtrm_amount: Some("-1000".to_string()),
+            bfefrmtrm_amount: Some("".to_string()),
+            ord: "1".to_string(),
+            currency: "KRW".to_string(),
+        }
+    }
+
+    #[test]
+    fn new_financial_item_should_parse_amounts() {
+        let item = new_financial_item(&item(), FsDiv::Consolidated)
+            .expect("Failed to convert financial statement item");
+
+        assert_eq!(item.current_amount, Some(195_936_557_000_000));
+        assert_eq!(item.previous_amount, Some(-1000));
+        assert_eq!(item.before_previous_amount, None);
+        assert_eq!(item.report_code, ReportCode::Annual);
+        assert_eq!(item.statement, Statement::BalanceSheet);
+    }
+
+    #[test]
+    fn new_financial_item_should_reject_unknown_statements() {
+        let item = FinancialStatementItem {
+            sj_div: "XX".to_string(),
+            ..item()
+        };
+
+        assert!(new_financial_item(&item, FsDiv::Separate).is_err());
+    }
+
+    fn key() -> FinancialStatementKey {
+        check(0, 0).statement_key()
+    }
+
+    fn check(item_count: i32, skipped_count: i32) -> FinancialStatementCheck {
+        FinancialStatementCheck {
+            dart_id: company::DartId::try_new("00126380").unwrap(),
+            business_year: 2023,
+            report_code: ReportCode::Annual,
+            fs_div: FsDiv::Consolidated,
+            item_count,
+            skipped_count,
+            run_id: None,
+            checked_at: datetime!(2024-11-01 0:00),
+        }
+    }
+
+    fn stored_filing(
+        receipt_number: &str,
+        report_nm: &str,
+        created_at: time::PrimitiveDateTime,
+    ) -> Filing {
+        let filing = new_filing_from_list_item(
+            "00126380",
+            "삼성전자",
+            report_nm,
+            receipt_number,
+            "삼성전자",
+            &receipt_number[..8],
+            "",
+        )
+        .expect("Failed to convert list item");
+        let flags = filing.remark.flags();
+        Filing {
+            dart_id: filing.dart_id,
+            report_name: filing.report_name,
+            receipt_number: filing.receipt_number,
+            filer_name: filing.filer_name,
+            receipt_date: filing.receipt_date,
+            remark: filing.remark,
+            created_at,
+            updated_at: created_at,
+            run_id: None,
+            is_consolidated: flags.consolidated,
+            is_corrected: flags.corrected,
+            is_withdrawn: flags.withdrawn,
+        }
+    }
+
+    #[test]
+    fn checked_statements_should_be_due_when_empty_or_incomplete_and_old() {
+        let key = key();
+        let now = datetime!(2024-11-15 0:00);
+
+        assert!(is_statement_due(&key, None, None, &[], now, 30));
+        assert!(!is_statement_due(
+            &key,
+            None,
+            Some(&check(0, 0)),
+            &[],
+            now,
+            30
+        ));
+        assert!(is_statement_due(
+            &key,
+            None,
+            Some(&check(0, 0)),
+            &[],
+            now,
+            14
+        ));
+        assert!(is_statement_due(
+            &key,
+            None,
+            Some(&check(10, 1)),
+            &[],
+            now,
+            14
+        ));
+        assert!(!is_statement_due(
+            &key,
+            None,
+            Some(&check(10, 0)),
+            &[],
+            now,
+            14
+        ));
+    }
+
+    #[test]
+    fn empty_statements_should_be_due_when_amended_after_the_check() {
+        let key = key();
+        let now = datetime!(2024-11-15 0:00);
+        let original = stored_filing(
+            "20241014000001",
+            "반기보고서 (2024.06)",
+            datetime!(2024-10-14 0:00),
+        );
+        let before = amendment_chains(vec![
+            original.clone(),
+            stored_filing(
+                "20241021000001",
+                "[기재정정]반기보고서 (2024.06)",
+                datetime!(2024-10-21 0:00),
+            ),
+        ]);
+        let after = amendment_chains(vec![
+            original,
+            stored_filing(
+                "20241104000001",
+                "[기재정정]반기보고서 (2024.06)",
+                datetime!(2024-11-04 0:00),
+            ),
+        ]);
+
+        assert!(!is_statement_due(
+            &key,
+            None,
+            Some(&check(0, 0)),
+            &before,
+            now,
+            30
+        ));
+        assert!(is_statement_due(
+            &key,
+            None,
+            Some(&check(0, 0)),
+            &after,
+            now,
+            30
+        ));
+        assert!(!is_statement_due(
+            &key,
+            None,
+            Some(&check(10, 0)),
+            &after,
+            now,
+            30
+        ));
+    }
+}
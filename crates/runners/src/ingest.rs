@@ -0,0 +1,122 @@
+use db::ingest::IngestRunDb;
+use db::model::ingest::{IngestCounts, NewIngestRun};
+use std::any::Any;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tracing::Instrument;
+
+/// The ingest run of `runner`, with the git revision it was built from.
+///
+/// `source_url` is the server the runner fetches from,
+/// and `config` is recorded as is, e.g. the `Debug` output of the settings.
+pub fn new_ingest_run(runner: &str, source_url: &str, config: Option<String>) -> NewIngestRun {
+    NewIngestRun {
+        runner: runner.to_string(),
+        git_rev: option_env!("GIT_REV").map(str::to_string),
+        config,
+        source_url: Some(source_url.to_string()),
+    }
+}
+
+/// Start `run`, run `ingest` with a handle whose writes reference the run,
+/// and finish the run with the counts `ingest` returns.
+///
+/// The runners `.expect` every step, so when `ingest` panics,
+/// the run is finished with the panic message as its error before the panic carries on.
+pub async fn with_ingest_run<D, F, Fut>(db: &mut D, run: NewIngestRun, ingest: F)
+where
+    D: IngestRunDb,
+    F: FnOnce(D) -> Fut,
+    Fut: Future<Output = IngestCounts>,
+{
+    let (run, run_db) = db
+        .start_ingest_run(run)
+        .in_current_span()
+        .await
+        .expect("Failed to start ingest run");
+
+    match CatchUnwind(Box::pin(ingest(run_db))).await {
+        Ok(counts) => {
+            db.finish_ingest_run(run.run_id, counts)
+                .in_current_span()
+                .await
+                .expect("Failed to finish ingest run");
+        }
+        Err(payload) => {
+            db.fail_ingest_run(run.run_id, panic_message(payload.as_ref()))
+                .in_current_span()
+                .await
+                .expect("Failed to record the failed ingest run");
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+/// Resolves to the panic of the inner future instead of unwinding through the caller.
+struct CatchUnwind<F>(Pin<Box<F>>);
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = Result<F::Output, Box<dyn Any + Send>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = self.0.as_mut();
+        match panic::catch_unwind(AssertUnwindSafe(|| inner.poll(cx))) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(payload) => Poll::Ready(Err(payload)),
+        }
+    }
+}
+
+/// The message `panic!` and `.expect` panic with, which are either a `String` or a `&str`.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else {
+        "Panicked with a non-string payload".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::InMemoryDb;
+
+    #[tokio::test]
+    async fn panicking_run_should_be_recorded_as_failed() {
+        // region: Arrange
+        tracing_setup::span!("test");
+        let mut db = InMemoryDb::default();
+        // endregion: Arrange
+
+        // region: Action
+        let panicked = AssertUnwindSafe(with_ingest_run(
+            &mut db,
+            new_ingest_run("test", "http://localhost", None),
+            |_| async {
+                let response: Result<IngestCounts, &str> = Err("connection refused");
+                response.expect("Failed to fetch")
+            },
+        ));
+        let result = CatchUnwind(Box::pin(panicked)).await;
+        let runs = db
+            .get_ingest_runs()
+            .await
+            .expect("Failed to get ingest runs");
+        // endregion: Action
+
+        // region: Assert
+        assert!(result.is_err());
+        assert_eq!(runs.len(), 1);
+        assert!(runs[0].finished_at.is_some());
+        assert_eq!(
+            runs[0].error.as_deref(),
+            Some("Failed to fetch: \"connection refused\"")
+        );
+        // endregion: Assert
+    }
+}
@@ -1,3 +1,27 @@
+mod company_profile;
 mod config;
+mod corp_code;
+mod database;
+mod filing;
+mod financial;
+mod ingest;
+mod link;
+mod list_snapshot;
+mod periodic_report;
+mod quota;
+mod recheck;
 
+pub use company_profile::{ingest_company_profiles, is_profile_stale, new_company_profile};
 pub use config::AppConfig;
+pub use corp_code::{changed_company_ids, company_id_from_corp_code, ingest_corp_codes};
+pub use database::Database;
+pub use filing::{changed_filings, ingest_filings, is_superseded, new_filing_from_list_item};
+pub use financial::{ingest_financial_statements, is_statement_due, new_financial_item};
+pub use ingest::{new_ingest_run, with_ingest_run};
+pub use link::link_companies;
+pub use list_snapshot::is_complete_crawl;
+pub use periodic_report::{
+    ingest_periodic_reports, is_section_due, new_employee, new_executive, new_major_shareholder,
+};
+pub use quota::{DartQuota, DART_DAILY_REQUEST_LIMIT};
+pub use recheck::is_recheck_due;
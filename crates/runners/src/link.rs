@@ -0,0 +1,31 @@
+use db::dart::{CompanyIdDb, CompanyProfileDb};
+use db::link::{dart_identities, match_companies, CompanyIdentity, CompanyLinkDb};
+use db::smes::CompanyDb;
+use db::DbError;
+use tracing::Instrument;
+
+/// Link the stored SMES companies with the stored DART companies, and return the number of links.
+///
+/// DART companies are matched by the registration numbers and representative of their profile,
+/// so companies without one in `dart.company_profile` are left unlinked.
+/// Links which no longer match are removed.
+pub async fn link_companies<D>(db: &mut D) -> Result<usize, DbError>
+where
+    D: CompanyDb + CompanyIdDb + CompanyProfileDb + CompanyLinkDb,
+{
+    let smes_companies: Vec<_> = db
+        .get_companies()
+        .in_current_span()
+        .await?
+        .iter()
+        .map(CompanyIdentity::from)
+        .collect();
+    let company_ids = db.get_company_ids().in_current_span().await?;
+    let profiles = db.get_company_profiles().in_current_span().await?;
+    let dart_companies = dart_identities(&company_ids, &profiles);
+
+    let links = match_companies(&smes_companies, &dart_companies);
+    let link_count = links.len();
+    db.replace_company_links(links).in_current_span().await?;
+    Ok(link_count)
+}
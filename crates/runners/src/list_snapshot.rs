@@ -0,0 +1,40 @@
+use db::model::smes::ListSnapshot;
+
+/// The share of the companies of the previous snapshot a crawl has to list to be recorded.
+const MIN_SNAPSHOT_RATIO: f64 = 0.9;
+
+/// Whether a list crawl which returned `listed` companies can be recorded as a snapshot.
+///
+/// A snapshot marks the companies missing from it as delisted,
+/// so a crawl which returned nothing, or far fewer companies than the `previous` snapshot,
+/// e.g. as the server cut the response short, isn't recorded.
+pub fn is_complete_crawl(listed: usize, previous: Option<&ListSnapshot>) -> bool {
+    match previous {
+        _ if listed == 0 => false,
+        Some(previous) => listed as f64 >= previous.company_count as f64 * MIN_SNAPSHOT_RATIO,
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn snapshot(company_count: i32) -> ListSnapshot {
+        ListSnapshot {
+            snapshot_id: 1,
+            company_count,
+            created_at: datetime!(2024-11-30 0:00),
+        }
+    }
+
+    #[test]
+    fn crawls_far_below_the_previous_snapshot_should_be_incomplete() {
+        assert!(!is_complete_crawl(0, None));
+        assert!(is_complete_crawl(1, None));
+        assert!(is_complete_crawl(9_500, Some(&snapshot(10_000))));
+        assert!(is_complete_crawl(10_500, Some(&snapshot(10_000))));
+        assert!(!is_complete_crawl(100, Some(&snapshot(10_000))));
+    }
+}
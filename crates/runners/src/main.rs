@@ -1,3 +1,37 @@
-fn main() {
-    println!("Hello, world!");
+//! Maintenance commands, the scrapers have a binary of their own in `src/bin`.
+//!
+//! ```sh
+//! runners db migrate
+//! ```
+
+use runners::Database;
+use tracing::Instrument;
+
+const USAGE: &str = "Usage: runners db migrate";
+
+#[tokio::main]
+async fn main() {
+    tracing_setup::span!("main");
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["db", "migrate"] => migrate().in_current_span().await,
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Apply the migrations embedded in the binary to `DATABASE_URL`,
+/// so deployments don't need the diesel CLI.
+async fn migrate() {
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
+    match Database::connect_and_migrate(&database_url)
+        .await
+        .expect("Failed to migrate db")
+    {
+        Database::Postgres(_) => tracing::info!("Migrated postgres db"),
+        Database::Sqlite(_) => tracing::info!("Migrated sqlite db"),
+    }
 }
@@ -0,0 +1,419 @@
+use crate::financial::parse_amount;
+use crate::recheck::{is_report_part_due, Check};
+use crate::DartQuota;
+use dart::{EmployeeItem, ExecutiveItem, MajorShareholderItem};
+use db::dart::{AmendmentChainDb, CompanyIdDb, PeriodicReportDb};
+use db::model::dart::{
+    AmendmentChain, Gender, NewEmployee, NewExecutive, NewMajorShareholder, NewPeriodicReportCheck,
+    PeriodicReportCheck, PeriodicReportKey, PeriodicReportSection, ReportCode,
+};
+use db::model::ingest::IngestCounts;
+use db::Db;
+use hashbrown::HashMap;
+use std::error::Error;
+use std::fmt::Debug;
+use tracing::Instrument;
+use types::{filing, YYYYMMDD};
+
+/// The major shareholder of the `ord`th row of `hyslrSttus`,
+/// or `None` for the total row, which can be summed from the others.
+pub fn new_major_shareholder(
+    item: &MajorShareholderItem,
+    ord: i32,
+) -> Result<Option<NewMajorShareholder>, Box<dyn Error>> {
+    let Some(name) = text(&item.nm).filter(|name| name != "계") else {
+        return Ok(None);
+    };
+    Ok(Some(NewMajorShareholder {
+        ord,
+        name,
+        relation: text(&item.relate),
+        stock_kind: text(&item.stock_knd),
+        shares_at_start: parse_amount(Some(&item.bsis_posesn_stock_co))?,
+        stake_at_start: parse_ratio(&item.bsis_posesn_stock_qota_rt)?,
+        shares_at_end: parse_amount(Some(&item.trmend_posesn_stock_co))?,
+        stake_at_end: parse_ratio(&item.trmend_posesn_stock_qota_rt)?,
+        remark: text(&item.rm),
+        settlement_date: parse_date(&item.stlm_dt)?,
+        receipt_number: filing::ReceiptNumber::try_from(item.rcept_no.trim())?,
+    }))
+}
+
+/// The executive of the `ord`th row of `exctvSttus`.
+pub fn new_executive(item: &ExecutiveItem, ord: i32) -> Result<NewExecutive, Box<dyn Error>> {
+    Ok(NewExecutive {
+        ord,
+        name: text(&item.nm).ok_or("Executive without name")?,
+        gender: parse_gender(&item.sexdstn),
+        birth_month: text(&item.birth_ym),
+        position: text(&item.ofcps),
+        registration: text(&item.rgist_exctv_at),
+        is_full_time: match item.fte_at.trim() {
+            "상근" => Some(true),
+            "비상근" => Some(false),
+            _ => None,
+        },
+        responsibility: text(&item.chrg_job),
+        main_career: text(&item.main_career),
+        relation_to_major_shareholder: text(&item.mxmm_shrholdr_relate),
+        tenure: text(&item.hffc_pd),
+        term_end: text(&item.tenure_end_on),
+        settlement_date: parse_date(&item.stlm_dt)?,
+        receipt_number: filing::ReceiptNumber::try_from(item.rcept_no.trim())?,
+    })
+}
+
+/// The employees of the `ord`th row of `empSttus`.
+pub fn new_employee(item: &EmployeeItem, ord: i32) -> Result<NewEmployee, Box<dyn Error>> {
+    Ok(NewEmployee {
+        ord,
+        business_segment: text(&item.fo_bbm),
+        gender: parse_gender(&item.sexdstn),
+        regular_count: parse_amount(Some(&item.rgllbr_co))?,
+        contract_count: parse_amount(Some(&item.cnttk_co))?,
+        total_count: parse_amount(Some(&item.sm))?,
+        average_tenure: text(&item.avrg_cnwk_sdytrn),
+        annual_salary_total: parse_amount(Some(&item.fyer_salary_totamt))?,
+        average_salary: parse_amount(Some(&item.jan_salary_am))?,
+        remark: text(&item.rm),
+        settlement_date: parse_date(&item.stlm_dt)?,
+        receipt_number: filing::ReceiptNumber::try_from(item.rcept_no.trim())?,
+    })
+}
+
+/// Whether `section` of the report `key`, stored from `receipt_number` and last checked by `check`, should be fetched.
+///
+/// A section is fetched when there is neither, and again once the filing it was taken from is superseded.
+/// A section OpenDART had no rows for, e.g. major shareholders of only a total row,
+/// or which lost rows as invalid, is fetched again
+/// once an amendment of the company was stored after the check, or the check is `recheck_days` old.
+pub fn is_section_due(
+    key: &PeriodicReportKey,
+    receipt_number: Option<&filing::ReceiptNumber>,
+    check: Option<&PeriodicReportCheck>,
+    chains: &[AmendmentChain],
+    now: time::PrimitiveDateTime,
+    recheck_days: i64,
+) -> bool {
+    let check = check.map(|check| Check {
+        checked_at: check.checked_at,
+        is_complete: check.row_count > 0 && check.skipped_count == 0,
+    });
+    is_report_part_due(
+        receipt_number,
+        check,
+        chains,
+        (key.report_code, key.business_year),
+        now,
+        recheck_days,
+    )
+}
+
+/// Store the major shareholder, executive and employee sections of the periodic reports
+/// of listed companies of `years`, every report.
+///
+/// Sections which aren't due, see [`is_section_due`], are skipped,
+/// so the next run picks up where the quota ran out.
+/// Every fetched section is recorded as checked, including those OpenDART has no rows for.
+/// Counts are of sections rather than of rows, and a section without rows counts as unchanged.
+pub async fn ingest_periodic_reports<D: Db>(
+    db: &mut D,
+    quota: &DartQuota,
+    years: &[i32],
+    recheck_days: i64,
+) -> Result<IngestCounts, Box<dyn Error>> {
+    // Listed companies are the ones which file periodic reports
+    let company_ids: Vec<_> = db
+        .get_company_ids()
+        .in_current_span()
+        .await?
+        .into_iter()
+        .filter(|company_id| company_id.stock_code.is_some())
+        .collect();
+    // Sections are replaced as a whole, so a stored section is complete
+    let mut stored: HashMap<(PeriodicReportSection, PeriodicReportKey), _> = HashMap::new();
+    for &section in PeriodicReportSection::ALL {
+        let receipts = db
+            .get_periodic_report_receipts(section)
+            .in_current_span()
+            .await?;
+        stored.extend(
+            receipts
+                .into_iter()
+                .map(|(key, receipt_number)| ((section, key), receipt_number)),
+        );
+    }
+    let checks: HashMap<_, _> = db
+        .get_periodic_report_checks()
+        .in_current_span()
+        .await?
+        .into_iter()
+        .map(|check| ((check.section, check.report_key()), check))
+        .collect();
+    tracing::info!(
+        companies = company_ids.len(),
+        stored = stored.len(),
+        checked = checks.len(),
+        "Fetching periodic reports"
+    );
+
+    let now = time::OffsetDateTime::now_utc();
+    let now = time::PrimitiveDateTime::new(now.date(), now.time());
+    let mut counts = IngestCounts::default();
+    'companies: for company_id in &company_ids {
+        let chains = db
+            .get_amendment_chains(&company_id.dart_id)
+            .in_current_span()
+            .await?;
+        for &business_year in years {
+            for &report_code in ReportCode::ALL {
+                let key = PeriodicReportKey {
+                    dart_id: company_id.dart_id.clone(),
+                    business_year,
+                    report_code,
+                };
+                let corp_code = company_id.dart_id.as_ref().as_str();
+                let bsns_year = business_year.to_string();
+                let reprt_code = report_code.as_str();
+                for &section in PeriodicReportSection::ALL {
+                    let receipt_number = stored.get(&(section, key.clone()));
+                    let check = checks.get(&(section, key.clone()));
+                    if !is_section_due(&key, receipt_number, check, &chains, now, recheck_days) {
+                        counts.unchanged += 1;
+                        continue;
+                    }
+                    tracing::debug!(?section, ?key, ?receipt_number, ?check, "Section is due");
+
+                    // Reports which weren't filed, or aren't due yet, have no rows
+                    let (row_count, skipped_count) = match section {
+                        PeriodicReportSection::MajorShareholder => {
+                            let Some(items) = quota
+                                .request(db, async |api| {
+                                    api.get_major_shareholders(corp_code, &bsns_year, reprt_code)
+                                        .await
+                                })
+                                .in_current_span()
+                                .await?
+                            else {
+                                break 'companies;
+                            };
+                            let (rows, skipped) = convert(&items, new_major_shareholder);
+                            // The total row is left out, so a section of only the total has no rows
+                            let rows: Vec<_> = rows.into_iter().flatten().collect();
+                            let row_count = rows.len();
+                            // A section without rows keeps what was stored from it
+                            if !rows.is_empty() {
+                                db.replace_major_shareholders(key.clone(), rows)
+                                    .in_current_span()
+                                    .await?;
+                            }
+                            (row_count, skipped)
+                        }
+                        PeriodicReportSection::Executive => {
+                            let Some(items) = quota
+                                .request(db, async |api| {
+                                    api.get_executives(corp_code, &bsns_year, reprt_code).await
+                                })
+                                .in_current_span()
+                                .await?
+                            else {
+                                break 'companies;
+                            };
+                            let (rows, skipped) = convert(&items, new_executive);
+                            let row_count = rows.len();
+                            if !rows.is_empty() {
+                                db.replace_executives(key.clone(), rows)
+                                    .in_current_span()
+                                    .await?;
+                            }
+                            (row_count, skipped)
+                        }
+                        PeriodicReportSection::Employee => {
+                            let Some(items) = quota
+                                .request(db, async |api| {
+                                    api.get_employees(corp_code, &bsns_year, reprt_code).await
+                                })
+                                .in_current_span()
+                                .await?
+                            else {
+                                break 'companies;
+                            };
+                            let (rows, skipped) = convert(&items, new_employee);
+                            let row_count = rows.len();
+                            if !rows.is_empty() {
+                                db.replace_employees(key.clone(), rows)
+                                    .in_current_span()
+                                    .await?;
+                            }
+                            (row_count, skipped)
+                        }
+                    };
+                    let check = NewPeriodicReportCheck::new(
+                        key.clone(),
+                        section,
+                        i32::try_from(row_count)?,
+                        i32::try_from(skipped_count)?,
+                    );
+                    tracing::debug!(?check, "Stored periodic report section");
+                    db.upsert_periodic_report_check(check)
+                        .in_current_span()
+                        .await?;
+                    if row_count == 0 {
+                        counts.unchanged += 1;
+                    } else if receipt_number.is_some() {
+                        counts.updated += 1;
+                    } else {
+                        counts.inserted += 1;
+                    }
+                }
+            }
+        }
+    }
+    Ok(counts)
+}
+
+/// Convert `items`, numbering them by their position in the response,
+/// and skipping the invalid ones, which are counted.
+fn convert<I: Debug, T>(
+    items: &[I],
+    new_row: impl Fn(&I, i32) -> Result<T, Box<dyn Error>>,
+) -> (Vec<T>, usize) {
+    let mut skipped = 0;
+    let rows = items
+        .iter()
+        .zip(1..)
+        .filter_map(|(item, ord)| match new_row(item, ord) {
+            Ok(row) => Some(row),
+            Err(e) => {
+                tracing::warn!(?e, ?item, "Skipping invalid periodic report item");
+                skipped += 1;
+                None
+            }
+        })
+        .collect();
+    (rows, skipped)
+}
+
+/// OpenDART fills fields without a value with `-`.
+fn text(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty() && value != "-").then(|| value.to_string())
+}
+
+/// Genders other than `남` and `여`, e.g. `합계` rows of some filers, become `None`.
+fn parse_gender(value: &str) -> Option<Gender> {
+    Gender::try_from(value.trim()).ok()
+}
+
+fn parse_ratio(value: &str) -> Result<Option<f64>, Box<dyn Error>> {
+    let Some(value) = text(value) else {
+        return Ok(None);
+    };
+    Ok(Some(value.trim_end_matches('%').replace(',', "").parse()?))
+}
+
+/// Settlement dates are formatted like `2023-12-31`.
+fn parse_date(value: &str) -> Result<Option<YYYYMMDD>, Box<dyn Error>> {
+    let Some(value) = text(value) else {
+        return Ok(None);
+    };
+    Ok(Some(YYYYMMDD::try_from(value.replace('-', "").as_str())?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn major_shareholder_item(nm: &str) -> MajorShareholderItem {
+        MajorShareholderItem {
+            rcept_no: "20240312000736".to_string(),
+            corp_code: "00126380".to_string(),
+            nm: nm.to_string(),
+            relate: "계열회사".to_string(),
+            stock_knd: "보통주".to_string(),
+            bsis_posesn_stock_co: "508,157,148".to_string(),
+            bsis_posesn_stock_qota_rt: "8.51".to_string(),
+            trmend_posesn_stock_co: "-".to_string(),
+            trmend_posesn_stock_qota_rt: "-".to_string(),
+            rm: "-".to_string(),
+            stlm_dt: "2023-12-31".to_string(),
+        }
+    }
+
+    #[test]
+    fn new_major_shareholder_should_parse_numbers() {
+        let row = new_major_shareholder(&major_shareholder_item("삼성생명보험(주)"), 1)
+            .expect("Failed to convert major shareholder")
+            .expect("The row should be kept");
+
+        assert_eq!(row.shares_at_start, Some(508_157_148));
+        assert_eq!(row.stake_at_start, Some(8.51));
+        assert_eq!(row.shares_at_end, None);
+        assert_eq!(row.stake_at_end, None);
+        assert_eq!(row.remark, None);
+        assert_eq!(
+            row.settlement_date,
+            Some(YYYYMMDD::try_from("20231231").unwrap())
+        );
+    }
+
+    #[test]
+    fn total_rows_should_be_skipped() {
+        let row = new_major_shareholder(&major_shareholder_item("계"), 2)
+            .expect("Failed to convert major shareholder");
+
+        assert_eq!(row, None);
+    }
+
+    #[test]
+    fn new_executive_should_type_flags() {
+        let item = ExecutiveItem {
+            rcept_no: "20240312000736".to_string(),
+            corp_code: "00126380".to_string(),
+            nm: "한종희".to_string(),
+            sexdstn: "남".to_string(),
+            birth_ym: "1962년 03월".to_string(),
+            ofcps: "대표이사".to_string(),
+            rgist_exctv_at: "사내이사".to_string(),
+            fte_at: "상근".to_string(),
+            chrg_job: "DX부문장".to_string(),
+            main_career: "-".to_string(),
+            mxmm_shrholdr_relate: "-".to_string(),
+            hffc_pd: "6년 10개월".to_string(),
+            tenure_end_on: "2025.03.15".to_string(),
+            stlm_dt: "2023-12-31".to_string(),
+        };
+
+        let row = new_executive(&item, 1).expect("Failed to convert executive");
+
+        assert_eq!(row.gender, Some(Gender::Male));
+        assert_eq!(row.is_full_time, Some(true));
+        assert_eq!(row.main_career, None);
+    }
+
+    #[test]
+    fn sections_of_only_a_total_row_should_be_due_after_recheck_days() {
+        let key = PeriodicReportKey {
+            dart_id: types::company::DartId::try_new("00126380").unwrap(),
+            business_year: 2023,
+            report_code: ReportCode::Annual,
+        };
+        let check = |row_count| PeriodicReportCheck {
+            dart_id: key.dart_id.clone(),
+            business_year: key.business_year,
+            report_code: key.report_code,
+            section: PeriodicReportSection::MajorShareholder,
+            row_count,
+            skipped_count: 0,
+            run_id: None,
+            checked_at: datetime!(2024-11-01 0:00),
+        };
+        let now = datetime!(2024-11-15 0:00);
+
+        assert!(is_section_due(&key, None, None, &[], now, 30));
+        assert!(!is_section_due(&key, None, Some(&check(0)), &[], now, 30));
+        assert!(is_section_due(&key, None, Some(&check(0)), &[], now, 14));
+        assert!(!is_section_due(&key, None, Some(&check(3)), &[], now, 14));
+    }
+}
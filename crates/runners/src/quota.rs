@@ -0,0 +1,88 @@
+use chrono::{FixedOffset, NaiveDate, Utc};
+use dart::{DartApi, DartError};
+use db::dart::ApiQuotaDb;
+use std::error::Error;
+use tracing::Instrument;
+
+/// The requests OpenDART serves per key and day, beyond which it answers status `020`.
+pub const DART_DAILY_REQUEST_LIMIT: i32 = 20_000;
+
+/// OpenDART keys, and the requests each may send per day.
+///
+/// Every request is reserved in `dart.api_quota` before it is sent,
+/// so runners sharing keys stop before they exceed the limit together.
+/// Keys are used in order, the next one once a key used its quota.
+#[derive(Debug, Clone)]
+pub struct DartQuota {
+    apis: Vec<DartApi>,
+    daily_request_limit: i32,
+}
+
+impl DartQuota {
+    pub fn new(apis: Vec<DartApi>, daily_request_limit: i32) -> Self {
+        Self {
+            apis,
+            daily_request_limit,
+        }
+    }
+
+    /// The keys of `OPEN_DART_API_KEY`, see [`DartApi::all_from_env`].
+    pub fn from_env(daily_request_limit: i32) -> Result<Self, DartError> {
+        Ok(Self::new(DartApi::all_from_env()?, daily_request_limit))
+    }
+
+    /// The server the requests are sent to, which is the same for every key.
+    pub fn domain(&self) -> &str {
+        self.apis.first().map_or("", |api| api.domain.as_str())
+    }
+
+    /// Send at most `requests_per_minute` requests per key, see [`DartApi::with_rate_limit`].
+    pub fn with_rate_limit(mut self, requests_per_minute: u32) -> Self {
+        self.apis = self
+            .apis
+            .into_iter()
+            .map(|api| api.with_rate_limit(requests_per_minute))
+            .collect();
+        self
+    }
+
+    /// Send `request` with the first key which has requests left today.
+    ///
+    /// Returns `None` without sending it when every key used its quota,
+    /// for the runner to stop and pick up where it left off on the next day.
+    /// A key OpenDART rate limits anyway, e.g. as it was also used elsewhere,
+    /// is marked exhausted and the request is sent with the next key.
+    pub async fn request<D: ApiQuotaDb, T>(
+        &self,
+        db: &mut D,
+        request: impl AsyncFn(&DartApi) -> Result<T, DartError>,
+    ) -> Result<Option<T>, Box<dyn Error>> {
+        let day = today();
+        for api in &self.apis {
+            let key_id = api.key_id();
+            if !db
+                .reserve_api_request(&key_id, day, self.daily_request_limit)
+                .in_current_span()
+                .await?
+            {
+                continue;
+            }
+
+            match request(api).await {
+                Err(DartError::RateLimited { message }) => {
+                    tracing::warn!(%key_id, %message, "OpenDART rate limited the key");
+                    db.exhaust_api_quota(&key_id, day).in_current_span().await?;
+                }
+                result => return Ok(Some(result?)),
+            }
+        }
+        tracing::warn!(%day, "Every OpenDART key used its quota for the day");
+        Ok(None)
+    }
+}
+
+/// The date in KST, when OpenDART resets the quotas.
+fn today() -> NaiveDate {
+    let kst = FixedOffset::east_opt(9 * 60 * 60).expect("KST is a valid offset");
+    Utc::now().with_timezone(&kst).date_naive()
+}
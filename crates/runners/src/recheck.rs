@@ -0,0 +1,55 @@
+use crate::is_superseded;
+use db::model::dart::{AmendmentChain, ReportCode};
+use types::filing;
+
+/// Whether a check at `checked_at` is `recheck_days` or more days old at `now`,
+/// so what OpenDART had nothing for is requested again.
+pub fn is_recheck_due(
+    checked_at: time::PrimitiveDateTime,
+    now: time::PrimitiveDateTime,
+    recheck_days: i64,
+) -> bool {
+    checked_at + time::Duration::days(recheck_days) <= now
+}
+
+/// The last check of a part of a report, e.g. a financial statement, see [`is_report_part_due`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Check {
+    pub(crate) checked_at: time::PrimitiveDateTime,
+    /// Whether OpenDART had rows, none of which were skipped as invalid
+    pub(crate) is_complete: bool,
+}
+
+/// Whether a part of the `report_code` report of `business_year`,
+/// stored from `receipt_number` and last checked by `check`, should be fetched.
+///
+/// A part is fetched when there is neither, and again once the filing it was taken from is superseded.
+/// An incomplete one is fetched again once an amendment of the company was stored after the check,
+/// or the check is `recheck_days` old.
+pub(crate) fn is_report_part_due(
+    receipt_number: Option<&filing::ReceiptNumber>,
+    check: Option<Check>,
+    chains: &[AmendmentChain],
+    (report_code, business_year): (ReportCode, i32),
+    now: time::PrimitiveDateTime,
+    recheck_days: i64,
+) -> bool {
+    if receipt_number.is_some_and(|receipt_number| {
+        is_superseded(chains, receipt_number, report_code, business_year)
+    }) {
+        return true;
+    }
+    match check {
+        // Parts stored before checks were recorded are complete
+        None => receipt_number.is_none(),
+        Some(check) if check.is_complete => false,
+        Some(check) => {
+            is_recheck_due(check.checked_at, now, recheck_days)
+                || chains.iter().any(|chain| {
+                    chain.filings[1..]
+                        .iter()
+                        .any(|amendment| amendment.created_at > check.checked_at)
+                })
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! A local OpenDART, which serves the fixtures in `tests/resources/dart`.
+
+use dart::DartApi;
+use runners::{DartQuota, DART_DAILY_REQUEST_LIMIT};
+use std::io::Write;
+use std::path::PathBuf;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+const API_KEY: &str = "test-key";
+
+pub struct DartMock {
+    server: MockServer,
+}
+
+impl DartMock {
+    pub async fn start() -> Self {
+        Self {
+            server: MockServer::start().await,
+        }
+    }
+
+    /// A client of the mock, with the key the mock expects.
+    pub fn api(&self) -> DartApi {
+        self.api_with_key(API_KEY)
+    }
+
+    /// A client of the mock with `api_key`, which only [`Self::rate_limited`] and
+    /// [`Self::fallback`] serve unless it is the key the mock expects.
+    pub fn api_with_key(&self, api_key: &str) -> DartApi {
+        DartApi::new(api_key).with_domain(&self.server.uri())
+    }
+
+    /// The quota of the key the mock expects, with the limit of OpenDART.
+    pub fn quota(&self) -> DartQuota {
+        DartQuota::new(vec![self.api()], DART_DAILY_REQUEST_LIMIT)
+    }
+
+    /// Serve `fixture` as the `CORPCODE.xml` of the corp code archive, `times` times.
+    pub async fn corp_codes(&self, fixture: &str, times: u64) {
+        let mut writer = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer
+            .start_file("CORPCODE.xml", SimpleFileOptions::default())
+            .expect("Failed to start file");
+        writer
+            .write_all(&read_fixture(fixture))
+            .expect("Failed to write file");
+        let archive = writer
+            .finish()
+            .expect("Failed to finish archive")
+            .into_inner();
+
+        Mock::given(method("GET"))
+            .and(path("/api/corpCode.xml"))
+            .and(query_param("crtfc_key", API_KEY))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(archive))
+            .expect(times)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Serve `fixture` as page `page_no` of the filings received on `date`, `YYYYMMDD`.
+    pub async fn list_page(&self, date: &str, page_no: u32, fixture: &str, times: u64) {
+        Mock::given(method("GET"))
+            .and(path("/api/list.json"))
+            .and(query_param("crtfc_key", API_KEY))
+            .and(query_param("bgn_de", date))
+            .and(query_param("end_de", date))
+            .and(query_param("page_no", page_no.to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(read_fixture(fixture)))
+            .expect(times)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Serve `fixture` for every request to `endpoint`, `times` times,
+    /// e.g. `no_data.json` for statements OpenDART has no items for.
+    pub async fn endpoint(&self, endpoint: &str, fixture: &str, times: u64) {
+        Mock::given(method("GET"))
+            .and(path(endpoint))
+            .and(query_param("crtfc_key", API_KEY))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(read_fixture(fixture)))
+            .expect(times)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Answer every request to `endpoint` with `api_key` with status 020,
+    /// as OpenDART does once the key exceeded its requests for the day.
+    pub async fn rate_limited(&self, endpoint: &str, api_key: &str) {
+        Mock::given(method("GET"))
+            .and(path(endpoint))
+            .and(query_param("crtfc_key", api_key))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_bytes(read_fixture("rate_limit.json")),
+            )
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Serve `fixture` for every request to `endpoint` no other mock matches,
+    /// e.g. `no_data.json` for days without filings.
+    pub async fn fallback(&self, endpoint: &str, fixture: &str) {
+        Mock::given(method("GET"))
+            .and(path(endpoint))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(read_fixture(fixture)))
+            .with_priority(u8::MAX)
+            .mount(&self.server)
+            .await;
+    }
+}
+
+fn read_fixture(name: &str) -> Vec<u8> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/resources/dart")
+        .join(name);
+    std::fs::read(&path).unwrap_or_else(|e| panic!("Failed to read fixture {path:?}: {e}"))
+}
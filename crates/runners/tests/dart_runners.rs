@@ -0,0 +1,267 @@
+//! The DART runners against a local OpenDART, see [`dart_mock::DartMock`].
+
+mod dart_mock;
+
+use chrono::{FixedOffset, NaiveDate, Utc};
+use dart_mock::DartMock;
+use db::dart::{
+    ApiQuotaDb, CompanyIdDb, CompanyProfileDb, FilingDb, FinancialItemDb, PeriodicReportDb,
+};
+use db::model::dart::{ApiQuota, CompanyId};
+use db::{Db, InMemoryDb};
+use runners::{
+    ingest_company_profiles, ingest_corp_codes, ingest_filings, ingest_financial_statements,
+    ingest_periodic_reports, DartQuota,
+};
+use types::company;
+
+async fn db() -> InMemoryDb {
+    InMemoryDb::new("").await.expect("Failed to create db")
+}
+
+fn date(date: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(date, "%Y%m%d").expect("Failed to parse date")
+}
+
+/// A listed company, which the runners of periodic disclosures cover.
+fn listed_company() -> CompanyId {
+    CompanyId {
+        dart_id: company::DartId::try_from("00126380").expect("Failed to create dart_id"),
+        company_name: company::Name::try_from("삼성전자").expect("Failed to create name"),
+        stock_code: Some(
+            company::StockCode::try_from("005930").expect("Failed to create stock code"),
+        ),
+        id_modify_date: None,
+    }
+}
+
+/// The quotas of today in KST, as keyed by [`DartQuota`], as `(key_id, request_count, exhausted)`.
+async fn quotas_of_today(db: &mut InMemoryDb) -> Vec<(String, i32, bool)> {
+    let kst = FixedOffset::east_opt(9 * 60 * 60).expect("KST is a valid offset");
+    db.get_api_quotas(Utc::now().with_timezone(&kst).date_naive())
+        .await
+        .expect("Failed to get quotas")
+        .into_iter()
+        .map(
+            |ApiQuota {
+                 key_id,
+                 request_count,
+                 exhausted,
+                 ..
+             }| (key_id, request_count, exhausted),
+        )
+        .collect()
+}
+
+#[tokio::test]
+async fn corp_codes_should_be_ingested_once() {
+    tracing_setup::span!("test");
+    let mock = DartMock::start().await;
+    mock.corp_codes("corp_codes.xml", 2).await;
+    let mut db = db().await;
+
+    let first = ingest_corp_codes(&mut db, &mock.quota())
+        .await
+        .expect("Failed to ingest corp codes");
+    let second = ingest_corp_codes(&mut db, &mock.quota())
+        .await
+        .expect("Failed to ingest corp codes");
+    let company_ids = db
+        .get_company_ids()
+        .await
+        .expect("Failed to get company ids");
+
+    assert_eq!((first.inserted, first.unchanged), (3, 0));
+    assert_eq!((second.inserted, second.unchanged), (0, 3));
+    assert_eq!(company_ids.len(), 3);
+    let unlisted = company_ids
+        .iter()
+        .find(|company_id| company_id.dart_id.as_ref() == "00434003")
+        .expect("The unlisted company should be stored");
+    assert_eq!(unlisted.stock_code, None);
+}
+
+#[tokio::test]
+async fn filings_should_be_ingested_across_pages_and_days() {
+    tracing_setup::span!("test");
+    let mock = DartMock::start().await;
+    mock.list_page("20241001", 1, "list_20241001_page_1.json", 2)
+        .await;
+    mock.list_page("20241001", 2, "list_20241001_page_2.json", 2)
+        .await;
+    // 2024-10-02 has no filings
+    mock.fallback("/api/list.json", "no_data.json").await;
+    let mut db = db().await;
+
+    let first = ingest_filings(&mut db, &mock.quota(), date("20241001"), date("20241002"))
+        .await
+        .expect("Failed to ingest filings");
+    let second = ingest_filings(&mut db, &mock.quota(), date("20241001"), date("20241002"))
+        .await
+        .expect("Failed to ingest filings");
+
+    assert_eq!((first.inserted, first.unchanged), (3, 0));
+    assert_eq!((second.inserted, second.unchanged), (0, 3));
+}
+
+#[tokio::test]
+async fn rate_limited_key_should_be_rotated() {
+    tracing_setup::span!("test");
+    let mock = DartMock::start().await;
+    mock.rate_limited("/api/list.json", "limited-key").await;
+    mock.list_page("20241001", 1, "list_20241001_page_1.json", 1)
+        .await;
+    mock.list_page("20241001", 2, "list_20241001_page_2.json", 1)
+        .await;
+    let limited = mock.api_with_key("limited-key");
+    let quota = DartQuota::new(vec![limited.clone(), mock.api()], 10);
+    let mut db = db().await;
+
+    let counts = ingest_filings(&mut db, &quota, date("20241001"), date("20241001"))
+        .await
+        .expect("Failed to ingest filings");
+
+    assert_eq!(counts.inserted, 3);
+    let mut expected = vec![(limited.key_id(), 1, true), (mock.api().key_id(), 2, false)];
+    expected.sort();
+    assert_eq!(quotas_of_today(&mut db).await, expected);
+}
+
+#[tokio::test]
+async fn used_quota_should_stop_ingestion_before_the_limit() {
+    tracing_setup::span!("test");
+    let mock = DartMock::start().await;
+    mock.list_page("20241001", 1, "list_20241001_page_1.json", 1)
+        .await;
+    mock.list_page("20241001", 2, "list_20241001_page_2.json", 0)
+        .await;
+    let quota = DartQuota::new(vec![mock.api()], 1);
+    let mut db = db().await;
+
+    let counts = ingest_filings(&mut db, &quota, date("20241001"), date("20241001"))
+        .await
+        .expect("Failed to ingest filings");
+
+    // The day wasn't fetched in full, so none of it is stored
+    assert_eq!(counts.inserted, 0);
+    assert_eq!(
+        db.get_latest_receipt_date()
+            .await
+            .expect("Failed to get latest receipt date"),
+        None
+    );
+    assert_eq!(
+        quotas_of_today(&mut db).await,
+        vec![(mock.api().key_id(), 1, false)]
+    );
+}
+
+#[tokio::test]
+async fn rate_limit_of_every_key_should_stop_ingestion() {
+    tracing_setup::span!("test");
+    let mock = DartMock::start().await;
+    mock.fallback("/api/list.json", "rate_limit.json").await;
+    let mut db = db().await;
+
+    let counts = ingest_filings(&mut db, &mock.quota(), date("20241001"), date("20241002"))
+        .await
+        .expect("A rate limited key should stop ingestion rather than fail it");
+
+    assert_eq!(counts.inserted, 0);
+    assert_eq!(
+        quotas_of_today(&mut db).await,
+        vec![(mock.api().key_id(), 1, true)]
+    );
+}
+
+#[tokio::test]
+async fn empty_financial_statements_should_be_requested_once() {
+    tracing_setup::span!("test");
+    let mock = DartMock::start().await;
+    // Every report and division of one year, on the first run only
+    mock.endpoint("/api/fnlttSinglAcntAll.json", "no_data.json", 8)
+        .await;
+    let mut db = db().await;
+    db.insert_company_ids(vec![listed_company()])
+        .await
+        .expect("Failed to insert company ids");
+
+    let first = ingest_financial_statements(&mut db, &mock.quota(), &[2023], 30)
+        .await
+        .expect("Failed to ingest financial statements");
+    let second = ingest_financial_statements(&mut db, &mock.quota(), &[2023], 30)
+        .await
+        .expect("Failed to ingest financial statements");
+    let checks = db
+        .get_financial_statement_checks()
+        .await
+        .expect("Failed to get financial statement checks");
+
+    assert_eq!((first.inserted, first.unchanged), (0, 8));
+    assert_eq!((second.inserted, second.unchanged), (0, 8));
+    assert_eq!(checks.len(), 8);
+    assert!(checks.iter().all(|check| check.item_count == 0));
+}
+
+#[tokio::test]
+async fn companies_without_overview_should_be_requested_once() {
+    tracing_setup::span!("test");
+    let mock = DartMock::start().await;
+    mock.endpoint("/api/company.json", "no_data.json", 1).await;
+    let mut db = db().await;
+    db.insert_company_ids(vec![listed_company()])
+        .await
+        .expect("Failed to insert company ids");
+
+    let first = ingest_company_profiles(&mut db, &mock.quota())
+        .await
+        .expect("Failed to ingest company profiles");
+    let second = ingest_company_profiles(&mut db, &mock.quota())
+        .await
+        .expect("Failed to ingest company profiles");
+    let profile = db
+        .get_company_profile("00126380")
+        .await
+        .expect("Failed to get company profile")
+        .expect("The check should be recorded");
+
+    assert_eq!((first.inserted, first.unchanged), (0, 1));
+    assert_eq!((second.inserted, second.unchanged), (0, 1));
+    assert_eq!(profile.business_registration_number, None);
+}
+
+#[tokio::test]
+async fn empty_periodic_report_sections_should_be_requested_once() {
+    tracing_setup::span!("test");
+    let mock = DartMock::start().await;
+    // Every report of one year, on the first run only
+    mock.endpoint(
+        "/api/hyslrSttus.json",
+        "major_shareholders_total_only.json",
+        4,
+    )
+    .await;
+    mock.endpoint("/api/exctvSttus.json", "no_data.json", 4)
+        .await;
+    mock.endpoint("/api/empSttus.json", "no_data.json", 4).await;
+    let mut db = db().await;
+    db.insert_company_ids(vec![listed_company()])
+        .await
+        .expect("Failed to insert company ids");
+
+    let first = ingest_periodic_reports(&mut db, &mock.quota(), &[2023], 30)
+        .await
+        .expect("Failed to ingest periodic reports");
+    let second = ingest_periodic_reports(&mut db, &mock.quota(), &[2023], 30)
+        .await
+        .expect("Failed to ingest periodic reports");
+    let checks = db
+        .get_periodic_report_checks()
+        .await
+        .expect("Failed to get periodic report checks");
+
+    assert_eq!((first.inserted, first.unchanged), (0, 12));
+    assert_eq!((second.inserted, second.unchanged), (0, 12));
+    assert_eq!(checks.len(), 12);
+    assert!(checks.iter().all(|check| check.row_count == 0));
+}
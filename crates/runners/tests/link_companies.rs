@@ -0,0 +1,124 @@
+//! `link_companies` against companies stored the way the SMES and DART runners store them.
+
+use db::dart::{CompanyIdDb, CompanyProfileDb};
+use db::link::CompanyLinkDb;
+use db::model::dart::{CompanyId, NewCompanyProfile};
+use db::model::link::MatchMethod;
+use db::model::smes::NewCompany;
+use db::smes::CompanyDb;
+use db::{Db, InMemoryDb};
+use runners::link_companies;
+
+fn smes_company(
+    smes_id: &str,
+    business_registration_number: Option<&str>,
+    name: &str,
+    representative_name: &str,
+) -> NewCompany {
+    NewCompany {
+        smes_id: smes_id.try_into().unwrap(),
+        representative_name: representative_name.try_into().unwrap(),
+        headquarters_address: "서울특별시 강남구".try_into().unwrap(),
+        business_registration_number: business_registration_number
+            .map(|number| number.try_into().unwrap()),
+        company_name: name.try_into().unwrap(),
+        industry_code: "58211".try_into().unwrap(),
+        industry_name: "게임 소프트웨어 개발 및 공급업".try_into().unwrap(),
+    }
+}
+
+fn company_id(dart_id: &str, name: &str) -> CompanyId {
+    CompanyId {
+        dart_id: dart_id.try_into().unwrap(),
+        company_name: name.try_into().unwrap(),
+        stock_code: None,
+        id_modify_date: Some("20241001".try_into().unwrap()),
+    }
+}
+
+fn profile(
+    dart_id: &str,
+    business_registration_number: Option<&str>,
+    representative_name: &str,
+) -> NewCompanyProfile {
+    NewCompanyProfile {
+        dart_id: dart_id.try_into().unwrap(),
+        corporation_registration_number: None,
+        business_registration_number: business_registration_number
+            .map(|number| number.try_into().unwrap()),
+        representative_name: Some(representative_name.try_into().unwrap()),
+        headquarters_address: None,
+        industry_code: None,
+        established_date: None,
+        fiscal_month: Some(12),
+    }
+}
+
+#[tokio::test]
+async fn dart_companies_should_be_linked_by_their_profile() {
+    // region: Arrange
+    tracing_setup::span!("test");
+    let mut db = InMemoryDb::new("").await.expect("Failed to create db");
+    db.insert_companies(vec![
+        smes_company("1071180", Some("5632000760"), "루키게임즈", "김성국"),
+        smes_company("1071181", None, "(주)한빛소프트", "김기영"),
+        smes_company("1071182", None, "무프로필", "박민수"),
+    ])
+    .await
+    .expect("Failed to insert smes companies");
+    db.insert_company_ids(vec![
+        company_id("00000001", "루키게임즈"),
+        company_id("00000002", "주식회사 한빛소프트"),
+        company_id("00000003", "무프로필"),
+    ])
+    .await
+    .expect("Failed to insert company ids");
+    // 00000003 has no profile, so only its name is known
+    db.upsert_company_profiles(vec![
+        profile("00000001", Some("5632000760"), "김성국"),
+        profile("00000002", None, "김 기영"),
+    ])
+    .await
+    .expect("Failed to upsert company profiles");
+    // endregion: Arrange
+
+    // region: Action
+    let link_count = link_companies(&mut db)
+        .await
+        .expect("Failed to link companies");
+    // endregion: Action
+
+    // region: Assert
+    let mut links: Vec<_> = db
+        .get_company_links()
+        .await
+        .expect("Failed to get company links")
+        .into_iter()
+        .map(|link| {
+            (
+                link.smes_id.as_ref().to_string(),
+                link.dart_id.as_ref().to_string(),
+                link.match_method,
+            )
+        })
+        .collect();
+    links.sort_by(|a, b| a.0.cmp(&b.0));
+
+    assert_eq!(link_count, 2);
+    assert_eq!(
+        links,
+        vec![
+            (
+                "1071180".to_string(),
+                "00000001".to_string(),
+                MatchMethod::BusinessRegistrationNumber
+            ),
+            (
+                "1071181".to_string(),
+                "00000002".to_string(),
+                MatchMethod::NameAndRepresentative
+            ),
+        ]
+    );
+    // endregion: Assert
+}
@@ -3,6 +3,7 @@ use cookie::CookieJar;
 use db::model::smes::NewHtml;
 use image::DynamicImage;
 use serde::{Deserialize, Serialize};
+use types::company::BusinessRegistrationNumber;
 
 // region: Captcha
 /// Represents a captcha which could be in the following three `State`s:
@@ -133,11 +134,29 @@ impl TryFrom<Company> for db::model::smes::NewCompany {
     type Error = SmesError;
 
     fn try_from(value: Company) -> Result<Self, Self::Error> {
+        // SMES provides empty strings for companies without a business registration number,
+        // and some numbers with invalid check digits.
+        // The latter are kept as is, rather than failing the whole list.
+        let business_registration_number = match value.bizrno.as_str() {
+            "" => None,
+            bizrno => {
+                let number = BusinessRegistrationNumber::try_new_without_checksum(bizrno)?;
+                if !number.has_valid_checksum() {
+                    tracing::warn!(
+                        vnia_sn = value.vnia_sn,
+                        bizrno,
+                        "Business registration number has an invalid check digit"
+                    );
+                }
+                Some(number)
+            }
+        };
+
         Ok(db::model::smes::NewCompany {
             smes_id: value.vnia_sn.to_string().as_str().try_into()?,
             representative_name: value.rprsv_nm.into(),
             headquarters_address: value.hdofc_addr.into(),
-            business_registration_number: value.bizrno.as_str().try_into()?,
+            business_registration_number,
             company_name: value.cmp_nm.into(),
             industry_code: value.indsty_cd.as_str().try_into()?,
             industry_name: value.indsty_nm.into(),
@@ -161,3 +180,35 @@ impl TryFrom<Html> for NewHtml {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn company(bizrno: &str) -> Company {
+        Company {
+            vnia_sn: 1071180,
+            rprsv_nm: "김성국".to_string(),
+            hdofc_addr: "경기도 김포시".to_string(),
+            bizrno: bizrno.to_string(),
+            cmp_nm: "루키게임즈".to_string(),
+            indsty_cd: "63999".to_string(),
+            indsty_nm: "그 외 기타 정보 서비스업".to_string(),
+        }
+    }
+
+    #[test]
+    fn empty_bizrno_should_convert_to_none() {
+        let company: db::model::smes::NewCompany = company("").try_into().unwrap();
+        assert_eq!(company.business_registration_number, None);
+    }
+
+    #[test]
+    fn bizrno_should_convert_to_some() {
+        let company: db::model::smes::NewCompany = company("5632000760").try_into().unwrap();
+        assert_eq!(
+            company.business_registration_number,
+            Some("5632000760".try_into().unwrap())
+        );
+    }
+}
@@ -99,10 +99,7 @@ macro_rules! text {
 pub(crate) use text;
 
 macro_rules! digits {
-    ($name:ident, $allow_empty:expr, $digits:expr) => {
-        digits!($name, $allow_empty, $digits, {});
-    };
-    ($name:ident, $allow_empty:expr, $digits:expr, {$(#[$doc:meta])*}) => {
+    (@base $name:ident, $allow_empty:expr, $digits:expr, {$(#[$doc:meta])*}) => {
         $(#[$doc])*
         #[derive(
             std::fmt::Debug,
@@ -126,10 +123,10 @@ macro_rules! digits {
         pub struct $name(String);
 
         impl $name {
-            pub fn try_new(value: &str) -> Result<Self, $crate::error::TypeError> {
+            fn validate_digits(value: &str) -> Result<(), $crate::error::TypeError> {
                 if value.is_empty() {
                     if $allow_empty {
-                        return Ok(Self(value.to_string()));
+                        return Ok(());
                     } else {
                         return Err($crate::error::ValidationError {
                             value: value.to_string(),
@@ -139,7 +136,7 @@ macro_rules! digits {
                 };
 
                 if value.len() == $digits && value.chars().all(|c| c.is_ascii_digit()) {
-                    Ok(Self(value.to_string()))
+                    Ok(())
                 } else {
                     Err($crate::error::ValidationError {
                         value: value.to_string(),
@@ -161,5 +158,49 @@ macro_rules! digits {
             }
         }
     };
+    ($name:ident, $allow_empty:expr, $digits:expr) => {
+        digits!($name, $allow_empty, $digits, {});
+    };
+    ($name:ident, $allow_empty:expr, $digits:expr, {$(#[$doc:meta])*}) => {
+        digits!(@base $name, $allow_empty, $digits, {$(#[$doc])*});
+
+        impl $name {
+            pub fn try_new(value: &str) -> Result<Self, $crate::error::TypeError> {
+                Self::validate_digits(value)?;
+                Ok(Self(value.to_string()))
+            }
+        }
+    };
+    // `$checksum` is a `fn(&str) -> bool`, which receives a value with the exact number of digits.
+    ($name:ident, $allow_empty:expr, $digits:expr, checksum = $checksum:path, {$(#[$doc:meta])*}) => {
+        digits!(@base $name, $allow_empty, $digits, {$(#[$doc])*});
+
+        impl $name {
+            /// Validates both the number of digits and the check digit.
+            pub fn try_new(value: &str) -> Result<Self, $crate::error::TypeError> {
+                Self::validate_digits(value)?;
+
+                if !value.is_empty() && !$checksum(value) {
+                    return Err($crate::error::ValidationError {
+                        value: value.to_string(),
+                        message: concat!(stringify!($name), " has an invalid check digit").to_string(),
+                    })?;
+                }
+                Ok(Self(value.to_string()))
+            }
+
+            /// Validates only the number of digits, skipping the check digit.
+            ///
+            /// Use this for source data which is known to contain invalid check digits.
+            pub fn try_new_without_checksum(value: &str) -> Result<Self, $crate::error::TypeError> {
+                Self::validate_digits(value)?;
+                Ok(Self(value.to_string()))
+            }
+
+            pub fn has_valid_checksum(&self) -> bool {
+                !self.0.is_empty() && $checksum(&self.0)
+            }
+        }
+    };
 }
 pub(crate) use digits;
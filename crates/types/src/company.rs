@@ -1,17 +1,27 @@
-use crate::base::{digits, non_empty_text};
+use crate::base::{digits, non_empty_text, text};
+use crate::name;
 
 // region: Digits
 
-digits!(BusinessRegistrationNumber, true, 10, {
+digits!(BusinessRegistrationNumber, false, 10, checksum = is_valid_business_registration_number, {
     /// ## 사업자번호
     ///
-    /// This field is a 10-digit number.
-    /// It also allows empty strings, since the website provides empty strings for some companies.
+    /// This field is a 10-digit number, `XXX-XX-XXXXX`.
+    /// - 1-3: 세무서 코드
+    /// - 4-5: 개인/법인 구분코드, see [`BusinessType`]
+    /// - 6-9: 일련번호
+    /// - 10: 검증번호
+    ///
+    /// Some companies don't have one, which should be represented as `None`.
 });
-digits!(CorporationRegistrationNumber, false, 13, {
+digits!(CorporationRegistrationNumber, false, 13, checksum = is_valid_corporation_registration_number, {
     /// ## 법인등록번호
     ///
-    /// This is a 13-digit number.
+    /// This is a 13-digit number, `XXXXXX-XXXXXXX`.
+    /// - 1-4: 등기관서 코드
+    /// - 5-6: 법인종류 코드, see [`CorporationKind`]
+    /// - 7-12: 일련번호
+    /// - 13: 검증번호
 });
 digits!(DartId, false, 8);
 digits!(IndustryCode, false, 5, {
@@ -52,12 +62,225 @@ non_empty_text!(IndustryName, {
 non_empty_text!(Name, {
     /// ## 기업명
 });
+text!(NormalizedName, {
+    /// ## 정규화된 기업명
+    ///
+    /// A [`Name`] without legal-form markers, whitespace and full-width characters,
+    /// to be used as a key for matching and search.
+    /// Could be empty when the name only consists of a legal-form marker.
+});
 non_empty_text!(RepresentativeName, {
     /// ## 대표자명
 });
 
 // endregion: Text
 
+// region: Normalized name
+
+impl Name {
+    pub fn normalized(&self) -> NormalizedName {
+        NormalizedName::from_name(self.0.as_str())
+    }
+}
+
+impl NormalizedName {
+    /// Normalize a raw company name, such as a search term.
+    pub fn from_name(name: &str) -> Self {
+        Self(name::normalize_company_name(name))
+    }
+
+    /// The name decomposed into jamo, see [`name::jamo_key`].
+    pub fn jamo_key(&self) -> String {
+        name::jamo_key(&self.0)
+    }
+
+    /// The name romanized jamo by jamo, see [`name::romanized_key`].
+    pub fn romanized_key(&self) -> String {
+        name::romanized_key(&self.0)
+    }
+}
+
+// endregion: Normalized name
+
+// region: Registration numbers
+
+fn digit_values(value: &str) -> Vec<u32> {
+    value.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+/// The check digit of a 사업자번호 is derived from the first nine digits,
+/// weighted by `1, 3, 7, 1, 3, 7, 1, 3, 5`,
+/// with the carry of the ninth digit times five added to the sum.
+fn is_valid_business_registration_number(value: &str) -> bool {
+    const WEIGHTS: [u32; 9] = [1, 3, 7, 1, 3, 7, 1, 3, 5];
+
+    let digits = digit_values(value);
+    if digits.len() != 10 {
+        return false;
+    }
+
+    let sum = digits.iter().zip(WEIGHTS).map(|(d, w)| d * w).sum::<u32>() + digits[8] * 5 / 10;
+    (10 - sum % 10) % 10 == digits[9]
+}
+
+/// The check digit of a 법인등록번호 is derived from the first twelve digits,
+/// weighted alternately by `1` and `2`.
+fn is_valid_corporation_registration_number(value: &str) -> bool {
+    let digits = digit_values(value);
+    if digits.len() != 13 {
+        return false;
+    }
+
+    let sum = digits[..12]
+        .iter()
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { *d } else { d * 2 })
+        .sum::<u32>();
+    (10 - sum % 10) % 10 == digits[12]
+}
+
+/// ## 개인/법인 구분코드
+///
+/// The 4th and 5th digits of a [`BusinessRegistrationNumber`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum BusinessType {
+    /// 01-79: 개인 과세사업자
+    IndividualTaxable,
+    /// 80: 아파트관리사무소, 다단계판매원 등
+    ApartmentOrDirectSales,
+    /// 81, 86, 87, 88: 영리법인 본점
+    ForProfitHeadOffice,
+    /// 82: 비영리법인 본점 및 지점
+    NonProfit,
+    /// 83: 국가, 지방자치단체, 지방자치단체조합
+    Government,
+    /// 84: 외국법인 본·지점 및 연락사무소
+    Foreign,
+    /// 85: 영리법인 지점
+    ForProfitBranch,
+    /// 89: 법인이 아닌 종교단체
+    ReligiousOrganization,
+    /// 90-99: 개인 면세사업자
+    IndividualTaxExempt,
+    /// Codes which are not assigned, such as 00
+    Unknown,
+}
+
+impl BusinessType {
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            1..=79 => Self::IndividualTaxable,
+            80 => Self::ApartmentOrDirectSales,
+            81 | 86 | 87 | 88 => Self::ForProfitHeadOffice,
+            82 => Self::NonProfit,
+            83 => Self::Government,
+            84 => Self::Foreign,
+            85 => Self::ForProfitBranch,
+            89 => Self::ReligiousOrganization,
+            90..=99 => Self::IndividualTaxExempt,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn is_corporation(&self) -> bool {
+        matches!(
+            self,
+            Self::ForProfitHeadOffice | Self::ForProfitBranch | Self::NonProfit | Self::Foreign
+        )
+    }
+}
+
+impl BusinessRegistrationNumber {
+    /// `123-45-67890`
+    ///
+    /// The accessors return `None` for values which aren't 10 digits,
+    /// which the unchecked conversions, e.g. from a database row, don't rule out.
+    pub fn formatted(&self) -> Option<String> {
+        let digits = self.digits()?;
+        Some(format!(
+            "{}-{}-{}",
+            &digits[..3],
+            &digits[3..5],
+            &digits[5..]
+        ))
+    }
+
+    /// 세무서 코드
+    pub fn tax_office_code(&self) -> Option<&str> {
+        Some(&self.digits()?[..3])
+    }
+
+    pub fn business_type(&self) -> Option<BusinessType> {
+        Some(BusinessType::from_code(self.digits()?[3..5].parse().ok()?))
+    }
+
+    fn digits(&self) -> Option<&str> {
+        (self.0.len() == 10 && self.0.chars().all(|c| c.is_ascii_digit())).then_some(&self.0)
+    }
+}
+
+/// ## 법인종류 코드
+///
+/// The 5th and 6th digits of a [`CorporationRegistrationNumber`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CorporationKind {
+    /// 11: 주식회사
+    StockCompany,
+    /// 12: 합명회사
+    GeneralPartnership,
+    /// 13: 합자회사
+    LimitedPartnership,
+    /// 14: 유한회사
+    LimitedCompany,
+    /// 21: 사단법인
+    IncorporatedAssociation,
+    /// 22: 재단법인
+    IncorporatedFoundation,
+    Other(u8),
+}
+
+impl CorporationKind {
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            11 => Self::StockCompany,
+            12 => Self::GeneralPartnership,
+            13 => Self::LimitedPartnership,
+            14 => Self::LimitedCompany,
+            21 => Self::IncorporatedAssociation,
+            22 => Self::IncorporatedFoundation,
+            code => Self::Other(code),
+        }
+    }
+}
+
+impl CorporationRegistrationNumber {
+    /// `110111-1234567`
+    ///
+    /// The accessors return `None` for values which aren't 13 digits,
+    /// which the unchecked conversions, e.g. from a database row, don't rule out.
+    pub fn formatted(&self) -> Option<String> {
+        let digits = self.digits()?;
+        Some(format!("{}-{}", &digits[..6], &digits[6..]))
+    }
+
+    /// 등기관서 코드
+    pub fn registry_office_code(&self) -> Option<&str> {
+        Some(&self.digits()?[..4])
+    }
+
+    pub fn corporation_kind(&self) -> Option<CorporationKind> {
+        Some(CorporationKind::from_code(
+            self.digits()?[4..6].parse().ok()?,
+        ))
+    }
+
+    fn digits(&self) -> Option<&str> {
+        (self.0.len() == 13 && self.0.chars().all(|c| c.is_ascii_digit())).then_some(&self.0)
+    }
+}
+
+// endregion: Registration numbers
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,17 +293,78 @@ mod tests {
         assert!(SmesId::try_new("123456a").is_err())
     }
 
+    #[test]
+    fn name_should_be_normalized() {
+        let name = Name::try_new("주식회사 루키게임즈").unwrap();
+        let normalized = name.normalized();
+        assert_eq!(normalized, NormalizedName::from_name("루키게임즈(주)"));
+        assert_eq!(normalized.romanized_key(), "rukigeimjeu");
+    }
+
     #[test]
     fn business_registration_number_should_be_ten_digits() {
-        assert!(BusinessRegistrationNumber::try_from("1234567890").is_ok());
+        assert!(BusinessRegistrationNumber::try_from("1234567891").is_ok());
         assert!(BusinessRegistrationNumber::try_from("123456789").is_err());
-        assert!(BusinessRegistrationNumber::try_new("1234567890").is_ok());
+        assert!(BusinessRegistrationNumber::try_new("1234567891").is_ok());
         assert!(BusinessRegistrationNumber::try_new("123456789").is_err());
     }
 
     #[test]
-    fn business_registration_number_should_allow_empty_string() {
-        assert!(BusinessRegistrationNumber::try_from("").is_ok());
-        assert!(BusinessRegistrationNumber::try_new("").is_ok());
+    fn business_registration_number_should_validate_check_digit() {
+        assert!(BusinessRegistrationNumber::try_new("5632000760").is_ok());
+        assert!(BusinessRegistrationNumber::try_new("5632000761").is_err());
+
+        let unchecked = BusinessRegistrationNumber::try_new_without_checksum("5632000761")
+            .expect("check digit should be skipped");
+        assert!(!unchecked.has_valid_checksum());
+        assert!(BusinessRegistrationNumber::try_new_without_checksum("563200076").is_err());
+    }
+
+    #[test]
+    fn business_registration_number_should_be_decoded() {
+        let number = BusinessRegistrationNumber::try_new("5632000760").unwrap();
+        assert_eq!(number.formatted().as_deref(), Some("563-20-00760"));
+        assert_eq!(number.tax_office_code(), Some("563"));
+        assert_eq!(
+            number.business_type(),
+            Some(BusinessType::IndividualTaxable)
+        );
+    }
+
+    #[test]
+    fn unchecked_registration_numbers_should_not_be_decoded() {
+        let number = BusinessRegistrationNumber::from("56320".to_string());
+        assert_eq!(number.formatted(), None);
+        assert_eq!(number.tax_office_code(), None);
+        assert_eq!(number.business_type(), None);
+
+        let number = CorporationRegistrationNumber::from("17011a0006868".to_string());
+        assert_eq!(number.formatted(), None);
+        assert_eq!(number.registry_office_code(), None);
+        assert_eq!(number.corporation_kind(), None);
+    }
+
+    #[test]
+    fn corporation_registration_number_should_validate_check_digit() {
+        assert!(CorporationRegistrationNumber::try_new("1701110006868").is_ok());
+        assert!(CorporationRegistrationNumber::try_new("1701110006869").is_err());
+        assert!(CorporationRegistrationNumber::try_new_without_checksum("1701110006869").is_ok());
+    }
+
+    #[test]
+    fn corporation_registration_number_should_be_decoded() {
+        let number = CorporationRegistrationNumber::try_new("1701110006868").unwrap();
+        assert_eq!(number.formatted().as_deref(), Some("170111-0006868"));
+        assert_eq!(number.registry_office_code(), Some("1701"));
+        assert_eq!(
+            number.corporation_kind(),
+            Some(CorporationKind::StockCompany)
+        );
+    }
+
+    #[test]
+    fn business_registration_number_should_not_allow_empty_string() {
+        assert!(BusinessRegistrationNumber::try_from("").is_err());
+        assert!(BusinessRegistrationNumber::try_new("").is_err());
     }
 }
@@ -33,6 +33,139 @@ non_empty_text!(ReportName, {
     /// - \[정정제출요구\] : 본 보고서에 대하여 금융감독원이 정정제출요구을 부과한 것임
 });
 
+impl Remark {
+    /// The markers of the remark, ignoring characters which aren't one.
+    pub fn flags(&self) -> RemarkFlags {
+        let mut flags = RemarkFlags::default();
+        for c in self.0.chars() {
+            match c {
+                '유' => flags.market = Some(Market::Kospi),
+                '코' => flags.market = Some(Market::Kosdaq),
+                '채' => flags.market = Some(Market::Bond),
+                '넥' => flags.market = Some(Market::Konex),
+                '공' => flags.fair_trade_commission = true,
+                '연' => flags.consolidated = true,
+                '정' => flags.corrected = true,
+                '철' => flags.withdrawn = true,
+                _ => {}
+            }
+        }
+        flags
+    }
+}
+
+/// The markers of a [`Remark`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct RemarkFlags {
+    /// 유, 코, 채, 넥
+    pub market: Option<Market>,
+    /// 공: 공정거래위원회 소관
+    pub fair_trade_commission: bool,
+    /// 연: 연결부분을 포함
+    pub consolidated: bool,
+    /// 정: 제출 후 정정신고가 있음, so the filing is superseded by its correction
+    pub corrected: bool,
+    /// 철: 철회(간주)됨
+    pub withdrawn: bool,
+}
+
+/// ## 소관 시장
+///
+/// The division of the Korea Exchange a filing belongs to, marked in its [`Remark`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Market {
+    /// 유: 유가증권시장본부
+    Kospi,
+    /// 코: 코스닥시장본부
+    Kosdaq,
+    /// 넥: 코넥스시장
+    Konex,
+    /// 채: 채권상장법인
+    Bond,
+}
+
+impl ReportName {
+    /// The kind of the amendment, when the name starts with one of its markers.
+    pub fn amendment_kind(&self) -> Option<AmendmentKind> {
+        self.split_amendment().0
+    }
+
+    /// The name without its amendment marker,
+    /// which is the name of the report the amendment amends.
+    ///
+    /// `[기재정정]사업보고서 (2023.12)` becomes `사업보고서 (2023.12)`.
+    pub fn base_name(&self) -> &str {
+        self.split_amendment().1
+    }
+
+    fn split_amendment(&self) -> (Option<AmendmentKind>, &str) {
+        let name = self.0.trim();
+        AmendmentKind::ALL
+            .iter()
+            .find_map(|&kind| {
+                name.strip_prefix(kind.marker())
+                    .map(|base_name| (Some(kind), base_name.trim_start()))
+            })
+            .unwrap_or((None, name))
+    }
+}
+
+/// The marker a [`ReportName`] starts with, when the report amends another one.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum AmendmentKind {
+    /// \[기재정정\]
+    Correction,
+    /// \[첨부정정\]
+    AttachmentCorrection,
+    /// \[첨부추가\]
+    AttachmentAddition,
+    /// \[변경등록\]
+    RegistrationChange,
+    /// \[연장결정\]
+    Extension,
+    /// \[발행조건확정\]
+    IssueTermsConfirmation,
+    /// \[정정명령부과\]
+    CorrectionOrder,
+    /// \[정정제출요구\]
+    CorrectionRequest,
+}
+
+impl AmendmentKind {
+    pub const ALL: [AmendmentKind; 8] = [
+        Self::Correction,
+        Self::AttachmentCorrection,
+        Self::AttachmentAddition,
+        Self::RegistrationChange,
+        Self::Extension,
+        Self::IssueTermsConfirmation,
+        Self::CorrectionOrder,
+        Self::CorrectionRequest,
+    ];
+
+    /// The marker, including its brackets.
+    pub fn marker(&self) -> &'static str {
+        match self {
+            Self::Correction => "[기재정정]",
+            Self::AttachmentCorrection => "[첨부정정]",
+            Self::AttachmentAddition => "[첨부추가]",
+            Self::RegistrationChange => "[변경등록]",
+            Self::Extension => "[연장결정]",
+            Self::IssueTermsConfirmation => "[발행조건확정]",
+            Self::CorrectionOrder => "[정정명령부과]",
+            Self::CorrectionRequest => "[정정제출요구]",
+        }
+    }
+
+    /// Whether the filer filed the report again, rather than the FSS ordering it to,
+    /// as `[정정명령부과]` and `[정정제출요구]` do.
+    pub fn is_refiling(&self) -> bool {
+        !matches!(self, Self::CorrectionOrder | Self::CorrectionRequest)
+    }
+}
+
+// endregion: Text
+
 // region: Digits
 
 digits!(ReceiptNumber, false, 14, {
@@ -48,3 +181,33 @@ digits!(ReceiptNumber, false, 14, {
 
 /// ## 공시 접수일자(YYYYMMDD)
 pub type ReceiptDate = date::YYYYMMDD;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remark_flags_should_be_parsed() {
+        let flags = Remark::new("유연정").flags();
+
+        assert_eq!(flags.market, Some(Market::Kospi));
+        assert!(flags.consolidated);
+        assert!(flags.corrected);
+        assert!(!flags.withdrawn);
+        assert!(!flags.fair_trade_commission);
+        assert_eq!(Remark::new("").flags(), RemarkFlags::default());
+    }
+
+    #[test]
+    fn report_name_should_split_amendment_marker() {
+        let amendment = ReportName::try_new("[기재정정]사업보고서 (2023.12)").unwrap();
+        let original = ReportName::try_new("사업보고서 (2023.12)").unwrap();
+        let unknown = ReportName::try_new("[기타]사업보고서 (2023.12)").unwrap();
+
+        assert_eq!(amendment.amendment_kind(), Some(AmendmentKind::Correction));
+        assert_eq!(amendment.base_name(), original.base_name());
+        assert_eq!(original.amendment_kind(), None);
+        assert_eq!(unknown.amendment_kind(), None);
+        assert_eq!(unknown.base_name(), "[기타]사업보고서 (2023.12)");
+    }
+}
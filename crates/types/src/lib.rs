@@ -10,6 +10,7 @@ pub mod company;
 pub mod date;
 mod error;
 pub mod filing;
+pub mod name;
 
 pub use date::YYYYMMDD;
 pub use error::TypeError;
@@ -0,0 +1,247 @@
+//! Normalization of Korean company names, for matching and search.
+//!
+//! Names such as "(주)루키게임즈", "주식회사 루키게임즈" and "루키게임즈(주)"
+//! all refer to the same entity, and are normalized to "루키게임즈".
+
+/// Legal-form markers which are stripped from company names.
+///
+/// Whitespace is removed before stripping, so the markers don't contain any.
+/// Longer markers come first, so they are stripped before the markers they contain.
+const LEGAL_FORM_MARKERS: [&str; 19] = [
+    "유한책임회사",
+    "주식회사",
+    "유한회사",
+    "합자회사",
+    "합명회사",
+    "사단법인",
+    "재단법인",
+    "협동조합",
+    "(주)",
+    "(유)",
+    "(합)",
+    "(사)",
+    "(재)",
+    "(株)",
+    "㈜",
+    "㈱",
+    "㈲",
+    "㈳",
+    "㈶",
+];
+
+/// Latin legal-form markers, in lowercase, which are stripped only as words of their own,
+/// so `inc.` is kept in e.g. `zinc.`.
+///
+/// Whitespace within a marker is skipped, so `co.,ltd.` also matches `Co., Ltd.`.
+/// Longer markers come first, so they are stripped before the markers they start with.
+const LATIN_LEGAL_FORM_MARKERS: [&str; 5] = ["co.,ltd.", "co.,ltd", "corp.", "inc.", "ltd."];
+
+// region: Hangul
+
+const HANGUL_SYLLABLES_START: u32 = 0xAC00;
+const HANGUL_SYLLABLES_END: u32 = 0xD7A3;
+const JUNGSEONG_COUNT: u32 = 21;
+const JONGSEONG_COUNT: u32 = 28;
+
+const CHOSEONG_JAMO: [char; 19] = [
+    'ㄱ', 'ㄲ', 'ㄴ', 'ㄷ', 'ㄸ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅃ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅉ', 'ㅊ', 'ㅋ',
+    'ㅌ', 'ㅍ', 'ㅎ',
+];
+const JUNGSEONG_JAMO: [char; 21] = [
+    'ㅏ', 'ㅐ', 'ㅑ', 'ㅒ', 'ㅓ', 'ㅔ', 'ㅕ', 'ㅖ', 'ㅗ', 'ㅘ', 'ㅙ', 'ㅚ', 'ㅛ', 'ㅜ', 'ㅝ', 'ㅞ',
+    'ㅟ', 'ㅠ', 'ㅡ', 'ㅢ', 'ㅣ',
+];
+/// The first entry is for syllables without a final consonant.
+const JONGSEONG_JAMO: [&str; 28] = [
+    "", "ㄱ", "ㄲ", "ㄳ", "ㄴ", "ㄵ", "ㄶ", "ㄷ", "ㄹ", "ㄺ", "ㄻ", "ㄼ", "ㄽ", "ㄾ", "ㄿ", "ㅀ",
+    "ㅁ", "ㅂ", "ㅄ", "ㅅ", "ㅆ", "ㅇ", "ㅈ", "ㅊ", "ㅋ", "ㅌ", "ㅍ", "ㅎ",
+];
+
+const CHOSEONG_ROMAN: [&str; 19] = [
+    "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t", "p",
+    "h",
+];
+const JUNGSEONG_ROMAN: [&str; 21] = [
+    "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we",
+    "wi", "yu", "eu", "ui", "i",
+];
+const JONGSEONG_ROMAN: [&str; 28] = [
+    "", "k", "k", "k", "n", "n", "n", "t", "l", "k", "m", "l", "l", "l", "p", "l", "m", "p", "p",
+    "t", "t", "ng", "t", "t", "k", "t", "p", "t",
+];
+
+/// Indices of the initial, medial and final jamo of a Hangul syllable.
+fn decompose(c: char) -> Option<(usize, usize, usize)> {
+    let code = c as u32;
+    if !(HANGUL_SYLLABLES_START..=HANGUL_SYLLABLES_END).contains(&code) {
+        return None;
+    }
+
+    let index = code - HANGUL_SYLLABLES_START;
+    let choseong = index / (JUNGSEONG_COUNT * JONGSEONG_COUNT);
+    let jungseong = (index % (JUNGSEONG_COUNT * JONGSEONG_COUNT)) / JONGSEONG_COUNT;
+    let jongseong = index % JONGSEONG_COUNT;
+    Some((choseong as usize, jungseong as usize, jongseong as usize))
+}
+
+// endregion: Hangul
+
+/// Convert full-width forms (e.g. `（`, `Ａ`) and the ideographic space to their ASCII counterparts.
+fn to_half_width(c: char) -> char {
+    match c {
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        '\u{3000}' => ' ',
+        c => c,
+    }
+}
+
+/// Normalize a company name for matching:
+/// 1. Full-width characters are converted to half-width and ASCII letters are lowercased
+/// 2. Latin legal-form markers such as `Co., Ltd.` and `Inc.` are removed as words of their own
+/// 3. Whitespace is removed
+/// 4. Legal-form markers such as `주식회사`, `(주)` and `㈜` are removed
+pub fn normalize_company_name(name: &str) -> String {
+    let name: Vec<char> = name
+        .chars()
+        .map(to_half_width)
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    let mut stripped = String::with_capacity(name.len());
+    let mut i = 0;
+    while i < name.len() {
+        let starts_word = i == 0 || !name[i - 1].is_ascii_alphanumeric();
+        let marker_end = starts_word
+            .then(|| {
+                LATIN_LEGAL_FORM_MARKERS
+                    .iter()
+                    .find_map(|marker| latin_marker_end(&name, i, marker))
+            })
+            .flatten();
+        match marker_end {
+            Some(end) => i = end,
+            None => {
+                if !name[i].is_whitespace() {
+                    stripped.push(name[i]);
+                }
+                i += 1;
+            }
+        }
+    }
+
+    for marker in LEGAL_FORM_MARKERS {
+        stripped = stripped.replace(marker, "");
+    }
+    stripped
+}
+
+/// Where `marker` ends when it starts at `start` of `name` and is a word of its own,
+/// skipping whitespace within `name`.
+fn latin_marker_end(name: &[char], start: usize, marker: &str) -> Option<usize> {
+    let mut i = start;
+    for expected in marker.chars() {
+        while i > start && name.get(i).is_some_and(|c| c.is_whitespace()) {
+            i += 1;
+        }
+        if name.get(i) != Some(&expected) {
+            return None;
+        }
+        i += 1;
+    }
+    let ends_word = name.get(i).is_none_or(|c| !c.is_ascii_alphanumeric());
+    ends_word.then_some(i)
+}
+
+/// Decompose Hangul syllables into compatibility jamo, e.g. `게임` to `ㄱㅔㅇㅣㅁ`.
+///
+/// Other characters are kept as is.
+pub fn jamo_key(value: &str) -> String {
+    let mut key = String::with_capacity(value.len() * 3);
+    for c in value.chars() {
+        match decompose(c) {
+            Some((choseong, jungseong, jongseong)) => {
+                key.push(CHOSEONG_JAMO[choseong]);
+                key.push(JUNGSEONG_JAMO[jungseong]);
+                key.push_str(JONGSEONG_JAMO[jongseong]);
+            }
+            None => key.push(c),
+        }
+    }
+    key
+}
+
+/// Romanize Hangul syllables jamo by jamo, e.g. `게임` to `geim`.
+///
+/// This roughly follows the Revised Romanization of Korean,
+/// but ignores the sound change rules between syllables,
+/// so it is only meant to be used as a search key.
+/// Other characters are kept as is.
+pub fn romanized_key(value: &str) -> String {
+    let mut key = String::with_capacity(value.len() * 2);
+    for c in value.chars() {
+        match decompose(c) {
+            Some((choseong, jungseong, jongseong)) => {
+                key.push_str(CHOSEONG_ROMAN[choseong]);
+                key.push_str(JUNGSEONG_ROMAN[jungseong]);
+                key.push_str(JONGSEONG_ROMAN[jongseong]);
+            }
+            None => key.push(c),
+        }
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legal_form_markers_should_be_removed() {
+        for name in [
+            "(주)루키게임즈",
+            "주식회사 루키게임즈",
+            "루키게임즈(주)",
+            "루키게임즈 주식회사",
+            "㈜루키게임즈",
+            "（주）루키게임즈",
+            "( 주 ) 루키 게임즈",
+        ] {
+            assert_eq!(normalize_company_name(name), "루키게임즈", "{}", name);
+        }
+    }
+
+    #[test]
+    fn full_width_and_case_should_be_normalized() {
+        assert_eq!(normalize_company_name("ＡＢＣ　Co., Ltd."), "abc");
+        assert_eq!(normalize_company_name("유한회사 에이비씨"), "에이비씨");
+    }
+
+    #[test]
+    fn latin_markers_should_be_removed_as_words_only() {
+        for (name, normalized) in [
+            ("Samsung Electronics Co.,Ltd", "samsungelectronics"),
+            ("ABC Corp.", "abc"),
+            ("ABC Inc.", "abc"),
+            ("ABC INC.", "abc"),
+            ("(주)ABC Ltd.", "abc"),
+            ("Zinc.Korea", "zinc.korea"),
+            ("Zinc. Korea", "zinc.korea"),
+            ("Bigcorp.", "bigcorp."),
+            ("Inc.Tech", "inc.tech"),
+        ] {
+            assert_eq!(normalize_company_name(name), normalized, "{}", name);
+        }
+    }
+
+    #[test]
+    fn jamo_key_should_decompose_syllables() {
+        assert_eq!(jamo_key("루키게임즈"), "ㄹㅜㅋㅣㄱㅔㅇㅣㅁㅈㅡ");
+        assert_eq!(jamo_key("abc1"), "abc1");
+    }
+
+    #[test]
+    fn romanized_key_should_romanize_syllables() {
+        assert_eq!(romanized_key("루키게임즈"), "rukigeimjeu");
+        assert_eq!(romanized_key("삼성전자"), "samseongjeonja");
+    }
+}
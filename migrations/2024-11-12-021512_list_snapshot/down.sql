@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE smes.company
+    DROP COLUMN delisted_at,
+    DROP COLUMN last_seen_at;
+
+DROP TABLE smes.list_snapshot_company;
+DROP TABLE smes.list_snapshot;
@@ -0,0 +1,20 @@
+-- Your SQL goes here
+CREATE TABLE smes.list_snapshot
+(
+    snapshot_id   SERIAL PRIMARY KEY,
+    company_count INTEGER   NOT NULL CHECK (company_count >= 0),
+    created_at    TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE smes.list_snapshot_company
+(
+    snapshot_id INTEGER NOT NULL,
+    smes_id     TEXT    NOT NULL CHECK (smes_id ~ '^[0-9]{7}$'),
+    PRIMARY KEY (snapshot_id, smes_id),
+    FOREIGN KEY (snapshot_id) REFERENCES smes.list_snapshot (snapshot_id) ON DELETE CASCADE,
+    FOREIGN KEY (smes_id) REFERENCES smes.company (smes_id) ON DELETE RESTRICT ON UPDATE CASCADE
+);
+
+ALTER TABLE smes.company
+    ADD COLUMN last_seen_at TIMESTAMP,
+    ADD COLUMN delisted_at  TIMESTAMP;
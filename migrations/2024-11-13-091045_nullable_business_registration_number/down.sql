@@ -0,0 +1,14 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE smes.company
+    DROP CONSTRAINT company_business_registration_number_check;
+
+UPDATE smes.company
+SET business_registration_number = ''
+WHERE business_registration_number IS NULL;
+
+ALTER TABLE smes.company
+    ALTER COLUMN business_registration_number SET NOT NULL,
+    ADD CONSTRAINT company_business_registration_number_check CHECK (
+        business_registration_number = '' OR
+        business_registration_number ~ '^[0-9]{10}$'
+        );
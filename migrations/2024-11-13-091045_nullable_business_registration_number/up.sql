@@ -0,0 +1,12 @@
+-- Your SQL goes here
+ALTER TABLE smes.company
+    DROP CONSTRAINT company_business_registration_number_check,
+    ALTER COLUMN business_registration_number DROP NOT NULL;
+
+UPDATE smes.company
+SET business_registration_number = NULL
+WHERE business_registration_number = '';
+
+ALTER TABLE smes.company
+    ADD CONSTRAINT company_business_registration_number_check
+        CHECK (business_registration_number ~ '^[0-9]{10}$');
@@ -0,0 +1,23 @@
+-- Your SQL goes here
+CREATE TABLE company_link
+(
+    smes_id                         TEXT             NOT NULL CHECK (smes_id ~ '^[0-9]{7}$'),
+    dart_id                         TEXT             NOT NULL CHECK (dart_id ~ '^[0-9]{8}$'),
+    business_registration_number    TEXT CHECK (business_registration_number ~ '^[0-9]{10}$'),
+    corporation_registration_number TEXT CHECK (corporation_registration_number ~ '^[0-9]{13}$'),
+    match_method                    TEXT             NOT NULL CHECK (match_method IN (
+                                                                                      'business_registration_number',
+                                                                                      'corporation_registration_number',
+                                                                                      'name_and_representative'
+        )),
+    confidence                      DOUBLE PRECISION NOT NULL CHECK (confidence > 0 AND confidence <= 1),
+    created_at                      TIMESTAMP        NOT NULL DEFAULT current_timestamp,
+    updated_at                      TIMESTAMP        NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (smes_id, dart_id),
+    FOREIGN KEY (smes_id) REFERENCES smes.company (smes_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (dart_id) REFERENCES dart.company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+SELECT diesel_manage_updated_at('company_link');
+
+CREATE INDEX company_link_dart_id_idx ON company_link (dart_id);
+CREATE INDEX company_link_corporation_registration_number_idx ON company_link (corporation_registration_number);
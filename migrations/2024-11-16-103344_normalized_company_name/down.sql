@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE dart.company_id
+    DROP COLUMN normalized_company_name;
+
+ALTER TABLE smes.company
+    DROP COLUMN normalized_company_name;
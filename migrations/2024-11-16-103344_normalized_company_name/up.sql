@@ -0,0 +1,28 @@
+-- Your SQL goes here
+
+-- Approximates `types::name::normalize_company_name` to backfill existing rows.
+-- The values are overwritten by the application on the next insert or upsert,
+-- and recomputed in Rust by `runners db migrate` right after the migrations.
+CREATE FUNCTION pg_temp.normalize_company_name(name TEXT) RETURNS TEXT AS
+$$
+SELECT regexp_replace(
+               lower(regexp_replace(translate(name, '（）　', '() '), '\s', '', 'g')),
+               '유한책임회사|주식회사|유한회사|합자회사|합명회사|사단법인|재단법인|협동조합|\((주|유|합|사|재|株)\)|㈜|㈱|㈲|㈳|㈶',
+               '', 'g')
+$$ LANGUAGE sql IMMUTABLE;
+
+ALTER TABLE smes.company
+    ADD COLUMN normalized_company_name TEXT;
+UPDATE smes.company
+SET normalized_company_name = pg_temp.normalize_company_name(company_name);
+ALTER TABLE smes.company
+    ALTER COLUMN normalized_company_name SET NOT NULL;
+CREATE INDEX company_normalized_company_name_idx ON smes.company (normalized_company_name);
+
+ALTER TABLE dart.company_id
+    ADD COLUMN normalized_company_name TEXT;
+UPDATE dart.company_id
+SET normalized_company_name = pg_temp.normalize_company_name(company_name);
+ALTER TABLE dart.company_id
+    ALTER COLUMN normalized_company_name SET NOT NULL;
+CREATE INDEX company_id_normalized_company_name_idx ON dart.company_id (normalized_company_name);
@@ -0,0 +1,33 @@
+-- This file should undo anything in `up.sql`
+
+-- Compressed bodies can't be restored in SQL,
+-- so reverting fails rather than dropping the pages of those companies.
+DO
+$$
+    BEGIN
+        IF EXISTS (SELECT
+                   FROM smes.html
+                            JOIN smes.html_revision USING (smes_id, sha256)
+                   WHERE substring(html_revision.body FROM 1 FOR 4) = '\x28b52ffd'::BYTEA) THEN
+            RAISE EXCEPTION 'smes.html_revision has compressed bodies, which can''t be restored in SQL';
+        END IF;
+    END
+$$;
+
+ALTER TABLE smes.html
+    DISABLE TRIGGER set_updated_at;
+ALTER TABLE smes.html
+    ADD COLUMN html_content TEXT;
+UPDATE smes.html
+SET html_content = convert_from(html_revision.body, 'UTF8')
+FROM smes.html_revision
+WHERE html_revision.smes_id = html.smes_id
+  AND html_revision.sha256 = html.sha256;
+ALTER TABLE smes.html
+    ENABLE TRIGGER set_updated_at;
+
+ALTER TABLE smes.html
+    ALTER COLUMN html_content SET NOT NULL,
+    DROP COLUMN sha256;
+
+DROP TABLE smes.html_revision;
@@ -0,0 +1,34 @@
+-- Your SQL goes here
+
+-- Every fetched page is kept once per company, addressed by the SHA-256 of its content.
+-- `fetched_at` is when the content last replaced a different page of the company.
+CREATE TABLE smes.html_revision
+(
+    smes_id    TEXT      NOT NULL CHECK (smes_id ~ '^[0-9]{7}$'),
+    sha256     TEXT      NOT NULL CHECK (sha256 ~ '^[0-9a-f]{64}$'),
+    fetched_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    -- zstd-compressed, the rows backfilled below are compressed by `PostgresDb::migrate`
+    body       BYTEA     NOT NULL,
+    PRIMARY KEY (smes_id, sha256),
+    FOREIGN KEY (smes_id) REFERENCES smes.company (smes_id) ON DELETE RESTRICT ON UPDATE CASCADE
+);
+
+INSERT INTO smes.html_revision (smes_id, sha256, fetched_at, body)
+SELECT smes_id, encode(sha256(convert_to(html_content, 'UTF8')), 'hex'), updated_at, convert_to(html_content, 'UTF8')
+FROM smes.html;
+
+-- `smes.html` points to the latest revision instead of carrying the page.
+-- The trigger is disabled so that the backfill doesn't touch `updated_at`.
+ALTER TABLE smes.html
+    DISABLE TRIGGER set_updated_at;
+ALTER TABLE smes.html
+    ADD COLUMN sha256 TEXT;
+UPDATE smes.html
+SET sha256 = encode(sha256(convert_to(html_content, 'UTF8')), 'hex');
+ALTER TABLE smes.html
+    ENABLE TRIGGER set_updated_at;
+
+ALTER TABLE smes.html
+    ALTER COLUMN sha256 SET NOT NULL,
+    ADD FOREIGN KEY (smes_id, sha256) REFERENCES smes.html_revision (smes_id, sha256),
+    DROP COLUMN html_content;
@@ -0,0 +1,13 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX dart.filing_dart_id_receipt_date_idx;
+
+-- Only the latest filing of each company fits the old key.
+DELETE
+FROM dart.filing a
+    USING dart.filing b
+WHERE a.dart_id = b.dart_id
+  AND (a.receipt_date, a.receipt_number) < (b.receipt_date, b.receipt_number);
+
+ALTER TABLE dart.filing
+    DROP CONSTRAINT filing_pkey,
+    ADD PRIMARY KEY (dart_id);
@@ -0,0 +1,14 @@
+-- A filing is identified by its receipt number, so a company can have any number of filings.
+-- Receipt numbers should already be unique, keep one row per number just in case.
+DELETE
+FROM dart.filing a
+    USING dart.filing b
+WHERE a.receipt_number = b.receipt_number
+  AND a.dart_id < b.dart_id;
+
+ALTER TABLE dart.filing
+    DROP CONSTRAINT filing_pkey,
+    ALTER COLUMN dart_id SET NOT NULL,
+    ADD PRIMARY KEY (receipt_number);
+
+CREATE INDEX filing_dart_id_receipt_date_idx ON dart.filing (dart_id, receipt_date);
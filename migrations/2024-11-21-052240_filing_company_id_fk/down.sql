@@ -0,0 +1,13 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE dart.filing
+    DROP CONSTRAINT filing_dart_id_fkey;
+
+-- Placeholders can't satisfy the old constraints.
+DELETE
+FROM dart.company_id
+WHERE stock_code IS NULL
+   OR id_modify_date IS NULL;
+
+ALTER TABLE dart.company_id
+    ALTER COLUMN stock_code SET NOT NULL,
+    ALTER COLUMN id_modify_date SET NOT NULL;
@@ -0,0 +1,26 @@
+-- Filings reference the company they belong to.
+-- Filings can arrive before the corp code list which contains their company,
+-- so a company id may be a placeholder without `stock_code` and `id_modify_date`,
+-- named after the filer until the next corp code list completes it.
+ALTER TABLE dart.company_id
+    ALTER COLUMN stock_code DROP NOT NULL,
+    ALTER COLUMN id_modify_date DROP NOT NULL;
+
+-- Copied from `2024-11-16-103344_normalized_company_name`, `pg_temp` functions don't outlive the session.
+CREATE FUNCTION pg_temp.normalize_company_name(name TEXT) RETURNS TEXT AS
+$$
+SELECT regexp_replace(
+               lower(regexp_replace(translate(name, '（）　', '() '), '\s', '', 'g')),
+               '유한책임회사|주식회사|유한회사|합자회사|합명회사|사단법인|재단법인|협동조합|\((주|유|합|사|재|株)\)|㈜|㈱|㈲|㈳|㈶',
+               '', 'g')
+$$ LANGUAGE sql IMMUTABLE;
+
+INSERT INTO dart.company_id (dart_id, company_name, normalized_company_name)
+SELECT DISTINCT ON (dart_id) dart_id, filer_name, pg_temp.normalize_company_name(filer_name)
+FROM dart.filing
+WHERE dart_id NOT IN (SELECT dart_id FROM dart.company_id)
+ORDER BY dart_id, receipt_date DESC, receipt_number DESC;
+
+ALTER TABLE dart.filing
+    ADD CONSTRAINT filing_dart_id_fkey FOREIGN KEY (dart_id)
+        REFERENCES dart.company_id (dart_id) ON DELETE RESTRICT ON UPDATE CASCADE;
@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE dart.filing
+    DROP COLUMN run_id;
+ALTER TABLE smes.html
+    DROP COLUMN run_id;
+ALTER TABLE smes.company
+    DROP COLUMN run_id;
+
+DROP TABLE ingest_run;
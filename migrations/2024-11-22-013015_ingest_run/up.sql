@@ -0,0 +1,28 @@
+-- A single run of a runner, so stored rows can be traced back to the run which last wrote them.
+CREATE TABLE ingest_run
+(
+    run_id          SERIAL PRIMARY KEY,
+    runner          TEXT      NOT NULL,
+    -- The git revision the runner was built from
+    git_rev         TEXT,
+    -- The settings the runner was started with, as the runner formats them
+    config          TEXT,
+    started_at      TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    -- The rest are set when the run finishes, and stay NULL for a run which didn't
+    finished_at     TIMESTAMP,
+    inserted_count  INTEGER CHECK (inserted_count >= 0),
+    updated_count   INTEGER CHECK (updated_count >= 0),
+    unchanged_count INTEGER CHECK (unchanged_count >= 0)
+);
+
+ALTER TABLE smes.company
+    ADD COLUMN run_id INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL;
+CREATE INDEX company_run_id_idx ON smes.company (run_id);
+
+ALTER TABLE smes.html
+    ADD COLUMN run_id INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL;
+CREATE INDEX html_run_id_idx ON smes.html (run_id);
+
+ALTER TABLE dart.filing
+    ADD COLUMN run_id INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL;
+CREATE INDEX filing_run_id_idx ON dart.filing (run_id);
@@ -0,0 +1,30 @@
+-- Accounts of the single-company financial statements of OpenDART (fnlttSinglAcntAll).
+-- A statement is identified by company, business year, report and whether it's consolidated (CFS) or separate (OFS),
+-- and an account within it by the statement kind, its order and its path of groups,
+-- like the groups and accounts of SMES tables.
+CREATE TABLE dart.financial_item
+(
+    dart_id                TEXT      NOT NULL CHECK (dart_id ~ '^[0-9]{8}$'),
+    business_year          INTEGER   NOT NULL CHECK (business_year BETWEEN 2015 AND 9999),
+    report_code            TEXT      NOT NULL CHECK (report_code IN ('11011', '11012', '11013', '11014')),
+    fs_div                 TEXT      NOT NULL CHECK (fs_div IN ('CFS', 'OFS')),
+    statement              TEXT      NOT NULL CHECK (statement IN ('BS', 'IS', 'CIS', 'CF', 'SCE')),
+    ord                    INTEGER   NOT NULL,
+    -- The `|`-separated groups of the account, '-' when it has none
+    account_detail         TEXT      NOT NULL,
+    -- The IFRS taxonomy id, or '-표준계정코드 미사용-' for accounts outside the taxonomy
+    account_id             TEXT      NOT NULL,
+    account_name           TEXT      NOT NULL,
+    current_amount         BIGINT,
+    previous_amount        BIGINT,
+    before_previous_amount BIGINT,
+    currency               TEXT      NOT NULL,
+    receipt_number         TEXT      NOT NULL CHECK (receipt_number ~ '^[0-9]{14}$'),
+    run_id                 INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    created_at             TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (dart_id, business_year, report_code, fs_div, statement, ord, account_detail),
+    FOREIGN KEY (dart_id) REFERENCES dart.company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX financial_item_account_id_idx ON dart.financial_item (account_id);
+CREATE INDEX financial_item_run_id_idx ON dart.financial_item (run_id);
@@ -0,0 +1,15 @@
+-- The document ZIP archive of a filing, as OpenDART serves it (document.xml).
+-- Archives are kept outside the database, addressed by the SHA-256 of their content,
+-- so the same archive is stored once however many times it is downloaded.
+CREATE TABLE dart.document
+(
+    receipt_number TEXT PRIMARY KEY CHECK (receipt_number ~ '^[0-9]{14}$'),
+    sha256         TEXT      NOT NULL CHECK (sha256 ~ '^[0-9a-f]{64}$'),
+    byte_size      BIGINT    NOT NULL CHECK (byte_size >= 0),
+    run_id         INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    downloaded_at  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    FOREIGN KEY (receipt_number) REFERENCES dart.filing (receipt_number) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX document_sha256_idx ON dart.document (sha256);
+CREATE INDEX document_run_id_idx ON dart.document (run_id);
@@ -0,0 +1,4 @@
+ALTER TABLE dart.filing
+    DROP COLUMN is_consolidated,
+    DROP COLUMN is_corrected,
+    DROP COLUMN is_withdrawn;
@@ -0,0 +1,12 @@
+-- Markers of `remark` as columns, so withdrawn and superseded filings can be excluded by an index.
+-- They are generated, so every write keeps them in sync with `remark`.
+ALTER TABLE dart.filing
+    -- 연: the report includes the consolidated statements
+    ADD COLUMN is_consolidated BOOLEAN NOT NULL GENERATED ALWAYS AS (strpos(remark, '연') > 0) STORED,
+    -- 정: a correction was filed after the report, which supersedes it
+    ADD COLUMN is_corrected    BOOLEAN NOT NULL GENERATED ALWAYS AS (strpos(remark, '정') > 0) STORED,
+    -- 철: the report was withdrawn
+    ADD COLUMN is_withdrawn    BOOLEAN NOT NULL GENERATED ALWAYS AS (strpos(remark, '철') > 0) STORED;
+
+CREATE INDEX filing_is_corrected_idx ON dart.filing (is_corrected);
+CREATE INDEX filing_is_withdrawn_idx ON dart.filing (is_withdrawn);
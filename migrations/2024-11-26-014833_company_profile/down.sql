@@ -0,0 +1 @@
+DROP TABLE dart.company_profile;
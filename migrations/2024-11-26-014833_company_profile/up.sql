@@ -0,0 +1,26 @@
+-- The overview of a company in OpenDART (company.json).
+-- Fields OpenDART leaves blank are NULL.
+-- The registration numbers are the keys to join with `smes.company`.
+CREATE TABLE dart.company_profile
+(
+    dart_id                         TEXT PRIMARY KEY CHECK (dart_id ~ '^[0-9]{8}$'),
+    corporation_registration_number TEXT CHECK (corporation_registration_number ~ '^[0-9]{13}$'),
+    business_registration_number    TEXT CHECK (business_registration_number ~ '^[0-9]{10}$'),
+    representative_name             TEXT,
+    headquarters_address            TEXT,
+    -- Only 5-digit codes, OpenDART also has coarser ones which SMES doesn't use
+    industry_code                   TEXT CHECK (industry_code ~ '^[0-9]{5}$'),
+    established_date                DATE,
+    fiscal_month                    INTEGER CHECK (fiscal_month BETWEEN 1 AND 12),
+    run_id                          INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    created_at                      TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at                      TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    FOREIGN KEY (dart_id) REFERENCES dart.company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+SELECT diesel_manage_updated_at('dart.company_profile');
+
+CREATE INDEX company_profile_corporation_registration_number_idx
+    ON dart.company_profile (corporation_registration_number);
+CREATE INDEX company_profile_business_registration_number_idx
+    ON dart.company_profile (business_registration_number);
+CREATE INDEX company_profile_run_id_idx ON dart.company_profile (run_id);
@@ -0,0 +1,3 @@
+DROP TABLE dart.employee;
+DROP TABLE dart.executive;
+DROP TABLE dart.major_shareholder;
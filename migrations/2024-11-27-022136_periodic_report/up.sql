@@ -0,0 +1,85 @@
+-- Sections of the periodic reports of OpenDART, per company, business year and report:
+-- 최대주주 현황 (hyslrSttus), 임원 현황 (exctvSttus) and 직원 현황 (empSttus).
+-- A section is replaced as a whole, and `ord` is the position of a row within it,
+-- as names, and even whole rows, repeat within a section.
+CREATE TABLE dart.major_shareholder
+(
+    dart_id          TEXT      NOT NULL CHECK (dart_id ~ '^[0-9]{8}$'),
+    business_year    INTEGER   NOT NULL CHECK (business_year BETWEEN 2015 AND 9999),
+    report_code      TEXT      NOT NULL CHECK (report_code IN ('11011', '11012', '11013', '11014')),
+    ord              INTEGER   NOT NULL CHECK (ord > 0),
+    name             TEXT      NOT NULL,
+    relation         TEXT,
+    stock_kind       TEXT,
+    -- Shares and stakes in percent at the start and at the end of the period
+    shares_at_start  BIGINT,
+    stake_at_start   DOUBLE PRECISION,
+    shares_at_end    BIGINT,
+    stake_at_end     DOUBLE PRECISION,
+    remark           TEXT,
+    settlement_date  DATE,
+    receipt_number   TEXT      NOT NULL CHECK (receipt_number ~ '^[0-9]{14}$'),
+    run_id           INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    created_at       TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (dart_id, business_year, report_code, ord),
+    FOREIGN KEY (dart_id) REFERENCES dart.company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX major_shareholder_name_idx ON dart.major_shareholder (name);
+CREATE INDEX major_shareholder_run_id_idx ON dart.major_shareholder (run_id);
+
+CREATE TABLE dart.executive
+(
+    dart_id                       TEXT      NOT NULL CHECK (dart_id ~ '^[0-9]{8}$'),
+    business_year                 INTEGER   NOT NULL CHECK (business_year BETWEEN 2015 AND 9999),
+    report_code                   TEXT      NOT NULL CHECK (report_code IN ('11011', '11012', '11013', '11014')),
+    ord                           INTEGER   NOT NULL CHECK (ord > 0),
+    name                          TEXT      NOT NULL,
+    gender                        TEXT CHECK (gender IN ('남', '여')),
+    -- As reported, e.g. '1968년 11월'
+    birth_month                   TEXT,
+    position                      TEXT,
+    -- e.g. '사내이사', '사외이사' or '미등기'
+    registration                  TEXT,
+    is_full_time                  BOOLEAN,
+    responsibility                TEXT,
+    main_career                   TEXT,
+    relation_to_major_shareholder TEXT,
+    tenure                        TEXT,
+    term_end                      TEXT,
+    settlement_date               DATE,
+    receipt_number                TEXT      NOT NULL CHECK (receipt_number ~ '^[0-9]{14}$'),
+    run_id                        INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    created_at                    TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (dart_id, business_year, report_code, ord),
+    FOREIGN KEY (dart_id) REFERENCES dart.company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX executive_name_idx ON dart.executive (name);
+CREATE INDEX executive_run_id_idx ON dart.executive (run_id);
+
+CREATE TABLE dart.employee
+(
+    dart_id             TEXT      NOT NULL CHECK (dart_id ~ '^[0-9]{8}$'),
+    business_year       INTEGER   NOT NULL CHECK (business_year BETWEEN 2015 AND 9999),
+    report_code         TEXT      NOT NULL CHECK (report_code IN ('11011', '11012', '11013', '11014')),
+    ord                 INTEGER   NOT NULL CHECK (ord > 0),
+    business_segment    TEXT,
+    gender              TEXT CHECK (gender IN ('남', '여')),
+    regular_count       BIGINT,
+    contract_count      BIGINT,
+    total_count         BIGINT,
+    -- As reported, e.g. '12.5' or '12년 6개월'
+    average_tenure      TEXT,
+    annual_salary_total BIGINT,
+    average_salary      BIGINT,
+    remark              TEXT,
+    settlement_date     DATE,
+    receipt_number      TEXT      NOT NULL CHECK (receipt_number ~ '^[0-9]{14}$'),
+    run_id              INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    created_at          TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (dart_id, business_year, report_code, ord),
+    FOREIGN KEY (dart_id) REFERENCES dart.company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX employee_run_id_idx ON dart.employee (run_id);
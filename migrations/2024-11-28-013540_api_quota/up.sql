@@ -0,0 +1,19 @@
+-- The requests sent to OpenDART per API key and day, shared by the runners,
+-- as OpenDART caps the requests of a key per day and answers status 020 beyond the cap.
+-- Keys are identified by a prefix of their SHA-256, so they aren't stored.
+-- `day` is the date in KST, when OpenDART resets the counts.
+CREATE TABLE dart.api_quota
+(
+    key_id        TEXT      NOT NULL CHECK (key_id ~ '^[0-9a-f]{16}$'),
+    day           DATE      NOT NULL,
+    request_count INTEGER   NOT NULL DEFAULT 0 CHECK (request_count >= 0),
+    -- Whether OpenDART answered 020, e.g. as the key was also used elsewhere
+    exhausted     BOOLEAN   NOT NULL DEFAULT FALSE,
+    run_id        INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    created_at    TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at    TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (key_id, day)
+);
+SELECT diesel_manage_updated_at('dart.api_quota');
+
+CREATE INDEX api_quota_run_id_idx ON dart.api_quota (run_id);
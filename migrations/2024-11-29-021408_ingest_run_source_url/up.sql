@@ -0,0 +1,4 @@
+-- The server a run fetched from, e.g. `https://opendart.fss.or.kr`,
+-- which tells rows fetched from a mock or a mirror apart from the real source.
+ALTER TABLE ingest_run
+    ADD COLUMN source_url TEXT;
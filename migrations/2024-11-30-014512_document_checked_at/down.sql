@@ -0,0 +1,11 @@
+DELETE
+FROM dart.document
+WHERE sha256 IS NULL;
+
+ALTER TABLE dart.document
+    RENAME COLUMN checked_at TO downloaded_at;
+
+ALTER TABLE dart.document
+    DROP CONSTRAINT document_archive_check,
+    ALTER COLUMN sha256 SET NOT NULL,
+    ALTER COLUMN byte_size SET NOT NULL;
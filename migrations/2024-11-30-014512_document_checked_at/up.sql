@@ -0,0 +1,10 @@
+-- Filings OpenDART has no document for (status 013 or 014) are recorded without an archive,
+-- so every run doesn't request them again.
+ALTER TABLE dart.document
+    ALTER COLUMN sha256 DROP NOT NULL,
+    ALTER COLUMN byte_size DROP NOT NULL,
+    ADD CONSTRAINT document_archive_check CHECK ((sha256 IS NULL) = (byte_size IS NULL));
+
+-- When OpenDART was last asked for the archive, whether or not it had one.
+ALTER TABLE dart.document
+    RENAME COLUMN downloaded_at TO checked_at;
@@ -0,0 +1 @@
+ALTER TABLE ingest_run DROP COLUMN error;
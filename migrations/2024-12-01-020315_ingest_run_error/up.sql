@@ -0,0 +1,4 @@
+-- Why a run failed, set together with `finished_at`.
+-- A run with `finished_at` but no `error` succeeded, and one without `finished_at` is in progress or was killed.
+ALTER TABLE ingest_run
+    ADD COLUMN error TEXT;
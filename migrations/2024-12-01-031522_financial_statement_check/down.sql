@@ -0,0 +1 @@
+DROP TABLE dart.financial_statement_check;
@@ -0,0 +1 @@
+DROP TABLE dart.periodic_report_check;
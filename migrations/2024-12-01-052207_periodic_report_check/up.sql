@@ -0,0 +1,17 @@
+-- When each section of a periodic report was last fetched, and what it had.
+-- A section without rows has no rows in its table, e.g. a major shareholder section of only the total row,
+-- so it's only recorded here, which keeps every run from requesting it again.
+-- `section` is the OpenDART endpoint of the section.
+CREATE TABLE dart.periodic_report_check
+(
+    dart_id       TEXT      NOT NULL CHECK (dart_id ~ '^[0-9]{8}$'),
+    business_year INTEGER   NOT NULL CHECK (business_year BETWEEN 2015 AND 9999),
+    report_code   TEXT      NOT NULL CHECK (report_code IN ('11011', '11012', '11013', '11014')),
+    section       TEXT      NOT NULL CHECK (section IN ('empSttus', 'exctvSttus', 'hyslrSttus')),
+    row_count     INTEGER   NOT NULL CHECK (row_count >= 0),
+    skipped_count INTEGER   NOT NULL CHECK (skipped_count >= 0),
+    run_id        INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    checked_at    TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (dart_id, business_year, report_code, section),
+    FOREIGN KEY (dart_id) REFERENCES dart.company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
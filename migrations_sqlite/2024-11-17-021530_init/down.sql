@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE company_link;
+DROP TABLE dart_company_id;
+DROP TABLE dart_filing;
+DROP TABLE smes_list_snapshot_company;
+DROP TABLE smes_list_snapshot;
+DROP TABLE smes_html;
+DROP TABLE smes_company;
@@ -0,0 +1,132 @@
+-- Your SQL goes here
+
+-- Mirrors the Postgres migrations in `migrations`, up to `normalized_company_name`.
+-- SQLite has no schemas, so `smes.company` becomes `smes_company` and so on,
+-- and has no `~` operator, so digit checks are written as `length(x) = n AND x NOT GLOB '*[^0-9]*'`.
+
+CREATE TABLE smes_company
+(
+    smes_id                      TEXT PRIMARY KEY NOT NULL CHECK (length(smes_id) = 7 AND smes_id NOT GLOB '*[^0-9]*'),
+    representative_name          TEXT      NOT NULL,
+    headquarters_address         TEXT      NOT NULL,
+    business_registration_number TEXT CHECK (
+        length(business_registration_number) = 10 AND
+        business_registration_number NOT GLOB '*[^0-9]*'
+        ),
+    company_name                 TEXT      NOT NULL,
+    industry_code                TEXT      NOT NULL CHECK (length(industry_code) = 5 AND industry_code NOT GLOB '*[^0-9]*'),
+    industry_name                TEXT      NOT NULL,
+    created_at                   TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at                   TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    last_seen_at                 TIMESTAMP,
+    delisted_at                  TIMESTAMP,
+    normalized_company_name      TEXT      NOT NULL
+);
+CREATE INDEX smes_company_normalized_company_name_idx ON smes_company (normalized_company_name);
+CREATE TRIGGER smes_company_updated_at
+    AFTER UPDATE
+    ON smes_company
+    FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE smes_company SET updated_at = current_timestamp WHERE smes_id = NEW.smes_id;
+END;
+
+CREATE TABLE smes_html
+(
+    smes_id      TEXT PRIMARY KEY NOT NULL CHECK (length(smes_id) = 7 AND smes_id NOT GLOB '*[^0-9]*'),
+    html_content TEXT      NOT NULL,
+    created_at   TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at   TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    FOREIGN KEY (smes_id) REFERENCES smes_company (smes_id) ON DELETE RESTRICT ON UPDATE CASCADE
+);
+CREATE TRIGGER smes_html_updated_at
+    AFTER UPDATE
+    ON smes_html
+    FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE smes_html SET updated_at = current_timestamp WHERE smes_id = NEW.smes_id;
+END;
+
+CREATE TABLE smes_list_snapshot
+(
+    snapshot_id   INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    company_count INTEGER   NOT NULL CHECK (company_count >= 0),
+    created_at    TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE smes_list_snapshot_company
+(
+    snapshot_id INTEGER NOT NULL,
+    smes_id     TEXT    NOT NULL,
+    PRIMARY KEY (snapshot_id, smes_id),
+    FOREIGN KEY (snapshot_id) REFERENCES smes_list_snapshot (snapshot_id) ON DELETE CASCADE,
+    FOREIGN KEY (smes_id) REFERENCES smes_company (smes_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE TABLE dart_filing
+(
+    dart_id        TEXT PRIMARY KEY NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    report_name    TEXT      NOT NULL,
+    receipt_number TEXT      NOT NULL CHECK (length(receipt_number) = 14 AND receipt_number NOT GLOB '*[^0-9]*'),
+    filer_name     TEXT      NOT NULL,
+    receipt_date   DATE      NOT NULL,
+    remark         TEXT      NOT NULL,
+    created_at     TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at     TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+CREATE TRIGGER dart_filing_updated_at
+    AFTER UPDATE
+    ON dart_filing
+    FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE dart_filing SET updated_at = current_timestamp WHERE dart_id = NEW.dart_id;
+END;
+
+CREATE TABLE dart_company_id
+(
+    dart_id                 TEXT PRIMARY KEY NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    company_name            TEXT NOT NULL,
+    stock_code              TEXT NOT NULL CHECK (length(stock_code) = 6 AND stock_code NOT GLOB '*[^0-9]*'),
+    id_modify_date          DATE NOT NULL,
+    normalized_company_name TEXT NOT NULL
+);
+CREATE INDEX dart_company_id_normalized_company_name_idx ON dart_company_id (normalized_company_name);
+
+CREATE TABLE company_link
+(
+    smes_id                         TEXT      NOT NULL CHECK (length(smes_id) = 7 AND smes_id NOT GLOB '*[^0-9]*'),
+    dart_id                         TEXT      NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    business_registration_number    TEXT CHECK (
+        length(business_registration_number) = 10 AND
+        business_registration_number NOT GLOB '*[^0-9]*'
+        ),
+    corporation_registration_number TEXT CHECK (
+        length(corporation_registration_number) = 13 AND
+        corporation_registration_number NOT GLOB '*[^0-9]*'
+        ),
+    match_method                    TEXT      NOT NULL CHECK (match_method IN (
+                                                                               'business_registration_number',
+                                                                               'corporation_registration_number',
+                                                                               'name_and_representative'
+        )),
+    confidence                      REAL      NOT NULL CHECK (confidence > 0 AND confidence <= 1),
+    created_at                      TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at                      TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (smes_id, dart_id),
+    FOREIGN KEY (smes_id) REFERENCES smes_company (smes_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (dart_id) REFERENCES dart_company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+CREATE TRIGGER company_link_updated_at
+    AFTER UPDATE
+    ON company_link
+    FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE company_link SET updated_at = current_timestamp WHERE smes_id = NEW.smes_id AND dart_id = NEW.dart_id;
+END;
+
+CREATE INDEX company_link_dart_id_idx ON company_link (dart_id);
+CREATE INDEX company_link_corporation_registration_number_idx ON company_link (corporation_registration_number);
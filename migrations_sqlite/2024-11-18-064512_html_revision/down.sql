@@ -0,0 +1,52 @@
+-- This file should undo anything in `up.sql`
+
+-- Compressed bodies can't be restored in SQL,
+-- so reverting fails rather than dropping the pages of those companies.
+-- SQLite only raises within triggers, so the check goes through a temporary one.
+CREATE TEMP TABLE html_revision_compressed_count
+(
+    compressed_count INTEGER NOT NULL
+);
+CREATE TEMP TRIGGER html_revision_compressed_check
+    BEFORE INSERT
+    ON html_revision_compressed_count
+    FOR EACH ROW
+    WHEN NEW.compressed_count > 0
+BEGIN
+    SELECT RAISE(ABORT, 'smes_html_revision has compressed bodies, which can''t be restored in SQL');
+END;
+INSERT INTO html_revision_compressed_count (compressed_count)
+SELECT count(*)
+FROM smes_html
+         JOIN smes_html_revision
+              ON smes_html_revision.smes_id = smes_html.smes_id AND smes_html_revision.sha256 = smes_html.sha256
+WHERE substr(smes_html_revision.body, 1, 4) = X'28B52FFD';
+DROP TABLE html_revision_compressed_count;
+
+CREATE TABLE smes_html_old
+(
+    smes_id      TEXT PRIMARY KEY NOT NULL CHECK (length(smes_id) = 7 AND smes_id NOT GLOB '*[^0-9]*'),
+    html_content TEXT      NOT NULL,
+    created_at   TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at   TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    FOREIGN KEY (smes_id) REFERENCES smes_company (smes_id) ON DELETE RESTRICT ON UPDATE CASCADE
+);
+INSERT INTO smes_html_old (smes_id, html_content, created_at, updated_at)
+SELECT smes_html.smes_id, CAST(smes_html_revision.body AS TEXT), smes_html.created_at, smes_html.updated_at
+FROM smes_html
+         JOIN smes_html_revision
+              ON smes_html_revision.smes_id = smes_html.smes_id AND smes_html_revision.sha256 = smes_html.sha256;
+DROP TABLE smes_html;
+ALTER TABLE smes_html_old
+    RENAME TO smes_html;
+
+CREATE TRIGGER smes_html_updated_at
+    AFTER UPDATE
+    ON smes_html
+    FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE smes_html SET updated_at = current_timestamp WHERE smes_id = NEW.smes_id;
+END;
+
+DROP TABLE smes_html_revision;
@@ -0,0 +1,44 @@
+-- Your SQL goes here
+
+-- Mirrors `migrations/2024-11-18-064512_html_revision`.
+-- `sha256_hex` isn't built into SQLite, it's registered on every connection by `SqliteDb`.
+CREATE TABLE smes_html_revision
+(
+    smes_id    TEXT      NOT NULL CHECK (length(smes_id) = 7 AND smes_id NOT GLOB '*[^0-9]*'),
+    sha256     TEXT      NOT NULL CHECK (length(sha256) = 64 AND sha256 NOT GLOB '*[^0-9a-f]*'),
+    fetched_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    -- zstd-compressed, the rows backfilled below are compressed by `SqliteDb`
+    body       BLOB      NOT NULL,
+    PRIMARY KEY (smes_id, sha256),
+    FOREIGN KEY (smes_id) REFERENCES smes_company (smes_id) ON DELETE RESTRICT ON UPDATE CASCADE
+);
+
+INSERT INTO smes_html_revision (smes_id, sha256, fetched_at, body)
+SELECT smes_id, sha256_hex(html_content), updated_at, CAST(html_content AS BLOB)
+FROM smes_html;
+
+-- SQLite can't add a NOT NULL foreign key column, so the table is rebuilt.
+CREATE TABLE smes_html_new
+(
+    smes_id    TEXT PRIMARY KEY NOT NULL CHECK (length(smes_id) = 7 AND smes_id NOT GLOB '*[^0-9]*'),
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    sha256     TEXT      NOT NULL,
+    FOREIGN KEY (smes_id) REFERENCES smes_company (smes_id) ON DELETE RESTRICT ON UPDATE CASCADE,
+    FOREIGN KEY (smes_id, sha256) REFERENCES smes_html_revision (smes_id, sha256)
+);
+INSERT INTO smes_html_new (smes_id, created_at, updated_at, sha256)
+SELECT smes_id, created_at, updated_at, sha256_hex(html_content)
+FROM smes_html;
+DROP TABLE smes_html;
+ALTER TABLE smes_html_new
+    RENAME TO smes_html;
+
+CREATE TRIGGER smes_html_updated_at
+    AFTER UPDATE
+    ON smes_html
+    FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE smes_html SET updated_at = current_timestamp WHERE smes_id = NEW.smes_id;
+END;
@@ -0,0 +1,10 @@
+-- Pages which failed a constraint while being stored, kept so a bad row doesn't abort a scrape.
+-- No constraints on purpose: a row has to be storable whatever it failed on.
+CREATE TABLE smes_html_dead_letter
+(
+    id           INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    smes_id      TEXT      NOT NULL,
+    html_content TEXT      NOT NULL,
+    error        TEXT      NOT NULL,
+    created_at   TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
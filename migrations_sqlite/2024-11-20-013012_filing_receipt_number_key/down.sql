@@ -0,0 +1,30 @@
+-- This file should undo anything in `up.sql`
+CREATE TABLE dart_filing_old
+(
+    dart_id        TEXT PRIMARY KEY NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    report_name    TEXT      NOT NULL,
+    receipt_number TEXT      NOT NULL CHECK (length(receipt_number) = 14 AND receipt_number NOT GLOB '*[^0-9]*'),
+    filer_name     TEXT      NOT NULL,
+    receipt_date   DATE      NOT NULL,
+    remark         TEXT      NOT NULL,
+    created_at     TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at     TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+-- Only the latest filing of each company fits the old key.
+INSERT OR IGNORE INTO dart_filing_old (dart_id, report_name, receipt_number, filer_name, receipt_date, remark,
+                                       created_at, updated_at)
+SELECT dart_id, report_name, receipt_number, filer_name, receipt_date, remark, created_at, updated_at
+FROM dart_filing
+ORDER BY receipt_date DESC, receipt_number DESC;
+DROP TABLE dart_filing;
+ALTER TABLE dart_filing_old
+    RENAME TO dart_filing;
+
+CREATE TRIGGER dart_filing_updated_at
+    AFTER UPDATE
+    ON dart_filing
+    FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE dart_filing SET updated_at = current_timestamp WHERE dart_id = NEW.dart_id;
+END;
@@ -0,0 +1,33 @@
+-- A filing is identified by its receipt number, so a company can have any number of filings.
+-- SQLite can't change a primary key, so the table is rebuilt.
+CREATE TABLE dart_filing_new
+(
+    dart_id        TEXT      NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    report_name    TEXT      NOT NULL,
+    receipt_number TEXT PRIMARY KEY NOT NULL CHECK (length(receipt_number) = 14 AND receipt_number NOT GLOB '*[^0-9]*'),
+    filer_name     TEXT      NOT NULL,
+    receipt_date   DATE      NOT NULL,
+    remark         TEXT      NOT NULL,
+    created_at     TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at     TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+-- Receipt numbers should already be unique, keep one row per number just in case.
+INSERT OR IGNORE INTO dart_filing_new (dart_id, report_name, receipt_number, filer_name, receipt_date, remark,
+                                       created_at, updated_at)
+SELECT dart_id, report_name, receipt_number, filer_name, receipt_date, remark, created_at, updated_at
+FROM dart_filing
+ORDER BY dart_id DESC;
+DROP TABLE dart_filing;
+ALTER TABLE dart_filing_new
+    RENAME TO dart_filing;
+
+CREATE INDEX dart_filing_dart_id_receipt_date_idx ON dart_filing (dart_id, receipt_date);
+
+CREATE TRIGGER dart_filing_updated_at
+    AFTER UPDATE
+    ON dart_filing
+    FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE dart_filing SET updated_at = current_timestamp WHERE receipt_number = NEW.receipt_number;
+END;
@@ -0,0 +1,59 @@
+-- This file should undo anything in `up.sql`
+PRAGMA foreign_keys = OFF;
+BEGIN;
+
+CREATE TABLE dart_filing_old
+(
+    dart_id        TEXT      NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    report_name    TEXT      NOT NULL,
+    receipt_number TEXT PRIMARY KEY NOT NULL CHECK (length(receipt_number) = 14 AND receipt_number NOT GLOB '*[^0-9]*'),
+    filer_name     TEXT      NOT NULL,
+    receipt_date   DATE      NOT NULL,
+    remark         TEXT      NOT NULL,
+    created_at     TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at     TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+INSERT INTO dart_filing_old (dart_id, report_name, receipt_number, filer_name, receipt_date, remark,
+                             created_at, updated_at)
+SELECT dart_id, report_name, receipt_number, filer_name, receipt_date, remark, created_at, updated_at
+FROM dart_filing;
+DROP TABLE dart_filing;
+ALTER TABLE dart_filing_old
+    RENAME TO dart_filing;
+
+CREATE INDEX dart_filing_dart_id_receipt_date_idx ON dart_filing (dart_id, receipt_date);
+
+CREATE TRIGGER dart_filing_updated_at
+    AFTER UPDATE
+    ON dart_filing
+    FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE dart_filing SET updated_at = current_timestamp WHERE receipt_number = NEW.receipt_number;
+END;
+
+-- Placeholders can't satisfy the old constraints, and neither can their links.
+DELETE
+FROM company_link
+WHERE dart_id IN (SELECT dart_id FROM dart_company_id WHERE stock_code IS NULL OR id_modify_date IS NULL);
+
+CREATE TABLE dart_company_id_old
+(
+    dart_id                 TEXT PRIMARY KEY NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    company_name            TEXT NOT NULL,
+    stock_code              TEXT NOT NULL CHECK (length(stock_code) = 6 AND stock_code NOT GLOB '*[^0-9]*'),
+    id_modify_date          DATE NOT NULL,
+    normalized_company_name TEXT NOT NULL
+);
+INSERT INTO dart_company_id_old (dart_id, company_name, stock_code, id_modify_date, normalized_company_name)
+SELECT dart_id, company_name, stock_code, id_modify_date, normalized_company_name
+FROM dart_company_id
+WHERE stock_code IS NOT NULL
+  AND id_modify_date IS NOT NULL;
+DROP TABLE dart_company_id;
+ALTER TABLE dart_company_id_old
+    RENAME TO dart_company_id;
+CREATE INDEX dart_company_id_normalized_company_name_idx ON dart_company_id (normalized_company_name);
+
+COMMIT;
+PRAGMA foreign_keys = ON;
@@ -0,0 +1,68 @@
+-- Mirrors `migrations/2024-11-21-052240_filing_company_id_fk`.
+-- Dropping `dart_company_id` with foreign keys on would delete the rows of `company_link` which reference it.
+PRAGMA foreign_keys = OFF;
+BEGIN;
+
+-- SQLite can't drop NOT NULL, so the table is rebuilt.
+CREATE TABLE dart_company_id_new
+(
+    dart_id                 TEXT PRIMARY KEY NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    company_name            TEXT NOT NULL,
+    stock_code              TEXT CHECK (length(stock_code) = 6 AND stock_code NOT GLOB '*[^0-9]*'),
+    id_modify_date          DATE,
+    normalized_company_name TEXT NOT NULL
+);
+INSERT INTO dart_company_id_new (dart_id, company_name, stock_code, id_modify_date, normalized_company_name)
+SELECT dart_id, company_name, stock_code, id_modify_date, normalized_company_name
+FROM dart_company_id;
+DROP TABLE dart_company_id;
+ALTER TABLE dart_company_id_new
+    RENAME TO dart_company_id;
+CREATE INDEX dart_company_id_normalized_company_name_idx ON dart_company_id (normalized_company_name);
+
+-- `normalize_company_name` isn't built into SQLite, it's registered on every connection by `SqliteDb`.
+-- Placeholders are named after the filer of the latest filing.
+INSERT INTO dart_company_id (dart_id, company_name, normalized_company_name)
+SELECT f.dart_id, f.filer_name, normalize_company_name(f.filer_name)
+FROM dart_filing f
+WHERE f.dart_id NOT IN (SELECT dart_id FROM dart_company_id)
+  AND f.receipt_number = (SELECT l.receipt_number
+                          FROM dart_filing l
+                          WHERE l.dart_id = f.dart_id
+                          ORDER BY l.receipt_date DESC, l.receipt_number DESC
+                          LIMIT 1);
+
+-- SQLite can't add a foreign key, so the table is rebuilt.
+CREATE TABLE dart_filing_new
+(
+    dart_id        TEXT      NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    report_name    TEXT      NOT NULL,
+    receipt_number TEXT PRIMARY KEY NOT NULL CHECK (length(receipt_number) = 14 AND receipt_number NOT GLOB '*[^0-9]*'),
+    filer_name     TEXT      NOT NULL,
+    receipt_date   DATE      NOT NULL,
+    remark         TEXT      NOT NULL,
+    created_at     TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at     TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    FOREIGN KEY (dart_id) REFERENCES dart_company_id (dart_id) ON DELETE RESTRICT ON UPDATE CASCADE
+);
+INSERT INTO dart_filing_new (dart_id, report_name, receipt_number, filer_name, receipt_date, remark,
+                             created_at, updated_at)
+SELECT dart_id, report_name, receipt_number, filer_name, receipt_date, remark, created_at, updated_at
+FROM dart_filing;
+DROP TABLE dart_filing;
+ALTER TABLE dart_filing_new
+    RENAME TO dart_filing;
+
+CREATE INDEX dart_filing_dart_id_receipt_date_idx ON dart_filing (dart_id, receipt_date);
+
+CREATE TRIGGER dart_filing_updated_at
+    AFTER UPDATE
+    ON dart_filing
+    FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE dart_filing SET updated_at = current_timestamp WHERE receipt_number = NEW.receipt_number;
+END;
+
+COMMIT;
+PRAGMA foreign_keys = ON;
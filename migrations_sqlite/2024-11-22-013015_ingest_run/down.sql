@@ -0,0 +1,12 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX dart_filing_run_id_idx;
+ALTER TABLE dart_filing
+    DROP COLUMN run_id;
+DROP INDEX smes_html_run_id_idx;
+ALTER TABLE smes_html
+    DROP COLUMN run_id;
+DROP INDEX smes_company_run_id_idx;
+ALTER TABLE smes_company
+    DROP COLUMN run_id;
+
+DROP TABLE ingest_run;
@@ -0,0 +1,26 @@
+-- Mirrors `migrations/2024-11-22-013015_ingest_run`.
+CREATE TABLE ingest_run
+(
+    run_id          INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    runner          TEXT      NOT NULL,
+    git_rev         TEXT,
+    config          TEXT,
+    started_at      TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    finished_at     TIMESTAMP,
+    inserted_count  INTEGER CHECK (inserted_count >= 0),
+    updated_count   INTEGER CHECK (updated_count >= 0),
+    unchanged_count INTEGER CHECK (unchanged_count >= 0)
+);
+
+-- SQLite can add a foreign key column as long as it defaults to NULL.
+ALTER TABLE smes_company
+    ADD COLUMN run_id INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL;
+CREATE INDEX smes_company_run_id_idx ON smes_company (run_id);
+
+ALTER TABLE smes_html
+    ADD COLUMN run_id INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL;
+CREATE INDEX smes_html_run_id_idx ON smes_html (run_id);
+
+ALTER TABLE dart_filing
+    ADD COLUMN run_id INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL;
+CREATE INDEX dart_filing_run_id_idx ON dart_filing (run_id);
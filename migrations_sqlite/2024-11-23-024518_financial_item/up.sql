@@ -0,0 +1,25 @@
+-- Mirrors `migrations/2024-11-23-024518_financial_item`.
+CREATE TABLE dart_financial_item
+(
+    dart_id                TEXT      NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    business_year          INTEGER   NOT NULL CHECK (business_year BETWEEN 2015 AND 9999),
+    report_code            TEXT      NOT NULL CHECK (report_code IN ('11011', '11012', '11013', '11014')),
+    fs_div                 TEXT      NOT NULL CHECK (fs_div IN ('CFS', 'OFS')),
+    statement              TEXT      NOT NULL CHECK (statement IN ('BS', 'IS', 'CIS', 'CF', 'SCE')),
+    ord                    INTEGER   NOT NULL,
+    account_detail         TEXT      NOT NULL,
+    account_id             TEXT      NOT NULL,
+    account_name           TEXT      NOT NULL,
+    current_amount         BIGINT,
+    previous_amount        BIGINT,
+    before_previous_amount BIGINT,
+    currency               TEXT      NOT NULL,
+    receipt_number         TEXT      NOT NULL CHECK (length(receipt_number) = 14 AND receipt_number NOT GLOB '*[^0-9]*'),
+    run_id                 INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    created_at             TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (dart_id, business_year, report_code, fs_div, statement, ord, account_detail),
+    FOREIGN KEY (dart_id) REFERENCES dart_company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX dart_financial_item_account_id_idx ON dart_financial_item (account_id);
+CREATE INDEX dart_financial_item_run_id_idx ON dart_financial_item (run_id);
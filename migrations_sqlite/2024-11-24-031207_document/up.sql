@@ -0,0 +1,13 @@
+-- Mirrors `migrations/2024-11-24-031207_document`.
+CREATE TABLE dart_document
+(
+    receipt_number TEXT PRIMARY KEY CHECK (length(receipt_number) = 14 AND receipt_number NOT GLOB '*[^0-9]*'),
+    sha256         TEXT      NOT NULL CHECK (length(sha256) = 64 AND sha256 NOT GLOB '*[^0-9a-f]*'),
+    byte_size      BIGINT    NOT NULL CHECK (byte_size >= 0),
+    run_id         INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    downloaded_at  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    FOREIGN KEY (receipt_number) REFERENCES dart_filing (receipt_number) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX dart_document_sha256_idx ON dart_document (sha256);
+CREATE INDEX dart_document_run_id_idx ON dart_document (run_id);
@@ -0,0 +1,8 @@
+DROP INDEX dart_filing_is_withdrawn_idx;
+DROP INDEX dart_filing_is_corrected_idx;
+ALTER TABLE dart_filing
+    DROP COLUMN is_withdrawn;
+ALTER TABLE dart_filing
+    DROP COLUMN is_corrected;
+ALTER TABLE dart_filing
+    DROP COLUMN is_consolidated;
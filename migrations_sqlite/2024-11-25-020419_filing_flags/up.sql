@@ -0,0 +1,11 @@
+-- Mirrors `migrations/2024-11-25-020419_filing_flags`.
+-- SQLite can only add virtual generated columns, which can still be indexed.
+ALTER TABLE dart_filing
+    ADD COLUMN is_consolidated BOOLEAN NOT NULL GENERATED ALWAYS AS (instr(remark, '연') > 0) VIRTUAL;
+ALTER TABLE dart_filing
+    ADD COLUMN is_corrected BOOLEAN NOT NULL GENERATED ALWAYS AS (instr(remark, '정') > 0) VIRTUAL;
+ALTER TABLE dart_filing
+    ADD COLUMN is_withdrawn BOOLEAN NOT NULL GENERATED ALWAYS AS (instr(remark, '철') > 0) VIRTUAL;
+
+CREATE INDEX dart_filing_is_corrected_idx ON dart_filing (is_corrected);
+CREATE INDEX dart_filing_is_withdrawn_idx ON dart_filing (is_withdrawn);
@@ -0,0 +1 @@
+DROP TABLE dart_company_profile;
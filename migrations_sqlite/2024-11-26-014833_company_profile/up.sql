@@ -0,0 +1,30 @@
+-- Mirrors `migrations/2024-11-26-014833_company_profile`.
+CREATE TABLE dart_company_profile
+(
+    dart_id                         TEXT PRIMARY KEY NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    corporation_registration_number TEXT CHECK (length(corporation_registration_number) = 13 AND corporation_registration_number NOT GLOB '*[^0-9]*'),
+    business_registration_number    TEXT CHECK (length(business_registration_number) = 10 AND business_registration_number NOT GLOB '*[^0-9]*'),
+    representative_name             TEXT,
+    headquarters_address            TEXT,
+    industry_code                   TEXT CHECK (length(industry_code) = 5 AND industry_code NOT GLOB '*[^0-9]*'),
+    established_date                DATE,
+    fiscal_month                    INTEGER CHECK (fiscal_month BETWEEN 1 AND 12),
+    run_id                          INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    created_at                      TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at                      TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    FOREIGN KEY (dart_id) REFERENCES dart_company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+CREATE TRIGGER dart_company_profile_updated_at
+    AFTER UPDATE
+    ON dart_company_profile
+    FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE dart_company_profile SET updated_at = current_timestamp WHERE dart_id = NEW.dart_id;
+END;
+
+CREATE INDEX dart_company_profile_corporation_registration_number_idx
+    ON dart_company_profile (corporation_registration_number);
+CREATE INDEX dart_company_profile_business_registration_number_idx
+    ON dart_company_profile (business_registration_number);
+CREATE INDEX dart_company_profile_run_id_idx ON dart_company_profile (run_id);
@@ -0,0 +1,3 @@
+DROP TABLE dart_employee;
+DROP TABLE dart_executive;
+DROP TABLE dart_major_shareholder;
@@ -0,0 +1,78 @@
+-- Mirrors `migrations/2024-11-27-022136_periodic_report`.
+CREATE TABLE dart_major_shareholder
+(
+    dart_id          TEXT      NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    business_year    INTEGER   NOT NULL CHECK (business_year BETWEEN 2015 AND 9999),
+    report_code      TEXT      NOT NULL CHECK (report_code IN ('11011', '11012', '11013', '11014')),
+    ord              INTEGER   NOT NULL CHECK (ord > 0),
+    name             TEXT      NOT NULL,
+    relation         TEXT,
+    stock_kind       TEXT,
+    shares_at_start  BIGINT,
+    stake_at_start   DOUBLE,
+    shares_at_end    BIGINT,
+    stake_at_end     DOUBLE,
+    remark           TEXT,
+    settlement_date  DATE,
+    receipt_number   TEXT      NOT NULL CHECK (length(receipt_number) = 14 AND receipt_number NOT GLOB '*[^0-9]*'),
+    run_id           INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    created_at       TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (dart_id, business_year, report_code, ord),
+    FOREIGN KEY (dart_id) REFERENCES dart_company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX dart_major_shareholder_name_idx ON dart_major_shareholder (name);
+CREATE INDEX dart_major_shareholder_run_id_idx ON dart_major_shareholder (run_id);
+
+CREATE TABLE dart_executive
+(
+    dart_id                       TEXT      NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    business_year                 INTEGER   NOT NULL CHECK (business_year BETWEEN 2015 AND 9999),
+    report_code                   TEXT      NOT NULL CHECK (report_code IN ('11011', '11012', '11013', '11014')),
+    ord                           INTEGER   NOT NULL CHECK (ord > 0),
+    name                          TEXT      NOT NULL,
+    gender                        TEXT CHECK (gender IN ('남', '여')),
+    birth_month                   TEXT,
+    position                      TEXT,
+    registration                  TEXT,
+    is_full_time                  BOOLEAN,
+    responsibility                TEXT,
+    main_career                   TEXT,
+    relation_to_major_shareholder TEXT,
+    tenure                        TEXT,
+    term_end                      TEXT,
+    settlement_date               DATE,
+    receipt_number                TEXT      NOT NULL CHECK (length(receipt_number) = 14 AND receipt_number NOT GLOB '*[^0-9]*'),
+    run_id                        INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    created_at                    TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (dart_id, business_year, report_code, ord),
+    FOREIGN KEY (dart_id) REFERENCES dart_company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX dart_executive_name_idx ON dart_executive (name);
+CREATE INDEX dart_executive_run_id_idx ON dart_executive (run_id);
+
+CREATE TABLE dart_employee
+(
+    dart_id             TEXT      NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    business_year       INTEGER   NOT NULL CHECK (business_year BETWEEN 2015 AND 9999),
+    report_code         TEXT      NOT NULL CHECK (report_code IN ('11011', '11012', '11013', '11014')),
+    ord                 INTEGER   NOT NULL CHECK (ord > 0),
+    business_segment    TEXT,
+    gender              TEXT CHECK (gender IN ('남', '여')),
+    regular_count       BIGINT,
+    contract_count      BIGINT,
+    total_count         BIGINT,
+    average_tenure      TEXT,
+    annual_salary_total BIGINT,
+    average_salary      BIGINT,
+    remark              TEXT,
+    settlement_date     DATE,
+    receipt_number      TEXT      NOT NULL CHECK (length(receipt_number) = 14 AND receipt_number NOT GLOB '*[^0-9]*'),
+    run_id              INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    created_at          TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (dart_id, business_year, report_code, ord),
+    FOREIGN KEY (dart_id) REFERENCES dart_company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX dart_employee_run_id_idx ON dart_employee (run_id);
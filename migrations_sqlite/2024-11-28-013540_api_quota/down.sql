@@ -0,0 +1 @@
+DROP TABLE dart_api_quota;
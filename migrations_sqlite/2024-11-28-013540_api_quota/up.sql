@@ -0,0 +1,22 @@
+-- Mirrors `migrations/2024-11-28-013540_api_quota`.
+CREATE TABLE dart_api_quota
+(
+    key_id        TEXT      NOT NULL CHECK (length(key_id) = 16 AND key_id NOT GLOB '*[^0-9a-f]*'),
+    day           DATE      NOT NULL,
+    request_count INTEGER   NOT NULL DEFAULT 0 CHECK (request_count >= 0),
+    exhausted     BOOLEAN   NOT NULL DEFAULT FALSE,
+    run_id        INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    created_at    TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at    TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (key_id, day)
+);
+CREATE TRIGGER dart_api_quota_updated_at
+    AFTER UPDATE
+    ON dart_api_quota
+    FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE dart_api_quota SET updated_at = current_timestamp WHERE key_id = NEW.key_id AND day = NEW.day;
+END;
+
+CREATE INDEX dart_api_quota_run_id_idx ON dart_api_quota (run_id);
@@ -0,0 +1,3 @@
+-- Mirrors `migrations/2024-11-29-021408_ingest_run_source_url`.
+ALTER TABLE ingest_run
+    ADD COLUMN source_url TEXT;
@@ -0,0 +1,19 @@
+CREATE TABLE dart_document_old
+(
+    receipt_number TEXT PRIMARY KEY CHECK (length(receipt_number) = 14 AND receipt_number NOT GLOB '*[^0-9]*'),
+    sha256         TEXT      NOT NULL CHECK (length(sha256) = 64 AND sha256 NOT GLOB '*[^0-9a-f]*'),
+    byte_size      BIGINT    NOT NULL CHECK (byte_size >= 0),
+    run_id         INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    downloaded_at  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    FOREIGN KEY (receipt_number) REFERENCES dart_filing (receipt_number) ON DELETE CASCADE ON UPDATE CASCADE
+);
+INSERT INTO dart_document_old (receipt_number, sha256, byte_size, run_id, downloaded_at)
+SELECT receipt_number, sha256, byte_size, run_id, checked_at
+FROM dart_document
+WHERE sha256 IS NOT NULL;
+DROP TABLE dart_document;
+ALTER TABLE dart_document_old
+    RENAME TO dart_document;
+
+CREATE INDEX dart_document_sha256_idx ON dart_document (sha256);
+CREATE INDEX dart_document_run_id_idx ON dart_document (run_id);
@@ -0,0 +1,21 @@
+-- Mirrors `migrations/2024-11-30-014512_document_checked_at`.
+-- SQLite can't drop NOT NULL, so the table is rebuilt.
+CREATE TABLE dart_document_new
+(
+    receipt_number TEXT PRIMARY KEY CHECK (length(receipt_number) = 14 AND receipt_number NOT GLOB '*[^0-9]*'),
+    sha256         TEXT CHECK (length(sha256) = 64 AND sha256 NOT GLOB '*[^0-9a-f]*'),
+    byte_size      BIGINT CHECK (byte_size >= 0),
+    run_id         INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    checked_at     TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    FOREIGN KEY (receipt_number) REFERENCES dart_filing (receipt_number) ON DELETE CASCADE ON UPDATE CASCADE,
+    CHECK ((sha256 IS NULL) = (byte_size IS NULL))
+);
+INSERT INTO dart_document_new (receipt_number, sha256, byte_size, run_id, checked_at)
+SELECT receipt_number, sha256, byte_size, run_id, downloaded_at
+FROM dart_document;
+DROP TABLE dart_document;
+ALTER TABLE dart_document_new
+    RENAME TO dart_document;
+
+CREATE INDEX dart_document_sha256_idx ON dart_document (sha256);
+CREATE INDEX dart_document_run_id_idx ON dart_document (run_id);
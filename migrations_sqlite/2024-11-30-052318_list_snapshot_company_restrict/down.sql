@@ -0,0 +1,14 @@
+CREATE TABLE smes_list_snapshot_company_old
+(
+    snapshot_id INTEGER NOT NULL,
+    smes_id     TEXT    NOT NULL,
+    PRIMARY KEY (snapshot_id, smes_id),
+    FOREIGN KEY (snapshot_id) REFERENCES smes_list_snapshot (snapshot_id) ON DELETE CASCADE,
+    FOREIGN KEY (smes_id) REFERENCES smes_company (smes_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+INSERT INTO smes_list_snapshot_company_old (snapshot_id, smes_id)
+SELECT snapshot_id, smes_id
+FROM smes_list_snapshot_company;
+DROP TABLE smes_list_snapshot_company;
+ALTER TABLE smes_list_snapshot_company_old
+    RENAME TO smes_list_snapshot_company;
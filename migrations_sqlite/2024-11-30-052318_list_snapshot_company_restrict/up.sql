@@ -0,0 +1,17 @@
+-- `smes.list_snapshot_company` restricts deleting a company which a snapshot lists,
+-- see `migrations/2024-11-12-021512_list_snapshot`, which `init` cascaded instead.
+-- SQLite can't alter a foreign key, so the table is rebuilt.
+CREATE TABLE smes_list_snapshot_company_new
+(
+    snapshot_id INTEGER NOT NULL,
+    smes_id     TEXT    NOT NULL,
+    PRIMARY KEY (snapshot_id, smes_id),
+    FOREIGN KEY (snapshot_id) REFERENCES smes_list_snapshot (snapshot_id) ON DELETE CASCADE,
+    FOREIGN KEY (smes_id) REFERENCES smes_company (smes_id) ON DELETE RESTRICT ON UPDATE CASCADE
+);
+INSERT INTO smes_list_snapshot_company_new (snapshot_id, smes_id)
+SELECT snapshot_id, smes_id
+FROM smes_list_snapshot_company;
+DROP TABLE smes_list_snapshot_company;
+ALTER TABLE smes_list_snapshot_company_new
+    RENAME TO smes_list_snapshot_company;
@@ -0,0 +1,3 @@
+-- Mirrors `migrations/2024-12-01-020315_ingest_run_error`.
+ALTER TABLE ingest_run
+    ADD COLUMN error TEXT;
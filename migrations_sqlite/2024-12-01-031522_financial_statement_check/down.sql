@@ -0,0 +1 @@
+DROP TABLE dart_financial_statement_check;
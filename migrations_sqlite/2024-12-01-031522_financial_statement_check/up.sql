@@ -0,0 +1,14 @@
+-- Mirrors `migrations/2024-12-01-031522_financial_statement_check`.
+CREATE TABLE dart_financial_statement_check
+(
+    dart_id       TEXT      NOT NULL CHECK (length(dart_id) = 8 AND dart_id NOT GLOB '*[^0-9]*'),
+    business_year INTEGER   NOT NULL CHECK (business_year BETWEEN 2015 AND 9999),
+    report_code   TEXT      NOT NULL CHECK (report_code IN ('11011', '11012', '11013', '11014')),
+    fs_div        TEXT      NOT NULL CHECK (fs_div IN ('CFS', 'OFS')),
+    item_count    INTEGER   NOT NULL CHECK (item_count >= 0),
+    skipped_count INTEGER   NOT NULL CHECK (skipped_count >= 0),
+    run_id        INTEGER REFERENCES ingest_run (run_id) ON DELETE SET NULL,
+    checked_at    TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (dart_id, business_year, report_code, fs_div),
+    FOREIGN KEY (dart_id) REFERENCES dart_company_id (dart_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
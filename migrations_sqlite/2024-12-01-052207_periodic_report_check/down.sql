@@ -0,0 +1 @@
+DROP TABLE dart_periodic_report_check;